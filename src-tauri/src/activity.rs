@@ -0,0 +1,1637 @@
+//! Structured activity extraction from session files.
+//!
+//! Pulls specific kinds of activity (todo lists, plans, permission prompts, errors,
+//! web access, MCP calls, thinking) out of a session's JSONL so the frontend can show them without
+//! parsing raw events, and derives a suggested commit message from the transcript.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use crate::claude_code::{
+    get_session_file_edits, get_session_file_path, truncate_string, FileEdit, FileEditType,
+};
+
+// =============================================================================
+// Session Scanning
+// =============================================================================
+
+/// Scan a session file, calling `f` with (sequence, byte_offset, json) for each line
+/// accepted by `matches`. Checking the raw line first skips JSON parsing for unrelated lines.
+fn scan_lines(
+    file_path: &Path,
+    matches: impl Fn(&str) -> bool,
+    mut f: impl FnMut(u32, u64, &Value),
+) {
+    let file = match File::open(file_path) {
+        Ok(f) => f,
+        Err(_) => return,
+    };
+
+    let mut reader = BufReader::new(file);
+    let mut line = String::new();
+    let mut byte_offset: u64 = 0;
+    let mut sequence: u32 = 0;
+
+    loop {
+        line.clear();
+        let bytes_read = match reader.read_line(&mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => n,
+        };
+
+        if matches(&line) {
+            if let Ok(json) = serde_json::from_str::<Value>(line.trim_end()) {
+                f(sequence, byte_offset, &json);
+            }
+        }
+
+        byte_offset += bytes_read as u64;
+        sequence += 1;
+    }
+}
+
+/// Iterate over the tool_use blocks of an assistant event as (name, input, tool_use_id).
+fn tool_uses(json: &Value) -> impl Iterator<Item = (&str, &Value, Option<&str>)> {
+    let items = if json.get("type").and_then(|t| t.as_str()) == Some("assistant") {
+        json.get("message")
+            .and_then(|m| m.get("content"))
+            .and_then(|c| c.as_array())
+    } else {
+        None
+    };
+
+    items.into_iter().flatten().filter_map(|item| {
+        if item.get("type").and_then(|t| t.as_str()) != Some("tool_use") {
+            return None;
+        }
+        let name = item.get("name").and_then(|n| n.as_str())?;
+        let input = item.get("input")?;
+        let id = item.get("id").and_then(|i| i.as_str());
+        Some((name, input, id))
+    })
+}
+
+/// Iterate over the tool_result blocks of a user event as (tool_use_id, block).
+fn tool_results(json: &Value) -> impl Iterator<Item = (&str, &Value)> {
+    let items = if json.get("type").and_then(|t| t.as_str()) == Some("user") {
+        json.get("message")
+            .and_then(|m| m.get("content"))
+            .and_then(|c| c.as_array())
+    } else {
+        None
+    };
+
+    items.into_iter().flatten().filter_map(|item| {
+        if item.get("type").and_then(|t| t.as_str()) != Some("tool_result") {
+            return None;
+        }
+        let id = item.get("tool_use_id").and_then(|i| i.as_str())?;
+        Some((id, item))
+    })
+}
+
+/// Get the text of a tool_result block (string content or joined text parts).
+fn tool_result_text(block: &Value) -> String {
+    match block.get("content") {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Array(parts)) => parts
+            .iter()
+            .filter_map(|part| part.get("text").and_then(|t| t.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// Look up the (name, input) of specific tool uses by ID.
+/// Used to enrich tool results, which only carry the tool_use_id.
+fn resolve_tool_uses(session_file: &Path, ids: &[String]) -> HashMap<String, (String, Value)> {
+    let mut resolved = HashMap::new();
+    if ids.is_empty() {
+        return resolved;
+    }
+
+    scan_lines(
+        session_file,
+        |line| line.contains("\"tool_use\"") && ids.iter().any(|id| line.contains(id)),
+        |_, _, json| {
+            for (name, input, id) in tool_uses(json) {
+                if let Some(id) = id.filter(|id| ids.iter().any(|wanted| wanted == id)) {
+                    resolved.insert(id.to_string(), (name.to_string(), input.clone()));
+                }
+            }
+        },
+    );
+
+    resolved
+}
+
+/// Outcome of a tool use, taken from its tool_result.
+struct ToolOutcome {
+    /// Length of the result text in characters
+    result_chars: u64,
+    /// Whether the tool reported an error
+    is_error: bool,
+}
+
+/// Look up the results of specific tool uses by ID.
+fn resolve_tool_results(session_file: &Path, ids: &[String]) -> HashMap<String, ToolOutcome> {
+    let mut resolved = HashMap::new();
+    if ids.is_empty() {
+        return resolved;
+    }
+
+    scan_lines(
+        session_file,
+        |line| line.contains("\"tool_result\"") && ids.iter().any(|id| line.contains(id)),
+        |_, _, json| {
+            for (id, block) in tool_results(json) {
+                if ids.iter().any(|wanted| wanted == id) {
+                    let outcome = ToolOutcome {
+                        result_chars: tool_result_text(block).chars().count() as u64,
+                        is_error: block
+                            .get("is_error")
+                            .and_then(|e| e.as_bool())
+                            .unwrap_or(false),
+                    };
+                    resolved.insert(id.to_string(), outcome);
+                }
+            }
+        },
+    );
+
+    resolved
+}
+
+/// Get an event's timestamp, if present.
+fn event_timestamp(json: &Value) -> Option<String> {
+    json.get("timestamp")
+        .and_then(|t| t.as_str())
+        .map(|t| t.to_string())
+}
+
+// =============================================================================
+// Todos (TodoWrite)
+// =============================================================================
+
+/// A single item on the agent's todo list.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TodoItem {
+    /// Task description
+    pub content: String,
+    /// Status: "pending", "in_progress", or "completed"
+    pub status: String,
+    /// Present-tense label shown while the task is in progress
+    pub active_form: Option<String>,
+}
+
+/// The todo list as written by one TodoWrite call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TodoSnapshot {
+    /// Sequence number of the TodoWrite event
+    pub sequence: u32,
+    /// Byte offset of the TodoWrite event
+    pub byte_offset: u64,
+    /// Timestamp of the TodoWrite event (ISO 8601)
+    pub timestamp: Option<String>,
+    /// Full list after this write (TodoWrite always replaces the whole list)
+    pub todos: Vec<TodoItem>,
+}
+
+/// The agent's task board for a session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionTodos {
+    /// Most recent todo list (empty if TodoWrite was never called)
+    pub latest: Vec<TodoItem>,
+    /// Every TodoWrite call in file order
+    pub history: Vec<TodoSnapshot>,
+}
+
+/// Get the latest todo list and its change history for a session.
+pub fn get_session_todos(project_path: &str, session_id: &str) -> SessionTodos {
+    let mut history = Vec::new();
+
+    if let Some(session_file) = get_session_file_path(project_path, session_id) {
+        scan_lines(
+            &session_file,
+            |line| line.contains("\"TodoWrite\""),
+            |sequence, byte_offset, json| {
+                for (name, input, _) in tool_uses(json) {
+                    if name == "TodoWrite" {
+                        history.push(TodoSnapshot {
+                            sequence,
+                            byte_offset,
+                            timestamp: event_timestamp(json),
+                            todos: parse_todos(input),
+                        });
+                    }
+                }
+            },
+        );
+    }
+
+    let latest = history
+        .last()
+        .map(|snapshot| snapshot.todos.clone())
+        .unwrap_or_default();
+
+    SessionTodos { latest, history }
+}
+
+/// Get the todo directory Claude Code persists task lists to (`~/.claude/todos`).
+pub(crate) fn get_claude_todos_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".claude").join("todos"))
+}
+
+/// Get the (session_id, agent_id) a persisted todo file belongs to.
+///
+/// Files are named `<session_id>-agent-<agent_id>.json`; the main agent's list uses the
+/// session ID as its agent ID.
+pub(crate) fn todo_file_owner(path: &Path) -> Option<(String, String)> {
+    let name = path.file_name()?.to_str()?.strip_suffix(".json")?;
+    let (session_id, agent_id) = name.split_once("-agent-")?;
+    Some((session_id.to_string(), agent_id.to_string()))
+}
+
+/// Read a persisted todo file (a JSON array of todo items).
+pub(crate) fn read_todo_file(path: &Path) -> Vec<TodoItem> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        .map(|todos| parse_todo_list(&todos))
+        .unwrap_or_default()
+}
+
+/// Get the todo list Claude Code currently holds for a session's main agent, read from
+/// `~/.claude/todos` (empty if the session has none).
+pub fn get_persisted_todos(session_id: &str) -> Vec<TodoItem> {
+    match get_claude_todos_dir() {
+        Some(dir) => read_todo_file(&dir.join(format!("{0}-agent-{0}.json", session_id))),
+        None => Vec::new(),
+    }
+}
+
+/// Parse the `todos` array from a TodoWrite input.
+fn parse_todos(input: &Value) -> Vec<TodoItem> {
+    input.get("todos").map(parse_todo_list).unwrap_or_default()
+}
+
+/// Parse an array of todo items, skipping entries without content.
+fn parse_todo_list(todos: &Value) -> Vec<TodoItem> {
+    todos
+        .as_array()
+        .map(|todos| {
+            todos
+                .iter()
+                .filter_map(|todo| {
+                    Some(TodoItem {
+                        content: todo.get("content")?.as_str()?.to_string(),
+                        status: todo
+                            .get("status")
+                            .and_then(|s| s.as_str())
+                            .unwrap_or("pending")
+                            .to_string(),
+                        active_form: todo
+                            .get("activeForm")
+                            .and_then(|a| a.as_str())
+                            .map(|a| a.to_string()),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// =============================================================================
+// Plans (ExitPlanMode)
+// =============================================================================
+
+/// A plan the agent proposed with ExitPlanMode.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionPlan {
+    /// Sequence number of the ExitPlanMode event
+    pub sequence: u32,
+    /// Byte offset of the ExitPlanMode event
+    pub byte_offset: u64,
+    /// Timestamp of the ExitPlanMode event (ISO 8601)
+    pub timestamp: Option<String>,
+    /// Plan markdown
+    pub plan: String,
+    /// Whether the user approved the plan (None if no response was recorded yet)
+    pub approved: Option<bool>,
+    /// Sequence number of the approval/rejection response
+    pub response_sequence: Option<u32>,
+}
+
+/// Get every plan proposed in a session and whether each was approved.
+pub fn get_session_plans(project_path: &str, session_id: &str) -> Vec<SessionPlan> {
+    match get_session_file_path(project_path, session_id) {
+        Some(session_file) => collect_plans(&session_file),
+        None => Vec::new(),
+    }
+}
+
+/// Collect plans from a session file, matching each to its approval response.
+fn collect_plans(session_file: &Path) -> Vec<SessionPlan> {
+    // Pass 1: collect the proposed plans, keyed by tool_use_id
+    let mut plans: Vec<SessionPlan> = Vec::new();
+    let mut plan_ids: Vec<(String, usize)> = Vec::new();
+
+    scan_lines(
+        session_file,
+        |line| line.contains("\"ExitPlanMode\""),
+        |sequence, byte_offset, json| {
+            for (name, input, id) in tool_uses(json) {
+                if name != "ExitPlanMode" {
+                    continue;
+                }
+                if let Some(id) = id {
+                    plan_ids.push((id.to_string(), plans.len()));
+                }
+                plans.push(SessionPlan {
+                    sequence,
+                    byte_offset,
+                    timestamp: event_timestamp(json),
+                    plan: input
+                        .get("plan")
+                        .and_then(|p| p.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                    approved: None,
+                    response_sequence: None,
+                });
+            }
+        },
+    );
+
+    if plan_ids.is_empty() {
+        return plans;
+    }
+
+    // Pass 2: find the tool results answering those plans.
+    // A rejected plan comes back as an error result ("The user doesn't want to proceed...")
+    scan_lines(
+        session_file,
+        |line| line.contains("\"tool_result\"") && plan_ids.iter().any(|(id, _)| line.contains(id)),
+        |sequence, _, json| {
+            for (tool_use_id, block) in tool_results(json) {
+                if let Some((_, idx)) = plan_ids.iter().find(|(id, _)| id == tool_use_id) {
+                    let is_error = block
+                        .get("is_error")
+                        .and_then(|e| e.as_bool())
+                        .unwrap_or(false);
+                    plans[*idx].approved = Some(!is_error);
+                    plans[*idx].response_sequence = Some(sequence);
+                }
+            }
+        },
+    );
+
+    plans
+}
+
+// =============================================================================
+// Permission Events
+// =============================================================================
+
+/// Tool results with these prefixes mean the user rejected the tool use.
+const REJECTION_MARKERS: &[&str] = &[
+    "The user doesn't want to proceed",
+    "The user doesn't want to take this action",
+];
+
+/// Text the CLI injects when the user interrupts a pending tool use.
+const INTERRUPT_MARKER: &str = "[Request interrupted by user for tool use]";
+
+/// A tool use that needed human approval and didn't get it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionEvent {
+    /// Sequence number of the event recording the denial
+    pub sequence: u32,
+    /// Byte offset of the event recording the denial
+    pub byte_offset: u64,
+    /// Timestamp of the event (ISO 8601)
+    pub timestamp: Option<String>,
+    /// "rejected" (user declined the prompt), "interrupted", or "denied" (permission_denials)
+    pub kind: String,
+    /// ID of the tool use that was refused, if known
+    pub tool_use_id: Option<String>,
+    /// Name of the tool that was refused, if known
+    pub tool_name: Option<String>,
+    /// Input the agent passed to the tool, if known
+    pub tool_input: Option<Value>,
+    /// Preview of the denial message
+    pub message: String,
+}
+
+/// Get every permission denial, rejected prompt, and interrupted tool use in a session.
+pub fn get_permission_events(project_path: &str, session_id: &str) -> Vec<PermissionEvent> {
+    match get_session_file_path(project_path, session_id) {
+        Some(session_file) => collect_permission_events(&session_file),
+        None => Vec::new(),
+    }
+}
+
+/// Collect permission events from a session file, resolving tool names and inputs.
+fn collect_permission_events(session_file: &Path) -> Vec<PermissionEvent> {
+    // Pass 1: find the denials
+    let mut events: Vec<PermissionEvent> = Vec::new();
+
+    scan_lines(
+        session_file,
+        |line| {
+            REJECTION_MARKERS.iter().any(|m| line.contains(m))
+                || line.contains(INTERRUPT_MARKER)
+                || line.contains("\"permission_denials\"")
+        },
+        |sequence, byte_offset, json| {
+            let timestamp = event_timestamp(json);
+
+            for (tool_use_id, block) in tool_results(json) {
+                let text = tool_result_text(block);
+                if REJECTION_MARKERS.iter().any(|m| text.starts_with(m)) {
+                    events.push(PermissionEvent {
+                        sequence,
+                        byte_offset,
+                        timestamp: timestamp.clone(),
+                        kind: "rejected".to_string(),
+                        tool_use_id: Some(tool_use_id.to_string()),
+                        tool_name: None,
+                        tool_input: None,
+                        message: truncate_string(&text, 200),
+                    });
+                }
+            }
+
+            if json.get("type").and_then(|t| t.as_str()) == Some("user")
+                && message_text(json).contains(INTERRUPT_MARKER)
+            {
+                events.push(PermissionEvent {
+                    sequence,
+                    byte_offset,
+                    timestamp: timestamp.clone(),
+                    kind: "interrupted".to_string(),
+                    tool_use_id: None,
+                    tool_name: None,
+                    tool_input: None,
+                    message: INTERRUPT_MARKER.to_string(),
+                });
+            }
+
+            // Headless runs report denials on the final result event
+            if let Some(denials) = json.get("permission_denials").and_then(|d| d.as_array()) {
+                for denial in denials {
+                    events.push(PermissionEvent {
+                        sequence,
+                        byte_offset,
+                        timestamp: timestamp.clone(),
+                        kind: "denied".to_string(),
+                        tool_use_id: denial
+                            .get("tool_use_id")
+                            .and_then(|i| i.as_str())
+                            .map(|i| i.to_string()),
+                        tool_name: denial
+                            .get("tool_name")
+                            .and_then(|n| n.as_str())
+                            .map(|n| n.to_string()),
+                        tool_input: denial.get("tool_input").cloned(),
+                        message: "Permission denied".to_string(),
+                    });
+                }
+            }
+        },
+    );
+
+    // Pass 2: resolve tool names and inputs for denials that only carry an ID
+    let unresolved: Vec<String> = events
+        .iter()
+        .filter(|e| e.tool_name.is_none())
+        .filter_map(|e| e.tool_use_id.clone())
+        .collect();
+    let resolved = resolve_tool_uses(session_file, &unresolved);
+
+    for event in events.iter_mut().filter(|e| e.tool_name.is_none()) {
+        if let Some((name, input)) = event.tool_use_id.as_ref().and_then(|id| resolved.get(id)) {
+            event.tool_name = Some(name.clone());
+            event.tool_input = Some(input.clone());
+        }
+    }
+
+    events
+}
+
+/// Get the text of a message (string content or joined text blocks).
+fn message_text(json: &Value) -> String {
+    match json.get("message").and_then(|m| m.get("content")) {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter(|item| item.get("type").and_then(|t| t.as_str()) == Some("text"))
+            .filter_map(|item| item.get("text").and_then(|t| t.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+// =============================================================================
+// Errors
+// =============================================================================
+
+/// A failure recorded in a session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionError {
+    /// Sequence number of the event recording the error
+    pub sequence: u32,
+    /// Byte offset of the event recording the error
+    pub byte_offset: u64,
+    /// Timestamp of the event (ISO 8601)
+    pub timestamp: Option<String>,
+    /// "api_error", "tool_error", or "bash_exit" (Bash command exited non-zero)
+    pub kind: String,
+    /// ID of the failed tool use (tool errors only)
+    pub tool_use_id: Option<String>,
+    /// Name of the failed tool (tool errors only)
+    pub tool_name: Option<String>,
+    /// Exit code of a failed Bash command
+    pub exit_code: Option<i32>,
+    /// Preview of the error message
+    pub preview: String,
+}
+
+/// Get API errors, failed tool results, and non-zero Bash exits for a session.
+pub fn get_session_errors(project_path: &str, session_id: &str) -> Vec<SessionError> {
+    match get_session_file_path(project_path, session_id) {
+        Some(session_file) => collect_errors(&session_file),
+        None => Vec::new(),
+    }
+}
+
+/// Collect errors from a session file in file order.
+fn collect_errors(session_file: &Path) -> Vec<SessionError> {
+    let mut errors: Vec<SessionError> = Vec::new();
+
+    scan_lines(
+        session_file,
+        |line| {
+            line.contains("\"is_error\":true")
+                || line.contains("\"isApiErrorMessage\":true")
+                || line.contains("\"level\":\"error\"")
+        },
+        |sequence, byte_offset, json| {
+            let timestamp = event_timestamp(json);
+
+            let is_api_error = json.get("isApiErrorMessage").and_then(|e| e.as_bool())
+                == Some(true)
+                || (json.get("type").and_then(|t| t.as_str()) == Some("system")
+                    && json.get("level").and_then(|l| l.as_str()) == Some("error"));
+            if is_api_error {
+                let text = match json.get("content").and_then(|c| c.as_str()) {
+                    Some(content) => content.to_string(),
+                    None => message_text(json),
+                };
+                errors.push(SessionError {
+                    sequence,
+                    byte_offset,
+                    timestamp: timestamp.clone(),
+                    kind: "api_error".to_string(),
+                    tool_use_id: None,
+                    tool_name: None,
+                    exit_code: None,
+                    preview: truncate_string(&text, 200),
+                });
+            }
+
+            for (tool_use_id, block) in tool_results(json) {
+                if block.get("is_error").and_then(|e| e.as_bool()) != Some(true) {
+                    continue;
+                }
+                let text = tool_result_text(block);
+                // Rejected prompts are reported by get_permission_events, not as failures
+                if REJECTION_MARKERS.iter().any(|m| text.starts_with(m)) {
+                    continue;
+                }
+                errors.push(SessionError {
+                    sequence,
+                    byte_offset,
+                    timestamp: timestamp.clone(),
+                    kind: "tool_error".to_string(),
+                    tool_use_id: Some(tool_use_id.to_string()),
+                    tool_name: None,
+                    exit_code: None,
+                    preview: truncate_string(&text, 200),
+                });
+            }
+        },
+    );
+
+    // Resolve tool names, and flag Bash failures with their exit code
+    let ids: Vec<String> = errors
+        .iter()
+        .filter_map(|e| e.tool_use_id.clone())
+        .collect();
+    let resolved = resolve_tool_uses(session_file, &ids);
+
+    for error in errors.iter_mut() {
+        let name = match error.tool_use_id.as_ref().and_then(|id| resolved.get(id)) {
+            Some((name, _)) => name.clone(),
+            None => continue,
+        };
+        if name == "Bash" {
+            if let Some(code) = parse_exit_code(&error.preview) {
+                error.kind = "bash_exit".to_string();
+                error.exit_code = Some(code);
+            }
+        }
+        error.tool_name = Some(name);
+    }
+
+    errors
+}
+
+/// Parse the exit code from a failed Bash result ("Exit code 127\n...").
+fn parse_exit_code(text: &str) -> Option<i32> {
+    let rest = &text[text.find("Exit code ")? + "Exit code ".len()..];
+    let digits: String = rest
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '-')
+        .collect();
+    digits.parse().ok()
+}
+
+// =============================================================================
+// Web Activity (WebSearch / WebFetch)
+// =============================================================================
+
+/// A WebSearch or WebFetch call made by the agent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebActivity {
+    /// Sequence number of the tool_use event
+    pub sequence: u32,
+    /// Byte offset of the tool_use event
+    pub byte_offset: u64,
+    /// Timestamp of the tool_use event (ISO 8601)
+    pub timestamp: Option<String>,
+    /// "WebSearch" or "WebFetch"
+    pub tool_name: String,
+    /// ID of the tool use
+    pub tool_use_id: Option<String>,
+    /// Search query (WebSearch)
+    pub query: Option<String>,
+    /// Fetched URL (WebFetch)
+    pub url: Option<String>,
+    /// Prompt used to process the fetched page (WebFetch)
+    pub prompt: Option<String>,
+    /// Size of the result in characters (None if no result was recorded)
+    pub result_chars: Option<u64>,
+    /// Whether the call failed (None if no result was recorded)
+    pub is_error: Option<bool>,
+}
+
+/// Get every WebSearch and WebFetch call in a session with its result size.
+pub fn get_session_web_activity(project_path: &str, session_id: &str) -> Vec<WebActivity> {
+    match get_session_file_path(project_path, session_id) {
+        Some(session_file) => collect_web_activity(&session_file),
+        None => Vec::new(),
+    }
+}
+
+/// Collect web tool calls from a session file, with sizes from their results.
+fn collect_web_activity(session_file: &Path) -> Vec<WebActivity> {
+    let mut activity: Vec<WebActivity> = Vec::new();
+    let input_str = |input: &Value, key: &str| {
+        input
+            .get(key)
+            .and_then(|v| v.as_str())
+            .map(|v| v.to_string())
+    };
+
+    scan_lines(
+        session_file,
+        |line| line.contains("\"WebSearch\"") || line.contains("\"WebFetch\""),
+        |sequence, byte_offset, json| {
+            for (name, input, id) in tool_uses(json) {
+                if name != "WebSearch" && name != "WebFetch" {
+                    continue;
+                }
+                activity.push(WebActivity {
+                    sequence,
+                    byte_offset,
+                    timestamp: event_timestamp(json),
+                    tool_name: name.to_string(),
+                    tool_use_id: id.map(|id| id.to_string()),
+                    query: input_str(input, "query"),
+                    url: input_str(input, "url"),
+                    prompt: input_str(input, "prompt"),
+                    result_chars: None,
+                    is_error: None,
+                });
+            }
+        },
+    );
+
+    let ids: Vec<String> = activity
+        .iter()
+        .filter_map(|a| a.tool_use_id.clone())
+        .collect();
+    let outcomes = resolve_tool_results(session_file, &ids);
+
+    for entry in activity.iter_mut() {
+        if let Some(outcome) = entry.tool_use_id.as_ref().and_then(|id| outcomes.get(id)) {
+            entry.result_chars = Some(outcome.result_chars);
+            entry.is_error = Some(outcome.is_error);
+        }
+    }
+
+    activity
+}
+
+// =============================================================================
+// MCP Usage
+// =============================================================================
+
+/// Calls to a single tool on an MCP server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpToolUsage {
+    /// Tool name without the server prefix
+    pub tool: String,
+    /// Number of calls
+    pub call_count: u32,
+    /// Number of calls whose result was an error
+    pub failure_count: u32,
+}
+
+/// Calls to one MCP server, grouped by tool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpServerUsage {
+    /// Server name (from `mcp__<server>__<tool>`)
+    pub server: String,
+    /// Total calls to this server
+    pub call_count: u32,
+    /// Total failed calls to this server
+    pub failure_count: u32,
+    /// Per-tool breakdown, sorted by tool name
+    pub tools: Vec<McpToolUsage>,
+}
+
+/// Split an MCP tool name (`mcp__<server>__<tool>`) into (server, tool).
+pub fn parse_mcp_tool_name(name: &str) -> Option<(&str, &str)> {
+    let rest = name.strip_prefix("mcp__")?;
+    let (server, tool) = rest.split_once("__")?;
+    if server.is_empty() || tool.is_empty() {
+        return None;
+    }
+    Some((server, tool))
+}
+
+/// Get MCP tool calls grouped per server, with call and failure counts.
+pub fn get_session_mcp_usage(project_path: &str, session_id: &str) -> Vec<McpServerUsage> {
+    match get_session_file_path(project_path, session_id) {
+        Some(session_file) => collect_mcp_usage(&session_file),
+        None => Vec::new(),
+    }
+}
+
+/// Collect MCP usage from a session file, sorted by server name.
+fn collect_mcp_usage(session_file: &Path) -> Vec<McpServerUsage> {
+    // (server, tool, tool_use_id) for every MCP call
+    let mut calls: Vec<(String, String, Option<String>)> = Vec::new();
+
+    scan_lines(
+        session_file,
+        |line| line.contains("\"mcp__"),
+        |_, _, json| {
+            for (name, _, id) in tool_uses(json) {
+                if let Some((server, tool)) = parse_mcp_tool_name(name) {
+                    calls.push((
+                        server.to_string(),
+                        tool.to_string(),
+                        id.map(|i| i.to_string()),
+                    ));
+                }
+            }
+        },
+    );
+
+    let ids: Vec<String> = calls.iter().filter_map(|(_, _, id)| id.clone()).collect();
+    let outcomes = resolve_tool_results(session_file, &ids);
+
+    let mut servers: HashMap<String, HashMap<String, McpToolUsage>> = HashMap::new();
+    for (server, tool, id) in calls {
+        let failed = id
+            .as_ref()
+            .and_then(|id| outcomes.get(id))
+            .map(|outcome| outcome.is_error)
+            .unwrap_or(false);
+
+        let usage = servers
+            .entry(server)
+            .or_default()
+            .entry(tool.clone())
+            .or_insert(McpToolUsage {
+                tool,
+                call_count: 0,
+                failure_count: 0,
+            });
+        usage.call_count += 1;
+        if failed {
+            usage.failure_count += 1;
+        }
+    }
+
+    let mut result: Vec<McpServerUsage> = servers
+        .into_iter()
+        .map(|(server, tools)| {
+            let mut tools: Vec<McpToolUsage> = tools.into_values().collect();
+            tools.sort_by(|a, b| a.tool.cmp(&b.tool));
+            McpServerUsage {
+                server,
+                call_count: tools.iter().map(|t| t.call_count).sum(),
+                failure_count: tools.iter().map(|t| t.failure_count).sum(),
+                tools,
+            }
+        })
+        .collect();
+    result.sort_by(|a, b| a.server.cmp(&b.server));
+
+    result
+}
+
+// =============================================================================
+// Thinking
+// =============================================================================
+
+/// A full extended-thinking block (previews truncate these at 500 chars).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThinkingBlock {
+    /// Sequence number of the assistant event
+    pub sequence: u32,
+    /// Byte offset of the assistant event
+    pub byte_offset: u64,
+    /// Timestamp of the assistant event (ISO 8601)
+    pub timestamp: Option<String>,
+    /// Full thinking text
+    pub text: String,
+}
+
+/// Get every extended-thinking block in a session, in order, with full text.
+pub fn get_session_thinking(project_path: &str, session_id: &str) -> Vec<ThinkingBlock> {
+    match get_session_file_path(project_path, session_id) {
+        Some(session_file) => collect_thinking(&session_file),
+        None => Vec::new(),
+    }
+}
+
+/// Collect thinking blocks from a session file. Redacted thinking has no text and is skipped.
+fn collect_thinking(session_file: &Path) -> Vec<ThinkingBlock> {
+    let mut blocks = Vec::new();
+
+    scan_lines(
+        session_file,
+        |line| line.contains("\"thinking\""),
+        |sequence, byte_offset, json| {
+            if json.get("type").and_then(|t| t.as_str()) != Some("assistant") {
+                return;
+            }
+            let items = match json
+                .get("message")
+                .and_then(|m| m.get("content"))
+                .and_then(|c| c.as_array())
+            {
+                Some(items) => items,
+                None => return,
+            };
+
+            for item in items {
+                if item.get("type").and_then(|t| t.as_str()) != Some("thinking") {
+                    continue;
+                }
+                if let Some(text) = item.get("thinking").and_then(|t| t.as_str()) {
+                    if !text.is_empty() {
+                        blocks.push(ThinkingBlock {
+                            sequence,
+                            byte_offset,
+                            timestamp: event_timestamp(json),
+                            text: text.to_string(),
+                        });
+                    }
+                }
+            }
+        },
+    );
+
+    blocks
+}
+
+// =============================================================================
+// Commit Message Suggestion
+// =============================================================================
+
+/// Maximum length of a suggested commit subject line (type, scope, and description).
+const COMMIT_SUBJECT_MAX_CHARS: usize = 72;
+
+/// Maximum number of prompts listed in a suggested commit body.
+const COMMIT_PROMPT_LIMIT: usize = 5;
+
+/// Suggest a conventional-commit message for a session's work.
+///
+/// Derived purely from the transcript: the latest summary (or first prompt) becomes
+/// the subject, the prompt chain and edited files become the body.
+pub fn suggest_commit_message(project_path: &str, session_id: &str) -> Result<String, String> {
+    let session_file = get_session_file_path(project_path, session_id)
+        .ok_or_else(|| format!("Session file not found for {}", session_id))?;
+
+    let (summary, prompts) = collect_summary_and_prompts(&session_file);
+    let edits = get_session_file_edits(project_path, session_id);
+    if summary.is_none() && prompts.is_empty() && edits.is_empty() {
+        return Err("Session has no prompts or file edits to describe".to_string());
+    }
+
+    Ok(build_commit_message(summary.as_deref(), &prompts, &edits))
+}
+
+/// Collect the latest summary and the human prompts (first line of each) from a session file.
+fn collect_summary_and_prompts(session_file: &Path) -> (Option<String>, Vec<String>) {
+    let mut summary = None;
+    let mut prompts = Vec::new();
+
+    scan_lines(
+        session_file,
+        |line| line.contains("\"summary\"") || line.contains("\"external\""),
+        |_, _, json| match json.get("type").and_then(|t| t.as_str()) {
+            Some("summary") => {
+                if let Some(text) = json.get("summary").and_then(|s| s.as_str()) {
+                    summary = Some(text.trim().to_string());
+                }
+            }
+            Some("user") if is_human_prompt(json) => {
+                let text = message_text(json);
+                if let Some(first_line) = text.lines().map(str::trim).find(|l| !l.is_empty()) {
+                    prompts.push(first_line.to_string());
+                }
+            }
+            _ => {}
+        },
+    );
+
+    (summary.filter(|s| !s.is_empty()), prompts)
+}
+
+/// Check whether a user event is a prompt typed by the human (not a tool result,
+/// meta message, compaction summary, or slash-command wrapper).
+fn is_human_prompt(json: &Value) -> bool {
+    if json.get("userType").and_then(|u| u.as_str()) != Some("external")
+        || json.get("isMeta").and_then(|m| m.as_bool()) == Some(true)
+        || json.get("isCompactSummary").and_then(|c| c.as_bool()) == Some(true)
+        || tool_results(json).next().is_some()
+    {
+        return false;
+    }
+    !message_text(json).trim_start().starts_with('<')
+}
+
+/// Assemble a conventional-commit message from a summary, prompts, and edited files.
+fn build_commit_message(summary: Option<&str>, prompts: &[String], edits: &[FileEdit]) -> String {
+    let description_source = summary
+        .or_else(|| prompts.first().map(String::as_str))
+        .unwrap_or("update session files");
+    let commit_type = infer_commit_type(description_source, edits);
+    let header = match commit_scope(edits) {
+        Some(scope) => format!("{}({})", commit_type, scope),
+        None => commit_type.to_string(),
+    };
+
+    let max_description = COMMIT_SUBJECT_MAX_CHARS.saturating_sub(header.len() + 2 + 3);
+    let description = commit_description(description_source);
+    let mut message = format!(
+        "{}: {}",
+        header,
+        truncate_string(&description, max_description)
+    );
+
+    if !prompts.is_empty() {
+        message.push_str("\n\nPrompts:");
+        for prompt in prompts.iter().take(COMMIT_PROMPT_LIMIT) {
+            message.push_str(&format!(
+                "\n- {}",
+                truncate_string(prompt, COMMIT_SUBJECT_MAX_CHARS)
+            ));
+        }
+        if prompts.len() > COMMIT_PROMPT_LIMIT {
+            message.push_str(&format!(
+                "\n- ...and {} more",
+                prompts.len() - COMMIT_PROMPT_LIMIT
+            ));
+        }
+    }
+
+    if !edits.is_empty() {
+        message.push_str("\n\nFiles:");
+        for edit in edits {
+            let marker = match edit.edit_type {
+                FileEditType::Added => 'A',
+                FileEditType::Modified => 'M',
+                FileEditType::Deleted => 'D',
+                FileEditType::Renamed => 'R',
+            };
+            match &edit.renamed_from {
+                Some(from) => {
+                    message.push_str(&format!("\n- {} {} -> {}", marker, from, edit.path))
+                }
+                None => message.push_str(&format!("\n- {} {}", marker, edit.path)),
+            }
+        }
+    }
+
+    message
+}
+
+/// Pick a conventional-commit type from the description and the kinds of files edited.
+fn infer_commit_type(description: &str, edits: &[FileEdit]) -> &'static str {
+    let is_doc = |path: &str| {
+        let lower = path.to_lowercase();
+        lower.ends_with(".md") || lower.ends_with(".txt") || lower.starts_with("docs/")
+    };
+
+    if !edits.is_empty() && edits.iter().all(|e| is_doc(&e.path)) {
+        "docs"
+    } else if !edits.is_empty() && edits.iter().all(|e| is_test_path(&e.path)) {
+        "test"
+    } else if mentions_any(description, &["fix", "bug", "error", "crash", "broken"]) {
+        "fix"
+    } else if mentions_any(
+        description,
+        &["refactor", "rename", "clean up", "cleanup", "simplify"],
+    ) {
+        "refactor"
+    } else if edits.iter().any(|e| e.edit_type == FileEditType::Added)
+        || mentions_any(description, &["add", "implement", "support", "create"])
+    {
+        "feat"
+    } else {
+        "chore"
+    }
+}
+
+/// Whether a path is a test: a directory such as `tests/` or `e2e/`, or a file name
+/// part such as `login.spec.ts` or `test_utils.py` (but not `inspect.rs`).
+fn is_test_path(path: &str) -> bool {
+    path.split('/').any(|segment| {
+        segment.split(['.', '_', '-']).any(|part| {
+            matches!(
+                part.to_lowercase().as_str(),
+                "test" | "tests" | "spec" | "specs" | "e2e"
+            )
+        })
+    })
+}
+
+/// Whether a description mentions any keyword (or phrase) as whole words, allowing
+/// plural and past-tense endings, so "fixed" counts as "fix" but "prefix" doesn't.
+fn mentions_any(description: &str, keywords: &[&str]) -> bool {
+    let words: Vec<String> = description
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    let matches = |word: &str, keyword: &str| {
+        word.strip_prefix(keyword)
+            .is_some_and(|ending| matches!(ending, "" | "s" | "es" | "d" | "ed" | "ing"))
+    };
+
+    keywords.iter().any(|keyword| {
+        let keyword: Vec<&str> = keyword.split(' ').collect();
+        words.windows(keyword.len()).any(|window| {
+            window
+                .iter()
+                .zip(&keyword)
+                .all(|(word, &keyword)| matches(word.as_str(), keyword))
+        })
+    })
+}
+
+/// Use the top-level directory as the scope when every edited file shares it.
+fn commit_scope(edits: &[FileEdit]) -> Option<String> {
+    let mut dirs = edits.iter().map(|e| {
+        let mut parts = e.path.split('/');
+        let first = parts.next().unwrap_or_default();
+        // Files at the project root have no directory to scope by
+        parts.next().map(|_| first)
+    });
+    let first = dirs.next()??;
+    // Absolute paths (outside the project) start with an empty segment
+    if first.is_empty() || first.starts_with('.') {
+        return None;
+    }
+    dirs.all(|dir| dir == Some(first))
+        .then(|| first.to_string())
+}
+
+/// Normalize a summary or prompt into a commit description: first line, lowercase
+/// first letter, no trailing period.
+fn commit_description(text: &str) -> String {
+    let line = text.lines().next().unwrap_or_default().trim();
+    let line = line.trim_end_matches('.');
+    let mut chars = line.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    /// Write JSONL lines to a temp file.
+    fn write_session(lines: &[Value]) -> tempfile::NamedTempFile {
+        let mut tmp = tempfile::NamedTempFile::new().unwrap();
+        for line in lines {
+            writeln!(tmp, "{}", line).unwrap();
+        }
+        tmp
+    }
+
+    // =============================================================================
+    // Scanning Tests
+    // =============================================================================
+
+    #[test]
+    fn test_scan_lines_tracks_sequence_and_offset() {
+        let tmp = write_session(&[
+            serde_json::json!({"type": "user", "message": {"content": "hello"}}),
+            serde_json::json!({"type": "assistant", "message": {"content": "needle"}}),
+        ]);
+
+        let mut seen = Vec::new();
+        scan_lines(
+            tmp.path(),
+            |line| line.contains("needle"),
+            |sequence, byte_offset, _| {
+                seen.push((sequence, byte_offset));
+            },
+        );
+
+        let first_len = std::fs::read_to_string(tmp.path())
+            .unwrap()
+            .lines()
+            .next()
+            .unwrap()
+            .len() as u64
+            + 1;
+        assert_eq!(seen, vec![(1, first_len)]);
+    }
+
+    #[test]
+    fn test_tool_uses_only_for_assistant() {
+        let assistant = serde_json::json!({
+            "type": "assistant",
+            "message": {"content": [
+                {"type": "text", "text": "working"},
+                {"type": "tool_use", "name": "Bash", "input": {"command": "ls"}}
+            ]}
+        });
+        let names: Vec<&str> = tool_uses(&assistant).map(|(name, _, _)| name).collect();
+        assert_eq!(names, vec!["Bash"]);
+
+        let user = serde_json::json!({
+            "type": "user",
+            "message": {"content": [{"type": "tool_use", "name": "Bash", "input": {}}]}
+        });
+        assert_eq!(tool_uses(&user).count(), 0);
+    }
+
+    // =============================================================================
+    // Todo Tests
+    // =============================================================================
+
+    #[test]
+    fn test_parse_todos() {
+        let input = serde_json::json!({"todos": [
+            {"content": "Write tests", "status": "in_progress", "activeForm": "Writing tests"},
+            {"content": "Ship it", "status": "pending"},
+            {"status": "pending"}
+        ]});
+        let todos = parse_todos(&input);
+
+        assert_eq!(todos.len(), 2);
+        assert_eq!(todos[0].content, "Write tests");
+        assert_eq!(todos[0].status, "in_progress");
+        assert_eq!(todos[0].active_form.as_deref(), Some("Writing tests"));
+        assert_eq!(todos[1].active_form, None);
+    }
+
+    #[test]
+    fn test_tool_results_only_for_user() {
+        let user = serde_json::json!({
+            "type": "user",
+            "message": {"content": [
+                {"type": "tool_result", "tool_use_id": "tu-001", "content": "ok"}
+            ]}
+        });
+        let ids: Vec<&str> = tool_results(&user).map(|(id, _)| id).collect();
+        assert_eq!(ids, vec!["tu-001"]);
+
+        let assistant = serde_json::json!({"type": "assistant", "message": {"content": "hi"}});
+        assert_eq!(tool_results(&assistant).count(), 0);
+    }
+
+    #[test]
+    fn test_parse_todos_missing() {
+        assert!(parse_todos(&serde_json::json!({})).is_empty());
+    }
+
+    #[test]
+    fn test_read_todo_file() {
+        let mut tmp = tempfile::NamedTempFile::new().unwrap();
+        write!(
+            tmp,
+            "{}",
+            serde_json::json!([
+                {"content": "Fix bug", "status": "completed", "activeForm": "Fixing bug", "id": "1"},
+                {"content": "Add test", "status": "pending", "id": "2"}
+            ])
+        )
+        .unwrap();
+        let todos = read_todo_file(tmp.path());
+
+        assert_eq!(todos.len(), 2);
+        assert_eq!(todos[0].status, "completed");
+        assert_eq!(todos[1].content, "Add test");
+    }
+
+    #[test]
+    fn test_todo_file_owner() {
+        assert_eq!(
+            todo_file_owner(Path::new("/t/abc-agent-abc.json")),
+            Some(("abc".to_string(), "abc".to_string()))
+        );
+        assert_eq!(
+            todo_file_owner(Path::new("/t/abc-agent-def.json")),
+            Some(("abc".to_string(), "def".to_string()))
+        );
+        assert_eq!(todo_file_owner(Path::new("/t/abc.json")), None);
+    }
+
+    // =============================================================================
+    // Plan Tests
+    // =============================================================================
+
+    #[test]
+    fn test_collect_plans_approval() {
+        let tmp = write_session(&[
+            serde_json::json!({"type": "assistant", "message": {"content": [
+                {"type": "tool_use", "id": "plan-1", "name": "ExitPlanMode", "input": {"plan": "# First"}}
+            ]}}),
+            serde_json::json!({"type": "user", "message": {"content": [
+                {"type": "tool_result", "tool_use_id": "plan-1", "is_error": true, "content": "The user doesn't want to proceed"}
+            ]}}),
+            serde_json::json!({"type": "assistant", "message": {"content": [
+                {"type": "tool_use", "id": "plan-2", "name": "ExitPlanMode", "input": {"plan": "# Second"}}
+            ]}}),
+            serde_json::json!({"type": "user", "message": {"content": [
+                {"type": "tool_result", "tool_use_id": "plan-2", "content": "User has approved your plan"}
+            ]}}),
+            serde_json::json!({"type": "assistant", "message": {"content": [
+                {"type": "tool_use", "id": "plan-3", "name": "ExitPlanMode", "input": {"plan": "# Third"}}
+            ]}}),
+        ]);
+        let plans = collect_plans(tmp.path());
+
+        assert_eq!(plans.len(), 3);
+        assert_eq!(plans[0].plan, "# First");
+        assert_eq!(plans[0].approved, Some(false));
+        assert_eq!(plans[0].response_sequence, Some(1));
+        assert_eq!(plans[1].approved, Some(true));
+        assert_eq!(plans[2].approved, None);
+    }
+
+    // =============================================================================
+    // Permission Event Tests
+    // =============================================================================
+
+    #[test]
+    fn test_collect_permission_events() {
+        let tmp = write_session(&[
+            serde_json::json!({"type": "assistant", "message": {"content": [
+                {"type": "tool_use", "id": "tu-1", "name": "Bash", "input": {"command": "rm -rf build"}}
+            ]}}),
+            serde_json::json!({"type": "user", "message": {"content": [
+                {"type": "tool_result", "tool_use_id": "tu-1", "is_error": true,
+                 "content": "The user doesn't want to proceed with this tool use. The tool use was rejected."}
+            ]}}),
+            serde_json::json!({"type": "user", "message": {"content": [
+                {"type": "text", "text": "[Request interrupted by user for tool use]"}
+            ]}}),
+            serde_json::json!({"type": "result", "permission_denials": [
+                {"tool_name": "Write", "tool_use_id": "tu-9", "tool_input": {"file_path": "/etc/hosts"}}
+            ]}),
+        ]);
+        let events = collect_permission_events(tmp.path());
+
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].kind, "rejected");
+        assert_eq!(events[0].sequence, 1);
+        assert_eq!(events[0].tool_name.as_deref(), Some("Bash"));
+        assert_eq!(
+            events[0].tool_input.as_ref().and_then(|i| i.get("command")),
+            Some(&serde_json::json!("rm -rf build"))
+        );
+        assert_eq!(events[1].kind, "interrupted");
+        assert_eq!(events[2].kind, "denied");
+        assert_eq!(events[2].tool_name.as_deref(), Some("Write"));
+    }
+
+    #[test]
+    fn test_tool_result_text() {
+        let block = serde_json::json!({"content": [{"type": "text", "text": "a"}, {"type": "text", "text": "b"}]});
+        assert_eq!(tool_result_text(&block), "a\nb");
+        assert_eq!(
+            tool_result_text(&serde_json::json!({"content": "plain"})),
+            "plain"
+        );
+    }
+
+    // =============================================================================
+    // Error Tests
+    // =============================================================================
+
+    #[test]
+    fn test_collect_errors() {
+        let tmp = write_session(&[
+            serde_json::json!({"type": "assistant", "isApiErrorMessage": true, "message": {"content": [
+                {"type": "text", "text": "API Error: 529 Overloaded"}
+            ]}}),
+            serde_json::json!({"type": "assistant", "message": {"content": [
+                {"type": "tool_use", "id": "tu-1", "name": "Bash", "input": {"command": "cargo test"}},
+                {"type": "tool_use", "id": "tu-2", "name": "Read", "input": {"file_path": "/missing"}}
+            ]}}),
+            serde_json::json!({"type": "user", "message": {"content": [
+                {"type": "tool_result", "tool_use_id": "tu-1", "is_error": true, "content": "Exit code 101\ntest failed"},
+                {"type": "tool_result", "tool_use_id": "tu-2", "is_error": true, "content": "File does not exist."}
+            ]}}),
+            serde_json::json!({"type": "user", "message": {"content": [
+                {"type": "tool_result", "tool_use_id": "tu-3", "is_error": false, "content": "fine"}
+            ]}}),
+        ]);
+        let errors = collect_errors(tmp.path());
+
+        assert_eq!(errors.len(), 3);
+        assert_eq!(errors[0].kind, "api_error");
+        assert_eq!(errors[0].preview, "API Error: 529 Overloaded");
+        assert_eq!(errors[1].kind, "bash_exit");
+        assert_eq!(errors[1].exit_code, Some(101));
+        assert_eq!(errors[1].tool_name.as_deref(), Some("Bash"));
+        assert_eq!(errors[2].kind, "tool_error");
+        assert_eq!(errors[2].tool_name.as_deref(), Some("Read"));
+    }
+
+    #[test]
+    fn test_parse_exit_code() {
+        assert_eq!(parse_exit_code("Exit code 1"), Some(1));
+        assert_eq!(
+            parse_exit_code("Error: Exit code 127\ncommand not found"),
+            Some(127)
+        );
+        assert_eq!(parse_exit_code("no code here"), None);
+    }
+
+    // =============================================================================
+    // Web Activity Tests
+    // =============================================================================
+
+    #[test]
+    fn test_collect_web_activity() {
+        let tmp = write_session(&[
+            serde_json::json!({"type": "assistant", "message": {"content": [
+                {"type": "tool_use", "id": "tu-1", "name": "WebSearch", "input": {"query": "tauri channels"}},
+                {"type": "tool_use", "id": "tu-2", "name": "WebFetch", "input": {"url": "https://v2.tauri.app", "prompt": "Summarize"}}
+            ]}}),
+            serde_json::json!({"type": "user", "message": {"content": [
+                {"type": "tool_result", "tool_use_id": "tu-1", "content": "12345"},
+                {"type": "tool_result", "tool_use_id": "tu-2", "is_error": true, "content": "timeout"}
+            ]}}),
+        ]);
+        let activity = collect_web_activity(tmp.path());
+
+        assert_eq!(activity.len(), 2);
+        assert_eq!(activity[0].tool_name, "WebSearch");
+        assert_eq!(activity[0].query.as_deref(), Some("tauri channels"));
+        assert_eq!(activity[0].result_chars, Some(5));
+        assert_eq!(activity[0].is_error, Some(false));
+        assert_eq!(activity[1].url.as_deref(), Some("https://v2.tauri.app"));
+        assert_eq!(activity[1].prompt.as_deref(), Some("Summarize"));
+        assert_eq!(activity[1].is_error, Some(true));
+    }
+
+    // =============================================================================
+    // MCP Usage Tests
+    // =============================================================================
+
+    #[test]
+    fn test_parse_mcp_tool_name() {
+        assert_eq!(
+            parse_mcp_tool_name("mcp__github__create_issue"),
+            Some(("github", "create_issue"))
+        );
+        assert_eq!(
+            parse_mcp_tool_name("mcp__my-server__tool__with__parts"),
+            Some(("my-server", "tool__with__parts"))
+        );
+        assert_eq!(parse_mcp_tool_name("Bash"), None);
+        assert_eq!(parse_mcp_tool_name("mcp__noseparator"), None);
+    }
+
+    #[test]
+    fn test_collect_mcp_usage() {
+        let tmp = write_session(&[
+            serde_json::json!({"type": "assistant", "message": {"content": [
+                {"type": "tool_use", "id": "tu-1", "name": "mcp__github__get_issue", "input": {}},
+                {"type": "tool_use", "id": "tu-2", "name": "mcp__github__get_issue", "input": {}},
+                {"type": "tool_use", "id": "tu-3", "name": "mcp__slack__post", "input": {}},
+                {"type": "tool_use", "id": "tu-4", "name": "Bash", "input": {}}
+            ]}}),
+            serde_json::json!({"type": "user", "message": {"content": [
+                {"type": "tool_result", "tool_use_id": "tu-1", "content": "ok"},
+                {"type": "tool_result", "tool_use_id": "tu-2", "is_error": true, "content": "rate limited"}
+            ]}}),
+        ]);
+        let usage = collect_mcp_usage(tmp.path());
+
+        assert_eq!(usage.len(), 2);
+        assert_eq!(usage[0].server, "github");
+        assert_eq!(usage[0].call_count, 2);
+        assert_eq!(usage[0].failure_count, 1);
+        assert_eq!(usage[0].tools[0].tool, "get_issue");
+        assert_eq!(usage[1].server, "slack");
+        assert_eq!(usage[1].failure_count, 0);
+    }
+
+    // =============================================================================
+    // Thinking Tests
+    // =============================================================================
+
+    #[test]
+    fn test_collect_thinking_full_text() {
+        let long_thought = "step ".repeat(200);
+        let tmp = write_session(&[
+            serde_json::json!({"type": "assistant", "message": {"content": [
+                {"type": "thinking", "thinking": long_thought},
+                {"type": "text", "text": "answer"}
+            ]}}),
+            serde_json::json!({"type": "user", "message": {"content": "what about \"thinking\"?"}}),
+            serde_json::json!({"type": "assistant", "message": {"content": [
+                {"type": "redacted_thinking", "data": "abc"},
+                {"type": "thinking", "thinking": "second thought"}
+            ]}}),
+        ]);
+        let blocks = collect_thinking(tmp.path());
+
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].text, long_thought);
+        assert_eq!(blocks[0].sequence, 0);
+        assert_eq!(blocks[1].text, "second thought");
+        assert_eq!(blocks[1].sequence, 2);
+    }
+    // =============================================================================
+    // Commit Message Tests
+    // =============================================================================
+
+    fn file_edit(path: &str, edit_type: FileEditType) -> FileEdit {
+        FileEdit {
+            path: path.to_string(),
+            edit_type,
+            last_edited_at: None,
+            edited_by: None,
+            lines_added: 0,
+            lines_removed: 0,
+            edit_count: 1,
+            renamed_from: None,
+            is_generated: false,
+        }
+    }
+
+    #[test]
+    fn test_collect_summary_and_prompts() {
+        let tmp = write_session(&[
+            serde_json::json!({"type": "summary", "summary": "Old summary"}),
+            serde_json::json!({"type": "user", "userType": "external", "message": {"content": "Fix the login bug\nIt fails on empty passwords"}}),
+            serde_json::json!({"type": "user", "userType": "external", "message": {"content": [
+                {"type": "tool_result", "tool_use_id": "tu-1", "content": "ok"}
+            ]}}),
+            serde_json::json!({"type": "user", "userType": "external", "isMeta": true, "message": {"content": "caveat"}}),
+            serde_json::json!({"type": "user", "userType": "external", "message": {"content": "<command-name>/clear</command-name>"}}),
+            serde_json::json!({"type": "user", "userType": "external", "message": {"content": [
+                {"type": "text", "text": "Also add a test"}
+            ]}}),
+            serde_json::json!({"type": "summary", "summary": "Login validation fix"}),
+        ]);
+        let (summary, prompts) = collect_summary_and_prompts(tmp.path());
+
+        assert_eq!(summary.as_deref(), Some("Login validation fix"));
+        assert_eq!(prompts, vec!["Fix the login bug", "Also add a test"]);
+    }
+
+    #[test]
+    fn test_build_commit_message() {
+        let prompts = ["Fix the login bug".to_string()];
+        let edits = [
+            file_edit("src/login.rs", FileEditType::Modified),
+            file_edit("src/validate.rs", FileEditType::Added),
+        ];
+        let message = build_commit_message(Some("Login validation fix."), &prompts, &edits);
+
+        assert_eq!(
+            message,
+            "fix(src): login validation fix\n\n\
+             Prompts:\n- Fix the login bug\n\n\
+             Files:\n- M src/login.rs\n- A src/validate.rs"
+        );
+    }
+
+    #[test]
+    fn test_build_commit_message_subject_length() {
+        let prompt = "Implement ".to_string() + &"a very long request ".repeat(10);
+        let edits = [
+            file_edit("README.md", FileEditType::Modified),
+            file_edit("src/main.rs", FileEditType::Modified),
+        ];
+        let message = build_commit_message(None, &[prompt], &edits);
+        let subject = message.lines().next().unwrap();
+
+        assert!(subject.starts_with("feat: implement a very long request"));
+        assert!(subject.chars().count() <= COMMIT_SUBJECT_MAX_CHARS);
+    }
+
+    #[test]
+    fn test_infer_commit_type_from_files() {
+        let docs = [file_edit("docs/guide.md", FileEditType::Modified)];
+        assert_eq!(infer_commit_type("Fix typo", &docs), "docs");
+
+        let tests = [file_edit("e2e/login.spec.ts", FileEditType::Added)];
+        assert_eq!(infer_commit_type("Cover login", &tests), "test");
+
+        let code = [file_edit("src/lib.rs", FileEditType::Modified)];
+        assert_eq!(infer_commit_type("Tweak logging", &code), "chore");
+        assert_eq!(commit_scope(&code).as_deref(), Some("src"));
+        assert_eq!(
+            commit_scope(&[file_edit("Cargo.toml", FileEditType::Modified)]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_infer_commit_type_whole_words() {
+        let code = [file_edit("src/lib.rs", FileEditType::Modified)];
+        assert_eq!(infer_commit_type("Fixed the parser", &code), "fix");
+        assert_eq!(infer_commit_type("Handle crashes on exit", &code), "fix");
+        assert_eq!(infer_commit_type("Cleaned up imports", &code), "refactor");
+        assert_eq!(infer_commit_type("Adds a retry flag", &code), "feat");
+
+        // Keywords inside other words don't count
+        assert_eq!(infer_commit_type("Strip the prefix", &code), "chore");
+        assert_eq!(infer_commit_type("Address review notes", &code), "chore");
+        assert_eq!(infer_commit_type("Terror-proof the docs", &code), "chore");
+    }
+
+    #[test]
+    fn test_is_test_path() {
+        assert!(is_test_path("src/login.spec.ts"));
+        assert!(is_test_path("tests/api.rs"));
+        assert!(is_test_path("pkg/test_utils.py"));
+        assert!(is_test_path("src/__tests__/app.tsx"));
+        assert!(is_test_path("e2e/login.ts"));
+        assert!(!is_test_path("src/inspect.rs"));
+        assert!(!is_test_path("src/contest/rules.rs"));
+    }
+
+    #[test]
+    fn test_commit_scope_absolute_path() {
+        let outside = [
+            file_edit("/etc/hosts", FileEditType::Modified),
+            file_edit("/etc/passwd", FileEditType::Modified),
+        ];
+        assert_eq!(commit_scope(&outside), None);
+        assert_eq!(
+            build_commit_message(Some("Update hosts"), &[], &outside)
+                .lines()
+                .next(),
+            Some("chore: update hosts")
+        );
+    }
+}
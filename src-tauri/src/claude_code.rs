@@ -9,8 +9,12 @@ use std::collections::{HashMap, HashSet};
 use std::fs::{self, File};
 use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use std::time::SystemTime;
 
+use crate::diff::count_line_changes;
+use crate::language::{detect_language, looks_generated};
+
 /// Represents an agent type supported by the collector.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "kebab-case")]
@@ -62,6 +66,9 @@ pub struct Project {
     pub last_activity: String,
     /// Individual sessions (sorted by last activity, descending)
     pub sessions: Vec<Session>,
+    /// Number of uncommitted changes in the project's repository
+    /// (None unless requested, or if the project isn't a git repository)
+    pub uncommitted_changes: Option<u32>,
 }
 
 /// Internal struct for extracting cwd from JSONL entries.
@@ -71,7 +78,7 @@ struct JsonlEntry {
 }
 
 /// Get the Claude Code projects directory path.
-fn get_claude_projects_dir() -> Option<PathBuf> {
+pub(crate) fn get_claude_projects_dir() -> Option<PathBuf> {
     dirs::home_dir().map(|h| h.join(".claude").join("projects"))
 }
 
@@ -105,7 +112,7 @@ fn system_time_to_iso(time: SystemTime) -> String {
 
     // Convert to ISO 8601 format
     let datetime = chrono::DateTime::from_timestamp(secs as i64, 0)
-        .unwrap_or_else(|| chrono::Utc::now());
+        .unwrap_or_else(chrono::Utc::now);
     datetime.to_rfc3339()
 }
 
@@ -125,23 +132,8 @@ pub fn discover_projects() -> Vec<Project> {
     };
 
     for entry in entries.flatten() {
-        let path = entry.path();
-        if !path.is_dir() {
-            continue;
-        }
-
-        let dir_name = match path.file_name() {
-            Some(n) => n.to_string_lossy().to_string(),
-            None => continue,
-        };
-
-        // Skip temp folders and non-user projects
-        if is_temp_project(&dir_name) || !dir_name.starts_with("-Users-") {
-            continue;
-        }
-
         // Process project directory
-        if let Some(project) = process_project_dir(&path) {
+        if let Some(project) = project_from_dir(&entry.path()) {
             let key = project.project_path.clone();
             projects.insert(key, project);
         }
@@ -153,6 +145,23 @@ pub fn discover_projects() -> Vec<Project> {
     result
 }
 
+/// Load a project from a directory in `~/.claude/projects`, skipping temp folders,
+/// non-user projects, and directories without sessions.
+pub(crate) fn project_from_dir(path: &Path) -> Option<Project> {
+    if !path.is_dir() {
+        return None;
+    }
+
+    let dir_name = path.file_name()?.to_string_lossy().to_string();
+
+    // Skip temp folders and non-user projects
+    if is_temp_project(&dir_name) || !dir_name.starts_with("-Users-") {
+        return None;
+    }
+
+    process_project_dir(path)
+}
+
 /// Process a single project directory (lightweight - no file content parsing).
 /// Only counts files and uses mtimes for the list view.
 fn process_project_dir(dir_path: &Path) -> Option<Project> {
@@ -190,7 +199,7 @@ fn process_project_dir(dir_path: &Path) -> Option<Project> {
         // Track file mtime (much faster than parsing content)
         if let Ok(metadata) = fs::metadata(&path) {
             if let Ok(mtime) = metadata.modified() {
-                if latest_mtime.map_or(true, |latest| mtime > latest) {
+                if latest_mtime.is_none_or(|latest| mtime > latest) {
                     latest_mtime = Some(mtime);
                 }
             }
@@ -236,18 +245,37 @@ fn process_project_dir(dir_path: &Path) -> Option<Project> {
         subagent_count,
         last_activity,
         sessions: Vec::new(), // Empty for list view - load on demand via get_project_sessions
+        uncommitted_changes: None,
     })
 }
 
 /// Convert a project path to its encoded directory name.
 /// e.g., "/Users/ramos/project" -> "-Users-ramos-project"
 fn encode_project_path(project_path: &str) -> String {
-    project_path.replace('/', "-").replace(' ', "-")
+    project_path.replace(['/', ' '], "-")
 }
 
 /// Get sessions for a specific project (lightweight - no file content parsing).
 /// Only returns session ID and last activity time from file metadata.
 pub fn get_sessions_for_project(project_path: &str) -> Vec<Session> {
+    get_session_modified_times(project_path)
+        .into_iter()
+        .map(|(id, modified)| Session {
+            id,
+            slug: None,
+            summary: None,
+            model: None,
+            version: None,
+            git_branch: None,
+            started_at: None,
+            last_activity: system_time_to_iso(modified),
+            message_count: 0,
+        })
+        .collect()
+}
+
+/// Get each session in a project with its file modification time, most recent first.
+pub(crate) fn get_session_modified_times(project_path: &str) -> Vec<(String, SystemTime)> {
     let projects_dir = match get_claude_projects_dir() {
         Some(p) if p.exists() => p,
         _ => return Vec::new(),
@@ -266,7 +294,7 @@ pub fn get_sessions_for_project(project_path: &str) -> Vec<Session> {
         Err(_) => return Vec::new(),
     };
 
-    let mut sessions: Vec<Session> = Vec::new();
+    let mut sessions: Vec<(String, SystemTime)> = Vec::new();
 
     for entry in entries.flatten() {
         let path = entry.path();
@@ -285,32 +313,21 @@ pub fn get_sessions_for_project(project_path: &str) -> Vec<Session> {
             continue;
         }
 
-        // Get file modification time for last_activity (no content parsing!)
-        let last_activity = fs::metadata(&path)
+        // File modification time stands in for last activity (no content parsing!)
+        let modified = fs::metadata(&path)
             .and_then(|m| m.modified())
-            .map(system_time_to_iso)
-            .unwrap_or_default();
+            .unwrap_or(SystemTime::UNIX_EPOCH);
 
-        sessions.push(Session {
-            id: file_name,
-            slug: None,
-            summary: None,
-            model: None,
-            version: None,
-            git_branch: None,
-            started_at: None,
-            last_activity,
-            message_count: 0,
-        });
+        sessions.push((file_name, modified));
     }
 
     // Sort by last activity descending
-    sessions.sort_by(|a, b| b.last_activity.cmp(&a.last_activity));
+    sessions.sort_by(|a, b| b.1.cmp(&a.1));
     sessions
 }
 
 /// Check if a string looks like a UUID (8-4-4-4-12 format).
-fn is_uuid_format(s: &str) -> bool {
+pub(crate) fn is_uuid_format(s: &str) -> bool {
     let parts: Vec<&str> = s.split('-').collect();
     if parts.len() != 5 {
         return false;
@@ -339,6 +356,8 @@ pub enum FileEditType {
     Added,
     Modified,
     Deleted,
+    /// Moved via `mv`/`git mv` without content changes
+    Renamed,
 }
 
 /// A file that was edited during a session.
@@ -351,6 +370,18 @@ pub struct FileEdit {
     pub edit_type: FileEditType,
     /// Timestamp of the last edit to this file (ISO 8601)
     pub last_edited_at: Option<String>,
+    /// ID of the sub-agent that made the edits (None when the main session edited the file)
+    pub edited_by: Option<String>,
+    /// Lines added across all edits
+    pub lines_added: u32,
+    /// Lines removed across all edits
+    pub lines_removed: u32,
+    /// Number of edit operations (each MultiEdit sub-edit counts)
+    pub edit_count: u32,
+    /// Path the file had before being moved in the session
+    pub renamed_from: Option<String>,
+    /// Binary or machine-generated content (lockfiles, minified bundles)
+    pub is_generated: bool,
 }
 
 /// A single diff operation on a file.
@@ -365,6 +396,18 @@ pub struct FileDiff {
     pub sequence: u32,
     /// Timestamp of the change (ISO 8601)
     pub timestamp: Option<String>,
+    /// ID of the sub-agent that made the change (None when the main session made it)
+    pub edited_by: Option<String>,
+    /// ID of the notebook cell that was changed (NotebookEdit only)
+    pub cell_id: Option<String>,
+    /// Notebook edit mode: "replace", "insert", or "delete" (NotebookEdit only)
+    pub cell_edit_mode: Option<String>,
+    /// Monaco language identifier detected from the file name or shebang
+    pub language: String,
+    /// Whether the edit would apply to the file currently on disk (None if unknown)
+    pub applies_cleanly: Option<bool>,
+    /// Whether the edit could be reverted on the file currently on disk (None if unknown)
+    pub reverts_cleanly: Option<bool>,
 }
 
 /// Internal struct for parsing JSONL entries to extract tool_use.
@@ -404,6 +447,38 @@ pub fn get_session_file_path(project_path: &str, session_id: &str) -> Option<Pat
     }
 }
 
+/// Get a session's human-readable slug (e.g., "async-knitting-panda") from its events.
+pub fn get_session_slug(project_path: &str, session_id: &str) -> Option<String> {
+    let session_file = get_session_file_path(project_path, session_id)?;
+    session_string_field(&session_file, "slug").next()
+}
+
+/// Get the git branch most recently recorded in a session's events.
+pub fn get_session_git_branch(project_path: &str, session_id: &str) -> Option<String> {
+    let session_file = get_session_file_path(project_path, session_id)?;
+    session_string_field(&session_file, "gitBranch")
+        .filter(|branch| !branch.is_empty())
+        .last()
+}
+
+/// Iterate over the values of a top-level string field across a session's events.
+fn session_string_field<'a>(
+    session_file: &Path,
+    key: &'a str,
+) -> impl Iterator<Item = String> + 'a {
+    let needle = format!("\"{}\"", key);
+    File::open(session_file)
+        .ok()
+        .map(BufReader::new)
+        .into_iter()
+        .flat_map(|reader| reader.lines().map_while(Result::ok))
+        .filter(move |line| line.contains(&needle))
+        .filter_map(move |line| {
+            let json: Value = serde_json::from_str(&line).ok()?;
+            json.get(key)?.as_str().map(String::from)
+        })
+}
+
 /// Get the sub-agent session file path for a project and agent ID.
 pub fn get_subagent_file_path(project_path: &str, agent_id: &str) -> Option<PathBuf> {
     let projects_dir = get_claude_projects_dir()?;
@@ -420,13 +495,240 @@ pub fn get_subagent_file_path(project_path: &str, agent_id: &str) -> Option<Path
 }
 
 /// Extract all file edits from a session (lightweight - just file list and types).
+///
+/// Edits made by Task sub-agents are merged in with `edited_by` set to the agent ID.
 pub fn get_session_file_edits(project_path: &str, session_id: &str) -> Vec<FileEdit> {
     let session_file = match get_session_file_path(project_path, session_id) {
         Some(p) => p,
         None => return Vec::new(),
     };
 
-    let file = match File::open(&session_file) {
+    let edits = extract_file_edits(&session_file, project_path);
+    merge_subagent_file_edits(project_path, session_id, edits)
+}
+
+/// Merge file edits made by a session's sub-agents into its own file edits.
+///
+/// Files the main session also edited keep their main-session entry.
+pub fn merge_subagent_file_edits(
+    project_path: &str,
+    session_id: &str,
+    edits: Vec<FileEdit>,
+) -> Vec<FileEdit> {
+    let session_file = match get_session_file_path(project_path, session_id) {
+        Some(p) => p,
+        None => return edits,
+    };
+
+    merge_transcript_file_edits(&session_file, project_path, edits)
+}
+
+/// Merge the file edits from the transcripts of a session file's sub-agents.
+fn merge_transcript_file_edits(
+    session_file: &Path,
+    project_path: &str,
+    mut edits: Vec<FileEdit>,
+) -> Vec<FileEdit> {
+    let mut changed = false;
+    for (agent_id, agent_file) in subagent_transcripts(session_file) {
+        for agent_edit in extract_file_edits(&agent_file, project_path) {
+            match edits.iter_mut().find(|e| e.path == agent_edit.path) {
+                Some(existing) => {
+                    // Keep the latest timestamp across main session and agents
+                    if agent_edit.last_edited_at > existing.last_edited_at {
+                        existing.last_edited_at = agent_edit.last_edited_at;
+                    }
+                    existing.lines_added += agent_edit.lines_added;
+                    existing.lines_removed += agent_edit.lines_removed;
+                    existing.edit_count += agent_edit.edit_count;
+                    existing.is_generated |= agent_edit.is_generated;
+                }
+                None => {
+                    edits.push(FileEdit {
+                        edited_by: Some(agent_id.clone()),
+                        ..agent_edit
+                    });
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    if changed {
+        edits.sort_by(|a, b| a.path.cmp(&b.path));
+    }
+    edits
+}
+
+/// Filter file edits by glob patterns (e.g. `src/**/*.rs`); patterns starting with `!` exclude.
+///
+/// With no include patterns every file is included unless excluded.
+pub fn filter_file_edits(
+    edits: Vec<FileEdit>,
+    patterns: &[String],
+) -> Result<Vec<FileEdit>, String> {
+    let mut includes = Vec::new();
+    let mut excludes = Vec::new();
+    for pattern in patterns {
+        let (list, raw) = match pattern.strip_prefix('!') {
+            Some(rest) => (&mut excludes, rest),
+            None => (&mut includes, pattern.as_str()),
+        };
+        let compiled =
+            glob::Pattern::new(raw).map_err(|e| format!("Invalid pattern '{}': {}", pattern, e))?;
+        list.push(compiled);
+    }
+
+    let options = glob::MatchOptions {
+        require_literal_separator: true,
+        ..Default::default()
+    };
+    let matches =
+        |list: &[glob::Pattern], path: &str| list.iter().any(|p| p.matches_with(path, options));
+
+    Ok(edits
+        .into_iter()
+        .filter(|edit| includes.is_empty() || matches(&includes, &edit.path))
+        .filter(|edit| !matches(&excludes, &edit.path))
+        .collect())
+}
+
+/// Where a sub-agent transcript came from, read from its first events.
+#[derive(Debug, Clone, Default)]
+pub struct SubagentOrigin {
+    /// ID of the session that launched the sub-agent
+    pub session_id: Option<String>,
+    /// Prompt the sub-agent was started with
+    pub prompt: Option<String>,
+}
+
+/// Read a sub-agent's parent session ID and launch prompt from the head of its file.
+pub fn read_subagent_origin(agent_file: &Path) -> SubagentOrigin {
+    let mut origin = SubagentOrigin::default();
+    let file = match File::open(agent_file) {
+        Ok(f) => f,
+        Err(_) => return origin,
+    };
+
+    for line in BufReader::new(file).lines().map_while(Result::ok).take(20) {
+        let json: Value = match serde_json::from_str(&line) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        if origin.session_id.is_none() {
+            origin.session_id = json
+                .get("sessionId")
+                .and_then(|v| v.as_str())
+                .map(String::from);
+        }
+        if origin.prompt.is_none() && json.get("type").and_then(|t| t.as_str()) == Some("user") {
+            origin.prompt = match json.get("message").and_then(|m| m.get("content")) {
+                Some(Value::String(text)) => Some(text.clone()),
+                Some(Value::Array(items)) => items
+                    .iter()
+                    .find_map(|item| item.get("text").and_then(|t| t.as_str()))
+                    .map(String::from),
+                _ => None,
+            };
+        }
+        if origin.session_id.is_some() && origin.prompt.is_some() {
+            break;
+        }
+    }
+    origin
+}
+
+/// Find the Task tool_use in a session that launched a sub-agent with `prompt`,
+/// returning its tool_use_id and description. The latest matching launch wins.
+pub fn find_task_launch(session_file: &Path, prompt: &str) -> Option<(String, Option<String>)> {
+    let file = File::open(session_file).ok()?;
+    let prompt = prompt.trim();
+
+    let mut found = None;
+    for line in BufReader::new(file).lines().map_while(Result::ok) {
+        if !line.contains("\"Task\"") {
+            continue;
+        }
+        let json: Value = match serde_json::from_str(&line) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        let items = match json
+            .get("message")
+            .and_then(|m| m.get("content"))
+            .and_then(|c| c.as_array())
+        {
+            Some(items) => items,
+            None => continue,
+        };
+        for item in items {
+            if item.get("type").and_then(|t| t.as_str()) != Some("tool_use")
+                || item.get("name").and_then(|n| n.as_str()) != Some("Task")
+            {
+                continue;
+            }
+            let input = item.get("input");
+            let launched_prompt = input.and_then(|i| i.get("prompt")).and_then(|p| p.as_str());
+            if launched_prompt.map(str::trim) != Some(prompt) {
+                continue;
+            }
+            if let Some(id) = item.get("id").and_then(|v| v.as_str()) {
+                let description = input
+                    .and_then(|i| i.get("description"))
+                    .and_then(|d| d.as_str())
+                    .map(String::from);
+                found = Some((id.to_string(), description));
+            }
+        }
+    }
+    found
+}
+
+/// Get the IDs of sub-agents launched by a session, in launch order.
+fn launched_agent_ids(session_file: &Path) -> Vec<String> {
+    let file = match File::open(session_file) {
+        Ok(f) => f,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut ids: Vec<String> = Vec::new();
+    for line in BufReader::new(file).lines().map_while(Result::ok) {
+        if !line.contains("\"agentId\"") {
+            continue;
+        }
+        let json: Value = match serde_json::from_str(&line) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        let agent_id = json
+            .get("toolUseResult")
+            .and_then(|r| r.get("agentId"))
+            .and_then(|v| v.as_str());
+        if let Some(id) = agent_id {
+            if !ids.iter().any(|existing| existing == id) {
+                ids.push(id.to_string());
+            }
+        }
+    }
+    ids
+}
+
+/// Get the transcripts of the sub-agents a session launched, in launch order.
+///
+/// Sub-agent files (`agent-<id>.jsonl`) live beside the session file.
+fn subagent_transcripts(session_file: &Path) -> Vec<(String, PathBuf)> {
+    launched_agent_ids(session_file)
+        .into_iter()
+        .filter_map(|agent_id| {
+            let agent_file = session_file.with_file_name(format!("agent-{}.jsonl", agent_id));
+            agent_file.exists().then_some((agent_id, agent_file))
+        })
+        .collect()
+}
+
+/// Extract the file edits recorded in a single session or agent file.
+fn extract_file_edits(session_file: &Path, project_path: &str) -> Vec<FileEdit> {
+    let file = match File::open(session_file) {
         Ok(f) => f,
         Err(_) => return Vec::new(),
     };
@@ -437,6 +739,8 @@ pub fn get_session_file_edits(project_path: &str, session_id: &str) -> Vec<FileE
     let mut file_operations: HashMap<String, FileEditType> = HashMap::new();
     let mut files_with_prior_content: HashSet<String> = HashSet::new();
     let mut file_timestamps: HashMap<String, String> = HashMap::new();
+    let mut file_stats: HashMap<String, EditStats> = HashMap::new();
+    let mut renamed_from: HashMap<String, String> = HashMap::new();
 
     for line in reader.lines() {
         let line = match line {
@@ -481,6 +785,13 @@ pub fn get_session_file_edits(project_path: &str, session_id: &str) -> Vec<FileE
 
             let timestamp = entry.timestamp.clone();
 
+            if let Some((file_path, stats)) = tool_edit_stats(tool_name, input) {
+                file_stats
+                    .entry(make_relative_path(file_path, project_path))
+                    .or_default()
+                    .add(stats);
+            }
+
             match tool_name {
                 "Edit" => {
                     if let Some(file_path) = input.get("file_path").and_then(|v| v.as_str()) {
@@ -502,6 +813,28 @@ pub fn get_session_file_edits(project_path: &str, session_id: &str) -> Vec<FileE
                         }
                     }
                 }
+                "MultiEdit" => {
+                    if let Some(file_path) = input.get("file_path").and_then(|v| v.as_str()) {
+                        let rel_path = make_relative_path(file_path, project_path);
+
+                        // Any sub-edit with old_string content indicates an existing file
+                        let has_prior_content = multi_edits(input).iter().any(|edit| {
+                            edit.get("old_string")
+                                .and_then(|v| v.as_str())
+                                .is_some_and(|s| !s.is_empty())
+                        });
+                        if has_prior_content {
+                            files_with_prior_content.insert(rel_path.clone());
+                        }
+
+                        file_operations.insert(rel_path.clone(), FileEditType::Modified);
+
+                        // Track timestamp (always update to get the latest)
+                        if let Some(ts) = timestamp {
+                            file_timestamps.insert(rel_path, ts);
+                        }
+                    }
+                }
                 "Write" => {
                     if let Some(file_path) = input.get("file_path").and_then(|v| v.as_str()) {
                         let rel_path = make_relative_path(file_path, project_path);
@@ -518,6 +851,35 @@ pub fn get_session_file_edits(project_path: &str, session_id: &str) -> Vec<FileE
                         }
                     }
                 }
+                "NotebookEdit" => {
+                    if let Some(notebook_path) = input.get("notebook_path").and_then(|v| v.as_str())
+                    {
+                        let rel_path = make_relative_path(notebook_path, project_path);
+
+                        // NotebookEdit only works on existing notebooks
+                        files_with_prior_content.insert(rel_path.clone());
+                        file_operations.insert(rel_path.clone(), FileEditType::Modified);
+
+                        // Track timestamp (always update to get the latest)
+                        if let Some(ts) = timestamp {
+                            file_timestamps.insert(rel_path, ts);
+                        }
+                    }
+                }
+                "Bash" => {
+                    for (source, dest) in bash_renames(input, project_path) {
+                        apply_rename(
+                            &source,
+                            &dest,
+                            timestamp.as_deref(),
+                            &mut file_operations,
+                            &mut files_with_prior_content,
+                            &mut file_timestamps,
+                            &mut file_stats,
+                            &mut renamed_from,
+                        );
+                    }
+                }
                 // TODO: Could track file deletions via Bash rm commands
                 _ => {}
             }
@@ -534,10 +896,18 @@ pub fn get_session_file_edits(project_path: &str, session_id: &str) -> Vec<FileE
                 edit_type = FileEditType::Added;
             }
             let last_edited_at = file_timestamps.get(&path).cloned();
+            let stats = file_stats.get(&path).copied().unwrap_or_default();
+            let renamed_from = renamed_from.get(&path).cloned();
             FileEdit {
+                edit_type: renamed_edit_type(edit_type, renamed_from.as_deref(), stats.edit_count),
                 path,
-                edit_type,
                 last_edited_at,
+                edited_by: None,
+                lines_added: stats.lines_added,
+                lines_removed: stats.lines_removed,
+                edit_count: stats.edit_count,
+                renamed_from,
+                is_generated: stats.generated,
             }
         })
         .collect();
@@ -547,22 +917,136 @@ pub fn get_session_file_edits(project_path: &str, session_id: &str) -> Vec<FileE
     edits
 }
 
-/// Get all diffs for a specific file in a session.
+/// How a recorded file operation changes the file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FileOperationKind {
+    /// Replaces the whole file
+    Write,
+    /// Replaces `old_string` once (or everywhere when `replace_all` is set)
+    Edit { replace_all: bool },
+    /// Changes a notebook cell (not replayable as text)
+    NotebookCell,
+}
+
+/// A file-changing tool call together with its diff.
+struct FileOperation {
+    kind: FileOperationKind,
+    diff: FileDiff,
+}
+
+/// Get all diffs for a specific file in a session, including its sub-agents' edits.
+///
+/// Each diff is checked against the file on disk to report whether it still applies
+/// (old text present) or reverts (new text present) cleanly.
 pub fn get_file_diffs(project_path: &str, session_id: &str, file_path: &str) -> Vec<FileDiff> {
-    let session_file = match get_session_file_path(project_path, session_id) {
-        Some(p) => p,
-        None => return Vec::new(),
-    };
+    match get_session_file_path(project_path, session_id) {
+        Some(session_file) => session_file_diffs(&session_file, project_path, file_path),
+        None => Vec::new(),
+    }
+}
+
+/// Get all diffs for a file from a session file and its sub-agents' transcripts.
+fn session_file_diffs(session_file: &Path, project_path: &str, file_path: &str) -> Vec<FileDiff> {
+    let disk_content = read_disk_content(&resolve_disk_path(project_path, file_path)).ok();
+    let operations = collect_file_operations(session_file, project_path, file_path);
+    let befores = contents_before_writes(session_file, project_path, file_path, &operations);
+
+    operations
+        .into_iter()
+        .zip(befores)
+        .map(|(op, before)| {
+            let (applies_cleanly, reverts_cleanly) = match &disk_content {
+                Some(content) => disk_compatibility(content, &op, before.as_deref()),
+                None => (None, None),
+            };
+            FileDiff {
+                applies_cleanly,
+                reverts_cleanly,
+                ..op.diff
+            }
+        })
+        .collect()
+}
+
+/// Check whether an operation would apply and revert cleanly on some content.
+///
+/// `before` is the content a Write replaced, if known (see `contents_before_writes`).
+fn disk_compatibility(
+    content: &str,
+    op: &FileOperation,
+    before: Option<&str>,
+) -> (Option<bool>, Option<bool>) {
+    match op.kind {
+        FileOperationKind::NotebookCell => (None, None),
+        FileOperationKind::Write => (
+            Some(check_write(content, op, before).is_ok()),
+            Some(content == op.diff.new_string),
+        ),
+        FileOperationKind::Edit { .. } => (
+            Some(apply_file_operation(content, op).is_ok()),
+            Some(revert_file_operation(content, op).is_ok()),
+        ),
+    }
+}
+
+/// Collect the file-changing tool calls for a file across a session and its sub-agents.
+///
+/// Operations are numbered in timestamp order, with sub-agent ones tagged `edited_by`.
+/// MultiEdit calls contribute one operation per sub-edit.
+fn collect_file_operations(
+    session_file: &Path,
+    project_path: &str,
+    file_path: &str,
+) -> Vec<FileOperation> {
+    let target_path = make_relative_path(file_path, project_path);
+    let mut operations = transcript_file_operations(session_file, project_path, &target_path, None);
+
+    let main_count = operations.len();
+    for (agent_id, agent_file) in subagent_transcripts(session_file) {
+        operations.extend(transcript_file_operations(
+            &agent_file,
+            project_path,
+            &target_path,
+            Some(&agent_id),
+        ));
+    }
+    if operations.len() > main_count {
+        // Stable, so operations with equal timestamps keep their transcript order
+        operations.sort_by(|a, b| a.diff.timestamp.cmp(&b.diff.timestamp));
+    }
+    for (sequence, op) in operations.iter_mut().enumerate() {
+        op.diff.sequence = sequence as u32;
+    }
+
+    // Detect once per file; a Write's content supplies the shebang for extensionless scripts
+    let first_write = operations
+        .iter()
+        .find(|op| op.kind == FileOperationKind::Write)
+        .map(|op| op.diff.new_string.as_str());
+    let language = detect_language(&target_path, first_write).to_string();
+    for op in &mut operations {
+        op.diff.language = language.clone();
+    }
+
+    operations
+}
 
-    let file = match File::open(&session_file) {
+/// Collect the file-changing tool calls for a file recorded in one transcript.
+///
+/// Sequence numbers and languages are left for `collect_file_operations` to fill in.
+fn transcript_file_operations(
+    transcript: &Path,
+    project_path: &str,
+    target_path: &str,
+    edited_by: Option<&str>,
+) -> Vec<FileOperation> {
+    let file = match File::open(transcript) {
         Ok(f) => f,
         Err(_) => return Vec::new(),
     };
 
     let reader = BufReader::new(file);
-    let target_path = make_relative_path(file_path, project_path);
-    let mut diffs: Vec<FileDiff> = Vec::new();
-    let mut sequence: u32 = 0;
+    let mut operations: Vec<FileOperation> = Vec::new();
 
     for line in reader.lines() {
         let line = match line {
@@ -604,7 +1088,7 @@ pub fn get_file_diffs(project_path: &str, session_id: &str, file_path: &str) ->
                 None => continue,
             };
 
-            let entry_path = match input.get("file_path").and_then(|v| v.as_str()) {
+            let entry_path = match edit_target_path(input) {
                 Some(p) => make_relative_path(p, project_path),
                 None => continue,
             };
@@ -614,1220 +1098,3947 @@ pub fn get_file_diffs(project_path: &str, session_id: &str, file_path: &str) ->
             }
 
             let timestamp = entry.timestamp.clone();
+            let mut push = |kind: FileOperationKind,
+                            old_string: String,
+                            new_string: String,
+                            cell: Option<(Option<String>, String)>| {
+                let (cell_id, cell_edit_mode) = match cell {
+                    Some((id, mode)) => (id, Some(mode)),
+                    None => (None, None),
+                };
+                operations.push(FileOperation {
+                    kind,
+                    diff: FileDiff {
+                        old_string,
+                        new_string,
+                        sequence: 0,
+                        timestamp: timestamp.clone(),
+                        edited_by: edited_by.map(String::from),
+                        cell_id,
+                        cell_edit_mode,
+                        language: String::new(),
+                        applies_cleanly: None,
+                        reverts_cleanly: None,
+                    },
+                });
+            };
 
             match tool_name {
                 "Edit" => {
-                    let old_string = input
-                        .get("old_string")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("")
-                        .to_string();
-                    let new_string = input
-                        .get("new_string")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("")
-                        .to_string();
-
-                    diffs.push(FileDiff {
-                        old_string,
-                        new_string,
-                        sequence,
-                        timestamp,
-                    });
-                    sequence += 1;
+                    push(
+                        FileOperationKind::Edit {
+                            replace_all: json_bool(input, "replace_all"),
+                        },
+                        json_string(input, "old_string"),
+                        json_string(input, "new_string"),
+                        None,
+                    );
+                }
+                "MultiEdit" => {
+                    for edit in multi_edits(input) {
+                        push(
+                            FileOperationKind::Edit {
+                                replace_all: json_bool(edit, "replace_all"),
+                            },
+                            json_string(edit, "old_string"),
+                            json_string(edit, "new_string"),
+                            None,
+                        );
+                    }
                 }
                 "Write" => {
-                    let content = input
-                        .get("content")
+                    push(
+                        FileOperationKind::Write,
+                        String::new(),
+                        json_string(input, "content"),
+                        None,
+                    );
+                }
+                "NotebookEdit" => {
+                    // The previous cell source isn't recorded, so only the new side is known
+                    let edit_mode = input
+                        .get("edit_mode")
                         .and_then(|v| v.as_str())
-                        .unwrap_or("")
+                        .unwrap_or("replace")
                         .to_string();
-
-                    diffs.push(FileDiff {
-                        old_string: String::new(),
-                        new_string: content,
-                        sequence,
-                        timestamp,
-                    });
-                    sequence += 1;
+                    let new_source = if edit_mode == "delete" {
+                        String::new()
+                    } else {
+                        json_string(input, "new_source")
+                    };
+                    let cell_id = input
+                        .get("cell_id")
+                        .and_then(|v| v.as_str())
+                        .map(String::from);
+
+                    push(
+                        FileOperationKind::NotebookCell,
+                        String::new(),
+                        new_source,
+                        Some((cell_id, edit_mode)),
+                    );
                 }
                 _ => {}
             }
         }
     }
 
-    diffs
+    operations
 }
 
-/// Convert an absolute file path to a relative path from the project root.
-fn make_relative_path(file_path: &str, project_path: &str) -> String {
-    // Ensure project_path ends without slash for consistent stripping
-    let project = project_path.trim_end_matches('/');
-
-    if file_path.starts_with(project) {
-        file_path
-            .strip_prefix(project)
-            .map(|p| p.trim_start_matches('/'))
-            .unwrap_or(file_path)
-            .to_string()
-    } else {
-        // If not under project, return as-is
-        file_path.to_string()
-    }
+/// Get a string field from a tool input, defaulting to empty.
+fn json_string(input: &Value, key: &str) -> String {
+    input
+        .get(key)
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string()
 }
 
-// =============================================================================
-// Session Event Log
-// =============================================================================
+/// Get a boolean field from a tool input, defaulting to false.
+fn json_bool(input: &Value, key: &str) -> bool {
+    input.get(key).and_then(|v| v.as_bool()).unwrap_or(false)
+}
 
-/// Metadata for compaction events.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct CompactMetadata {
-    /// Whether compaction was triggered automatically or manually
-    pub trigger: String,
-    /// Number of tokens before compaction
-    pub pre_tokens: u64,
+/// Get the individual edits of a MultiEdit tool input.
+pub(crate) fn multi_edits(input: &Value) -> &[Value] {
+    input
+        .get("edits")
+        .and_then(|v| v.as_array())
+        .map(|a| a.as_slice())
+        .unwrap_or(&[])
 }
 
-/// Response from get_session_events with pagination info.
+/// What a file looked like at a point in the session.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct SessionEventsResponse {
-    /// Events for the requested page
-    pub events: Vec<SessionEvent>,
-    /// Total number of events in the session
-    pub total_count: u32,
-    /// Current offset
-    pub offset: u32,
-    /// Whether there are more events after this page
-    pub has_more: bool,
+pub struct FileSnapshot {
+    /// Path of the file (relative to the project)
+    pub file_path: String,
+    /// Sequence of the last edit applied (same as `FileDiff.sequence`)
+    pub sequence: u32,
+    /// Total number of edits to the file in the session
+    pub total_edits: u32,
+    /// Timestamp of the last edit applied (ISO 8601)
+    pub timestamp: Option<String>,
+    /// Reconstructed file content
+    pub content: String,
+    /// Edits that could not be replayed (old text not found, or notebook cell edits)
+    pub skipped_edits: Vec<u32>,
 }
 
-/// A single event in the session log.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct SessionEvent {
-    /// Sequence number (line number in file, 0-indexed)
-    pub sequence: u32,
-    /// Event UUID if present
-    pub uuid: Option<String>,
-    /// Timestamp (ISO 8601)
-    pub timestamp: Option<String>,
-    /// Event type: "user", "assistant", "system", "summary"
-    pub event_type: String,
-    /// Subtype for system events (e.g., "compact_boundary")
-    pub subtype: Option<String>,
-    /// Tool name if this is a tool_use event
-    pub tool_name: Option<String>,
-    /// Preview text (truncated content for display)
-    pub preview: String,
-    /// Byte offset in file for on-demand raw JSON loading
-    pub byte_offset: u64,
-    /// Compaction metadata (only for compact_boundary events)
-    pub compact_metadata: Option<CompactMetadata>,
-    /// Summary text (for summary events)
-    pub summary: Option<String>,
-    /// Logical parent UUID (for linking compaction to summary)
-    pub logical_parent_uuid: Option<String>,
-    /// Leaf UUID (for summary events)
-    pub leaf_uuid: Option<String>,
-    /// Agent ID if this event is a sub-agent launch result (from Task tool)
-    pub launched_agent_id: Option<String>,
-    /// Description of the sub-agent task (from Task tool)
-    pub launched_agent_description: Option<String>,
-    /// Full prompt given to the sub-agent
-    pub launched_agent_prompt: Option<String>,
-    /// Whether the sub-agent is running async
-    pub launched_agent_is_async: Option<bool>,
-    /// Status of the sub-agent launch
-    pub launched_agent_status: Option<String>,
-    /// User type: "external" for actual human input, None or other for system-injected
-    pub user_type: Option<String>,
-    /// Whether this is a compact summary (context continuation)
-    pub is_compact_summary: Option<bool>,
-    /// Whether this is a tool result (message.content is array with tool_result)
-    pub is_tool_result: bool,
-    /// Whether this is a meta/context injection (isMeta: true)
-    pub is_meta: bool,
-}
+/// Reconstruct a file's content after a given edit by replaying Write/Edit/MultiEdit calls.
+///
+/// `sequence` defaults to the last edit. When the session doesn't start with a Write,
+/// the starting content comes from the `originalFile` Claude Code records on edit results.
+pub fn get_file_snapshot(
+    project_path: &str,
+    session_id: &str,
+    file_path: &str,
+    sequence: Option<u32>,
+) -> Result<FileSnapshot, String> {
+    let session_file = get_session_file_path(project_path, session_id)
+        .ok_or_else(|| format!("Session file not found for {}", session_id))?;
+    let operations = collect_file_operations(&session_file, project_path, file_path);
+    let last = match operations.last() {
+        Some(op) => op.diff.sequence,
+        None => return Err(format!("No edits found for {}", file_path)),
+    };
+    let sequence = sequence.unwrap_or(last);
+    if sequence > last {
+        return Err(format!(
+            "Edit {} out of range ({} edits)",
+            sequence,
+            operations.len()
+        ));
+    }
 
-/// Internal struct for parsing JSONL entries for event log.
-#[derive(Deserialize)]
-struct JsonlEventEntry {
-    #[serde(rename = "type")]
-    entry_type: Option<String>,
-    subtype: Option<String>,
-    uuid: Option<String>,
-    timestamp: Option<String>,
-    message: Option<JsonlEventMessage>,
-    content: Option<String>,
-    summary: Option<String>,
-    #[serde(rename = "logicalParentUuid")]
-    logical_parent_uuid: Option<String>,
-    #[serde(rename = "leafUuid")]
-    leaf_uuid: Option<String>,
-    #[serde(rename = "compactMetadata")]
-    compact_metadata: Option<JsonlCompactMetadata>,
-    /// Tool use result (contains agentId for Task tool results)
-    #[serde(rename = "toolUseResult")]
-    tool_use_result: Option<JsonlToolUseResult>,
-    /// User type: "external" for actual human input, other values for system-injected
-    #[serde(rename = "userType")]
-    user_type: Option<String>,
-    /// Whether this is a compact summary (system-injected context)
-    #[serde(rename = "isCompactSummary")]
-    is_compact_summary: Option<bool>,
-    /// Whether this is a meta/context injection
-    #[serde(rename = "isMeta")]
-    is_meta: Option<bool>,
-}
+    let applied = &operations[..=sequence as usize];
+    let base = if applied[0].kind == FileOperationKind::Write {
+        None
+    } else {
+        let target_path = make_relative_path(file_path, project_path);
+        Some(
+            find_original_file_content(&session_file, project_path, &target_path)
+                .ok_or_else(|| format!("Original content of {} is not recorded", file_path))?,
+        )
+    };
 
-#[derive(Deserialize)]
-struct JsonlToolUseResult {
-    #[serde(rename = "agentId")]
-    agent_id: Option<String>,
-    /// Short description of the sub-agent task
-    description: Option<String>,
-    /// The full prompt given to the sub-agent
-    prompt: Option<String>,
-    /// Whether the agent is running async
-    #[serde(rename = "isAsync")]
-    is_async: Option<bool>,
-    /// Status of the agent launch
-    status: Option<String>,
+    let (content, skipped_edits) = replay_file_operations(base.unwrap_or_default(), applied);
+
+    Ok(FileSnapshot {
+        file_path: make_relative_path(file_path, project_path),
+        sequence,
+        total_edits: operations.len() as u32,
+        timestamp: applied[sequence as usize].diff.timestamp.clone(),
+        content,
+        skipped_edits,
+    })
 }
 
-#[derive(Deserialize)]
-struct JsonlEventMessage {
-    content: Option<Value>,
+/// A file's content at the start and end of a session.
+#[derive(Debug, Clone)]
+pub struct SessionFileContents {
+    /// Content before the session's first edit (None if the session created the file)
+    pub before: Option<String>,
+    /// Content after the session's last edit
+    pub after: String,
+    /// Edits that could not be replayed
+    pub skipped_edits: Vec<u32>,
 }
 
-#[derive(Deserialize)]
-struct JsonlCompactMetadata {
-    trigger: Option<String>,
-    #[serde(rename = "preTokens")]
-    pre_tokens: Option<u64>,
+/// Reconstruct a file's content before and after all of a session's edits.
+///
+/// Edits made by the session's sub-agents are included.
+pub fn get_session_file_contents(
+    project_path: &str,
+    session_id: &str,
+    file_path: &str,
+) -> Result<SessionFileContents, String> {
+    let session_file = get_session_file_path(project_path, session_id)
+        .ok_or_else(|| format!("Session file not found for {}", session_id))?;
+    let operations = collect_file_operations(&session_file, project_path, file_path);
+    let first = operations
+        .first()
+        .ok_or_else(|| format!("No edits found for {}", file_path))?;
+
+    let target_path = make_relative_path(file_path, project_path);
+    let before = match find_original_file_content(&session_file, project_path, &target_path) {
+        Some(content) => Some(content),
+        None if first.kind == FileOperationKind::Write => None,
+        None => return Err(format!("Original content of {} is not recorded", file_path)),
+    };
+
+    let (after, skipped_edits) =
+        replay_file_operations(before.clone().unwrap_or_default(), &operations);
+
+    Ok(SessionFileContents {
+        before,
+        after,
+        skipped_edits,
+    })
 }
 
-/// Extract a preview from message content.
-fn extract_preview_from_content(content: &Value) -> String {
-    match content {
-        Value::String(s) => truncate_string(s, 500),
-        Value::Array(arr) => {
-            // Look for text content first, then thinking, then tool_use
-            for item in arr {
-                if let Some(obj) = item.as_object() {
-                    // Check for text type
-                    if obj.get("type").and_then(|t| t.as_str()) == Some("text") {
-                        if let Some(text) = obj.get("text").and_then(|t| t.as_str()) {
-                            return truncate_string(text, 500);
-                        }
-                    }
-                }
-            }
-            // Check for thinking type (extended thinking)
-            for item in arr {
-                if let Some(obj) = item.as_object() {
-                    if obj.get("type").and_then(|t| t.as_str()) == Some("thinking") {
-                        if let Some(thinking) = obj.get("thinking").and_then(|t| t.as_str()) {
-                            return truncate_string(thinking, 500);
-                        }
-                    }
-                }
-            }
-            // Check for tool_use - return tool name
-            for item in arr {
-                if let Some(obj) = item.as_object() {
-                    if obj.get("type").and_then(|t| t.as_str()) == Some("tool_use") {
-                        if let Some(name) = obj.get("name").and_then(|n| n.as_str()) {
-                            return format!("[Tool: {}]", name);
-                        }
-                    }
-                    // Check for tool_result
-                    if obj.get("type").and_then(|t| t.as_str()) == Some("tool_result") {
-                        if let Some(content) = obj.get("content").and_then(|c| c.as_str()) {
-                            return truncate_string(content, 500);
-                        }
-                    }
-                }
-            }
-            // Fallback: stringify first item
-            arr.first()
-                .map(|v| truncate_string(&v.to_string(), 500))
-                .unwrap_or_default()
+/// Apply file operations to a starting content, returning the result and skipped edits.
+fn replay_file_operations(base: String, operations: &[FileOperation]) -> (String, Vec<u32>) {
+    let mut content = base;
+    let mut skipped = Vec::new();
+
+    for op in operations {
+        match apply_file_operation(&content, op) {
+            Ok(next) => content = next,
+            Err(_) => skipped.push(op.diff.sequence),
         }
-        _ => truncate_string(&content.to_string(), 500),
     }
+
+    (content, skipped)
 }
 
-/// Check if message content is a tool_result (array containing tool_result items).
-fn is_tool_result_content(content: &Value) -> bool {
-    if let Value::Array(arr) = content {
-        arr.iter().any(|item| {
-            item.as_object()
-                .and_then(|obj| obj.get("type"))
-                .and_then(|t| t.as_str())
-                == Some("tool_result")
+/// Reconstruct the content each Write replaced, aligned with `operations`.
+///
+/// Entries are None for other operations, and for Writes whose earlier content is unknown
+/// because the file's original content wasn't recorded. A session that starts with a Write
+/// without a recorded original is treated as creating the file.
+fn contents_before_writes(
+    session_file: &Path,
+    project_path: &str,
+    file_path: &str,
+    operations: &[FileOperation],
+) -> Vec<Option<String>> {
+    let target_path = make_relative_path(file_path, project_path);
+    let original = find_original_file_content(session_file, project_path, &target_path);
+    let starts_with_write = operations
+        .first()
+        .is_some_and(|op| op.kind == FileOperationKind::Write);
+    let base = if starts_with_write {
+        Some(original.unwrap_or_default())
+    } else {
+        original
+    };
+
+    replay_contents_before_writes(base, operations)
+}
+
+/// Replay operations from a (possibly unknown) base, keeping the content before each Write.
+fn replay_contents_before_writes(
+    base: Option<String>,
+    operations: &[FileOperation],
+) -> Vec<Option<String>> {
+    let mut content = base;
+    operations
+        .iter()
+        .map(|op| {
+            let before = match op.kind {
+                FileOperationKind::Write => content.clone(),
+                _ => None,
+            };
+            content = match op.kind {
+                FileOperationKind::Write => Some(op.diff.new_string.clone()),
+                // Skipped edits leave the content as it was, as in replay_file_operations
+                _ => content.map(|c| apply_file_operation(&c, op).unwrap_or(c)),
+            };
+            before
         })
+        .collect()
+}
+
+/// Check that a Write can be applied without clobbering changes made since it was recorded.
+///
+/// The file must still hold the content the Write replaced, or what it wrote.
+fn check_write(content: &str, op: &FileOperation, before: Option<&str>) -> Result<(), String> {
+    if content == op.diff.new_string || before == Some(content) {
+        Ok(())
     } else {
-        false
+        Err("File has changed since this Write was recorded".to_string())
     }
 }
 
-/// Extract tool names and content types from message content.
-fn extract_tool_names(content: &Value) -> Option<String> {
-    if let Value::Array(arr) = content {
-        let mut labels: Vec<String> = Vec::new();
-
-        // Check for thinking blocks
-        let has_thinking = arr.iter().any(|item| {
-            item.as_object()
-                .and_then(|obj| obj.get("type"))
-                .and_then(|t| t.as_str())
-                == Some("thinking")
-        });
-        if has_thinking {
-            labels.push("thinking".to_string());
+/// Whether an Edit's result is already in some content.
+///
+/// `old_string` must be gone and `new_string` present exactly once, so text that merely
+/// happens to contain `new_string` isn't mistaken for the edit.
+fn edit_already_applied(content: &str, op: &FileOperation) -> bool {
+    let diff = &op.diff;
+    match op.kind {
+        // An empty old_string creates the file
+        FileOperationKind::Edit { .. } if diff.old_string.is_empty() => {
+            !content.is_empty() && content == diff.new_string
+        }
+        FileOperationKind::Edit { .. } => {
+            !diff.new_string.is_empty()
+                && !content.contains(&diff.old_string)
+                && content.matches(&diff.new_string).count() == 1
         }
+        _ => false,
+    }
+}
 
-        // Collect tool names
-        for item in arr {
-            if let Some(obj) = item.as_object() {
-                if obj.get("type").and_then(|t| t.as_str()) == Some("tool_use") {
-                    if let Some(name) = obj.get("name").and_then(|n| n.as_str()) {
-                        labels.push(name.to_string());
-                    }
-                }
+/// Apply one file operation to some content, or explain why it doesn't apply.
+fn apply_file_operation(content: &str, op: &FileOperation) -> Result<String, String> {
+    let diff = &op.diff;
+    match op.kind {
+        FileOperationKind::Write => Ok(diff.new_string.clone()),
+        // An empty old_string creates the file
+        FileOperationKind::Edit { .. } if diff.old_string.is_empty() => {
+            if content.is_empty() {
+                Ok(diff.new_string.clone())
+            } else {
+                Err("File already has content".to_string())
             }
         }
-
-        if !labels.is_empty() {
-            return Some(labels.join(", "));
+        FileOperationKind::Edit { replace_all } => {
+            if !content.contains(&diff.old_string) {
+                Err("Current content no longer contains old_string".to_string())
+            } else if replace_all {
+                Ok(content.replace(&diff.old_string, &diff.new_string))
+            } else {
+                Ok(content.replacen(&diff.old_string, &diff.new_string, 1))
+            }
         }
+        FileOperationKind::NotebookCell => Err("Notebook cell edits can't be applied".to_string()),
     }
-    None
 }
 
-/// Truncate string to max length with ellipsis (UTF-8 safe).
-fn truncate_string(s: &str, max_chars: usize) -> String {
-    let char_count = s.chars().count();
-    if char_count <= max_chars {
-        s.to_string()
-    } else {
-        let truncated: String = s.chars().take(max_chars).collect();
-        format!("{}...", truncated)
-    }
-}
+// =============================================================================
+// Applying Session Edits
+// =============================================================================
 
-/// Build an index of line byte offsets for a file.
-/// Returns Vec of (byte_offset, line_length) for each line.
-fn build_line_index(file: &mut File) -> std::io::Result<Vec<(u64, usize)>> {
-    use std::io::{BufRead, Seek, SeekFrom};
+/// An edit that could not be applied to the working tree.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditConflict {
+    /// Sequence of the conflicting edit (same as `FileDiff.sequence`)
+    pub sequence: u32,
+    /// Why the edit doesn't apply
+    pub reason: String,
+}
 
-    file.seek(SeekFrom::Start(0))?;
-    let mut reader = BufReader::new(file);
-    let mut index = Vec::new();
-    let mut offset: u64 = 0;
-    let mut line = String::new();
+/// Result of applying or reverting session edits on a file on disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyEditResult {
+    /// Absolute path of the file on disk
+    pub file_path: String,
+    /// Edits applied (or reverted) on the file
+    pub applied: Vec<u32>,
+    /// Edits already in the requested state (nothing to do)
+    pub already_applied: Vec<u32>,
+    /// Edits that conflict with the current content
+    pub conflicts: Vec<EditConflict>,
+    /// Whether the file was written (false if there were conflicts or nothing changed)
+    pub written: bool,
+}
 
-    loop {
-        line.clear();
-        let bytes_read = reader.read_line(&mut line)?;
-        if bytes_read == 0 {
-            break;
-        }
-        index.push((offset, bytes_read));
-        offset += bytes_read as u64;
+/// Apply a session edit (or all edits for a file when `sequence` is None) to the file on disk.
+///
+/// Nothing is written if any edit conflicts with the current content. A Write conflicts
+/// unless the file still holds the content it replaced or what it wrote.
+pub fn apply_session_edit(
+    project_path: &str,
+    session_id: &str,
+    file_path: &str,
+    sequence: Option<u32>,
+) -> Result<ApplyEditResult, String> {
+    let session_file = get_session_file_path(project_path, session_id)
+        .ok_or_else(|| format!("Session file not found for {}", session_id))?;
+    let operations = collect_file_operations(&session_file, project_path, file_path);
+    let befores = contents_before_writes(&session_file, project_path, file_path, &operations);
+    let selected: Vec<(&FileOperation, Option<&str>)> = operations
+        .iter()
+        .zip(&befores)
+        .filter(|(op, _)| sequence.is_none_or(|seq| op.diff.sequence == seq))
+        .map(|(op, before)| (op, before.as_deref()))
+        .collect();
+    if selected.is_empty() {
+        return Err(match sequence {
+            Some(seq) => format!("Edit {} not found for {}", seq, file_path),
+            None => format!("No edits found for {}", file_path),
+        });
     }
 
-    Ok(index)
-}
-
-/// Read a specific line from a file given its byte offset and length.
-fn read_line_at_offset(file: &mut File, offset: u64, length: usize) -> std::io::Result<String> {
-    use std::io::{Read, Seek, SeekFrom};
+    let disk_path = resolve_disk_path(project_path, file_path);
+    let original = read_disk_content(&disk_path)?;
 
-    file.seek(SeekFrom::Start(offset))?;
-    let mut buffer = vec![0u8; length];
-    file.read_exact(&mut buffer)?;
+    let mut result = ApplyEditResult {
+        file_path: disk_path.to_string_lossy().to_string(),
+        applied: Vec::new(),
+        already_applied: Vec::new(),
+        conflicts: Vec::new(),
+        written: false,
+    };
+    let content = apply_operations(original.clone(), &selected, &mut result);
 
-    // Remove trailing newline
-    if buffer.last() == Some(&b'\n') {
-        buffer.pop();
-    }
-    if buffer.last() == Some(&b'\r') {
-        buffer.pop();
+    if result.conflicts.is_empty() && content != original {
+        write_disk_content(&disk_path, &content)?;
+        result.written = true;
     }
 
-    String::from_utf8(buffer).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    Ok(result)
 }
 
-/// Parse a single JSONL line into a SessionEvent.
-pub fn parse_session_event(line: &str, sequence: u32, byte_offset: u64) -> Option<SessionEvent> {
-    let entry: JsonlEventEntry = serde_json::from_str(line).ok()?;
-
-    let event_type = entry.entry_type.clone().unwrap_or_else(|| "unknown".to_string());
-
-    // Extract preview based on event type
-    let preview = match event_type.as_str() {
-        "user" | "assistant" => {
-            if let Some(ref msg) = entry.message {
-                if let Some(ref content) = msg.content {
-                    extract_preview_from_content(content)
-                } else {
-                    String::new()
-                }
-            } else {
-                String::new()
+/// Apply operations in order, recording each outcome, and return the resulting content.
+///
+/// Each operation is paired with the content it replaced when it's a Write.
+fn apply_operations(
+    mut content: String,
+    operations: &[(&FileOperation, Option<&str>)],
+    result: &mut ApplyEditResult,
+) -> String {
+    for &(op, before) in operations {
+        let sequence = op.diff.sequence;
+        let outcome = match op.kind {
+            FileOperationKind::Write if content == op.diff.new_string => {
+                result.already_applied.push(sequence);
+                continue;
+            }
+            FileOperationKind::Write => {
+                check_write(&content, op, before).map(|()| op.diff.new_string.clone())
+            }
+            _ => apply_file_operation(&content, op),
+        };
+        match outcome {
+            Ok(next) => {
+                content = next;
+                result.applied.push(sequence);
+            }
+            Err(_) if edit_already_applied(&content, op) => {
+                result.already_applied.push(sequence);
             }
+            Err(reason) => result.conflicts.push(EditConflict { sequence, reason }),
         }
-        "system" => entry.content.clone().unwrap_or_default(),
-        "summary" => entry.summary.clone().unwrap_or_default(),
-        _ => String::new(),
+    }
+
+    content
+}
+
+/// Revert a single session edit in the working tree by swapping `new_string` back to `old_string`.
+///
+/// A Write is reverted to the file's reconstructed content before it, and only if the
+/// file still holds exactly what was written.
+pub fn revert_session_edit(
+    project_path: &str,
+    session_id: &str,
+    file_path: &str,
+    sequence: u32,
+) -> Result<ApplyEditResult, String> {
+    let session_file = get_session_file_path(project_path, session_id)
+        .ok_or_else(|| format!("Session file not found for {}", session_id))?;
+    let op = collect_file_operations(&session_file, project_path, file_path)
+        .into_iter()
+        .find(|op| op.diff.sequence == sequence)
+        .ok_or_else(|| format!("Edit {} not found for {}", sequence, file_path))?;
+
+    let disk_path = resolve_disk_path(project_path, file_path);
+    let current = read_disk_content(&disk_path)?;
+    let mut result = ApplyEditResult {
+        file_path: disk_path.to_string_lossy().to_string(),
+        applied: Vec::new(),
+        already_applied: Vec::new(),
+        conflicts: Vec::new(),
+        written: false,
     };
 
-    // Extract tool names for assistant messages
-    let tool_name = if event_type == "assistant" {
-        entry
-            .message
-            .as_ref()
-            .and_then(|m| m.content.as_ref())
-            .and_then(extract_tool_names)
-    } else {
-        None
+    let reverted = match op.kind {
+        FileOperationKind::Write if sequence == 0 => {
+            Err("No earlier content to restore before this Write".to_string())
+        }
+        FileOperationKind::Write if current != op.diff.new_string => {
+            Err("File has changed since it was written".to_string())
+        }
+        FileOperationKind::Write => {
+            get_file_snapshot(project_path, session_id, file_path, Some(sequence - 1))
+                .map(|snapshot| snapshot.content)
+        }
+        _ => revert_file_operation(&current, &op),
     };
 
-    // Extract compact metadata if present
-    let compact_metadata = entry.compact_metadata.as_ref().map(|cm| CompactMetadata {
-        trigger: cm.trigger.clone().unwrap_or_else(|| "unknown".to_string()),
-        pre_tokens: cm.pre_tokens.unwrap_or(0),
-    });
+    match reverted {
+        Ok(content) => {
+            result.applied.push(sequence);
+            if content != current {
+                write_disk_content(&disk_path, &content)?;
+                result.written = true;
+            }
+        }
+        Err(_)
+            if op.kind != FileOperationKind::Write
+                && !op.diff.old_string.is_empty()
+                && current.contains(&op.diff.old_string)
+                && !current.contains(&op.diff.new_string) =>
+        {
+            result.already_applied.push(sequence);
+        }
+        Err(reason) => result.conflicts.push(EditConflict { sequence, reason }),
+    }
 
-    // Extract launched agent data from tool_use_result
-    // Both sync and async Task completions include agentId in toolUseResult
-    // - Async launch: { agentId, isAsync: true, status: "async_launched", description }
-    // - Sync/Async completion: { agentId, status: "completed", prompt, content, ... }
-    let tool_result = entry.tool_use_result.as_ref();
-    let launched_agent_id = tool_result.and_then(|r| r.agent_id.clone());
-    let launched_agent_description = tool_result.and_then(|r| r.description.clone());
-    let launched_agent_prompt = tool_result.and_then(|r| r.prompt.clone());
-    let launched_agent_is_async = tool_result.and_then(|r| r.is_async);
-    let launched_agent_status = tool_result.and_then(|r| r.status.clone());
+    Ok(result)
+}
 
-    // Detect if this is a tool_result message (message.content is array with tool_result)
-    let is_tool_result = entry
-        .message
-        .as_ref()
-        .and_then(|m| m.content.as_ref())
-        .map(is_tool_result_content)
-        .unwrap_or(false);
+/// Undo one Edit on some content, or explain why it can't be undone.
+fn revert_file_operation(content: &str, op: &FileOperation) -> Result<String, String> {
+    let diff = &op.diff;
+    match op.kind {
+        FileOperationKind::Edit { .. } if diff.new_string.is_empty() => {
+            Err("Deleted text has no anchor to restore it at".to_string())
+        }
+        FileOperationKind::Edit { replace_all } => {
+            let matches = content.matches(&diff.new_string).count();
+            if matches == 0 {
+                Err("Current content no longer contains new_string".to_string())
+            } else if replace_all {
+                Ok(content.replace(&diff.new_string, &diff.old_string))
+            } else if matches > 1 {
+                Err(format!("new_string appears {} times", matches))
+            } else {
+                Ok(content.replacen(&diff.new_string, &diff.old_string, 1))
+            }
+        }
+        FileOperationKind::Write => Err("Writes are reverted from a snapshot".to_string()),
+        FileOperationKind::NotebookCell => Err("Notebook cell edits can't be reverted".to_string()),
+    }
+}
 
-    // isMeta indicates context injection
-    let is_meta = entry.is_meta.unwrap_or(false);
+/// Resolve a (possibly project-relative) file path to its location on disk.
+fn resolve_disk_path(project_path: &str, file_path: &str) -> PathBuf {
+    if Path::new(file_path).is_absolute() {
+        PathBuf::from(file_path)
+    } else {
+        Path::new(project_path).join(file_path)
+    }
+}
 
-    Some(SessionEvent {
-        sequence,
-        uuid: entry.uuid,
-        timestamp: entry.timestamp,
-        event_type,
-        subtype: entry.subtype,
-        tool_name,
-        preview,
-        byte_offset,
-        compact_metadata,
-        summary: entry.summary,
-        logical_parent_uuid: entry.logical_parent_uuid,
-        leaf_uuid: entry.leaf_uuid,
-        launched_agent_id,
-        launched_agent_description,
-        launched_agent_prompt,
-        launched_agent_is_async,
-        launched_agent_status,
-        user_type: entry.user_type,
-        is_compact_summary: entry.is_compact_summary,
-        is_tool_result,
-        is_meta,
-    })
+/// Read a file's current content, treating a missing file as empty.
+fn read_disk_content(path: &Path) -> Result<String, String> {
+    match fs::read_to_string(path) {
+        Ok(c) => Ok(c),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+    }
 }
 
-/// Get paginated events from a session for the log viewer.
-/// Events are returned in descending order (newest first).
+/// Write a file, creating parent directories as needed.
+fn write_disk_content(path: &Path, content: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Find the content a file had before its first edit in the session or its sub-agents.
 ///
-/// Parameters:
-/// - offset: Number of events to skip from the newest (default 0)
-/// - limit: Maximum events to return (default 200)
-pub fn get_session_events(
+/// Edit results carry `toolUseResult.originalFile` (`originalFileContents` for MultiEdit).
+/// Returns None if the file's first result is a Write that created it.
+fn find_original_file_content(
+    session_file: &Path,
     project_path: &str,
-    session_id: &str,
-    offset: Option<u32>,
-    limit: Option<u32>,
-) -> SessionEventsResponse {
-    let empty_response = SessionEventsResponse {
-        events: Vec::new(),
-        total_count: 0,
-        offset: 0,
-        has_more: false,
-    };
+    target_path: &str,
+) -> Option<String> {
+    let agent_files = subagent_transcripts(session_file)
+        .into_iter()
+        .map(|(_, agent_file)| agent_file);
+    std::iter::once(session_file.to_path_buf())
+        .chain(agent_files)
+        .filter_map(|transcript| find_original_file_record(&transcript, project_path, target_path))
+        .min_by(|a, b| a.timestamp.cmp(&b.timestamp))
+        .and_then(|record| record.content)
+}
 
-    let session_file = match get_session_file_path(project_path, session_id) {
-        Some(p) => p,
-        None => return empty_response,
-    };
+/// The first edit result recorded for a file in one transcript.
+struct OriginalFileRecord {
+    /// Timestamp of the result (ISO 8601)
+    timestamp: Option<String>,
+    /// Content before the edit (None if the result is a Write that created the file)
+    content: Option<String>,
+}
 
-    let mut file = match File::open(&session_file) {
-        Ok(f) => f,
-        Err(_) => return empty_response,
-    };
+/// Find the first edit result for a file in one transcript that records its original content.
+fn find_original_file_record(
+    transcript: &Path,
+    project_path: &str,
+    target_path: &str,
+) -> Option<OriginalFileRecord> {
+    let file = File::open(transcript).ok()?;
 
-    // Phase 1: Build line index (fast, no JSON parsing)
-    let line_index = match build_line_index(&mut file) {
-        Ok(idx) => idx,
-        Err(_) => return empty_response,
-    };
+    for line in BufReader::new(file).lines().map_while(Result::ok) {
+        if !line.contains("\"toolUseResult\"") || !line.contains("\"filePath\"") {
+            continue;
+        }
+        let json: Value = match serde_json::from_str(&line) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        let result = match json.get("toolUseResult") {
+            Some(r) => r,
+            None => continue,
+        };
+        let path = match result.get("filePath").and_then(|v| v.as_str()) {
+            Some(p) => make_relative_path(p, project_path),
+            None => continue,
+        };
+        if path != target_path {
+            continue;
+        }
+        let timestamp = json
+            .get("timestamp")
+            .and_then(|v| v.as_str())
+            .map(String::from);
+        if result.get("type").and_then(|v| v.as_str()) == Some("create") {
+            return Some(OriginalFileRecord {
+                timestamp,
+                content: None,
+            });
+        }
+        let original = result
+            .get("originalFile")
+            .or_else(|| result.get("originalFileContents"))
+            .and_then(|v| v.as_str());
+        if let Some(original) = original {
+            return Some(OriginalFileRecord {
+                timestamp,
+                content: Some(original.to_string()),
+            });
+        }
+    }
 
-    let total_count = line_index.len() as u32;
-    let offset = offset.unwrap_or(0);
-    let limit = limit.unwrap_or(200);
+    None
+}
 
-    // For descending order, we want the LAST lines first
-    // offset=0 means the last `limit` lines
-    // offset=100 means skip the last 100, then take `limit` lines
+/// Line and operation counts for edits to a file.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct EditStats {
+    pub lines_added: u32,
+    pub lines_removed: u32,
+    pub edit_count: u32,
+    /// Whether the file looks binary or machine-generated
+    pub generated: bool,
+}
 
-    if offset >= total_count {
-        return SessionEventsResponse {
-            events: Vec::new(),
-            total_count,
-            offset,
-            has_more: false,
-        };
+impl EditStats {
+    pub(crate) fn add(&mut self, other: EditStats) {
+        self.lines_added += other.lines_added;
+        self.lines_removed += other.lines_removed;
+        self.edit_count += other.edit_count;
+        self.generated |= other.generated;
     }
+}
 
-    // Calculate which lines to read (in original file order)
-    // For descending: newest (last in file) comes first in result
-    let available = total_count - offset;
-    let take_count = std::cmp::min(limit, available) as usize;
+/// Get the (source, destination) paths moved by a Bash tool call's `mv`/`git mv` commands.
+pub(crate) fn bash_renames(input: &Value, project_path: &str) -> Vec<(String, String)> {
+    let command = match input.get("command").and_then(|v| v.as_str()) {
+        Some(c) => c,
+        None => return Vec::new(),
+    };
+    parse_mv_commands(command)
+        .into_iter()
+        .map(|(source, dest)| {
+            (
+                make_relative_path(&source, project_path),
+                make_relative_path(&dest, project_path),
+            )
+        })
+        .collect()
+}
 
-    // Start from the end, skip `offset`, take `limit`
-    // line_index indices: 0, 1, 2, ..., total-1
-    // For offset=0, limit=3, total=10: we want lines 9, 8, 7 (indices)
-    // start_idx = total - offset - 1 = 9
-    // end_idx = total - offset - take_count = 7
+/// Get the renames made by a Bash tool_use content item.
+pub(crate) fn tool_use_renames(item: &Value, project_path: &str) -> Vec<(String, String)> {
+    let is_bash = item.get("type").and_then(|v| v.as_str()) == Some("tool_use")
+        && item.get("name").and_then(|v| v.as_str()) == Some("Bash");
+    match item.get("input") {
+        Some(input) if is_bash => bash_renames(input, project_path),
+        _ => Vec::new(),
+    }
+}
 
-    let start_idx = (total_count - offset - 1) as usize;
-    let end_idx = if take_count > start_idx + 1 { 0 } else { start_idx + 1 - take_count };
+/// Parse `mv` and `git mv` invocations in a shell command into (source, destination) pairs.
+///
+/// Moving several sources (or into a path ending in `/`) resolves each destination to
+/// `dir/basename`. Options and redirections aren't interpreted beyond being skipped.
+fn parse_mv_commands(command: &str) -> Vec<(String, String)> {
+    let mut renames = Vec::new();
+
+    for words in shell_commands(command) {
+        let args = match words
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .as_slice()
+        {
+            ["mv", rest @ ..] => rest.to_vec(),
+            ["git", "mv", rest @ ..] => rest.to_vec(),
+            _ => continue,
+        };
 
-    // Phase 2: Parse only the requested lines (in reverse order for descending)
-    let mut events = Vec::with_capacity(take_count);
+        let mut paths: Vec<&str> = Vec::new();
+        let mut options_done = false;
+        for arg in args {
+            if !options_done && arg == "--" {
+                options_done = true;
+            } else if !options_done && arg.starts_with('-') {
+                continue;
+            } else {
+                paths.push(arg);
+            }
+        }
 
-    for idx in (end_idx..=start_idx).rev() {
-        let (byte_offset, line_len) = line_index[idx];
+        let dest = match paths.pop() {
+            Some(d) if !paths.is_empty() => d,
+            _ => continue,
+        };
+        let into_dir = paths.len() > 1 || dest.ends_with('/');
+        for source in paths {
+            let target = if into_dir {
+                let name = source
+                    .trim_end_matches('/')
+                    .rsplit('/')
+                    .next()
+                    .unwrap_or(source);
+                format!("{}/{}", dest.trim_end_matches('/'), name)
+            } else {
+                dest.to_string()
+            };
+            renames.push((source.to_string(), target));
+        }
+    }
 
-        if let Ok(line) = read_line_at_offset(&mut file, byte_offset, line_len) {
-            if let Some(event) = parse_session_event(&line, idx as u32, byte_offset) {
-                events.push(event);
+    renames
+}
+
+/// Split a shell command line into simple commands, each a list of unquoted words.
+fn shell_commands(command: &str) -> Vec<Vec<String>> {
+    let mut commands: Vec<Vec<String>> = Vec::new();
+    let mut words: Vec<String> = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    let mut chars = command.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') => {
+                if let Some(next) = chars.next() {
+                    word.push(next);
+                }
+            }
+            (Some(_), c) => word.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, '\\') => {
+                if let Some(next) = chars.next() {
+                    word.push(next);
+                    in_word = true;
+                }
+            }
+            (None, ';' | '&' | '|' | '\n') => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+                if !words.is_empty() {
+                    commands.push(std::mem::take(&mut words));
+                }
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                word.push(c);
+                in_word = true;
             }
         }
     }
 
-    let has_more = (offset + take_count as u32) < total_count;
-
-    SessionEventsResponse {
-        events,
-        total_count,
-        offset,
-        has_more,
+    if in_word {
+        words.push(word);
     }
+    if !words.is_empty() {
+        commands.push(words);
+    }
+    commands
 }
 
-/// Get the raw JSON for a specific event by its byte offset.
-pub fn get_event_raw_json(project_path: &str, session_id: &str, byte_offset: u64) -> Option<String> {
-    let session_file = get_session_file_path(project_path, session_id)?;
-    let mut file = File::open(&session_file).ok()?;
-
-    use std::io::{BufRead, BufReader, Seek, SeekFrom};
-
-    file.seek(SeekFrom::Start(byte_offset)).ok()?;
-    let mut reader = BufReader::new(file);
-    let mut line = String::new();
-    reader.read_line(&mut line).ok()?;
+/// Move a file's accumulated edit state from its old path to its new one.
+///
+/// A file that wasn't edited before the move is recorded as an existing file at the
+/// new path. Files created in the session don't get a `renamed_from`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn apply_rename(
+    source: &str,
+    dest: &str,
+    timestamp: Option<&str>,
+    file_operations: &mut HashMap<String, FileEditType>,
+    files_with_prior_content: &mut HashSet<String>,
+    file_timestamps: &mut HashMap<String, String>,
+    file_stats: &mut HashMap<String, EditStats>,
+    renamed_from: &mut HashMap<String, String>,
+) {
+    if source == dest {
+        return;
+    }
 
-    // Remove trailing newline
-    if line.ends_with('\n') {
-        line.pop();
+    let edit_type = file_operations
+        .remove(source)
+        .unwrap_or(FileEditType::Modified);
+    let had_prior_content =
+        files_with_prior_content.remove(source) || !matches!(edit_type, FileEditType::Added);
+    let original = renamed_from
+        .remove(source)
+        .unwrap_or_else(|| source.to_string());
+
+    file_operations.insert(dest.to_string(), edit_type.clone());
+    if had_prior_content {
+        files_with_prior_content.insert(dest.to_string());
     }
-    if line.ends_with('\r') {
-        line.pop();
+    if edit_type != FileEditType::Added && original != dest {
+        renamed_from.insert(dest.to_string(), original);
+    }
+    if let Some(stats) = file_stats.remove(source) {
+        file_stats.insert(dest.to_string(), stats);
     }
+    file_timestamps.remove(source);
+    if let Some(ts) = timestamp {
+        file_timestamps.insert(dest.to_string(), ts.to_string());
+    }
+}
 
-    Some(line)
+/// Final edit type for a file that may have been moved: moves without edits are renames.
+pub(crate) fn renamed_edit_type(
+    edit_type: FileEditType,
+    renamed_from: Option<&str>,
+    edit_count: u32,
+) -> FileEditType {
+    match (renamed_from, edit_type) {
+        (Some(_), FileEditType::Modified) if edit_count == 0 => FileEditType::Renamed,
+        (Some(_), FileEditType::Renamed) if edit_count > 0 => FileEditType::Modified,
+        (_, edit_type) => edit_type,
+    }
 }
 
-/// Get paginated events using a pre-built session index.
-/// This is O(k) seeks instead of O(n) scan since line offsets are cached.
-pub fn get_session_events_with_index(
-    project_path: &str,
+/// Get the target path and line stats of a file-editing tool call.
+///
+/// Write and NotebookEdit count all new lines as added, since the prior content isn't recorded.
+/// Lockfiles, minified bundles, and Writes with binary or generated content are flagged.
+pub(crate) fn tool_edit_stats<'a>(
+    tool_name: &str,
+    input: &'a Value,
+) -> Option<(&'a str, EditStats)> {
+    let file_path = edit_target_path(input)?;
+    let stats = match tool_name {
+        "Edit" => edit_line_stats(input),
+        "MultiEdit" => {
+            let mut stats = EditStats::default();
+            for edit in multi_edits(input) {
+                stats.add(edit_line_stats(edit));
+            }
+            stats
+        }
+        "Write" => {
+            let content = json_string(input, "content");
+            EditStats {
+                lines_added: content.lines().count() as u32,
+                edit_count: 1,
+                generated: looks_generated(file_path, Some(&content)),
+                ..Default::default()
+            }
+        }
+        "NotebookEdit" => EditStats {
+            lines_added: json_string(input, "new_source").lines().count() as u32,
+            edit_count: 1,
+            ..Default::default()
+        },
+        _ => return None,
+    };
+    Some((
+        file_path,
+        EditStats {
+            generated: stats.generated || looks_generated(file_path, None),
+            ..stats
+        },
+    ))
+}
+
+/// Get the target path and line stats of a tool_use content item.
+pub(crate) fn tool_use_edit_stats(item: &Value) -> Option<(&str, EditStats)> {
+    if item.get("type").and_then(|v| v.as_str()) != Some("tool_use") {
+        return None;
+    }
+    let tool_name = item.get("name").and_then(|v| v.as_str())?;
+    tool_edit_stats(tool_name, item.get("input")?)
+}
+
+/// Line stats for a single old_string/new_string replacement.
+fn edit_line_stats(edit: &Value) -> EditStats {
+    let (lines_added, lines_removed) = count_line_changes(
+        &json_string(edit, "old_string"),
+        &json_string(edit, "new_string"),
+    );
+    EditStats {
+        lines_added,
+        lines_removed,
+        edit_count: 1,
+        generated: false,
+    }
+}
+
+/// Get the path a file-editing tool targets (`notebook_path` for NotebookEdit).
+fn edit_target_path(input: &Value) -> Option<&str> {
+    input
+        .get("file_path")
+        .or_else(|| input.get("notebook_path"))
+        .and_then(|v| v.as_str())
+}
+
+/// Convert an absolute file path to a relative path from the project root.
+pub(crate) fn make_relative_path(file_path: &str, project_path: &str) -> String {
+    // Ensure project_path ends without slash for consistent stripping
+    let project = project_path.trim_end_matches('/');
+
+    if file_path.starts_with(project) {
+        file_path
+            .strip_prefix(project)
+            .map(|p| p.trim_start_matches('/'))
+            .unwrap_or(file_path)
+            .to_string()
+    } else {
+        // If not under project, return as-is
+        file_path.to_string()
+    }
+}
+
+// =============================================================================
+// Session Event Log
+// =============================================================================
+
+/// Metadata for compaction events.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompactMetadata {
+    /// Whether compaction was triggered automatically or manually
+    pub trigger: String,
+    /// Number of tokens before compaction
+    pub pre_tokens: u64,
+}
+
+/// Token usage reported on an assistant event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenUsage {
+    /// Uncached input tokens
+    pub input_tokens: u64,
+    /// Output tokens generated
+    pub output_tokens: u64,
+    /// Input tokens read from the prompt cache
+    pub cache_read_input_tokens: u64,
+    /// Input tokens written to the prompt cache
+    pub cache_creation_input_tokens: u64,
+}
+
+/// Response from get_session_events with pagination info.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionEventsResponse {
+    /// Events for the requested page
+    pub events: Vec<SessionEvent>,
+    /// Total number of events in the session
+    pub total_count: u32,
+    /// Current offset
+    pub offset: u32,
+    /// Whether there are more events after this page
+    pub has_more: bool,
+    /// Opaque cursor for the next (older) page, stable while the file grows
+    pub next_cursor: Option<String>,
+}
+
+/// A single event in the session log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionEvent {
+    /// Sequence number (line number in file, 0-indexed)
+    pub sequence: u32,
+    /// Event UUID if present
+    pub uuid: Option<String>,
+    /// Timestamp (ISO 8601)
+    pub timestamp: Option<String>,
+    /// Event type: "user", "assistant", "system", "summary"
+    pub event_type: String,
+    /// Subtype for system events (e.g., "compact_boundary")
+    pub subtype: Option<String>,
+    /// Tool name if this is a tool_use event
+    pub tool_name: Option<String>,
+    /// Preview text (truncated content for display)
+    pub preview: String,
+    /// Byte offset in file for on-demand raw JSON loading
+    pub byte_offset: u64,
+    /// Compaction metadata (only for compact_boundary events)
+    pub compact_metadata: Option<CompactMetadata>,
+    /// Summary text (for summary events)
+    pub summary: Option<String>,
+    /// Logical parent UUID (for linking compaction to summary)
+    pub logical_parent_uuid: Option<String>,
+    /// Leaf UUID (for summary events)
+    pub leaf_uuid: Option<String>,
+    /// Agent ID if this event is a sub-agent launch result (from Task tool)
+    pub launched_agent_id: Option<String>,
+    /// Description of the sub-agent task (from Task tool)
+    pub launched_agent_description: Option<String>,
+    /// Full prompt given to the sub-agent
+    pub launched_agent_prompt: Option<String>,
+    /// Whether the sub-agent is running async
+    pub launched_agent_is_async: Option<bool>,
+    /// Status of the sub-agent launch
+    pub launched_agent_status: Option<String>,
+    /// User type: "external" for actual human input, None or other for system-injected
+    pub user_type: Option<String>,
+    /// Whether this is a compact summary (context continuation)
+    pub is_compact_summary: Option<bool>,
+    /// Whether this is a tool result (message.content is array with tool_result)
+    pub is_tool_result: bool,
+    /// Whether this is a meta/context injection (isMeta: true)
+    pub is_meta: bool,
+    /// Token usage (only for assistant events that report it)
+    pub usage: Option<TokenUsage>,
+}
+
+/// Internal struct for parsing JSONL entries for event log.
+#[derive(Deserialize)]
+struct JsonlEventEntry {
+    #[serde(rename = "type")]
+    entry_type: Option<String>,
+    subtype: Option<String>,
+    uuid: Option<String>,
+    timestamp: Option<String>,
+    message: Option<JsonlEventMessage>,
+    content: Option<String>,
+    summary: Option<String>,
+    #[serde(rename = "logicalParentUuid")]
+    logical_parent_uuid: Option<String>,
+    #[serde(rename = "leafUuid")]
+    leaf_uuid: Option<String>,
+    #[serde(rename = "compactMetadata")]
+    compact_metadata: Option<JsonlCompactMetadata>,
+    /// Tool use result (contains agentId for Task tool results)
+    #[serde(rename = "toolUseResult")]
+    tool_use_result: Option<JsonlToolUseResult>,
+    /// User type: "external" for actual human input, other values for system-injected
+    #[serde(rename = "userType")]
+    user_type: Option<String>,
+    /// Whether this is a compact summary (system-injected context)
+    #[serde(rename = "isCompactSummary")]
+    is_compact_summary: Option<bool>,
+    /// Whether this is a meta/context injection
+    #[serde(rename = "isMeta")]
+    is_meta: Option<bool>,
+}
+
+#[derive(Deserialize)]
+struct JsonlToolUseResult {
+    #[serde(rename = "agentId")]
+    agent_id: Option<String>,
+    /// Short description of the sub-agent task
+    description: Option<String>,
+    /// The full prompt given to the sub-agent
+    prompt: Option<String>,
+    /// Whether the agent is running async
+    #[serde(rename = "isAsync")]
+    is_async: Option<bool>,
+    /// Status of the agent launch
+    status: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct JsonlEventMessage {
+    content: Option<Value>,
+    usage: Option<JsonlUsage>,
+}
+
+#[derive(Deserialize)]
+struct JsonlUsage {
+    input_tokens: Option<u64>,
+    output_tokens: Option<u64>,
+    cache_read_input_tokens: Option<u64>,
+    cache_creation_input_tokens: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct JsonlCompactMetadata {
+    trigger: Option<String>,
+    #[serde(rename = "preTokens")]
+    pre_tokens: Option<u64>,
+}
+
+/// A kind of content that can be used for an event preview.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum PreviewSource {
+    /// Text blocks
+    Text,
+    /// Extended thinking blocks
+    Thinking,
+    /// Tool calls ("[Tool: name]") and tool result content
+    Tool,
+}
+
+/// Options controlling how event previews are built.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PreviewOptions {
+    /// Maximum preview length in characters
+    pub max_chars: usize,
+    /// Content kinds to try, in order; the first one present wins
+    pub precedence: Vec<PreviewSource>,
+}
+
+impl Default for PreviewOptions {
+    fn default() -> Self {
+        Self {
+            max_chars: 500,
+            precedence: vec![
+                PreviewSource::Text,
+                PreviewSource::Thinking,
+                PreviewSource::Tool,
+            ],
+        }
+    }
+}
+
+/// Extract a preview from message content.
+fn extract_preview_from_content(content: &Value, options: &PreviewOptions) -> String {
+    let max_chars = options.max_chars;
+
+    match content {
+        Value::String(s) => truncate_string(s, max_chars),
+        Value::Array(arr) => {
+            // Try each content kind in precedence order
+            for source in &options.precedence {
+                for item in arr {
+                    let obj = match item.as_object() {
+                        Some(o) => o,
+                        None => continue,
+                    };
+                    let item_type = obj.get("type").and_then(|t| t.as_str());
+
+                    match (source, item_type) {
+                        (PreviewSource::Text, Some("text")) => {
+                            if let Some(text) = obj.get("text").and_then(|t| t.as_str()) {
+                                return truncate_string(text, max_chars);
+                            }
+                        }
+                        // Extended thinking
+                        (PreviewSource::Thinking, Some("thinking")) => {
+                            if let Some(thinking) = obj.get("thinking").and_then(|t| t.as_str()) {
+                                return truncate_string(thinking, max_chars);
+                            }
+                        }
+                        // tool_use - return tool name
+                        (PreviewSource::Tool, Some("tool_use")) => {
+                            if let Some(name) = obj.get("name").and_then(|n| n.as_str()) {
+                                return format!("[Tool: {}]", name);
+                            }
+                        }
+                        (PreviewSource::Tool, Some("tool_result")) => {
+                            if let Some(content) = obj.get("content").and_then(|c| c.as_str()) {
+                                return truncate_string(content, max_chars);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            // Fallback: stringify first item
+            arr.first()
+                .map(|v| truncate_string(&v.to_string(), max_chars))
+                .unwrap_or_default()
+        }
+        _ => truncate_string(&content.to_string(), max_chars),
+    }
+}
+
+/// Check if message content is a tool_result (array containing tool_result items).
+fn is_tool_result_content(content: &Value) -> bool {
+    if let Value::Array(arr) = content {
+        arr.iter().any(|item| {
+            item.as_object()
+                .and_then(|obj| obj.get("type"))
+                .and_then(|t| t.as_str())
+                == Some("tool_result")
+        })
+    } else {
+        false
+    }
+}
+
+/// Extract tool names and content types from message content.
+fn extract_tool_names(content: &Value) -> Option<String> {
+    if let Value::Array(arr) = content {
+        let mut labels: Vec<String> = Vec::new();
+
+        // Check for thinking blocks
+        let has_thinking = arr.iter().any(|item| {
+            item.as_object()
+                .and_then(|obj| obj.get("type"))
+                .and_then(|t| t.as_str())
+                == Some("thinking")
+        });
+        if has_thinking {
+            labels.push("thinking".to_string());
+        }
+
+        // Collect tool names
+        for item in arr {
+            if let Some(obj) = item.as_object() {
+                if obj.get("type").and_then(|t| t.as_str()) == Some("tool_use") {
+                    if let Some(name) = obj.get("name").and_then(|n| n.as_str()) {
+                        labels.push(name.to_string());
+                    }
+                }
+            }
+        }
+
+        if !labels.is_empty() {
+            return Some(labels.join(", "));
+        }
+    }
+    None
+}
+
+/// Truncate string to max length with ellipsis (UTF-8 safe).
+pub(crate) fn truncate_string(s: &str, max_chars: usize) -> String {
+    let char_count = s.chars().count();
+    if char_count <= max_chars {
+        s.to_string()
+    } else {
+        let truncated: String = s.chars().take(max_chars).collect();
+        format!("{}...", truncated)
+    }
+}
+
+/// Build an index of line byte offsets for a file.
+/// Returns Vec of (byte_offset, line_length) for each line.
+fn build_line_index(file: &mut File) -> std::io::Result<Vec<(u64, usize)>> {
+    use std::io::{BufRead, Seek, SeekFrom};
+
+    file.seek(SeekFrom::Start(0))?;
+    let mut reader = BufReader::new(file);
+    let mut index = Vec::new();
+    let mut offset: u64 = 0;
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+        let bytes_read = reader.read_line(&mut line)?;
+        if bytes_read == 0 {
+            break;
+        }
+        index.push((offset, bytes_read));
+        offset += bytes_read as u64;
+    }
+
+    Ok(index)
+}
+
+/// Read a specific line from a file given its byte offset and length.
+fn read_line_at_offset(file: &mut File, offset: u64, length: usize) -> std::io::Result<String> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    file.seek(SeekFrom::Start(offset))?;
+    let mut buffer = vec![0u8; length];
+    file.read_exact(&mut buffer)?;
+
+    // Remove trailing newline
+    if buffer.last() == Some(&b'\n') {
+        buffer.pop();
+    }
+    if buffer.last() == Some(&b'\r') {
+        buffer.pop();
+    }
+
+    String::from_utf8(buffer).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
+/// Parse a single JSONL line into a SessionEvent.
+pub fn parse_session_event(line: &str, sequence: u32, byte_offset: u64) -> Option<SessionEvent> {
+    parse_session_event_with_preview(line, sequence, byte_offset, &PreviewOptions::default())
+}
+
+/// Parse a single JSONL line into a SessionEvent, building the preview with custom options.
+pub fn parse_session_event_with_preview(
+    line: &str,
+    sequence: u32,
+    byte_offset: u64,
+    preview_options: &PreviewOptions,
+) -> Option<SessionEvent> {
+    let entry: JsonlEventEntry = serde_json::from_str(line).ok()?;
+
+    let event_type = entry.entry_type.clone().unwrap_or_else(|| "unknown".to_string());
+
+    // Extract preview based on event type
+    let preview = match event_type.as_str() {
+        "user" | "assistant" => {
+            if let Some(ref msg) = entry.message {
+                if let Some(ref content) = msg.content {
+                    extract_preview_from_content(content, preview_options)
+                } else {
+                    String::new()
+                }
+            } else {
+                String::new()
+            }
+        }
+        "system" => entry.content.clone().unwrap_or_default(),
+        "summary" => entry.summary.clone().unwrap_or_default(),
+        _ => String::new(),
+    };
+
+    // Extract tool names for assistant messages
+    let tool_name = if event_type == "assistant" {
+        entry
+            .message
+            .as_ref()
+            .and_then(|m| m.content.as_ref())
+            .and_then(extract_tool_names)
+    } else {
+        None
+    };
+
+    // Extract compact metadata if present
+    let compact_metadata = entry.compact_metadata.as_ref().map(|cm| CompactMetadata {
+        trigger: cm.trigger.clone().unwrap_or_else(|| "unknown".to_string()),
+        pre_tokens: cm.pre_tokens.unwrap_or(0),
+    });
+
+    // Extract launched agent data from tool_use_result
+    // Both sync and async Task completions include agentId in toolUseResult
+    // - Async launch: { agentId, isAsync: true, status: "async_launched", description }
+    // - Sync/Async completion: { agentId, status: "completed", prompt, content, ... }
+    let tool_result = entry.tool_use_result.as_ref();
+    let launched_agent_id = tool_result.and_then(|r| r.agent_id.clone());
+    let launched_agent_description = tool_result.and_then(|r| r.description.clone());
+    let launched_agent_prompt = tool_result.and_then(|r| r.prompt.clone());
+    let launched_agent_is_async = tool_result.and_then(|r| r.is_async);
+    let launched_agent_status = tool_result.and_then(|r| r.status.clone());
+
+    // Detect if this is a tool_result message (message.content is array with tool_result)
+    let is_tool_result = entry
+        .message
+        .as_ref()
+        .and_then(|m| m.content.as_ref())
+        .map(is_tool_result_content)
+        .unwrap_or(false);
+
+    // isMeta indicates context injection
+    let is_meta = entry.is_meta.unwrap_or(false);
+
+    // Token usage is only reported on assistant messages
+    let usage = if event_type == "assistant" {
+        entry
+            .message
+            .as_ref()
+            .and_then(|m| m.usage.as_ref())
+            .map(|u| TokenUsage {
+                input_tokens: u.input_tokens.unwrap_or(0),
+                output_tokens: u.output_tokens.unwrap_or(0),
+                cache_read_input_tokens: u.cache_read_input_tokens.unwrap_or(0),
+                cache_creation_input_tokens: u.cache_creation_input_tokens.unwrap_or(0),
+            })
+    } else {
+        None
+    };
+
+    Some(SessionEvent {
+        sequence,
+        uuid: entry.uuid,
+        timestamp: entry.timestamp,
+        event_type,
+        subtype: entry.subtype,
+        tool_name,
+        preview,
+        byte_offset,
+        compact_metadata,
+        summary: entry.summary,
+        logical_parent_uuid: entry.logical_parent_uuid,
+        leaf_uuid: entry.leaf_uuid,
+        launched_agent_id,
+        launched_agent_description,
+        launched_agent_prompt,
+        launched_agent_is_async,
+        launched_agent_status,
+        user_type: entry.user_type,
+        is_compact_summary: entry.is_compact_summary,
+        is_tool_result,
+        is_meta,
+        usage,
+    })
+}
+
+/// Encode a pagination cursor anchored to the oldest event already returned.
+fn encode_cursor(sequence: u32, byte_offset: u64) -> String {
+    format!("{}-{}", sequence, byte_offset)
+}
+
+/// Resolve a cursor to the sequence it points at.
+/// Returns None if the cursor is malformed or the line at that sequence has moved
+/// (the file was rewritten, e.g. truncated by compaction).
+fn resolve_cursor(cursor: &str, line_index: &[(u64, usize)]) -> Option<u32> {
+    let (sequence, byte_offset) = cursor.split_once('-')?;
+    let sequence: u32 = sequence.parse().ok()?;
+    let byte_offset: u64 = byte_offset.parse().ok()?;
+
+    match line_index.get(sequence as usize) {
+        Some(&(offset, _)) if offset == byte_offset => Some(sequence),
+        _ => None,
+    }
+}
+
+/// Read one page of events in descending order (newest first).
+///
+/// Pages are addressed either by `offset` (events to skip from the newest) or by `cursor`.
+/// Offsets shift when new lines are appended between requests; cursors don't, since they
+/// name the oldest event already loaded.
+fn read_events_page(
+    file: &mut File,
+    line_index: &[(u64, usize)],
+    offset: Option<u32>,
+    cursor: Option<&str>,
+    limit: Option<u32>,
+    preview: &PreviewOptions,
+) -> SessionEventsResponse {
+    let total_count = line_index.len() as u32;
+    let limit = limit.unwrap_or(200);
+
+    // A cursor pointing at sequence N means "events before N" - i.e. skip everything from N on
+    let offset = match cursor {
+        Some(cursor) => match resolve_cursor(cursor, line_index) {
+            Some(sequence) => total_count - sequence,
+            None => total_count, // Stale cursor - nothing older to return
+        },
+        None => offset.unwrap_or(0),
+    };
+
+    // For descending order, we want the LAST lines first
+    // offset=0 means the last `limit` lines
+    // offset=100 means skip the last 100, then take `limit` lines
+    if offset >= total_count {
+        return SessionEventsResponse {
+            events: Vec::new(),
+            total_count,
+            offset,
+            has_more: false,
+            next_cursor: None,
+        };
+    }
+
+    // Calculate which lines to read (in original file order)
+    // For descending: newest (last in file) comes first in result
+    let available = total_count - offset;
+    let take_count = std::cmp::min(limit, available) as usize;
+
+    // Start from the end, skip `offset`, take `limit`
+    // line_index indices: 0, 1, 2, ..., total-1
+    // For offset=0, limit=3, total=10: we want lines 9, 8, 7 (indices)
+    // start_idx = total - offset - 1 = 9
+    // end_idx = total - offset - take_count = 7
+    let start_idx = (total_count - offset - 1) as usize;
+    let end_idx = (start_idx + 1).saturating_sub(take_count);
+
+    // Parse only the requested lines (in reverse order for descending)
+    let mut events = Vec::with_capacity(take_count);
+
+    for idx in (end_idx..=start_idx).rev() {
+        let (byte_offset, line_len) = line_index[idx];
+
+        if let Ok(line) = read_line_at_offset(file, byte_offset, line_len) {
+            if let Some(event) =
+                parse_session_event_with_preview(&line, idx as u32, byte_offset, preview)
+            {
+                events.push(event);
+            }
+        }
+    }
+
+    let has_more = (offset + take_count as u32) < total_count;
+    let next_cursor = if has_more {
+        Some(encode_cursor(end_idx as u32, line_index[end_idx].0))
+    } else {
+        None
+    };
+
+    SessionEventsResponse {
+        events,
+        total_count,
+        offset,
+        has_more,
+        next_cursor,
+    }
+}
+
+/// Get paginated events from a session for the log viewer.
+/// Events are returned in descending order (newest first).
+///
+/// Parameters:
+/// - offset: Number of events to skip from the newest (default 0)
+/// - cursor: `next_cursor` from a previous page; takes precedence over offset
+/// - limit: Maximum events to return (default 200)
+/// - preview: Preview length and content precedence
+pub fn get_session_events(
+    project_path: &str,
+    session_id: &str,
+    offset: Option<u32>,
+    cursor: Option<&str>,
+    limit: Option<u32>,
+    preview: &PreviewOptions,
+) -> SessionEventsResponse {
+    let empty_response = SessionEventsResponse {
+        events: Vec::new(),
+        total_count: 0,
+        offset: 0,
+        has_more: false,
+        next_cursor: None,
+    };
+
+    let session_file = match get_session_file_path(project_path, session_id) {
+        Some(p) => p,
+        None => return empty_response,
+    };
+
+    let mut file = match File::open(&session_file) {
+        Ok(f) => f,
+        Err(_) => return empty_response,
+    };
+
+    // Phase 1: Build line index (fast, no JSON parsing)
+    let line_index = match build_line_index(&mut file) {
+        Ok(idx) => idx,
+        Err(_) => return empty_response,
+    };
+
+    read_events_page(&mut file, &line_index, offset, cursor, limit, preview)
+}
+
+/// Get the raw JSON for a specific event by its byte offset.
+pub fn get_event_raw_json(project_path: &str, session_id: &str, byte_offset: u64) -> Option<String> {
+    let session_file = get_session_file_path(project_path, session_id)?;
+    let mut file = File::open(&session_file).ok()?;
+
+    use std::io::{BufRead, BufReader, Seek, SeekFrom};
+
+    file.seek(SeekFrom::Start(byte_offset)).ok()?;
+    let mut reader = BufReader::new(file);
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+
+    // Remove trailing newline
+    if line.ends_with('\n') {
+        line.pop();
+    }
+    if line.ends_with('\r') {
+        line.pop();
+    }
+
+    Some(line)
+}
+
+/// Raw event JSON prepared for display, with size metadata.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RawEventJson {
+    /// The (possibly pretty-printed and truncated) JSON text
+    pub json: String,
+    /// Size of the original line in bytes
+    pub original_bytes: u64,
+    /// Size of the returned JSON text in bytes
+    pub returned_bytes: u64,
+    /// Number of string values that were shortened
+    pub truncated_strings: u32,
+}
+
+/// Get the raw JSON for an event, optionally pretty-printed and with long strings cut.
+///
+/// Multi-megabyte lines (large Write contents, base64 images) can freeze the webview,
+/// so `max_string_chars` shortens any nested string beyond that length and the size
+/// metadata lets the frontend warn before rendering.
+pub fn get_event_raw_json_formatted(
+    project_path: &str,
+    session_id: &str,
+    byte_offset: u64,
+    pretty: bool,
+    max_string_chars: Option<usize>,
+) -> Result<RawEventJson, String> {
+    let line = get_event_raw_json(project_path, session_id, byte_offset)
+        .ok_or_else(|| format!("No event found at offset {}", byte_offset))?;
+    format_raw_json(&line, pretty, max_string_chars)
+}
+
+/// Format a raw JSONL line for display.
+fn format_raw_json(
+    line: &str,
+    pretty: bool,
+    max_string_chars: Option<usize>,
+) -> Result<RawEventJson, String> {
+    let original_bytes = line.len() as u64;
+
+    // Fast path: nothing to change
+    if !pretty && max_string_chars.is_none() {
+        return Ok(RawEventJson {
+            json: line.to_string(),
+            original_bytes,
+            returned_bytes: original_bytes,
+            truncated_strings: 0,
+        });
+    }
+
+    let mut value: Value =
+        serde_json::from_str(line).map_err(|e| format!("Failed to parse event: {}", e))?;
+
+    let mut truncated_strings = 0;
+    if let Some(max_chars) = max_string_chars {
+        truncate_json_strings(&mut value, max_chars, &mut truncated_strings);
+    }
+
+    let json = if pretty {
+        serde_json::to_string_pretty(&value)
+    } else {
+        serde_json::to_string(&value)
+    }
+    .map_err(|e| format!("Failed to serialize event: {}", e))?;
+
+    Ok(RawEventJson {
+        returned_bytes: json.len() as u64,
+        json,
+        original_bytes,
+        truncated_strings,
+    })
+}
+
+/// Recursively shorten string values longer than `max_chars`, counting how many were cut.
+fn truncate_json_strings(value: &mut Value, max_chars: usize, count: &mut u32) {
+    match value {
+        Value::String(s) => {
+            let char_count = s.chars().count();
+            if char_count > max_chars {
+                let kept: String = s.chars().take(max_chars).collect();
+                *s = format!("{}... [{} more chars]", kept, char_count - max_chars);
+                *count += 1;
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                truncate_json_strings(item, max_chars, count);
+            }
+        }
+        Value::Object(map) => {
+            for (_, item) in map.iter_mut() {
+                truncate_json_strings(item, max_chars, count);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Get paginated events using a pre-built session index.
+/// This is O(k) seeks instead of O(n) scan since line offsets are cached.
+pub fn get_session_events_with_index(
+    project_path: &str,
+    session_id: &str,
+    index: &crate::session_index::SessionIndex,
+    offset: Option<u32>,
+    cursor: Option<&str>,
+    limit: Option<u32>,
+    preview: &PreviewOptions,
+) -> SessionEventsResponse {
+    let empty_response = SessionEventsResponse {
+        events: Vec::new(),
+        total_count: 0,
+        offset: 0,
+        has_more: false,
+        next_cursor: None,
+    };
+
+    let session_file = match get_session_file_path(project_path, session_id) {
+        Some(p) => p,
+        None => return empty_response,
+    };
+
+    let mut file = match File::open(&session_file) {
+        Ok(f) => f,
+        Err(_) => return empty_response,
+    };
+
+    // Use pre-built line index from the session index
+    read_events_page(
+        &mut file,
+        &index.line_offsets,
+        offset,
+        cursor,
+        limit,
+        preview,
+    )
+}
+
+/// Get full SessionEvent objects for specific byte offsets.
+/// Used to fetch search match results efficiently.
+/// Returns events in the order provided (typically by sequence descending for newest-first).
+pub fn get_events_by_offsets(
+    project_path: &str,
     session_id: &str,
-    index: &crate::session_index::SessionIndex,
+    offsets: Vec<(u32, u64)>, // (sequence, byte_offset) pairs
+) -> Vec<SessionEvent> {
+    let session_file = match get_session_file_path(project_path, session_id) {
+        Some(p) => p,
+        None => return Vec::new(),
+    };
+
+    let mut file = match File::open(&session_file) {
+        Ok(f) => f,
+        Err(_) => return Vec::new(),
+    };
+
+    use std::io::{Seek, SeekFrom};
+
+    let mut events = Vec::with_capacity(offsets.len());
+
+    for (sequence, byte_offset) in offsets {
+        // Seek to offset
+        if file.seek(SeekFrom::Start(byte_offset)).is_err() {
+            continue;
+        }
+
+        // Read the line
+        let mut reader = BufReader::new(&file);
+        let mut line = String::new();
+        if reader.read_line(&mut line).is_err() {
+            continue;
+        }
+
+        // Remove trailing newline
+        if line.ends_with('\n') {
+            line.pop();
+        }
+        if line.ends_with('\r') {
+            line.pop();
+        }
+
+        // Parse into SessionEvent
+        if let Some(event) = parse_session_event(&line, sequence, byte_offset) {
+            events.push(event);
+        }
+    }
+
+    events
+}
+
+/// Get paginated events from a sub-agent session for the log viewer.
+/// Events are returned in descending order (newest first).
+pub fn get_subagent_events(
+    project_path: &str,
+    agent_id: &str,
+    offset: Option<u32>,
+    cursor: Option<&str>,
+    limit: Option<u32>,
+    preview: &PreviewOptions,
+) -> SessionEventsResponse {
+    let empty_response = SessionEventsResponse {
+        events: Vec::new(),
+        total_count: 0,
+        offset: 0,
+        has_more: false,
+        next_cursor: None,
+    };
+
+    let agent_file = match get_subagent_file_path(project_path, agent_id) {
+        Some(p) => p,
+        None => return empty_response,
+    };
+
+    let mut file = match File::open(&agent_file) {
+        Ok(f) => f,
+        Err(_) => return empty_response,
+    };
+
+    // Phase 1: Build line index (fast - no JSON parsing)
+    let line_index = match build_line_index(&mut file) {
+        Ok(idx) => idx,
+        Err(_) => return empty_response,
+    };
+
+    read_events_page(&mut file, &line_index, offset, cursor, limit, preview)
+}
+
+/// Get the raw JSON for a specific event in a sub-agent session by its byte offset.
+pub fn get_subagent_raw_json(project_path: &str, agent_id: &str, byte_offset: u64) -> Option<String> {
+    let agent_file = get_subagent_file_path(project_path, agent_id)?;
+    let mut file = File::open(&agent_file).ok()?;
+
+    use std::io::{BufRead, BufReader, Seek, SeekFrom};
+
+    file.seek(SeekFrom::Start(byte_offset)).ok()?;
+    let mut reader = BufReader::new(file);
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+
+    // Remove trailing newline
+    if line.ends_with('\n') {
+        line.pop();
+    }
+    if line.ends_with('\r') {
+        line.pop();
+    }
+
+    Some(line)
+}
+
+// =============================================================================
+// Tool Result Content
+// =============================================================================
+
+/// Full content of a tool result (event previews truncate at 500 chars).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolResultContent {
+    /// ID of the tool_use this result answers
+    pub tool_use_id: String,
+    /// Byte offset of the event containing the result
+    pub byte_offset: u64,
+    /// Result text (text blocks joined by newlines)
+    pub content: String,
+    /// Whether the tool reported an error
+    pub is_error: bool,
+    /// Length of the full content in characters
+    pub total_chars: u64,
+    /// Whether content was cut off at max_chars
+    pub truncated: bool,
+}
+
+/// Get the complete content of a tool result.
+///
+/// The result is located by byte offset when given (fast seek), otherwise by scanning
+/// the session file for the tool_use_id. When both are given, the offset is used and
+/// the id selects which tool_result block to return if the event has several.
+pub fn get_tool_result_content(
+    project_path: &str,
+    session_id: &str,
+    tool_use_id: Option<&str>,
+    byte_offset: Option<u64>,
+    max_chars: Option<usize>,
+) -> Result<ToolResultContent, String> {
+    let (line, byte_offset) = match (byte_offset, tool_use_id) {
+        (Some(offset), _) => {
+            let line = get_event_raw_json(project_path, session_id, offset)
+                .ok_or_else(|| format!("No event found at offset {}", offset))?;
+            (line, offset)
+        }
+        (None, Some(id)) => find_tool_result_line(project_path, session_id, id)?,
+        (None, None) => return Err("Either toolUseId or byteOffset is required".to_string()),
+    };
+
+    let json: Value =
+        serde_json::from_str(&line).map_err(|e| format!("Failed to parse event: {}", e))?;
+    let (tool_use_id, content, is_error) = extract_tool_result(&json, tool_use_id)
+        .ok_or_else(|| "Event does not contain a matching tool result".to_string())?;
+
+    let total_chars = content.chars().count();
+    let (content, truncated) = match max_chars {
+        Some(max) if total_chars > max => (content.chars().take(max).collect(), true),
+        _ => (content, false),
+    };
+
+    Ok(ToolResultContent {
+        tool_use_id,
+        byte_offset,
+        content,
+        is_error,
+        total_chars: total_chars as u64,
+        truncated,
+    })
+}
+
+/// Scan a session file for the line holding the tool_result for a tool_use_id.
+/// Returns the line and its byte offset.
+fn find_tool_result_line(
+    project_path: &str,
+    session_id: &str,
+    tool_use_id: &str,
+) -> Result<(String, u64), String> {
+    let session_file = get_session_file_path(project_path, session_id)
+        .ok_or_else(|| format!("Session file not found for {}", session_id))?;
+    let file = File::open(&session_file).map_err(|e| format!("Failed to open session: {}", e))?;
+
+    let mut reader = BufReader::new(file);
+    let mut offset: u64 = 0;
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+        let bytes_read = reader
+            .read_line(&mut line)
+            .map_err(|e| format!("Failed to read session: {}", e))?;
+        if bytes_read == 0 {
+            break;
+        }
+
+        // Cheap substring check before parsing
+        if line.contains("\"tool_result\"") && line.contains(tool_use_id) {
+            let trimmed = line.trim_end_matches(['\n', '\r']);
+            if let Ok(json) = serde_json::from_str::<Value>(trimmed) {
+                if extract_tool_result(&json, Some(tool_use_id)).is_some() {
+                    return Ok((trimmed.to_string(), offset));
+                }
+            }
+        }
+
+        offset += bytes_read as u64;
+    }
+
+    Err(format!("No tool result found for {}", tool_use_id))
+}
+
+/// Extract (tool_use_id, content, is_error) from a tool_result block in an event.
+/// Picks the block matching tool_use_id, or the first tool_result if None.
+fn extract_tool_result(json: &Value, tool_use_id: Option<&str>) -> Option<(String, String, bool)> {
+    let items = json.get("message")?.get("content")?.as_array()?;
+
+    let block = items.iter().find(|item| {
+        item.get("type").and_then(|t| t.as_str()) == Some("tool_result")
+            && tool_use_id
+                .is_none_or(|id| item.get("tool_use_id").and_then(|t| t.as_str()) == Some(id))
+    })?;
+
+    let id = block
+        .get("tool_use_id")
+        .and_then(|t| t.as_str())
+        .unwrap_or_default()
+        .to_string();
+    let is_error = block
+        .get("is_error")
+        .and_then(|e| e.as_bool())
+        .unwrap_or(false);
+
+    let content = match block.get("content") {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Array(parts)) => parts
+            .iter()
+            .map(|part| match part.get("type").and_then(|t| t.as_str()) {
+                Some("text") => part
+                    .get("text")
+                    .and_then(|t| t.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                Some(other) => format!("[{}]", other),
+                None => part.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Some(other) => other.to_string(),
+        None => String::new(),
+    };
+
+    Some((id, content, is_error))
+}
+
+// =============================================================================
+// Event Attachments
+// =============================================================================
+
+/// Attachments smaller than this (encoded size) are returned inline as base64.
+const INLINE_ATTACHMENT_LIMIT: usize = 64 * 1024;
+
+/// Cached attachment files older than this are removed by `prune_attachment_cache`.
+pub const ATTACHMENT_CACHE_TTL: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
+
+/// An image or document block extracted from an event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventAttachment {
+    /// Position of this attachment within the event (0-indexed)
+    pub index: u32,
+    /// Block type: "image" or "document"
+    pub kind: String,
+    /// MIME type (e.g., "image/png")
+    pub media_type: String,
+    /// Decoded size in bytes
+    pub size_bytes: u64,
+    /// Base64 data for small attachments (None if written to the cache)
+    pub data: Option<String>,
+    /// Path to the cached file for large attachments (None if inlined)
+    pub path: Option<String>,
+}
+
+/// Get image and document attachments for an event by its byte offset.
+///
+/// Small attachments are returned inline; larger ones are decoded and written to
+/// `cache_dir` so the webview doesn't have to hold megabytes of base64.
+pub fn get_event_attachments(
+    project_path: &str,
+    session_id: &str,
+    byte_offset: u64,
+    cache_dir: &Path,
+) -> Result<Vec<EventAttachment>, String> {
+    use base64::Engine;
+
+    let line = get_event_raw_json(project_path, session_id, byte_offset)
+        .ok_or_else(|| format!("No event found at offset {}", byte_offset))?;
+    let json: Value =
+        serde_json::from_str(&line).map_err(|e| format!("Failed to parse event: {}", e))?;
+
+    let content = match json.get("message").and_then(|m| m.get("content")) {
+        Some(c) => c,
+        None => return Ok(Vec::new()),
+    };
+
+    let mut attachments = Vec::new();
+
+    for (index, block) in collect_attachment_blocks(content).into_iter().enumerate() {
+        let decoded = base64::engine::general_purpose::STANDARD
+            .decode(block.data)
+            .map_err(|e| format!("Failed to decode attachment {}: {}", index, e))?;
+        let size_bytes = decoded.len() as u64;
+
+        let (data, path) = if block.data.len() <= INLINE_ATTACHMENT_LIMIT {
+            (Some(block.data.to_string()), None)
+        } else {
+            fs::create_dir_all(cache_dir)
+                .map_err(|e| format!("Failed to create attachment cache: {}", e))?;
+            let file_name = format!(
+                "{}-{}-{}.{}",
+                session_id,
+                byte_offset,
+                index,
+                extension_for_media_type(block.media_type)
+            );
+            let file_path = cache_dir.join(file_name);
+            fs::write(&file_path, &decoded)
+                .map_err(|e| format!("Failed to write attachment: {}", e))?;
+            (None, Some(file_path.to_string_lossy().to_string()))
+        };
+
+        attachments.push(EventAttachment {
+            index: index as u32,
+            kind: block.kind.to_string(),
+            media_type: block.media_type.to_string(),
+            size_bytes,
+            data,
+            path,
+        });
+    }
+
+    Ok(attachments)
+}
+
+/// Remove cached attachment files last written more than `max_age` ago.
+///
+/// Returns how many files were removed. A missing cache directory is not an error.
+pub fn prune_attachment_cache(cache_dir: &Path, max_age: std::time::Duration) -> usize {
+    let entries = match fs::read_dir(cache_dir) {
+        Ok(entries) => entries,
+        Err(_) => return 0,
+    };
+
+    entries
+        .flatten()
+        .filter(|entry| {
+            entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|age| age > max_age)
+        })
+        .filter(|entry| fs::remove_file(entry.path()).is_ok())
+        .count()
+}
+
+/// A base64 content block found in message content.
+struct AttachmentBlock<'a> {
+    kind: &'a str,
+    media_type: &'a str,
+    data: &'a str,
+}
+
+/// Collect base64 image/document blocks from message content, in order.
+/// Also descends into tool_result content (e.g., images returned by the Read tool).
+fn collect_attachment_blocks(content: &Value) -> Vec<AttachmentBlock<'_>> {
+    let mut blocks = Vec::new();
+
+    if let Value::Array(items) = content {
+        for item in items {
+            let block_type = item.get("type").and_then(|t| t.as_str());
+            match block_type {
+                Some(kind @ ("image" | "document")) => {
+                    let source = match item.get("source") {
+                        Some(s) => s,
+                        None => continue,
+                    };
+                    if source.get("type").and_then(|t| t.as_str()) != Some("base64") {
+                        continue;
+                    }
+                    let media_type = source
+                        .get("media_type")
+                        .and_then(|m| m.as_str())
+                        .unwrap_or("application/octet-stream");
+                    if let Some(data) = source.get("data").and_then(|d| d.as_str()) {
+                        blocks.push(AttachmentBlock {
+                            kind,
+                            media_type,
+                            data,
+                        });
+                    }
+                }
+                Some("tool_result") => {
+                    if let Some(inner) = item.get("content") {
+                        blocks.extend(collect_attachment_blocks(inner));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    blocks
+}
+
+/// Map a MIME type to a file extension for cached attachments.
+fn extension_for_media_type(media_type: &str) -> &'static str {
+    match media_type {
+        "image/png" => "png",
+        "image/jpeg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "application/pdf" => "pdf",
+        "text/plain" => "txt",
+        _ => "bin",
+    }
+}
+
+// =============================================================================
+// Policy Evaluation Telemetry
+// =============================================================================
+
+/// Summary of a policy evaluation for list display.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyEvaluation {
+    /// Filename of the telemetry file
+    pub filename: String,
+    /// Timestamp (ISO 8601)
+    pub timestamp: String,
+    /// Event type (e.g., "PreToolUse")
+    pub event_type: Option<String>,
+    /// Tool name (e.g., "Bash")
+    pub tool_name: Option<String>,
+    /// Final decision (e.g., "Allow", "Block")
+    pub decision: Option<String>,
+    /// Total duration in milliseconds
+    pub duration_ms: u64,
+    /// Trace ID
+    pub trace_id: String,
+}
+
+/// Get the policy telemetry directory for a project.
+fn get_telemetry_dir(project_path: &str) -> PathBuf {
+    PathBuf::from(project_path)
+        .join(".cupcake")
+        .join("telemetry")
+}
+
+/// Default number of policy evaluations per page.
+const DEFAULT_POLICY_PAGE_SIZE: u32 = 100;
+
+/// A page of policy evaluations, newest first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyEvaluationsResponse {
+    /// Evaluations for the requested page (unreadable telemetry files are skipped)
+    pub evaluations: Vec<PolicyEvaluation>,
+    /// Total number of telemetry files
+    pub total_count: u32,
+    /// Current offset
+    pub offset: u32,
+    /// Whether there are more evaluations after this page
+    pub has_more: bool,
+}
+
+/// Filters for listing policy evaluations. Unset fields match everything.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PolicyEvaluationFilter {
+    /// Final decision, case-insensitive (e.g., "Deny")
+    pub decision: Option<String>,
+    /// Tool name, case-insensitive (e.g., "Bash")
+    pub tool_name: Option<String>,
+    /// Event type, case-insensitive (e.g., "PreToolUse")
+    pub event_type: Option<String>,
+    /// Only evaluations at or after this time (ISO 8601)
+    pub since: Option<String>,
+    /// Only evaluations at or before this time (ISO 8601)
+    pub until: Option<String>,
+    /// Trace ID, or a prefix of it
+    pub trace_id: Option<String>,
+}
+
+impl PolicyEvaluationFilter {
+    fn is_empty(&self) -> bool {
+        self.decision.is_none()
+            && self.tool_name.is_none()
+            && self.event_type.is_none()
+            && self.since.is_none()
+            && self.until.is_none()
+            && self.trace_id.is_none()
+    }
+
+    /// Whether an evaluation passes every filter. Evaluations without a parseable
+    /// timestamp never match a time range.
+    fn matches(
+        &self,
+        evaluation: &PolicyEvaluation,
+        since: Option<chrono::DateTime<chrono::Utc>>,
+        until: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> bool {
+        let field_matches = |filter: &Option<String>, value: &Option<String>| match filter {
+            Some(filter) => value
+                .as_deref()
+                .is_some_and(|v| v.eq_ignore_ascii_case(filter)),
+            None => true,
+        };
+        if !field_matches(&self.decision, &evaluation.decision)
+            || !field_matches(&self.tool_name, &evaluation.tool_name)
+            || !field_matches(&self.event_type, &evaluation.event_type)
+        {
+            return false;
+        }
+
+        if let Some(trace_id) = &self.trace_id {
+            if !evaluation.trace_id.starts_with(trace_id.as_str()) {
+                return false;
+            }
+        }
+
+        if since.is_some() || until.is_some() {
+            let timestamp = match chrono::DateTime::parse_from_rfc3339(&evaluation.timestamp) {
+                Ok(t) => t.with_timezone(&chrono::Utc),
+                Err(_) => return false,
+            };
+            if since.is_some_and(|since| timestamp < since)
+                || until.is_some_and(|until| timestamp > until)
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Parse an ISO 8601 time from a filter.
+fn parse_filter_time(time: Option<&str>) -> Result<Option<chrono::DateTime<chrono::Utc>>, String> {
+    time.map(|t| {
+        chrono::DateTime::parse_from_rfc3339(t)
+            .map(|t| t.with_timezone(&chrono::Utc))
+            .map_err(|e| format!("Invalid time {}: {}", t, e))
+    })
+    .transpose()
+}
+
+/// Parsed policy evaluation summaries, so paging through evaluations only parses
+/// telemetry files that are new or have changed since they were last read.
+pub struct PolicyEvaluationCache {
+    /// Map of telemetry file path -> (modified time, summary)
+    summaries: Mutex<HashMap<PathBuf, (SystemTime, PolicyEvaluation)>>,
+}
+
+impl PolicyEvaluationCache {
+    pub fn new() -> Self {
+        Self {
+            summaries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Summary of a telemetry file, parsed when not cached or modified since.
+    fn summary(&self, path: &Path, modified: SystemTime) -> Option<PolicyEvaluation> {
+        if let Ok(summaries) = self.summaries.lock() {
+            if let Some((cached_modified, summary)) = summaries.get(path) {
+                if *cached_modified == modified {
+                    return Some(summary.clone());
+                }
+            }
+        }
+
+        let summary = read_policy_evaluation(path)?;
+        if let Ok(mut summaries) = self.summaries.lock() {
+            summaries.insert(path.to_path_buf(), (modified, summary.clone()));
+        }
+        Some(summary)
+    }
+
+    /// Drop cached summaries of files removed from a telemetry directory.
+    fn prune(&self, telemetry_dir: &Path, files: &[(PathBuf, SystemTime)]) {
+        let current: HashSet<&PathBuf> = files.iter().map(|(path, _)| path).collect();
+        if let Ok(mut summaries) = self.summaries.lock() {
+            summaries
+                .retain(|path, _| path.parent() != Some(telemetry_dir) || current.contains(path));
+        }
+    }
+}
+
+/// List telemetry files with their modification times, newest first. Only file
+/// metadata is read, so this stays cheap with many evaluations.
+fn list_telemetry_files(telemetry_dir: &Path) -> Vec<(PathBuf, SystemTime)> {
+    let entries = match fs::read_dir(telemetry_dir) {
+        Ok(e) => e,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut files: Vec<(PathBuf, SystemTime)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            // Only process .json files
+            if path.extension().map(|e| e != "json").unwrap_or(true) {
+                return None;
+            }
+            let modified = entry.metadata().and_then(|m| m.modified()).ok()?;
+            Some((path, modified))
+        })
+        .collect();
+
+    // Newest first; filenames break ties between files written in the same instant
+    files.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| b.0.cmp(&a.0)));
+    files
+}
+
+/// Get a page of policy evaluations for a project, newest first, that match `filter`.
+///
+/// Without filters only the requested page's files are read. Filtering reads every
+/// candidate file (parsed summaries are cached), except that files last modified
+/// before `since` are skipped, as they can't hold a later evaluation.
+pub fn get_policy_evaluations(
+    project_path: &str,
     offset: Option<u32>,
     limit: Option<u32>,
-) -> SessionEventsResponse {
-    let empty_response = SessionEventsResponse {
-        events: Vec::new(),
-        total_count: 0,
-        offset: 0,
-        has_more: false,
+    filter: &PolicyEvaluationFilter,
+    cache: &PolicyEvaluationCache,
+) -> Result<PolicyEvaluationsResponse, String> {
+    let telemetry_dir = get_telemetry_dir(project_path);
+    let files = list_telemetry_files(&telemetry_dir);
+    cache.prune(&telemetry_dir, &files);
+
+    let offset = offset.unwrap_or(0);
+    let limit = limit.unwrap_or(DEFAULT_POLICY_PAGE_SIZE);
+
+    let (evaluations, total_count) = if filter.is_empty() {
+        let evaluations = files
+            .iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .filter_map(|(path, modified)| cache.summary(path, *modified))
+            .collect();
+        (evaluations, files.len() as u32)
+    } else {
+        let since = parse_filter_time(filter.since.as_deref())?;
+        let until = parse_filter_time(filter.until.as_deref())?;
+        let modified_since = since.map(SystemTime::from);
+
+        let matching: Vec<PolicyEvaluation> = files
+            .iter()
+            .filter(|(_, modified)| modified_since.is_none_or(|since| *modified >= since))
+            .filter_map(|(path, modified)| cache.summary(path, *modified))
+            .filter(|evaluation| filter.matches(evaluation, since, until))
+            .collect();
+        let total_count = matching.len() as u32;
+        let evaluations = matching
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .collect();
+        (evaluations, total_count)
     };
 
-    let session_file = match get_session_file_path(project_path, session_id) {
-        Some(p) => p,
-        None => return empty_response,
+    let offset = offset.min(total_count);
+    Ok(PolicyEvaluationsResponse {
+        evaluations,
+        total_count,
+        offset,
+        has_more: offset.saturating_add(limit) < total_count,
+    })
+}
+
+/// Read a telemetry file's summary for list display.
+fn read_policy_evaluation(path: &Path) -> Option<PolicyEvaluation> {
+    let filename = path.file_name()?.to_string_lossy().to_string();
+
+    // Parse the JSON file to extract summary info
+    let content = fs::read_to_string(path).ok()?;
+    let span: Value = serde_json::from_str(&content).ok()?;
+    Some(summarize_policy_span(filename, &span))
+}
+
+/// Summarize a parsed CupcakeSpan for list display.
+fn summarize_policy_span(filename: String, span: &Value) -> PolicyEvaluation {
+    // Extract fields from the CupcakeSpan
+    let timestamp = span
+        .get("timestamp")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+
+    let trace_id = span
+        .get("trace_id")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+
+    let raw_event = span.get("raw_event");
+    let event_type = raw_event
+        .and_then(|e| e.get("hook_event_name"))
+        .and_then(|v| v.as_str())
+        .map(String::from);
+
+    let tool_name = raw_event
+        .and_then(|e| e.get("tool_name"))
+        .and_then(|v| v.as_str())
+        .map(String::from);
+
+    let decision = final_decision(span).map(|(kind, _)| kind.to_string());
+
+    let duration_ms = span
+        .get("total_duration_ms")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
+
+    PolicyEvaluation {
+        filename,
+        timestamp,
+        event_type,
+        tool_name,
+        decision,
+        duration_ms,
+        trace_id,
+    }
+}
+
+/// A span's final decision and its content, from the response or else the last phase.
+fn final_decision(span: &Value) -> Option<(&str, &Value)> {
+    span.get("response")
+        .and_then(|r| r.get("decision"))
+        .and_then(tagged_decision)
+        .or_else(|| {
+            span.get("phases")
+                .and_then(|p| p.as_array())
+                .and_then(|arr| arr.last())
+                .and_then(|phase| phase.get("evaluation"))
+                .and_then(|eval| eval.get("final_decision"))
+                .and_then(tagged_decision)
+        })
+}
+
+/// Split a decision tagged union like {"Allow": {...}} or {"Deny": {...}} into its
+/// type and content.
+fn tagged_decision(decision: &Value) -> Option<(&str, &Value)> {
+    decision
+        .as_object()
+        .and_then(|obj| obj.iter().next())
+        .map(|(kind, content)| (kind.as_str(), content))
+}
+
+/// A policy evaluation's phases and results, parsed from its telemetry file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyEvaluationDetail {
+    /// Summary shown in the evaluation list
+    pub evaluation: PolicyEvaluation,
+    /// Harness that sent the event (e.g., "ClaudeCode")
+    pub harness: Option<String>,
+    /// Reason given with the final decision
+    pub reason: Option<String>,
+    /// Messages returned to the agent with the final decision
+    pub agent_messages: Vec<String>,
+    /// Policy phases in evaluation order (global, catalogs, project)
+    pub phases: Vec<PolicyPhaseDetail>,
+    /// Errors encountered during evaluation
+    pub errors: Vec<String>,
+}
+
+/// One phase of a policy evaluation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyPhaseDetail {
+    /// Phase name (e.g., "global", "project", "catalog:xyz")
+    pub name: String,
+    /// When the phase started, in milliseconds after the evaluation started
+    pub start_offset_ms: f64,
+    /// Duration in milliseconds
+    pub duration_ms: f64,
+    /// Whether the event was routed to this phase's policies
+    pub routed: bool,
+    /// Names of policies that matched the event
+    pub matched_policies: Vec<String>,
+    /// Reason the phase exited early, if it did
+    pub exit_reason: Option<String>,
+    /// The phase's decision (e.g., "Allow", "Deny")
+    pub decision: Option<String>,
+    /// Rule results that contributed to the decision
+    pub results: Vec<PolicyRuleResult>,
+    /// Signals run to gather input for the policies
+    pub signals: Vec<PolicySignalResult>,
+}
+
+/// A policy rule's verdict, such as a denial and its reason.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyRuleResult {
+    /// Verdict kind ("halt", "deny", "block", or "ask")
+    pub kind: String,
+    /// ID of the rule that produced it
+    pub rule_id: String,
+    /// Violation message
+    pub reason: String,
+    /// Severity level
+    pub severity: Option<String>,
+}
+
+/// A signal run during a policy phase.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicySignalResult {
+    /// Signal name
+    pub name: String,
+    /// Command that was run
+    pub command: String,
+    /// Duration in milliseconds
+    pub duration_ms: Option<f64>,
+    /// Exit code, if the signal ran a process
+    pub exit_code: Option<i64>,
+}
+
+/// Verdict lists in a WASM decision set, with the kind each holds.
+const DECISION_SET_VERDICTS: [(&str, &str); 4] = [
+    ("halts", "halt"),
+    ("denials", "deny"),
+    ("blocks", "block"),
+    ("asks", "ask"),
+];
+
+/// Parse a CupcakeSpan into a typed evaluation detail.
+fn parse_policy_evaluation_detail(filename: String, span: &Value) -> PolicyEvaluationDetail {
+    let decision = final_decision(span).map(|(_, content)| content);
+    let trace_start = span.get("start_time_unix_nano").and_then(|v| v.as_u64());
+
+    let phases = span
+        .get("phases")
+        .and_then(|p| p.as_array())
+        .map(|phases| {
+            phases
+                .iter()
+                .map(|phase| parse_policy_phase(phase, trace_start))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    PolicyEvaluationDetail {
+        evaluation: summarize_policy_span(filename, span),
+        harness: json_optional_string(span, "harness"),
+        reason: decision.and_then(|d| json_optional_string(d, "reason")),
+        agent_messages: decision
+            .map(|d| json_string_list(d, "agent_messages"))
+            .unwrap_or_default(),
+        phases,
+        errors: json_string_list(span, "errors"),
+    }
+}
+
+/// Parse one entry of a CupcakeSpan's `phases`.
+fn parse_policy_phase(phase: &Value, trace_start: Option<u64>) -> PolicyPhaseDetail {
+    let nanos = |key: &str| phase.get(key).and_then(|v| v.as_u64()).filter(|n| *n > 0);
+    let (start, end) = (nanos("start_time_unix_nano"), nanos("end_time_unix_nano"));
+
+    // Timestamps are more precise than duration_ms, which rounds short phases to 0
+    let duration_ms = match (start, end) {
+        (Some(start), Some(end)) if end >= start => (end - start) as f64 / 1_000_000.0,
+        _ => phase
+            .get("duration_ms")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(0.0),
+    };
+    let start_offset_ms = match (start, trace_start) {
+        (Some(start), Some(trace_start)) if start >= trace_start => {
+            (start - trace_start) as f64 / 1_000_000.0
+        }
+        _ => 0.0,
+    };
+
+    let empty = Value::Null;
+    let evaluation = phase.get("evaluation").unwrap_or(&empty);
+    let decision_set = evaluation.get("wasm_decision_set").unwrap_or(&empty);
+    let results = DECISION_SET_VERDICTS
+        .iter()
+        .flat_map(|(list, kind)| {
+            decision_set
+                .get(*list)
+                .and_then(|v| v.as_array())
+                .into_iter()
+                .flatten()
+                .map(move |result| PolicyRuleResult {
+                    kind: kind.to_string(),
+                    rule_id: json_string(result, "rule_id"),
+                    reason: json_string(result, "reason"),
+                    severity: json_optional_string(result, "severity"),
+                })
+        })
+        .collect();
+
+    let signals = phase
+        .get("signals")
+        .and_then(|s| s.get("signals"))
+        .and_then(|s| s.as_array())
+        .map(|signals| {
+            signals
+                .iter()
+                .map(|signal| PolicySignalResult {
+                    name: json_string(signal, "name"),
+                    command: json_string(signal, "command"),
+                    duration_ms: signal.get("duration_ms").and_then(|v| v.as_f64()),
+                    exit_code: signal.get("exit_code").and_then(|v| v.as_i64()),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    PolicyPhaseDetail {
+        name: json_string(phase, "name"),
+        start_offset_ms,
+        duration_ms,
+        routed: json_bool(evaluation, "routed"),
+        matched_policies: json_string_list(evaluation, "matched_policies"),
+        exit_reason: json_optional_string(evaluation, "exit_reason"),
+        decision: evaluation
+            .get("final_decision")
+            .and_then(tagged_decision)
+            .map(|(kind, _)| kind.to_string()),
+        results,
+        signals,
+    }
+}
+
+/// Get a string field, if present.
+fn json_optional_string(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(|v| v.as_str()).map(String::from)
+}
+
+/// Get the strings in an array field, defaulting to empty.
+fn json_string_list(value: &Value, key: &str) -> Vec<String> {
+    value
+        .get(key)
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Get the raw JSON content of a specific policy evaluation.
+pub fn get_policy_evaluation(project_path: &str, filename: &str) -> Option<String> {
+    let telemetry_dir = get_telemetry_dir(project_path);
+    let file_path = telemetry_dir.join(filename);
+
+    if !file_path.exists() {
+        return None;
+    }
+
+    fs::read_to_string(&file_path).ok()
+}
+
+/// Get the parsed phases and results of a specific policy evaluation.
+pub fn get_policy_evaluation_detail(
+    project_path: &str,
+    filename: &str,
+) -> Option<PolicyEvaluationDetail> {
+    let content = get_policy_evaluation(project_path, filename)?;
+    let span: Value = serde_json::from_str(&content).ok()?;
+    Some(parse_policy_evaluation_detail(filename.to_string(), &span))
+}
+
+// =============================================================================
+// Cupcake Policy Files
+// =============================================================================
+
+/// Kind of file in a project's `.cupcake` directory.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PolicyFileKind {
+    /// Rego policy under `policies/`
+    Policy,
+    /// Guidebook configuration (guidebook.yml)
+    Guidebook,
+    /// Rulebook configuration (rulebook.yml)
+    Rulebook,
+}
+
+/// A Cupcake policy or configuration file.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyFile {
+    /// Path relative to the `.cupcake` directory (e.g., "policies/no_force_push.rego")
+    pub path: String,
+    /// Kind of file
+    pub kind: PolicyFileKind,
+    /// Rego package (e.g., "cupcake.policies.no_force_push"), for policies
+    pub package: Option<String>,
+    /// Size in bytes
+    pub size: u64,
+    /// Last modified (ISO 8601)
+    pub modified: String,
+}
+
+/// A Cupcake policy or configuration file with its contents.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyFileContent {
+    /// File metadata
+    pub file: PolicyFile,
+    /// File contents
+    pub content: String,
+}
+
+/// Configuration files at the top of the `.cupcake` directory.
+const CUPCAKE_CONFIG_FILES: [(&str, PolicyFileKind); 4] = [
+    ("guidebook.yml", PolicyFileKind::Guidebook),
+    ("guidebook.yaml", PolicyFileKind::Guidebook),
+    ("rulebook.yml", PolicyFileKind::Rulebook),
+    ("rulebook.yaml", PolicyFileKind::Rulebook),
+];
+
+/// Get the Cupcake directory for a project.
+fn get_cupcake_dir(project_path: &str) -> PathBuf {
+    PathBuf::from(project_path).join(".cupcake")
+}
+
+/// List a project's Cupcake configuration files and `.rego` policies (recursively
+/// under `.cupcake/policies`), configuration first and policies sorted by path.
+pub fn get_policy_files(project_path: &str) -> Vec<PolicyFile> {
+    let cupcake_dir = get_cupcake_dir(project_path);
+
+    let mut files: Vec<PolicyFile> = CUPCAKE_CONFIG_FILES
+        .iter()
+        .filter_map(|(name, kind)| read_policy_file_metadata(&cupcake_dir, name, *kind))
+        .collect();
+
+    let mut policies = Vec::new();
+    collect_rego_files(&cupcake_dir.join("policies"), &mut policies);
+    let mut policies: Vec<PolicyFile> = policies
+        .iter()
+        .filter_map(|path| {
+            let relative = path.strip_prefix(&cupcake_dir).ok()?;
+            let relative = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            read_policy_file_metadata(&cupcake_dir, &relative, PolicyFileKind::Policy)
+        })
+        .collect();
+    policies.sort_by(|a, b| a.path.cmp(&b.path));
+
+    files.extend(policies);
+    files
+}
+
+/// Get a Cupcake policy or configuration file's contents. `path` must be one listed
+/// by `get_policy_files`.
+pub fn get_policy_file(project_path: &str, path: &str) -> Result<PolicyFileContent, String> {
+    let file = get_policy_files(project_path)
+        .into_iter()
+        .find(|f| f.path == path)
+        .ok_or_else(|| format!("Policy file not found: {}", path))?;
+
+    let content = fs::read_to_string(get_cupcake_dir(project_path).join(path))
+        .map_err(|e| format!("Failed to read policy file: {}", e))?;
+
+    Ok(PolicyFileContent { file, content })
+}
+
+/// Recursively collect `.rego` files. Symlinked directories aren't followed.
+fn collect_rego_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(e) => e,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        match entry.file_type() {
+            Ok(t) if t.is_dir() => collect_rego_files(&path, files),
+            Ok(_) if path.extension().is_some_and(|e| e == "rego") => files.push(path),
+            _ => {}
+        }
+    }
+}
+
+/// Read a Cupcake file's metadata, or None if it doesn't exist.
+fn read_policy_file_metadata(
+    cupcake_dir: &Path,
+    relative: &str,
+    kind: PolicyFileKind,
+) -> Option<PolicyFile> {
+    let full_path = cupcake_dir.join(relative);
+    let metadata = fs::metadata(&full_path).ok().filter(|m| m.is_file())?;
+
+    let package = match kind {
+        PolicyFileKind::Policy => fs::read_to_string(&full_path)
+            .ok()
+            .and_then(|content| rego_package(&content)),
+        _ => None,
     };
 
-    let mut file = match File::open(&session_file) {
-        Ok(f) => f,
-        Err(_) => return empty_response,
-    };
+    Some(PolicyFile {
+        path: relative.to_string(),
+        kind,
+        package,
+        size: metadata.len(),
+        modified: metadata
+            .modified()
+            .map(system_time_to_iso)
+            .unwrap_or_default(),
+    })
+}
+
+/// Get the package a Rego file declares.
+fn rego_package(content: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        line.trim()
+            .strip_prefix("package ")
+            .map(|package| package.trim().to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    // =============================================================================
+    // UUID Format Tests
+    // =============================================================================
+
+    #[test]
+    fn test_is_uuid_format_valid() {
+        assert!(is_uuid_format("040f5516-2ff1-4738-8190-2b8248f631de"));
+        assert!(is_uuid_format("00000000-0000-0000-0000-000000000000"));
+        assert!(is_uuid_format("ffffffff-ffff-ffff-ffff-ffffffffffff"));
+        assert!(is_uuid_format("ABCDEF12-3456-7890-abcd-ef1234567890"));
+    }
+
+    #[test]
+    fn test_is_uuid_format_invalid() {
+        assert!(!is_uuid_format("agent-01cdb344"));
+        assert!(!is_uuid_format("not-a-uuid"));
+        assert!(!is_uuid_format(""));
+        assert!(!is_uuid_format("040f5516-2ff1-4738-8190")); // Too short
+        assert!(!is_uuid_format("040f5516-2ff1-4738-8190-2b8248f631de-extra")); // Too long
+        assert!(!is_uuid_format("040f5516-2ff1-4738-8190-2b8248f631dg")); // Invalid hex char 'g'
+        assert!(!is_uuid_format("040f55162ff1-4738-8190-2b8248f631de")); // Wrong segment length
+        assert!(!is_uuid_format("040f5516-2ff14738-8190-2b8248f631de")); // Missing dash
+    }
+
+    // =============================================================================
+    // Temp Project Detection Tests
+    // =============================================================================
+
+    #[test]
+    fn test_is_temp_project() {
+        assert!(is_temp_project(
+            "-private-var-folders-8s-x9ypf18955j7w6-zgzqtpclr0000gn-T--tmp08X8zw"
+        ));
+        assert!(!is_temp_project("-Users-ramos-cupcake-cupcake-rego-cupcake-rewrite"));
+        assert!(!is_temp_project("-Users-john-my-project"));
+        assert!(!is_temp_project("-home-user-code"));
+    }
+
+    // =============================================================================
+    // Path Encoding Tests
+    // =============================================================================
+
+    #[test]
+    fn test_encode_project_path() {
+        assert_eq!(encode_project_path("/Users/john/project"), "-Users-john-project");
+        assert_eq!(encode_project_path("/home/user/my project"), "-home-user-my-project");
+        assert_eq!(encode_project_path("/"), "-");
+        assert_eq!(encode_project_path("/a/b/c"), "-a-b-c");
+    }
+
+    // =============================================================================
+    // Relative Path Tests
+    // =============================================================================
+
+    #[test]
+    fn test_make_relative_path() {
+        assert_eq!(
+            make_relative_path("/Users/john/project/src/main.rs", "/Users/john/project"),
+            "src/main.rs"
+        );
+        assert_eq!(
+            make_relative_path("/Users/john/project/src/main.rs", "/Users/john/project/"),
+            "src/main.rs"
+        );
+        assert_eq!(
+            make_relative_path("/other/path/file.rs", "/Users/john/project"),
+            "/other/path/file.rs"
+        );
+        assert_eq!(
+            make_relative_path("/Users/john/project/file.rs", "/Users/john/project"),
+            "file.rs"
+        );
+    }
+
+    // =============================================================================
+    // Truncation Tests
+    // =============================================================================
 
-    // Use pre-built line index from the session index
-    let line_index = &index.line_offsets;
-    let total_count = line_index.len() as u32;
-    let offset = offset.unwrap_or(0);
-    let limit = limit.unwrap_or(200);
+    #[test]
+    fn test_truncate_string() {
+        assert_eq!(truncate_string("hello", 10), "hello");
+        assert_eq!(truncate_string("hello world", 5), "hello...");
+        assert_eq!(truncate_string("", 5), "");
+        assert_eq!(truncate_string("abc", 3), "abc");
+        assert_eq!(truncate_string("abcd", 3), "abc...");
+    }
 
-    // For descending order, we want the LAST lines first
-    if offset >= total_count {
-        return SessionEventsResponse {
-            events: Vec::new(),
-            total_count,
-            offset,
-            has_more: false,
-        };
+    #[test]
+    fn test_truncate_string_unicode() {
+        // Multi-byte UTF-8 characters should be handled correctly
+        let unicode_str = "hello";
+        assert_eq!(truncate_string(unicode_str, 3), "hel...");
+        assert_eq!(truncate_string(unicode_str, 10), "hello");
     }
 
-    // Calculate which lines to read (in original file order)
-    let available = total_count - offset;
-    let take_count = std::cmp::min(limit, available) as usize;
+    // =============================================================================
+    // Preview Extraction Tests
+    // =============================================================================
 
-    let start_idx = (total_count - offset - 1) as usize;
-    let end_idx = if take_count > start_idx + 1 { 0 } else { start_idx + 1 - take_count };
+    #[test]
+    fn test_extract_preview_from_text_content() {
+        let content = serde_json::json!([{
+            "type": "text",
+            "text": "This is a test message"
+        }]);
+        assert_eq!(
+            extract_preview_from_content(&content, &PreviewOptions::default()),
+            "This is a test message"
+        );
+    }
 
-    // Parse only the requested lines (in reverse order for descending)
-    let mut events = Vec::with_capacity(take_count);
+    #[test]
+    fn test_extract_preview_from_thinking() {
+        let content = serde_json::json!([{
+            "type": "thinking",
+            "thinking": "I am thinking about this"
+        }]);
+        assert_eq!(
+            extract_preview_from_content(&content, &PreviewOptions::default()),
+            "I am thinking about this"
+        );
+    }
 
-    for idx in (end_idx..=start_idx).rev() {
-        let (byte_offset, line_len) = line_index[idx];
+    #[test]
+    fn test_extract_preview_from_tool_use() {
+        let content = serde_json::json!([{
+            "type": "tool_use",
+            "name": "Edit"
+        }]);
+        assert_eq!(
+            extract_preview_from_content(&content, &PreviewOptions::default()),
+            "[Tool: Edit]"
+        );
+    }
 
-        if let Ok(line) = read_line_at_offset(&mut file, byte_offset, line_len) {
-            if let Some(event) = parse_session_event(&line, idx as u32, byte_offset) {
-                events.push(event);
-            }
-        }
+    #[test]
+    fn test_extract_preview_text_takes_precedence() {
+        // When both text and thinking are present, text should be preferred
+        let content = serde_json::json!([
+            {"type": "thinking", "thinking": "Thinking..."},
+            {"type": "text", "text": "Response text"}
+        ]);
+        assert_eq!(
+            extract_preview_from_content(&content, &PreviewOptions::default()),
+            "Response text"
+        );
     }
 
-    let has_more = (offset + take_count as u32) < total_count;
+    #[test]
+    fn test_extract_preview_custom_precedence() {
+        let content = serde_json::json!([
+            {"type": "text", "text": "Response text"},
+            {"type": "tool_use", "name": "Bash"}
+        ]);
+        let options = PreviewOptions {
+            max_chars: 500,
+            precedence: vec![PreviewSource::Tool, PreviewSource::Text],
+        };
+        assert_eq!(
+            extract_preview_from_content(&content, &options),
+            "[Tool: Bash]"
+        );
+    }
 
-    SessionEventsResponse {
-        events,
-        total_count,
-        offset,
-        has_more,
+    #[test]
+    fn test_extract_preview_custom_length() {
+        let content = serde_json::json!("abcdefghij");
+        let options = PreviewOptions {
+            max_chars: 4,
+            ..PreviewOptions::default()
+        };
+        assert_eq!(extract_preview_from_content(&content, &options), "abcd...");
     }
-}
 
-/// Get full SessionEvent objects for specific byte offsets.
-/// Used to fetch search match results efficiently.
-/// Returns events in the order provided (typically by sequence descending for newest-first).
-pub fn get_events_by_offsets(
-    project_path: &str,
-    session_id: &str,
-    offsets: Vec<(u32, u64)>, // (sequence, byte_offset) pairs
-) -> Vec<SessionEvent> {
-    let session_file = match get_session_file_path(project_path, session_id) {
-        Some(p) => p,
-        None => return Vec::new(),
-    };
+    #[test]
+    fn test_preview_options_deserialize_partial() {
+        let options: PreviewOptions = serde_json::from_str(r#"{"maxChars": 2000}"#).unwrap();
+        assert_eq!(options.max_chars, 2000);
+        assert_eq!(options.precedence, PreviewOptions::default().precedence);
+    }
 
-    let mut file = match File::open(&session_file) {
-        Ok(f) => f,
-        Err(_) => return Vec::new(),
-    };
+    #[test]
+    fn test_extract_preview_string_content() {
+        let content = serde_json::json!("Simple string content");
+        assert_eq!(
+            extract_preview_from_content(&content, &PreviewOptions::default()),
+            "Simple string content"
+        );
+    }
 
-    use std::io::{Seek, SeekFrom};
+    // =============================================================================
+    // Tool Result Detection Tests
+    // =============================================================================
 
-    let mut events = Vec::with_capacity(offsets.len());
+    #[test]
+    fn test_is_tool_result_content() {
+        let tool_result = serde_json::json!([{
+            "type": "tool_result",
+            "tool_use_id": "test123",
+            "content": "Result content"
+        }]);
+        assert!(is_tool_result_content(&tool_result));
 
-    for (sequence, byte_offset) in offsets {
-        // Seek to offset
-        if file.seek(SeekFrom::Start(byte_offset)).is_err() {
-            continue;
-        }
+        let text_content = serde_json::json!([{
+            "type": "text",
+            "text": "hello"
+        }]);
+        assert!(!is_tool_result_content(&text_content));
 
-        // Read the line
-        let mut reader = BufReader::new(&file);
-        let mut line = String::new();
-        if reader.read_line(&mut line).is_err() {
-            continue;
-        }
+        let string_content = serde_json::json!("plain string");
+        assert!(!is_tool_result_content(&string_content));
+    }
 
-        // Remove trailing newline
-        if line.ends_with('\n') {
-            line.pop();
-        }
-        if line.ends_with('\r') {
-            line.pop();
-        }
+    // =============================================================================
+    // Tool Name Extraction Tests
+    // =============================================================================
 
-        // Parse into SessionEvent
-        if let Some(event) = parse_session_event(&line, sequence, byte_offset) {
-            events.push(event);
-        }
+    #[test]
+    fn test_extract_tool_names_single() {
+        let content = serde_json::json!([{
+            "type": "tool_use",
+            "name": "Bash"
+        }]);
+        assert_eq!(extract_tool_names(&content), Some("Bash".to_string()));
     }
 
-    events
-}
+    #[test]
+    fn test_extract_tool_names_multiple() {
+        let content = serde_json::json!([
+            {"type": "tool_use", "name": "Read"},
+            {"type": "tool_use", "name": "Write"}
+        ]);
+        assert_eq!(extract_tool_names(&content), Some("Read, Write".to_string()));
+    }
 
-/// Get paginated events from a sub-agent session for the log viewer.
-/// Events are returned in descending order (newest first).
-pub fn get_subagent_events(
-    project_path: &str,
-    agent_id: &str,
-    offset: Option<u32>,
-    limit: Option<u32>,
-) -> SessionEventsResponse {
-    let empty_response = SessionEventsResponse {
-        events: Vec::new(),
-        total_count: 0,
-        offset: 0,
-        has_more: false,
-    };
+    #[test]
+    fn test_extract_tool_names_with_thinking() {
+        let content = serde_json::json!([
+            {"type": "thinking", "thinking": "Let me think..."},
+            {"type": "tool_use", "name": "Edit"}
+        ]);
+        assert_eq!(extract_tool_names(&content), Some("thinking, Edit".to_string()));
+    }
 
-    let agent_file = match get_subagent_file_path(project_path, agent_id) {
-        Some(p) => p,
-        None => return empty_response,
-    };
+    #[test]
+    fn test_extract_tool_names_none() {
+        let content = serde_json::json!([{
+            "type": "text",
+            "text": "Just text"
+        }]);
+        assert_eq!(extract_tool_names(&content), None);
+    }
 
-    let mut file = match File::open(&agent_file) {
-        Ok(f) => f,
-        Err(_) => return empty_response,
-    };
+    // =============================================================================
+    // Event Parsing Tests
+    // =============================================================================
 
-    // Phase 1: Build line index (fast - no JSON parsing)
-    let line_index = match build_line_index(&mut file) {
-        Ok(idx) => idx,
-        Err(_) => return empty_response,
-    };
+    #[test]
+    fn test_parse_session_event_user_message() {
+        let line = r#"{"type":"user","userType":"external","uuid":"abc-123-456-789-012","message":{"content":"Hello world"},"timestamp":"2024-01-01T00:00:00Z"}"#;
+        let event = parse_session_event(line, 0, 0).unwrap();
 
-    let total_count = line_index.len() as u32;
-    if total_count == 0 {
-        return empty_response;
+        assert_eq!(event.event_type, "user");
+        assert_eq!(event.uuid, Some("abc-123-456-789-012".to_string()));
+        assert_eq!(event.user_type, Some("external".to_string()));
+        assert_eq!(event.preview, "Hello world");
+        assert_eq!(event.sequence, 0);
+        assert_eq!(event.byte_offset, 0);
     }
 
-    let offset = offset.unwrap_or(0);
-    let limit = limit.unwrap_or(200);
+    #[test]
+    fn test_parse_session_event_assistant_with_tool() {
+        let line = r#"{"type":"assistant","uuid":"def-456","message":{"content":[{"type":"tool_use","name":"Bash","input":{"command":"ls"}}]},"timestamp":"2024-01-01T00:00:01Z"}"#;
+        let event = parse_session_event(line, 1, 100).unwrap();
 
-    if offset >= total_count {
-        return SessionEventsResponse {
-            events: Vec::new(),
-            total_count,
-            offset,
-            has_more: false,
-        };
+        assert_eq!(event.event_type, "assistant");
+        assert_eq!(event.tool_name, Some("Bash".to_string()));
+        assert_eq!(event.sequence, 1);
+        assert_eq!(event.byte_offset, 100);
     }
 
-    let available = total_count - offset;
-    let take_count = std::cmp::min(limit, available) as usize;
-    let start_idx = (total_count - offset - 1) as usize;
-    let end_idx = if take_count > start_idx + 1 { 0 } else { start_idx + 1 - take_count };
+    #[test]
+    fn test_parse_session_event_compact_boundary() {
+        let line = r#"{"type":"system","subtype":"compact_boundary","uuid":"sys-001","compactMetadata":{"trigger":"automatic","preTokens":50000},"timestamp":"2024-01-01T00:00:00Z"}"#;
+        let event = parse_session_event(line, 0, 0).unwrap();
 
-    let mut events = Vec::with_capacity(take_count);
+        assert_eq!(event.event_type, "system");
+        assert_eq!(event.subtype, Some("compact_boundary".to_string()));
+        assert!(event.compact_metadata.is_some());
+        let meta = event.compact_metadata.unwrap();
+        assert_eq!(meta.trigger, "automatic");
+        assert_eq!(meta.pre_tokens, 50000);
+    }
 
-    for idx in (end_idx..=start_idx).rev() {
-        let (byte_offset, line_len) = line_index[idx];
+    #[test]
+    fn test_parse_session_event_summary() {
+        let line = r#"{"type":"summary","uuid":"sum-001","summary":"Session involved creating a React component","leafUuid":"leaf-001","timestamp":"2024-01-01T00:00:00Z"}"#;
+        let event = parse_session_event(line, 0, 0).unwrap();
 
-        if let Ok(line) = read_line_at_offset(&mut file, byte_offset, line_len) {
-            if let Some(event) = parse_session_event(&line, idx as u32, byte_offset) {
-                events.push(event);
-            }
-        }
+        assert_eq!(event.event_type, "summary");
+        assert_eq!(event.summary, Some("Session involved creating a React component".to_string()));
+        assert_eq!(event.leaf_uuid, Some("leaf-001".to_string()));
     }
 
-    let has_more = (offset + take_count as u32) < total_count;
+    #[test]
+    fn test_parse_session_event_with_task_launch() {
+        let line = r#"{"type":"user","uuid":"task-123","toolUseResult":{"agentId":"abc123","description":"Research task","isAsync":true,"status":"async_launched"},"timestamp":"2024-01-01T00:00:00Z"}"#;
+        let event = parse_session_event(line, 0, 0).unwrap();
 
-    SessionEventsResponse {
-        events,
-        total_count,
-        offset,
-        has_more,
+        assert_eq!(event.launched_agent_id, Some("abc123".to_string()));
+        assert_eq!(event.launched_agent_description, Some("Research task".to_string()));
+        assert_eq!(event.launched_agent_is_async, Some(true));
+        assert_eq!(event.launched_agent_status, Some("async_launched".to_string()));
     }
-}
 
-/// Get the raw JSON for a specific event in a sub-agent session by its byte offset.
-pub fn get_subagent_raw_json(project_path: &str, agent_id: &str, byte_offset: u64) -> Option<String> {
-    let agent_file = get_subagent_file_path(project_path, agent_id)?;
-    let mut file = File::open(&agent_file).ok()?;
+    #[test]
+    fn test_parse_session_event_tool_result() {
+        let line = r#"{"type":"user","uuid":"tr-001","message":{"content":[{"type":"tool_result","tool_use_id":"tu-001","content":"Command output"}]}}"#;
+        let event = parse_session_event(line, 0, 0).unwrap();
 
-    use std::io::{BufRead, BufReader, Seek, SeekFrom};
+        assert!(event.is_tool_result);
+    }
 
-    file.seek(SeekFrom::Start(byte_offset)).ok()?;
-    let mut reader = BufReader::new(file);
-    let mut line = String::new();
-    reader.read_line(&mut line).ok()?;
+    #[test]
+    fn test_parse_session_event_meta_context() {
+        let line = r#"{"type":"user","uuid":"meta-001","isMeta":true,"message":{"content":"Context injection"}}"#;
+        let event = parse_session_event(line, 0, 0).unwrap();
 
-    // Remove trailing newline
-    if line.ends_with('\n') {
-        line.pop();
-    }
-    if line.ends_with('\r') {
-        line.pop();
+        assert!(event.is_meta);
     }
 
-    Some(line)
-}
+    #[test]
+    fn test_parse_session_event_token_usage() {
+        let line = r#"{"type":"assistant","uuid":"usage-001","message":{"content":[{"type":"text","text":"Done"}],"usage":{"input_tokens":12,"output_tokens":340,"cache_read_input_tokens":5000,"cache_creation_input_tokens":800}}}"#;
+        let event = parse_session_event(line, 0, 0).unwrap();
 
-// =============================================================================
-// Policy Evaluation Telemetry
-// =============================================================================
+        let usage = event.usage.unwrap();
+        assert_eq!(usage.input_tokens, 12);
+        assert_eq!(usage.output_tokens, 340);
+        assert_eq!(usage.cache_read_input_tokens, 5000);
+        assert_eq!(usage.cache_creation_input_tokens, 800);
+    }
 
-/// Summary of a policy evaluation for list display.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct PolicyEvaluation {
-    /// Filename of the telemetry file
-    pub filename: String,
-    /// Timestamp (ISO 8601)
-    pub timestamp: String,
-    /// Event type (e.g., "PreToolUse")
-    pub event_type: Option<String>,
-    /// Tool name (e.g., "Bash")
-    pub tool_name: Option<String>,
-    /// Final decision (e.g., "Allow", "Block")
-    pub decision: Option<String>,
-    /// Total duration in milliseconds
-    pub duration_ms: u64,
-    /// Trace ID
-    pub trace_id: String,
-}
+    #[test]
+    fn test_parse_session_event_usage_only_on_assistant() {
+        let line = r#"{"type":"user","uuid":"user-001","message":{"content":"Hi","usage":{"input_tokens":1}}}"#;
+        let event = parse_session_event(line, 0, 0).unwrap();
 
-/// Get the policy telemetry directory for a project.
-fn get_telemetry_dir(project_path: &str) -> PathBuf {
-    PathBuf::from(project_path)
-        .join(".cupcake")
-        .join("telemetry")
-}
+        assert!(event.usage.is_none());
+    }
 
-/// Get list of policy evaluations for a project.
-pub fn get_policy_evaluations(project_path: &str) -> Vec<PolicyEvaluation> {
-    let telemetry_dir = get_telemetry_dir(project_path);
+    #[test]
+    fn test_parse_session_event_invalid_json() {
+        let line = "not valid json";
+        let event = parse_session_event(line, 0, 0);
 
-    if !telemetry_dir.exists() {
-        return Vec::new();
+        assert!(event.is_none());
     }
 
-    let entries = match fs::read_dir(&telemetry_dir) {
-        Ok(e) => e,
-        Err(_) => return Vec::new(),
-    };
+    // =============================================================================
+    // Edit Target Tests
+    // =============================================================================
 
-    let mut evaluations: Vec<PolicyEvaluation> = Vec::new();
+    #[test]
+    fn test_edit_target_path_notebook() {
+        let edit = serde_json::json!({"file_path": "/p/src/main.rs", "old_string": "a"});
+        let notebook =
+            serde_json::json!({"notebook_path": "/p/analysis.ipynb", "new_source": "x = 1"});
+
+        assert_eq!(edit_target_path(&edit), Some("/p/src/main.rs"));
+        assert_eq!(edit_target_path(&notebook), Some("/p/analysis.ipynb"));
+        assert_eq!(edit_target_path(&serde_json::json!({})), None);
+    }
 
-    for entry in entries.flatten() {
-        let path = entry.path();
+    // =============================================================================
+    // File Snapshot Tests
+    // =============================================================================
 
-        // Only process .json files
-        if path.extension().map(|e| e != "json").unwrap_or(true) {
-            continue;
+    fn file_op(kind: FileOperationKind, sequence: u32, old: &str, new: &str) -> FileOperation {
+        FileOperation {
+            kind,
+            diff: FileDiff {
+                old_string: old.to_string(),
+                new_string: new.to_string(),
+                sequence,
+                timestamp: None,
+                edited_by: None,
+                cell_id: None,
+                cell_edit_mode: None,
+                language: "plaintext".to_string(),
+                applies_cleanly: None,
+                reverts_cleanly: None,
+            },
         }
+    }
 
-        let filename = match path.file_name() {
-            Some(n) => n.to_string_lossy().to_string(),
-            None => continue,
-        };
-
-        // Parse the JSON file to extract summary info
-        let content = match fs::read_to_string(&path) {
-            Ok(c) => c,
-            Err(_) => continue,
-        };
-
-        let span: Value = match serde_json::from_str(&content) {
-            Ok(v) => v,
-            Err(_) => continue,
-        };
-
-        // Extract fields from the CupcakeSpan
-        let timestamp = span
-            .get("timestamp")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
-
-        let trace_id = span
-            .get("trace_id")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
-
-        let raw_event = span.get("raw_event");
-        let event_type = raw_event
-            .and_then(|e| e.get("hook_event_name"))
-            .and_then(|v| v.as_str())
-            .map(String::from);
+    #[test]
+    fn test_replay_file_operations() {
+        let edit = FileOperationKind::Edit { replace_all: false };
+        let ops = vec![
+            file_op(FileOperationKind::Write, 0, "", "a b a\n"),
+            file_op(edit, 1, "a", "x"),
+            file_op(FileOperationKind::Edit { replace_all: true }, 2, "a", "y"),
+            file_op(edit, 3, "missing", "z"),
+            file_op(FileOperationKind::NotebookCell, 4, "", "cell"),
+        ];
+
+        let (content, skipped) = replay_file_operations(String::new(), &ops);
+        assert_eq!(content, "x b y\n");
+        assert_eq!(skipped, vec![3, 4]);
+
+        let (content, _) = replay_file_operations(String::new(), &ops[..2]);
+        assert_eq!(content, "x b a\n");
+    }
 
-        let tool_name = raw_event
-            .and_then(|e| e.get("tool_name"))
-            .and_then(|v| v.as_str())
-            .map(String::from);
+    #[test]
+    fn test_replay_file_operations_from_base() {
+        let ops = vec![file_op(
+            FileOperationKind::Edit { replace_all: false },
+            0,
+            "old",
+            "new",
+        )];
+
+        let (content, skipped) = replay_file_operations("keep old".to_string(), &ops);
+        assert_eq!(content, "keep new");
+        assert!(skipped.is_empty());
+    }
 
-        // Extract decision from response or phases
-        // final_decision is a tagged union like {"Allow": {...}} or {"Deny": {...}}
-        let decision = span
-            .get("response")
-            .and_then(|r| r.get("decision"))
-            .and_then(|d| {
-                // Tagged union - get the first key
-                d.as_object().and_then(|obj| obj.keys().next().cloned())
-            })
-            .or_else(|| {
-                // Try to get from last phase's final_decision
-                span.get("phases")
-                    .and_then(|p| p.as_array())
-                    .and_then(|arr| arr.last())
-                    .and_then(|phase| phase.get("evaluation"))
-                    .and_then(|eval| eval.get("final_decision"))
-                    .and_then(|d| {
-                        // Tagged union - get the first key
-                        d.as_object().and_then(|obj| obj.keys().next().cloned())
-                    })
-            });
+    #[test]
+    fn test_apply_file_operation_conflicts() {
+        let edit = file_op(
+            FileOperationKind::Edit { replace_all: false },
+            0,
+            "old",
+            "new",
+        );
+        let create = file_op(
+            FileOperationKind::Edit { replace_all: false },
+            1,
+            "",
+            "new file",
+        );
 
-        let duration_ms = span
-            .get("total_duration_ms")
-            .and_then(|v| v.as_u64())
-            .unwrap_or(0);
-
-        evaluations.push(PolicyEvaluation {
-            filename,
-            timestamp,
-            event_type,
-            tool_name,
-            decision,
-            duration_ms,
-            trace_id,
-        });
+        assert_eq!(
+            apply_file_operation("an old line", &edit).unwrap(),
+            "an new line"
+        );
+        assert!(apply_file_operation("rewritten", &edit).is_err());
+        assert_eq!(apply_file_operation("", &create).unwrap(), "new file");
+        assert!(apply_file_operation("existing", &create).is_err());
     }
 
-    // Sort by timestamp descending (newest first)
-    evaluations.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
-    evaluations
-}
-
-/// Get the raw JSON content of a specific policy evaluation.
-pub fn get_policy_evaluation(project_path: &str, filename: &str) -> Option<String> {
-    let telemetry_dir = get_telemetry_dir(project_path);
-    let file_path = telemetry_dir.join(filename);
+    #[test]
+    fn test_revert_file_operation() {
+        let edit = file_op(
+            FileOperationKind::Edit { replace_all: false },
+            0,
+            "old",
+            "new",
+        );
+        let all = file_op(FileOperationKind::Edit { replace_all: true }, 1, "a", "b");
 
-    if !file_path.exists() {
-        return None;
+        assert_eq!(
+            revert_file_operation("a new line", &edit).unwrap(),
+            "a old line"
+        );
+        assert!(revert_file_operation("new and new", &edit).is_err());
+        assert!(revert_file_operation("gone", &edit).is_err());
+        assert_eq!(revert_file_operation("b b", &all).unwrap(), "a a");
     }
 
-    fs::read_to_string(&file_path).ok()
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::time::Instant;
+    #[test]
+    fn test_launched_agent_ids_dedupes() {
+        use std::io::Write;
+
+        let mut tmp = tempfile::NamedTempFile::new().unwrap();
+        for line in [
+            r#"{"type":"user","toolUseResult":{"agentId":"a1","isAsync":true,"status":"async_launched"}}"#,
+            r#"{"type":"assistant","message":{"content":[]}}"#,
+            r#"{"type":"user","toolUseResult":{"agentId":"a1","status":"completed"}}"#,
+            r#"{"type":"user","toolUseResult":{"agentId":"b2","status":"completed"}}"#,
+        ] {
+            writeln!(tmp, "{}", line).unwrap();
+        }
 
-    // =============================================================================
-    // UUID Format Tests
-    // =============================================================================
+        assert_eq!(launched_agent_ids(tmp.path()), vec!["a1", "b2"]);
+    }
 
     #[test]
-    fn test_is_uuid_format_valid() {
-        assert!(is_uuid_format("040f5516-2ff1-4738-8190-2b8248f631de"));
-        assert!(is_uuid_format("00000000-0000-0000-0000-000000000000"));
-        assert!(is_uuid_format("ffffffff-ffff-ffff-ffff-ffffffffffff"));
-        assert!(is_uuid_format("ABCDEF12-3456-7890-abcd-ef1234567890"));
+    fn test_subagent_origin_and_task_launch() {
+        use std::io::Write;
+
+        let mut agent = tempfile::NamedTempFile::new().unwrap();
+        writeln!(
+            agent,
+            r#"{{"type":"user","sessionId":"s-1","agentId":"a1","message":{{"role":"user","content":"Find the bug"}}}}"#
+        )
+        .unwrap();
+        let origin = read_subagent_origin(agent.path());
+        assert_eq!(origin.session_id.as_deref(), Some("s-1"));
+        assert_eq!(origin.prompt.as_deref(), Some("Find the bug"));
+
+        let mut session = tempfile::NamedTempFile::new().unwrap();
+        for line in [
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","id":"tu-1","name":"Task","input":{"description":"Other","prompt":"Write docs"}}]}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","id":"tu-2","name":"Task","input":{"description":"Debug","prompt":"Find the bug"}}]}}"#,
+        ] {
+            writeln!(session, "{}", line).unwrap();
+        }
+
+        assert_eq!(
+            find_task_launch(session.path(), "Find the bug"),
+            Some(("tu-2".to_string(), Some("Debug".to_string())))
+        );
+        assert_eq!(find_task_launch(session.path(), "Unknown"), None);
     }
 
     #[test]
-    fn test_is_uuid_format_invalid() {
-        assert!(!is_uuid_format("agent-01cdb344"));
-        assert!(!is_uuid_format("not-a-uuid"));
-        assert!(!is_uuid_format(""));
-        assert!(!is_uuid_format("040f5516-2ff1-4738-8190")); // Too short
-        assert!(!is_uuid_format("040f5516-2ff1-4738-8190-2b8248f631de-extra")); // Too long
-        assert!(!is_uuid_format("040f5516-2ff1-4738-8190-2b8248f631dg")); // Invalid hex char 'g'
-        assert!(!is_uuid_format("040f55162ff1-4738-8190-2b8248f631de")); // Wrong segment length
-        assert!(!is_uuid_format("040f5516-2ff14738-8190-2b8248f631de")); // Missing dash
-    }
+    fn test_subagent_only_file_edit_diffs() {
+        use serde_json::json;
+
+        let transcripts = tempfile::tempdir().unwrap();
+        let project = tempfile::tempdir().unwrap();
+        let project_path = project.path().to_str().unwrap();
+        let lib_path = project.path().join("src/lib.rs");
+        fs::create_dir_all(lib_path.parent().unwrap()).unwrap();
+        fs::write(&lib_path, "fn b() {}\n").unwrap();
+        let lib_path = lib_path.to_str().unwrap();
+
+        let write_lines = |name: &str, lines: &[Value]| {
+            let text: Vec<String> = lines.iter().map(Value::to_string).collect();
+            let path = transcripts.path().join(name);
+            fs::write(&path, text.join("\n")).unwrap();
+            path
+        };
+        let session = write_lines(
+            "s-1.jsonl",
+            &[json!({
+                "type": "user",
+                "timestamp": "2026-01-01T00:00:05Z",
+                "toolUseResult": {"agentId": "a1", "status": "completed"}
+            })],
+        );
+        write_lines(
+            "agent-a1.jsonl",
+            &[
+                json!({
+                    "type": "assistant",
+                    "timestamp": "2026-01-01T00:00:02Z",
+                    "message": {"content": [{
+                        "type": "tool_use",
+                        "id": "tu-1",
+                        "name": "Edit",
+                        "input": {
+                            "file_path": lib_path,
+                            "old_string": "fn a() {}",
+                            "new_string": "fn b() {}"
+                        }
+                    }]}
+                }),
+                json!({
+                    "type": "user",
+                    "timestamp": "2026-01-01T00:00:03Z",
+                    "toolUseResult": {"filePath": lib_path, "originalFile": "fn a() {}\n"}
+                }),
+            ],
+        );
 
-    // =============================================================================
-    // Temp Project Detection Tests
-    // =============================================================================
+        let edits = extract_file_edits(&session, project_path);
+        assert!(edits.is_empty());
+        let edits = merge_transcript_file_edits(&session, project_path, edits);
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].path, "src/lib.rs");
+        assert_eq!(edits[0].edited_by.as_deref(), Some("a1"));
+
+        // The sub-agent's transcript supplies the diff and the file's original content
+        let diffs = session_file_diffs(&session, project_path, &edits[0].path);
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].sequence, 0);
+        assert_eq!(diffs[0].edited_by.as_deref(), Some("a1"));
+        assert_eq!(diffs[0].new_string, "fn b() {}");
+        assert_eq!(diffs[0].applies_cleanly, Some(false));
+        assert_eq!(diffs[0].reverts_cleanly, Some(true));
+        assert_eq!(
+            find_original_file_content(&session, project_path, "src/lib.rs").as_deref(),
+            Some("fn a() {}\n")
+        );
+    }
 
     #[test]
-    fn test_is_temp_project() {
-        assert!(is_temp_project(
-            "-private-var-folders-8s-x9ypf18955j7w6-zgzqtpclr0000gn-T--tmp08X8zw"
-        ));
-        assert!(!is_temp_project("-Users-ramos-cupcake-cupcake-rego-cupcake-rewrite"));
-        assert!(!is_temp_project("-Users-john-my-project"));
-        assert!(!is_temp_project("-home-user-code"));
-    }
+    fn test_tool_edit_stats() {
+        let edit = serde_json::json!({"file_path": "/p/a.rs", "old_string": "a\nb", "new_string": "a\nc\nd"});
+        let multi = serde_json::json!({
+            "file_path": "/p/a.rs",
+            "edits": [{"old_string": "x", "new_string": "y"}, {"old_string": "z", "new_string": ""}]
+        });
+        let write = serde_json::json!({"file_path": "/p/b.rs", "content": "1\n2\n3\n"});
 
-    // =============================================================================
-    // Path Encoding Tests
-    // =============================================================================
+        let expected = EditStats {
+            lines_added: 2,
+            lines_removed: 1,
+            edit_count: 1,
+            generated: false,
+        };
+        assert_eq!(tool_edit_stats("Edit", &edit), Some(("/p/a.rs", expected)));
+        assert_eq!(
+            tool_edit_stats("MultiEdit", &multi).map(|(_, s)| s),
+            Some(EditStats {
+                lines_added: 1,
+                lines_removed: 2,
+                edit_count: 2,
+                generated: false,
+            })
+        );
+        assert_eq!(tool_edit_stats("Write", &write).unwrap().1.lines_added, 3);
+        assert_eq!(tool_edit_stats("Read", &write), None);
 
-    #[test]
-    fn test_encode_project_path() {
-        assert_eq!(encode_project_path("/Users/john/project"), "-Users-john-project");
-        assert_eq!(encode_project_path("/home/user/my project"), "-home-user-my-project");
-        assert_eq!(encode_project_path("/"), "-");
-        assert_eq!(encode_project_path("/a/b/c"), "-a-b-c");
+        let lock =
+            serde_json::json!({"file_path": "/p/Cargo.lock", "old_string": "a", "new_string": "b"});
+        assert!(tool_edit_stats("Edit", &lock).unwrap().1.generated);
     }
 
-    // =============================================================================
-    // Relative Path Tests
-    // =============================================================================
-
     #[test]
-    fn test_make_relative_path() {
-        assert_eq!(
-            make_relative_path("/Users/john/project/src/main.rs", "/Users/john/project"),
-            "src/main.rs"
-        );
+    fn test_parse_mv_commands() {
         assert_eq!(
-            make_relative_path("/Users/john/project/src/main.rs", "/Users/john/project/"),
-            "src/main.rs"
+            parse_mv_commands("cd /p && git mv -f src/old.rs src/new.rs"),
+            vec![("src/old.rs".to_string(), "src/new.rs".to_string())]
         );
         assert_eq!(
-            make_relative_path("/other/path/file.rs", "/Users/john/project"),
-            "/other/path/file.rs"
+            parse_mv_commands("mv 'my file.txt' docs/ ; ls"),
+            vec![("my file.txt".to_string(), "docs/my file.txt".to_string())]
         );
         assert_eq!(
-            make_relative_path("/Users/john/project/file.rs", "/Users/john/project"),
-            "file.rs"
+            parse_mv_commands("mv a.rs b.rs lib"),
+            vec![
+                ("a.rs".to_string(), "lib/a.rs".to_string()),
+                ("b.rs".to_string(), "lib/b.rs".to_string()),
+            ]
         );
+        assert!(parse_mv_commands("echo mv a b | cat").is_empty());
+        assert!(parse_mv_commands("mv onlyone").is_empty());
     }
 
-    // =============================================================================
-    // Truncation Tests
-    // =============================================================================
-
     #[test]
-    fn test_truncate_string() {
-        assert_eq!(truncate_string("hello", 10), "hello");
-        assert_eq!(truncate_string("hello world", 5), "hello...");
-        assert_eq!(truncate_string("", 5), "");
-        assert_eq!(truncate_string("abc", 3), "abc");
-        assert_eq!(truncate_string("abcd", 3), "abc...");
-    }
+    fn test_apply_rename_tracks_original_path() {
+        let mut ops = HashMap::new();
+        let mut prior = HashSet::new();
+        let mut timestamps = HashMap::new();
+        let mut stats = HashMap::new();
+        let mut renamed = HashMap::new();
+        let mut rename = |src: &str, dst: &str, ops: &mut HashMap<String, FileEditType>| {
+            apply_rename(
+                src,
+                dst,
+                None,
+                ops,
+                &mut prior,
+                &mut timestamps,
+                &mut stats,
+                &mut renamed,
+            )
+        };
 
-    #[test]
-    fn test_truncate_string_unicode() {
-        // Multi-byte UTF-8 characters should be handled correctly
-        let unicode_str = "hello";
-        assert_eq!(truncate_string(unicode_str, 3), "hel...");
-        assert_eq!(truncate_string(unicode_str, 10), "hello");
-    }
+        rename("a.rs", "b.rs", &mut ops);
+        rename("b.rs", "c.rs", &mut ops);
+        ops.insert("new.rs".to_string(), FileEditType::Added);
+        rename("new.rs", "moved.rs", &mut ops);
 
-    // =============================================================================
-    // Preview Extraction Tests
-    // =============================================================================
+        assert_eq!(renamed.get("c.rs"), Some(&"a.rs".to_string()));
+        assert!(!renamed.contains_key("moved.rs"));
+        assert_eq!(ops.get("moved.rs"), Some(&FileEditType::Added));
+        assert!(!ops.contains_key("a.rs") && !ops.contains_key("b.rs"));
+        assert_eq!(
+            renamed_edit_type(FileEditType::Modified, Some("a.rs"), 0),
+            FileEditType::Renamed
+        );
+    }
 
     #[test]
-    fn test_extract_preview_from_text_content() {
-        let content = serde_json::json!([{
-            "type": "text",
-            "text": "This is a test message"
-        }]);
-        assert_eq!(extract_preview_from_content(&content), "This is a test message");
+    fn test_filter_file_edits() {
+        let edits: Vec<FileEdit> = [
+            "src/main.rs",
+            "src/gen/api.rs",
+            "src/test/util.rs",
+            "README.md",
+        ]
+        .iter()
+        .map(|path| FileEdit {
+            path: path.to_string(),
+            edit_type: FileEditType::Modified,
+            last_edited_at: None,
+            edited_by: None,
+            lines_added: 0,
+            lines_removed: 0,
+            edit_count: 1,
+            renamed_from: None,
+            is_generated: false,
+        })
+        .collect();
+        let paths = |patterns: &[&str]| -> Vec<String> {
+            let patterns: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+            filter_file_edits(edits.clone(), &patterns)
+                .unwrap()
+                .into_iter()
+                .map(|e| e.path)
+                .collect()
+        };
+
+        assert_eq!(
+            paths(&["src/**/*.rs", "!**/test/**"]),
+            vec!["src/main.rs", "src/gen/api.rs"]
+        );
+        assert_eq!(paths(&["src/*.rs"]), vec!["src/main.rs"]);
+        assert_eq!(paths(&["!src/gen/**"]).len(), 3);
+        assert!(filter_file_edits(edits.clone(), &["src/[".to_string()]).is_err());
     }
 
     #[test]
-    fn test_extract_preview_from_thinking() {
-        let content = serde_json::json!([{
-            "type": "thinking",
-            "thinking": "I am thinking about this"
-        }]);
-        assert_eq!(extract_preview_from_content(&content), "I am thinking about this");
+    fn test_disk_compatibility() {
+        let edit = file_op(
+            FileOperationKind::Edit { replace_all: false },
+            0,
+            "old",
+            "new",
+        );
+        let write = file_op(FileOperationKind::Write, 1, "", "whole file");
+
+        assert_eq!(
+            disk_compatibility("an old line", &edit, None),
+            (Some(true), Some(false))
+        );
+        assert_eq!(
+            disk_compatibility("a new line", &edit, None),
+            (Some(false), Some(true))
+        );
+        assert_eq!(
+            disk_compatibility("whole file", &write, Some("earlier")),
+            (Some(true), Some(true))
+        );
+        assert_eq!(
+            disk_compatibility("earlier", &write, Some("earlier")),
+            (Some(true), Some(false))
+        );
+        // Changed since the Write was recorded: writing would clobber it
+        assert_eq!(
+            disk_compatibility("changed", &write, Some("earlier")),
+            (Some(false), Some(false))
+        );
+        assert_eq!(
+            disk_compatibility("changed", &write, None),
+            (Some(false), Some(false))
+        );
     }
 
     #[test]
-    fn test_extract_preview_from_tool_use() {
-        let content = serde_json::json!([{
-            "type": "tool_use",
-            "name": "Edit"
-        }]);
-        assert_eq!(extract_preview_from_content(&content), "[Tool: Edit]");
+    fn test_replay_contents_before_writes() {
+        let edit = FileOperationKind::Edit { replace_all: false };
+        let ops = vec![
+            file_op(edit, 0, "a", "b"),
+            file_op(FileOperationKind::Write, 1, "", "written"),
+            file_op(edit, 2, "written", "edited"),
+            file_op(FileOperationKind::Write, 3, "", "final"),
+        ];
+
+        assert_eq!(
+            replay_contents_before_writes(Some("a".to_string()), &ops),
+            vec![
+                None,
+                Some("b".to_string()),
+                None,
+                Some("edited".to_string())
+            ]
+        );
+        // Unknown until the first Write
+        assert_eq!(
+            replay_contents_before_writes(None, &ops),
+            vec![None, None, None, Some("edited".to_string())]
+        );
     }
 
     #[test]
-    fn test_extract_preview_text_takes_precedence() {
-        // When both text and thinking are present, text should be preferred
-        let content = serde_json::json!([
-            {"type": "thinking", "thinking": "Thinking..."},
-            {"type": "text", "text": "Response text"}
-        ]);
-        assert_eq!(extract_preview_from_content(&content), "Response text");
+    fn test_apply_operations() {
+        let edit = FileOperationKind::Edit { replace_all: false };
+        let new_result = || ApplyEditResult {
+            file_path: "f".to_string(),
+            applied: Vec::new(),
+            already_applied: Vec::new(),
+            conflicts: Vec::new(),
+            written: false,
+        };
+
+        let change = file_op(edit, 0, "old", "new");
+        let write = file_op(FileOperationKind::Write, 1, "", "written");
+        let ops = [(&change, None), (&write, Some("a new line"))];
+
+        let mut result = new_result();
+        assert_eq!(
+            apply_operations("an old line".to_string(), &ops, &mut result),
+            "written"
+        );
+        assert_eq!(result.applied, vec![0, 1]);
+
+        // Already written: nothing to do
+        let mut result = new_result();
+        apply_operations("written".to_string(), &ops[1..], &mut result);
+        assert_eq!(result.already_applied, vec![1]);
+
+        // Changed since the Write: conflict instead of clobbering
+        let mut result = new_result();
+        let content = apply_operations("hand edited".to_string(), &ops[1..], &mut result);
+        assert_eq!(content, "hand edited");
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(result.conflicts[0].sequence, 1);
+
+        // new_string present once and old_string gone: already applied
+        let mut result = new_result();
+        apply_operations("a new line".to_string(), &ops[..1], &mut result);
+        assert_eq!(result.already_applied, vec![0]);
+
+        // new_string merely appears somewhere (twice here): a conflict, not already applied
+        let mut result = new_result();
+        apply_operations("renew and new".to_string(), &ops[..1], &mut result);
+        assert!(result.already_applied.is_empty());
+        assert_eq!(result.conflicts.len(), 1);
     }
 
     #[test]
-    fn test_extract_preview_string_content() {
-        let content = serde_json::json!("Simple string content");
-        assert_eq!(extract_preview_from_content(&content), "Simple string content");
+    fn test_multi_edits() {
+        let input = serde_json::json!({
+            "file_path": "/p/a.rs",
+            "edits": [{"old_string": "a", "new_string": "b"}, {"old_string": "c", "new_string": "d"}]
+        });
+
+        assert_eq!(multi_edits(&input).len(), 2);
+        assert!(multi_edits(&serde_json::json!({"file_path": "/p/a.rs"})).is_empty());
     }
 
     // =============================================================================
-    // Tool Result Detection Tests
+    // Raw JSON Formatting Tests
     // =============================================================================
 
     #[test]
-    fn test_is_tool_result_content() {
-        let tool_result = serde_json::json!([{
-            "type": "tool_result",
-            "tool_use_id": "test123",
-            "content": "Result content"
-        }]);
-        assert!(is_tool_result_content(&tool_result));
+    fn test_format_raw_json_passthrough() {
+        let line = r#"{"type":"user","message":{"content":"hi"}}"#;
+        let raw = format_raw_json(line, false, None).unwrap();
 
-        let text_content = serde_json::json!([{
-            "type": "text",
-            "text": "hello"
-        }]);
-        assert!(!is_tool_result_content(&text_content));
+        assert_eq!(raw.json, line);
+        assert_eq!(raw.original_bytes, line.len() as u64);
+        assert_eq!(raw.returned_bytes, raw.original_bytes);
+    }
 
-        let string_content = serde_json::json!("plain string");
-        assert!(!is_tool_result_content(&string_content));
+    #[test]
+    fn test_format_raw_json_truncates_nested_strings() {
+        let long = "x".repeat(100);
+        let line = serde_json::json!({
+            "type": "assistant",
+            "message": {"content": [{"type": "text", "text": long}, {"type": "text", "text": "short"}]}
+        })
+        .to_string();
+        let raw = format_raw_json(&line, true, Some(10)).unwrap();
+
+        assert_eq!(raw.truncated_strings, 1);
+        assert!(raw.json.contains("xxxxxxxxxx... [90 more chars]"));
+        assert!(raw.json.contains("\"short\""));
+        assert!(raw.json.contains('\n'), "pretty output should span lines");
+        assert_eq!(raw.original_bytes, line.len() as u64);
+    }
+
+    #[test]
+    fn test_format_raw_json_invalid() {
+        assert!(format_raw_json("not json", true, None).is_err());
     }
 
     // =============================================================================
-    // Tool Name Extraction Tests
+    // Cursor Pagination Tests
     // =============================================================================
 
-    #[test]
-    fn test_extract_tool_names_single() {
-        let content = serde_json::json!([{
-            "type": "tool_use",
-            "name": "Bash"
-        }]);
-        assert_eq!(extract_tool_names(&content), Some("Bash".to_string()));
+    /// Write `count` user events to a temp file and return it with its line index.
+    fn write_events_file(count: usize) -> (tempfile::NamedTempFile, Vec<(u64, usize)>) {
+        use std::io::Write;
+        let mut tmp = tempfile::NamedTempFile::new().unwrap();
+        for i in 0..count {
+            writeln!(
+                tmp,
+                r#"{{"type":"user","uuid":"u-{}","message":{{"content":"event {}"}}}}"#,
+                i, i
+            )
+            .unwrap();
+        }
+        let mut file = File::open(tmp.path()).unwrap();
+        let line_index = build_line_index(&mut file).unwrap();
+        (tmp, line_index)
     }
 
     #[test]
-    fn test_extract_tool_names_multiple() {
-        let content = serde_json::json!([
-            {"type": "tool_use", "name": "Read"},
-            {"type": "tool_use", "name": "Write"}
-        ]);
-        assert_eq!(extract_tool_names(&content), Some("Read, Write".to_string()));
+    fn test_read_events_page_cursor_stable_across_appends() {
+        let (tmp, line_index) = write_events_file(10);
+        let mut file = File::open(tmp.path()).unwrap();
+        let preview = PreviewOptions::default();
+
+        let first = read_events_page(&mut file, &line_index, None, None, Some(4), &preview);
+        let sequences: Vec<u32> = first.events.iter().map(|e| e.sequence).collect();
+        assert_eq!(sequences, vec![9, 8, 7, 6]);
+        assert!(first.has_more);
+        let cursor = first.next_cursor.unwrap();
+
+        // Simulate 3 events appended between requests
+        let (tmp, line_index) = write_events_file(13);
+        let mut file = File::open(tmp.path()).unwrap();
+
+        let second = read_events_page(
+            &mut file,
+            &line_index,
+            None,
+            Some(&cursor),
+            Some(4),
+            &preview,
+        );
+        let sequences: Vec<u32> = second.events.iter().map(|e| e.sequence).collect();
+        assert_eq!(sequences, vec![5, 4, 3, 2]);
+        assert_eq!(second.total_count, 13);
     }
 
     #[test]
-    fn test_extract_tool_names_with_thinking() {
-        let content = serde_json::json!([
-            {"type": "thinking", "thinking": "Let me think..."},
-            {"type": "tool_use", "name": "Edit"}
-        ]);
-        assert_eq!(extract_tool_names(&content), Some("thinking, Edit".to_string()));
+    fn test_read_events_page_last_page_has_no_cursor() {
+        let (tmp, line_index) = write_events_file(3);
+        let mut file = File::open(tmp.path()).unwrap();
+
+        let page = read_events_page(
+            &mut file,
+            &line_index,
+            None,
+            None,
+            Some(5),
+            &PreviewOptions::default(),
+        );
+        assert_eq!(page.events.len(), 3);
+        assert!(!page.has_more);
+        assert!(page.next_cursor.is_none());
     }
 
     #[test]
-    fn test_extract_tool_names_none() {
-        let content = serde_json::json!([{
-            "type": "text",
-            "text": "Just text"
-        }]);
-        assert_eq!(extract_tool_names(&content), None);
+    fn test_resolve_cursor_rejects_stale_or_malformed() {
+        let (_tmp, line_index) = write_events_file(3);
+
+        let valid = encode_cursor(1, line_index[1].0);
+        assert_eq!(resolve_cursor(&valid, &line_index), Some(1));
+        assert_eq!(resolve_cursor("1-99999", &line_index), None);
+        assert_eq!(resolve_cursor("10-0", &line_index), None);
+        assert_eq!(resolve_cursor("garbage", &line_index), None);
     }
 
     // =============================================================================
-    // Event Parsing Tests
+    // Tool Result Extraction Tests
     // =============================================================================
 
     #[test]
-    fn test_parse_session_event_user_message() {
-        let line = r#"{"type":"user","userType":"external","uuid":"abc-123-456-789-012","message":{"content":"Hello world"},"timestamp":"2024-01-01T00:00:00Z"}"#;
-        let event = parse_session_event(line, 0, 0).unwrap();
+    fn test_extract_tool_result_string_content() {
+        let json = serde_json::json!({
+            "type": "user",
+            "message": {"content": [
+                {"type": "tool_result", "tool_use_id": "tu-001", "content": "full output"}
+            ]}
+        });
+        let (id, content, is_error) = extract_tool_result(&json, None).unwrap();
 
-        assert_eq!(event.event_type, "user");
-        assert_eq!(event.uuid, Some("abc-123-456-789-012".to_string()));
-        assert_eq!(event.user_type, Some("external".to_string()));
-        assert_eq!(event.preview, "Hello world");
-        assert_eq!(event.sequence, 0);
-        assert_eq!(event.byte_offset, 0);
+        assert_eq!(id, "tu-001");
+        assert_eq!(content, "full output");
+        assert!(!is_error);
     }
 
     #[test]
-    fn test_parse_session_event_assistant_with_tool() {
-        let line = r#"{"type":"assistant","uuid":"def-456","message":{"content":[{"type":"tool_use","name":"Bash","input":{"command":"ls"}}]},"timestamp":"2024-01-01T00:00:01Z"}"#;
-        let event = parse_session_event(line, 1, 100).unwrap();
+    fn test_extract_tool_result_selects_by_id() {
+        let json = serde_json::json!({
+            "type": "user",
+            "message": {"content": [
+                {"type": "tool_result", "tool_use_id": "tu-001", "content": "first"},
+                {"type": "tool_result", "tool_use_id": "tu-002", "is_error": true, "content": [
+                    {"type": "text", "text": "line one"},
+                    {"type": "image", "source": {}},
+                    {"type": "text", "text": "line two"}
+                ]}
+            ]}
+        });
+        let (id, content, is_error) = extract_tool_result(&json, Some("tu-002")).unwrap();
 
-        assert_eq!(event.event_type, "assistant");
-        assert_eq!(event.tool_name, Some("Bash".to_string()));
-        assert_eq!(event.sequence, 1);
-        assert_eq!(event.byte_offset, 100);
+        assert_eq!(id, "tu-002");
+        assert_eq!(content, "line one\n[image]\nline two");
+        assert!(is_error);
+        assert!(extract_tool_result(&json, Some("tu-999")).is_none());
     }
 
     #[test]
-    fn test_parse_session_event_compact_boundary() {
-        let line = r#"{"type":"system","subtype":"compact_boundary","uuid":"sys-001","compactMetadata":{"trigger":"automatic","preTokens":50000},"timestamp":"2024-01-01T00:00:00Z"}"#;
-        let event = parse_session_event(line, 0, 0).unwrap();
-
-        assert_eq!(event.event_type, "system");
-        assert_eq!(event.subtype, Some("compact_boundary".to_string()));
-        assert!(event.compact_metadata.is_some());
-        let meta = event.compact_metadata.unwrap();
-        assert_eq!(meta.trigger, "automatic");
-        assert_eq!(meta.pre_tokens, 50000);
+    fn test_extract_tool_result_not_a_result() {
+        let json = serde_json::json!({
+            "type": "assistant",
+            "message": {"content": [{"type": "text", "text": "hello"}]}
+        });
+        assert!(extract_tool_result(&json, None).is_none());
     }
 
+    // =============================================================================
+    // Attachment Extraction Tests
+    // =============================================================================
+
     #[test]
-    fn test_parse_session_event_summary() {
-        let line = r#"{"type":"summary","uuid":"sum-001","summary":"Session involved creating a React component","leafUuid":"leaf-001","timestamp":"2024-01-01T00:00:00Z"}"#;
-        let event = parse_session_event(line, 0, 0).unwrap();
+    fn test_collect_attachment_blocks_image() {
+        let content = serde_json::json!([
+            {"type": "text", "text": "Look at this screenshot"},
+            {"type": "image", "source": {"type": "base64", "media_type": "image/png", "data": "aGVsbG8="}}
+        ]);
+        let blocks = collect_attachment_blocks(&content);
 
-        assert_eq!(event.event_type, "summary");
-        assert_eq!(event.summary, Some("Session involved creating a React component".to_string()));
-        assert_eq!(event.leaf_uuid, Some("leaf-001".to_string()));
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].kind, "image");
+        assert_eq!(blocks[0].media_type, "image/png");
+        assert_eq!(blocks[0].data, "aGVsbG8=");
     }
 
     #[test]
-    fn test_parse_session_event_with_task_launch() {
-        let line = r#"{"type":"user","uuid":"task-123","toolUseResult":{"agentId":"abc123","description":"Research task","isAsync":true,"status":"async_launched"},"timestamp":"2024-01-01T00:00:00Z"}"#;
-        let event = parse_session_event(line, 0, 0).unwrap();
+    fn test_collect_attachment_blocks_inside_tool_result() {
+        let content = serde_json::json!([{
+            "type": "tool_result",
+            "tool_use_id": "tu-001",
+            "content": [
+                {"type": "image", "source": {"type": "base64", "media_type": "image/jpeg", "data": "AAAA"}}
+            ]
+        }]);
+        let blocks = collect_attachment_blocks(&content);
 
-        assert_eq!(event.launched_agent_id, Some("abc123".to_string()));
-        assert_eq!(event.launched_agent_description, Some("Research task".to_string()));
-        assert_eq!(event.launched_agent_is_async, Some(true));
-        assert_eq!(event.launched_agent_status, Some("async_launched".to_string()));
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].media_type, "image/jpeg");
     }
 
     #[test]
-    fn test_parse_session_event_tool_result() {
-        let line = r#"{"type":"user","uuid":"tr-001","message":{"content":[{"type":"tool_result","tool_use_id":"tu-001","content":"Command output"}]}}"#;
-        let event = parse_session_event(line, 0, 0).unwrap();
-
-        assert!(event.is_tool_result);
+    fn test_collect_attachment_blocks_skips_non_base64() {
+        let content = serde_json::json!([
+            {"type": "image", "source": {"type": "url", "url": "https://example.com/a.png"}},
+            {"type": "text", "text": "no attachments"}
+        ]);
+        assert!(collect_attachment_blocks(&content).is_empty());
+        assert!(collect_attachment_blocks(&serde_json::json!("plain string")).is_empty());
     }
 
     #[test]
-    fn test_parse_session_event_meta_context() {
-        let line = r#"{"type":"user","uuid":"meta-001","isMeta":true,"message":{"content":"Context injection"}}"#;
-        let event = parse_session_event(line, 0, 0).unwrap();
-
-        assert!(event.is_meta);
+    fn test_extension_for_media_type() {
+        assert_eq!(extension_for_media_type("image/png"), "png");
+        assert_eq!(extension_for_media_type("image/jpeg"), "jpg");
+        assert_eq!(extension_for_media_type("application/pdf"), "pdf");
+        assert_eq!(extension_for_media_type("application/x-unknown"), "bin");
     }
 
     #[test]
-    fn test_parse_session_event_invalid_json() {
-        let line = "not valid json";
-        let event = parse_session_event(line, 0, 0);
+    fn test_prune_attachment_cache() {
+        let cache = tempfile::tempdir().unwrap();
+        let stale = cache.path().join("s1-0-0.png");
+        let fresh = cache.path().join("s1-0-1.png");
+        fs::write(&stale, b"old").unwrap();
+        fs::write(&fresh, b"new").unwrap();
+        let two_days_ago = SystemTime::now() - std::time::Duration::from_secs(2 * 24 * 60 * 60);
+        File::options()
+            .write(true)
+            .open(&stale)
+            .unwrap()
+            .set_modified(two_days_ago)
+            .unwrap();
 
-        assert!(event.is_none());
+        assert_eq!(
+            prune_attachment_cache(cache.path(), ATTACHMENT_CACHE_TTL),
+            1
+        );
+        assert!(!stale.exists());
+        assert!(fresh.exists());
+        assert_eq!(
+            prune_attachment_cache(&cache.path().join("missing"), ATTACHMENT_CACHE_TTL),
+            0
+        );
     }
 
     // =============================================================================
@@ -1850,6 +5061,234 @@ mod tests {
         );
     }
 
+    // =============================================================================
+    // Policy Evaluation Tests
+    // =============================================================================
+
+    fn write_telemetry(dir: &Path, name: &str, tool: &str, decision: &str, modified_secs: u64) {
+        let timestamp = chrono::DateTime::from_timestamp(modified_secs as i64, 0).unwrap();
+        let span = serde_json::json!({
+            "timestamp": timestamp.to_rfc3339(),
+            "trace_id": name,
+            "raw_event": { "hook_event_name": "PreToolUse", "tool_name": tool },
+            "response": { "decision": { decision: {} } },
+            "total_duration_ms": 3
+        });
+        let path = dir.join(format!("{}.json", name));
+        fs::write(&path, span.to_string()).unwrap();
+        let modified = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(modified_secs);
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_get_policy_evaluations_pages_newest_first() {
+        let project = tempfile::tempdir().unwrap();
+        let telemetry_dir = get_telemetry_dir(&project.path().to_string_lossy());
+        fs::create_dir_all(&telemetry_dir).unwrap();
+        write_telemetry(&telemetry_dir, "a", "Bash", "Allow", 1_000);
+        write_telemetry(&telemetry_dir, "b", "Bash", "Block", 3_000);
+        write_telemetry(&telemetry_dir, "c", "Bash", "Allow", 2_000);
+        fs::write(telemetry_dir.join("notes.txt"), "ignored").unwrap();
+
+        let cache = PolicyEvaluationCache::new();
+        let project_path = project.path().to_string_lossy().to_string();
+        let all = PolicyEvaluationFilter::default();
+
+        let first = get_policy_evaluations(&project_path, None, Some(2), &all, &cache).unwrap();
+        assert_eq!(first.total_count, 3);
+        assert!(first.has_more);
+        let ids: Vec<&str> = first
+            .evaluations
+            .iter()
+            .map(|e| e.trace_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["b", "c"]);
+        assert_eq!(first.evaluations[0].decision.as_deref(), Some("Block"));
+        assert_eq!(first.evaluations[0].tool_name.as_deref(), Some("Bash"));
+
+        let second = get_policy_evaluations(&project_path, Some(2), Some(2), &all, &cache).unwrap();
+        assert!(!second.has_more);
+        assert_eq!(second.evaluations.len(), 1);
+        assert_eq!(second.evaluations[0].trace_id, "a");
+
+        // A rewritten file is parsed again rather than served from the cache
+        write_telemetry(&telemetry_dir, "a", "Bash", "Ask", 4_000);
+        let refreshed = get_policy_evaluations(&project_path, None, Some(1), &all, &cache).unwrap();
+        assert_eq!(refreshed.evaluations[0].decision.as_deref(), Some("Ask"));
+    }
+
+    #[test]
+    fn test_get_policy_evaluations_filtered() {
+        let project = tempfile::tempdir().unwrap();
+        let telemetry_dir = get_telemetry_dir(&project.path().to_string_lossy());
+        fs::create_dir_all(&telemetry_dir).unwrap();
+        write_telemetry(&telemetry_dir, "old-deny", "Bash", "Deny", 1_000);
+        write_telemetry(&telemetry_dir, "new-deny", "Bash", "Deny", 90_000);
+        write_telemetry(&telemetry_dir, "new-allow", "Bash", "Allow", 90_001);
+        write_telemetry(&telemetry_dir, "new-edit", "Edit", "Deny", 90_002);
+
+        let cache = PolicyEvaluationCache::new();
+        let project_path = project.path().to_string_lossy().to_string();
+        let trace_ids = |filter: &PolicyEvaluationFilter| -> Vec<String> {
+            get_policy_evaluations(&project_path, None, None, filter, &cache)
+                .unwrap()
+                .evaluations
+                .into_iter()
+                .map(|e| e.trace_id)
+                .collect()
+        };
+
+        let bash_denials = PolicyEvaluationFilter {
+            decision: Some("deny".to_string()),
+            tool_name: Some("bash".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(trace_ids(&bash_denials), vec!["new-deny", "old-deny"]);
+
+        let recent_bash_denials = PolicyEvaluationFilter {
+            since: Some("1970-01-02T00:00:00Z".to_string()),
+            ..bash_denials.clone()
+        };
+        assert_eq!(trace_ids(&recent_bash_denials), vec!["new-deny"]);
+
+        let by_trace = PolicyEvaluationFilter {
+            trace_id: Some("new-".to_string()),
+            until: Some("1970-01-02T01:00:01Z".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(trace_ids(&by_trace), vec!["new-allow", "new-deny"]);
+
+        let invalid = PolicyEvaluationFilter {
+            since: Some("yesterday".to_string()),
+            ..Default::default()
+        };
+        assert!(get_policy_evaluations(&project_path, None, None, &invalid, &cache).is_err());
+    }
+
+    #[test]
+    fn test_parse_policy_evaluation_detail() {
+        let span = serde_json::json!({
+            "trace_id": "t1",
+            "timestamp": "2026-01-01T00:00:00Z",
+            "harness": "ClaudeCode",
+            "start_time_unix_nano": 1_000_000_000u64,
+            "raw_event": { "hook_event_name": "PreToolUse", "tool_name": "Bash" },
+            "phases": [
+                {
+                    "name": "global",
+                    "start_time_unix_nano": 1_000_500_000u64,
+                    "end_time_unix_nano": 1_002_000_000u64,
+                    "duration_ms": 1,
+                    "signals": {
+                        "signals": [
+                            { "name": "git_branch", "command": "git branch --show-current",
+                              "duration_ms": 0.4, "exit_code": 0 }
+                        ]
+                    },
+                    "evaluation": {
+                        "routed": true,
+                        "matched_policies": ["no_force_push"],
+                        "wasm_decision_set": {
+                            "halts": [],
+                            "denials": [
+                                { "rule_id": "NFP-1", "reason": "Force push blocked",
+                                  "severity": "HIGH" }
+                            ],
+                            "asks": [{ "rule_id": "ASK-1", "reason": "Confirm push" }]
+                        },
+                        "final_decision": {
+                            "Deny": { "reason": "Force push blocked", "agent_messages": [] }
+                        }
+                    }
+                }
+            ],
+            "response": {
+                "decision": {
+                    "Deny": {
+                        "reason": "Force push blocked",
+                        "agent_messages": ["Use a regular push"]
+                    }
+                }
+            },
+            "errors": [],
+            "total_duration_ms": 2
+        });
+
+        let detail = parse_policy_evaluation_detail("t1.json".to_string(), &span);
+        assert_eq!(detail.evaluation.decision.as_deref(), Some("Deny"));
+        assert_eq!(detail.harness.as_deref(), Some("ClaudeCode"));
+        assert_eq!(detail.reason.as_deref(), Some("Force push blocked"));
+        assert_eq!(detail.agent_messages, vec!["Use a regular push"]);
+
+        let phase = &detail.phases[0];
+        assert_eq!(phase.name, "global");
+        assert_eq!(phase.start_offset_ms, 0.5);
+        assert_eq!(phase.duration_ms, 1.5);
+        assert!(phase.routed);
+        assert_eq!(phase.matched_policies, vec!["no_force_push"]);
+        assert_eq!(phase.decision.as_deref(), Some("Deny"));
+        assert_eq!(
+            phase.results,
+            vec![
+                PolicyRuleResult {
+                    kind: "deny".to_string(),
+                    rule_id: "NFP-1".to_string(),
+                    reason: "Force push blocked".to_string(),
+                    severity: Some("HIGH".to_string()),
+                },
+                PolicyRuleResult {
+                    kind: "ask".to_string(),
+                    rule_id: "ASK-1".to_string(),
+                    reason: "Confirm push".to_string(),
+                    severity: None,
+                },
+            ]
+        );
+        assert_eq!(phase.signals[0].name, "git_branch");
+        assert_eq!(phase.signals[0].exit_code, Some(0));
+    }
+
+    // =============================================================================
+    // Cupcake Policy File Tests
+    // =============================================================================
+
+    #[test]
+    fn test_get_policy_files() {
+        let project = tempfile::tempdir().unwrap();
+        let cupcake_dir = project.path().join(".cupcake");
+        fs::create_dir_all(cupcake_dir.join("policies/claude")).unwrap();
+        fs::write(cupcake_dir.join("rulebook.yml"), "signals: {}\n").unwrap();
+        fs::write(
+            cupcake_dir.join("policies/claude/no_force_push.rego"),
+            "# METADATA\npackage cupcake.policies.no_force_push\n\nimport rego.v1\n",
+        )
+        .unwrap();
+        fs::write(cupcake_dir.join("policies/README.md"), "not a policy").unwrap();
+
+        let project_path = project.path().to_string_lossy().to_string();
+        let files = get_policy_files(&project_path);
+        let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec!["rulebook.yml", "policies/claude/no_force_push.rego"]
+        );
+        assert_eq!(files[0].kind, PolicyFileKind::Rulebook);
+        assert_eq!(
+            files[1].package.as_deref(),
+            Some("cupcake.policies.no_force_push")
+        );
+
+        let policy = get_policy_file(&project_path, "policies/claude/no_force_push.rego").unwrap();
+        assert!(policy.content.contains("import rego.v1"));
+        assert!(get_policy_file(&project_path, "../.cupcake/rulebook.yml").is_err());
+        assert!(get_policy_file(&project_path, "policies/README.md").is_err());
+    }
+
     // =============================================================================
     // Performance Benchmark
     // =============================================================================
@@ -0,0 +1,1004 @@
+//! Unified diff generation for session file edits.
+//!
+//! Turns the old/new strings of a `FileDiff` into unified hunks with line
+//! numbers, so the frontend doesn't have to run its own diff, exports whole
+//! sessions as git patches, and compares session results with HEAD and disk.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::claude_code::{
+    get_file_diffs, get_session_file_contents, get_session_file_edits, make_relative_path,
+};
+use crate::git::get_git_file_diff;
+use crate::language::detect_language;
+
+/// Default number of unchanged lines shown around each change.
+const DEFAULT_CONTEXT_LINES: usize = 3;
+
+/// Kind of a line in a diff hunk.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffLineKind {
+    /// Unchanged line
+    Context,
+    /// Line only in the new text
+    Add,
+    /// Line only in the old text
+    Remove,
+}
+
+/// A single line in a diff hunk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffLine {
+    /// Whether the line was kept, added, or removed
+    pub kind: DiffLineKind,
+    /// Line text (without trailing newline)
+    pub content: String,
+    /// 1-based line number in the old text (None for added lines)
+    pub old_line: Option<u32>,
+    /// 1-based line number in the new text (None for removed lines)
+    pub new_line: Option<u32>,
+}
+
+/// A unified diff hunk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffHunk {
+    /// First old line covered by the hunk (0 if the hunk only adds lines at the start)
+    pub old_start: u32,
+    /// Number of old lines in the hunk
+    pub old_lines: u32,
+    /// First new line covered by the hunk (0 if the hunk only removes lines at the start)
+    pub new_start: u32,
+    /// Number of new lines in the hunk
+    pub new_lines: u32,
+    /// Hunk header, e.g. "@@ -1,4 +1,5 @@"
+    pub header: String,
+    /// Lines in the hunk
+    pub lines: Vec<DiffLine>,
+}
+
+/// Hunks for one edit of a file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDiffHunks {
+    /// Sequence number of the edit (same as `FileDiff.sequence`)
+    pub sequence: u32,
+    /// Timestamp of the edit (ISO 8601)
+    pub timestamp: Option<String>,
+    /// Monaco language identifier for the file
+    pub language: String,
+    /// Number of added lines
+    pub additions: u32,
+    /// Number of removed lines
+    pub deletions: u32,
+    /// Unified hunks (line numbers are relative to the edited snippet)
+    pub hunks: Vec<DiffHunk>,
+}
+
+/// Get unified hunks for every edit of a file in a session.
+///
+/// Line numbers are relative to each edit's old/new strings, since an Edit only
+/// records the replaced snippet rather than the whole file.
+pub fn get_file_diff_hunks(
+    project_path: &str,
+    session_id: &str,
+    file_path: &str,
+    context_lines: Option<usize>,
+) -> Vec<FileDiffHunks> {
+    let context = context_lines.unwrap_or(DEFAULT_CONTEXT_LINES);
+
+    get_file_diffs(project_path, session_id, file_path)
+        .into_iter()
+        .map(|diff| {
+            let hunks = compute_hunks(&diff.old_string, &diff.new_string, context);
+            let (additions, deletions) = count_changes(&hunks);
+            FileDiffHunks {
+                sequence: diff.sequence,
+                timestamp: diff.timestamp,
+                language: diff.language,
+                additions,
+                deletions,
+                hunks,
+            }
+        })
+        .collect()
+}
+
+/// A file left out of an exported patch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedPatchFile {
+    /// Path of the file (relative to the project)
+    pub file_path: String,
+    /// Why the file was left out
+    pub reason: String,
+}
+
+/// Result of exporting session edits as a patch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchExport {
+    /// Where the patch was written
+    pub output_path: String,
+    /// Files included in the patch
+    pub files: Vec<String>,
+    /// Files that couldn't be exported
+    pub skipped: Vec<SkippedPatchFile>,
+    /// Number of added lines
+    pub additions: u32,
+    /// Number of removed lines
+    pub deletions: u32,
+}
+
+/// Export a session's edits (optionally limited to some files) as a git-applyable patch.
+pub fn export_session_patch(
+    project_path: &str,
+    session_id: &str,
+    file_paths: Option<&[String]>,
+    output_path: &str,
+) -> Result<PatchExport, String> {
+    let files: Vec<String> = match file_paths {
+        Some(paths) => paths.to_vec(),
+        None => get_session_file_edits(project_path, session_id)
+            .into_iter()
+            .map(|edit| edit.path)
+            .collect(),
+    };
+
+    let mut patch = String::new();
+    let mut export = PatchExport {
+        output_path: output_path.to_string(),
+        files: Vec::new(),
+        skipped: Vec::new(),
+        additions: 0,
+        deletions: 0,
+    };
+
+    for file_path in files {
+        let relative = make_relative_path(&file_path, project_path);
+        let mut skip = |reason: String| {
+            export.skipped.push(SkippedPatchFile {
+                file_path: relative.clone(),
+                reason,
+            })
+        };
+
+        if Path::new(&relative).is_absolute() {
+            skip("File is outside the project".to_string());
+            continue;
+        }
+        let contents = match get_session_file_contents(project_path, session_id, &file_path) {
+            Ok(c) => c,
+            Err(e) => {
+                skip(e);
+                continue;
+            }
+        };
+        if !contents.skipped_edits.is_empty() {
+            skip(format!(
+                "{} edits could not be replayed",
+                contents.skipped_edits.len()
+            ));
+            continue;
+        }
+
+        let hunks = patch_hunks(contents.before.as_deref().unwrap_or(""), &contents.after);
+        if hunks.is_empty() && contents.before.is_some() {
+            continue;
+        }
+        let (additions, deletions) = count_changes(&hunks);
+        export.additions += additions;
+        export.deletions += deletions;
+
+        patch.push_str(&render_file_patch(
+            &relative,
+            contents.before.is_none(),
+            &hunks,
+        ));
+        export.files.push(relative);
+    }
+
+    if export.files.is_empty() {
+        return Err("No session edits to export".to_string());
+    }
+
+    let output = Path::new(output_path);
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::write(output, patch).map_err(|e| format!("Failed to write {}: {}", output_path, e))?;
+
+    Ok(export)
+}
+
+/// How a file on disk compares with its end-of-session content.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DriftStatus {
+    /// Disk matches what the session left behind
+    Unchanged,
+    /// Disk content differs from the session's result
+    Drifted,
+    /// The file no longer exists on disk
+    Missing,
+    /// The session's result couldn't be reconstructed
+    Unknown,
+}
+
+/// Drift of one edited file since the session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDrift {
+    /// Path of the file (relative to the project)
+    pub file_path: String,
+    /// How the file on disk compares with the session's result
+    pub status: DriftStatus,
+    /// Lines on disk that the session didn't leave behind
+    pub additions: u32,
+    /// Lines the session left behind that are no longer on disk
+    pub deletions: u32,
+    /// Hunks from the session's result to the current disk content
+    pub hunks: Vec<DiffHunk>,
+    /// Why the status is unknown, if it is
+    pub reason: Option<String>,
+}
+
+/// Compare every file edited in a session against the file currently on disk.
+pub fn get_session_drift(project_path: &str, session_id: &str) -> Vec<FileDrift> {
+    get_session_file_edits(project_path, session_id)
+        .into_iter()
+        .map(|edit| file_drift(project_path, session_id, edit.path))
+        .collect()
+}
+
+/// Diff one file's end-of-session content against disk.
+fn file_drift(project_path: &str, session_id: &str, file_path: String) -> FileDrift {
+    let mut drift = FileDrift {
+        file_path,
+        status: DriftStatus::Unknown,
+        additions: 0,
+        deletions: 0,
+        hunks: Vec::new(),
+        reason: None,
+    };
+
+    let contents = match get_session_file_contents(project_path, session_id, &drift.file_path) {
+        Ok(c) if c.skipped_edits.is_empty() => c,
+        Ok(c) => {
+            drift.reason = Some(format!(
+                "{} edits could not be replayed",
+                c.skipped_edits.len()
+            ));
+            return drift;
+        }
+        Err(e) => {
+            drift.reason = Some(e);
+            return drift;
+        }
+    };
+
+    let disk_path = Path::new(project_path).join(&drift.file_path);
+    let current = match fs::read_to_string(&disk_path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            drift.status = DriftStatus::Missing;
+            return drift;
+        }
+        Err(e) => {
+            drift.reason = Some(format!("Failed to read {}: {}", disk_path.display(), e));
+            return drift;
+        }
+    };
+
+    if current == contents.after {
+        drift.status = DriftStatus::Unchanged;
+        return drift;
+    }
+
+    drift.status = DriftStatus::Drifted;
+    drift.hunks = compute_hunks(&contents.after, &current, DEFAULT_CONTEXT_LINES);
+    (drift.additions, drift.deletions) = count_changes(&drift.hunks);
+    drift
+}
+
+/// HEAD, end-of-session, and working-tree versions of a file with pairwise diffs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileThreeWay {
+    /// Path of the file (relative to the project)
+    pub file_path: String,
+    /// Monaco language identifier for the file
+    pub language: String,
+    /// Content at HEAD (None if untracked, new, or not in a git repository)
+    pub head: Option<String>,
+    /// Content the session left behind (None if it couldn't be reconstructed)
+    pub session: Option<String>,
+    /// Current content on disk (None if the file is missing)
+    pub working: Option<String>,
+    /// What the agent changed relative to HEAD
+    pub head_to_session: Vec<DiffHunk>,
+    /// What changed on disk after the session (e.g. human edits)
+    pub session_to_working: Vec<DiffHunk>,
+    /// Everything uncommitted, regardless of who changed it
+    pub head_to_working: Vec<DiffHunk>,
+    /// Why a version is missing or approximate, if one is
+    pub reason: Option<String>,
+}
+
+/// Compare a file at HEAD, at the end of a session, and in the working tree.
+///
+/// Missing versions are diffed as empty files.
+pub fn get_file_three_way(project_path: &str, session_id: &str, file_path: &str) -> FileThreeWay {
+    let relative_path = make_relative_path(file_path, project_path);
+    let mut reasons = Vec::new();
+
+    let (head, working) = match get_git_file_diff(project_path, &relative_path) {
+        Ok(git) => (
+            git.exists_at_head.then_some(git.original),
+            git.exists_in_workdir.then_some(git.current),
+        ),
+        Err(e) => {
+            reasons.push(e);
+            let disk_path = Path::new(project_path).join(&relative_path);
+            (None, fs::read_to_string(disk_path).ok())
+        }
+    };
+
+    let session = match get_session_file_contents(project_path, session_id, &relative_path) {
+        Ok(contents) => {
+            if !contents.skipped_edits.is_empty() {
+                reasons.push(format!(
+                    "{} edits could not be replayed",
+                    contents.skipped_edits.len()
+                ));
+            }
+            Some(contents.after)
+        }
+        Err(e) => {
+            reasons.push(e);
+            None
+        }
+    };
+
+    let text = |content: &Option<String>| content.clone().unwrap_or_default();
+    let (head_text, session_text, working_text) = (text(&head), text(&session), text(&working));
+    let sample = [&session, &working, &head].into_iter().flatten().next();
+
+    FileThreeWay {
+        language: detect_language(&relative_path, sample.map(String::as_str)).to_string(),
+        file_path: relative_path,
+        head_to_session: if session.is_some() {
+            compute_hunks(&head_text, &session_text, DEFAULT_CONTEXT_LINES)
+        } else {
+            Vec::new()
+        },
+        session_to_working: if session.is_some() {
+            compute_hunks(&session_text, &working_text, DEFAULT_CONTEXT_LINES)
+        } else {
+            Vec::new()
+        },
+        head_to_working: compute_hunks(&head_text, &working_text, DEFAULT_CONTEXT_LINES),
+        head,
+        session,
+        working,
+        reason: (!reasons.is_empty()).then(|| reasons.join("; ")),
+    }
+}
+
+/// Compute hunks for a patch, keeping line terminators so missing final newlines survive.
+pub(crate) fn patch_hunks(before: &str, after: &str) -> Vec<DiffHunk> {
+    let old_lines: Vec<&str> = before.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = after.split_inclusive('\n').collect();
+    hunks_for_lines(&old_lines, &new_lines, DEFAULT_CONTEXT_LINES)
+}
+
+/// Render one file's section of a git patch from terminator-preserving hunks.
+fn render_file_patch(path: &str, is_new: bool, hunks: &[DiffHunk]) -> String {
+    let mut out = format!("diff --git a/{} b/{}\n", path, path);
+    if is_new {
+        out.push_str("new file mode 100644\n");
+        if hunks.is_empty() {
+            return out;
+        }
+        out.push_str("--- /dev/null\n");
+    } else {
+        out.push_str(&format!("--- a/{}\n", path));
+    }
+    out.push_str(&format!("+++ b/{}\n", path));
+
+    for hunk in hunks {
+        out.push_str(&hunk.header);
+        out.push('\n');
+        for line in &hunk.lines {
+            out.push(match line.kind {
+                DiffLineKind::Context => ' ',
+                DiffLineKind::Add => '+',
+                DiffLineKind::Remove => '-',
+            });
+            out.push_str(&line.content);
+            if !line.content.ends_with('\n') {
+                out.push_str("\n\\ No newline at end of file\n");
+            }
+        }
+    }
+    out
+}
+
+/// Default cap on search results.
+const DEFAULT_SEARCH_LIMIT: usize = 200;
+
+/// A session edit whose diff mentions the search text.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditSearchMatch {
+    /// Path of the file (relative to the project)
+    pub file_path: String,
+    /// Sequence of the edit (same as `FileDiff.sequence`)
+    pub sequence: u32,
+    /// Timestamp of the edit (ISO 8601)
+    pub timestamp: Option<String>,
+    /// Hunks of the edit that contain the search text
+    pub hunks: Vec<DiffHunk>,
+}
+
+/// Search the diffs of every file edited in a session for some text.
+///
+/// An edit matches when the text appears in one of its hunks (changed lines or their context).
+pub fn search_file_edits(
+    project_path: &str,
+    session_id: &str,
+    query: &str,
+    case_sensitive: bool,
+    limit: Option<usize>,
+) -> Vec<EditSearchMatch> {
+    let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+    let needle = if case_sensitive {
+        query.to_string()
+    } else {
+        query.to_lowercase()
+    };
+    let contains = |text: &str| {
+        if case_sensitive {
+            text.contains(&needle)
+        } else {
+            text.to_lowercase().contains(&needle)
+        }
+    };
+
+    let mut matches = Vec::new();
+    if needle.is_empty() {
+        return matches;
+    }
+
+    for edit in get_session_file_edits(project_path, session_id) {
+        for diff in get_file_diffs(project_path, session_id, &edit.path) {
+            if !contains(&diff.old_string) && !contains(&diff.new_string) {
+                continue;
+            }
+            let hunks: Vec<DiffHunk> =
+                compute_hunks(&diff.old_string, &diff.new_string, DEFAULT_CONTEXT_LINES)
+                    .into_iter()
+                    .filter(|hunk| hunk.lines.iter().any(|line| contains(&line.content)))
+                    .collect();
+            if hunks.is_empty() {
+                continue;
+            }
+
+            matches.push(EditSearchMatch {
+                file_path: edit.path.clone(),
+                sequence: diff.sequence,
+                timestamp: diff.timestamp,
+                hunks,
+            });
+            if matches.len() >= limit {
+                return matches;
+            }
+        }
+    }
+
+    matches
+}
+
+/// Apply a subset of `old`-to-new hunks to `old`, keeping the old side of the rest.
+///
+/// Hunks must come from `patch_hunks(old, ..)` so line terminators are preserved.
+pub(crate) fn apply_selected_hunks(
+    old: &str,
+    hunks: &[DiffHunk],
+    selected: impl Fn(usize) -> bool,
+) -> String {
+    let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+    let mut result = String::with_capacity(old.len());
+    let mut cursor = 0;
+
+    for (i, hunk) in hunks.iter().enumerate() {
+        // A hunk with no old lines starts after line `old_start`
+        let start = if hunk.old_lines > 0 {
+            hunk.old_start as usize - 1
+        } else {
+            hunk.old_start as usize
+        };
+        for line in &old_lines[cursor..start] {
+            result.push_str(line);
+        }
+
+        let keep_new = selected(i);
+        for line in &hunk.lines {
+            let kept = match line.kind {
+                DiffLineKind::Context => true,
+                DiffLineKind::Add => keep_new,
+                DiffLineKind::Remove => !keep_new,
+            };
+            if kept {
+                result.push_str(&line.content);
+            }
+        }
+        cursor = start + hunk.old_lines as usize;
+    }
+
+    for line in &old_lines[cursor..] {
+        result.push_str(line);
+    }
+    result
+}
+
+/// Count added and removed lines across hunks.
+pub(crate) fn count_changes(hunks: &[DiffHunk]) -> (u32, u32) {
+    let mut additions = 0;
+    let mut deletions = 0;
+    for line in hunks.iter().flat_map(|h| &h.lines) {
+        match line.kind {
+            DiffLineKind::Add => additions += 1,
+            DiffLineKind::Remove => deletions += 1,
+            DiffLineKind::Context => {}
+        }
+    }
+    (additions, deletions)
+}
+
+/// Count lines added and removed between two texts.
+pub fn count_line_changes(old: &str, new: &str) -> (u32, u32) {
+    count_changes(&compute_hunks(old, new, 0))
+}
+
+/// Compute unified hunks between two texts.
+pub fn compute_hunks(old: &str, new: &str, context: usize) -> Vec<DiffHunk> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    hunks_for_lines(&old_lines, &new_lines, context)
+}
+
+/// Compute unified hunks between two line lists.
+fn hunks_for_lines(old_lines: &[&str], new_lines: &[&str], context: usize) -> Vec<DiffHunk> {
+    let lines = diff_lines(old_lines, new_lines);
+
+    let changes: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| l.kind != DiffLineKind::Context)
+        .map(|(i, _)| i)
+        .collect();
+
+    // Group changes whose context windows touch or overlap into one hunk
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for &idx in &changes {
+        let start = idx.saturating_sub(context);
+        let end = (idx + context + 1).min(lines.len());
+        match ranges.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => ranges.push((start, end)),
+        }
+    }
+
+    ranges
+        .into_iter()
+        .map(|(start, end)| build_hunk(&lines, start, end))
+        .collect()
+}
+
+/// Build a hunk from lines[start..end], deriving its header from the line numbers.
+fn build_hunk(lines: &[DiffLine], start: usize, end: usize) -> DiffHunk {
+    // Lines of each side that come before the hunk
+    let old_before = lines[..start]
+        .iter()
+        .filter(|l| l.old_line.is_some())
+        .count() as u32;
+    let new_before = lines[..start]
+        .iter()
+        .filter(|l| l.new_line.is_some())
+        .count() as u32;
+
+    let hunk_lines = lines[start..end].to_vec();
+    let old_count = hunk_lines.iter().filter(|l| l.old_line.is_some()).count() as u32;
+    let new_count = hunk_lines.iter().filter(|l| l.new_line.is_some()).count() as u32;
+
+    // Unified diff convention: an empty side starts at the line before the hunk
+    let old_start = if old_count > 0 {
+        old_before + 1
+    } else {
+        old_before
+    };
+    let new_start = if new_count > 0 {
+        new_before + 1
+    } else {
+        new_before
+    };
+
+    DiffHunk {
+        old_start,
+        old_lines: old_count,
+        new_start,
+        new_lines: new_count,
+        header: format!(
+            "@@ -{},{} +{},{} @@",
+            old_start, old_count, new_start, new_count
+        ),
+        lines: hunk_lines,
+    }
+}
+
+/// Diff two line lists, returning every line tagged with its kind and line numbers.
+fn diff_lines(old: &[&str], new: &[&str]) -> Vec<DiffLine> {
+    // Trim the common prefix and suffix so the diff only runs over the changed middle
+    let prefix = old
+        .iter()
+        .zip(new.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let mut ops = Vec::with_capacity(old.len().max(new.len()));
+    ops.extend((0..prefix).map(|i| Op::Equal(i, i)));
+    ops.extend(myers(old_mid, new_mid).into_iter().map(|op| match op {
+        Op::Equal(a, b) => Op::Equal(a + prefix, b + prefix),
+        Op::Delete(a) => Op::Delete(a + prefix),
+        Op::Insert(b) => Op::Insert(b + prefix),
+    }));
+    let old_tail = old.len() - suffix;
+    let new_tail = new.len() - suffix;
+    ops.extend((0..suffix).map(|i| Op::Equal(old_tail + i, new_tail + i)));
+
+    ops.into_iter()
+        .map(|op| match op {
+            Op::Equal(a, b) => DiffLine {
+                kind: DiffLineKind::Context,
+                content: old[a].to_string(),
+                old_line: Some(a as u32 + 1),
+                new_line: Some(b as u32 + 1),
+            },
+            Op::Delete(a) => DiffLine {
+                kind: DiffLineKind::Remove,
+                content: old[a].to_string(),
+                old_line: Some(a as u32 + 1),
+                new_line: None,
+            },
+            Op::Insert(b) => DiffLine {
+                kind: DiffLineKind::Add,
+                content: new[b].to_string(),
+                old_line: None,
+                new_line: Some(b as u32 + 1),
+            },
+        })
+        .collect()
+}
+
+/// An edit operation, indexing into the old and/or new line lists.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Equal(usize, usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+/// Edit distance beyond which `myers` stops searching and replaces the input wholesale.
+///
+/// Keeps time and trace memory bounded when a large file is mostly rewritten.
+const MAX_EDIT_DISTANCE: isize = 1000;
+
+/// Myers' O((N+M)D) shortest edit script between two line lists.
+///
+/// Only the diagonals reachable at each step are kept for the backtrack, so the trace
+/// grows with D² rather than (N+M)·D. Past `MAX_EDIT_DISTANCE` every old line is deleted
+/// and every new line inserted.
+fn myers(old: &[&str], new: &[&str]) -> Vec<Op> {
+    let n = old.len() as isize;
+    let m = new.len() as isize;
+    let max = n + m;
+    if max == 0 {
+        return Vec::new();
+    }
+
+    // v[k + offset] = furthest x reached on diagonal k
+    let offset = max as usize + 1;
+    let mut v = vec![0isize; 2 * offset + 1];
+    // trace[d][i] = v on diagonal i - d - 1 before step d
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+    let mut found = false;
+
+    'search: for d in 0..=max.min(MAX_EDIT_DISTANCE) {
+        let window = offset - d as usize - 1..=offset + d as usize + 1;
+        trace.push(v[window].to_vec());
+        for k in (-d..=d).step_by(2) {
+            let idx = (k + offset as isize) as usize;
+            let mut x = if k == -d || (k != d && v[idx - 1] < v[idx + 1]) {
+                v[idx + 1] // step down (insertion)
+            } else {
+                v[idx - 1] + 1 // step right (deletion)
+            };
+            let mut y = x - k;
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[idx] = x;
+            if x >= n && y >= m {
+                found = true;
+                break 'search;
+            }
+        }
+    }
+
+    if !found {
+        let mut ops: Vec<Op> = (0..old.len()).map(Op::Delete).collect();
+        ops.extend((0..new.len()).map(Op::Insert));
+        return ops;
+    }
+
+    // Walk the trace backwards to recover the path
+    let mut ops = Vec::new();
+    let (mut x, mut y) = (n, m);
+
+    for (d, window) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let at = |k: isize| window[(k + d + 1) as usize];
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && at(k - 1) < at(k + 1)) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = at(prev_k);
+        let prev_y = prev_x - prev_k;
+
+        while x > prev_x && y > prev_y {
+            ops.push(Op::Equal(x as usize - 1, y as usize - 1));
+            x -= 1;
+            y -= 1;
+        }
+        if d > 0 {
+            if x == prev_x {
+                ops.push(Op::Insert(y as usize - 1));
+            } else {
+                ops.push(Op::Delete(x as usize - 1));
+            }
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+
+    ops.reverse();
+    ops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Render hunks as unified diff text for easy comparison.
+    fn render(hunks: &[DiffHunk]) -> String {
+        let mut out = String::new();
+        for hunk in hunks {
+            out.push_str(&hunk.header);
+            out.push('\n');
+            for line in &hunk.lines {
+                let prefix = match line.kind {
+                    DiffLineKind::Context => ' ',
+                    DiffLineKind::Add => '+',
+                    DiffLineKind::Remove => '-',
+                };
+                out.push(prefix);
+                out.push_str(&line.content);
+                out.push('\n');
+            }
+        }
+        out
+    }
+
+    // =============================================================================
+    // Myers Diff Tests
+    // =============================================================================
+
+    #[test]
+    fn test_myers_identical() {
+        let a = ["x", "y"];
+        assert_eq!(myers(&a, &a), vec![Op::Equal(0, 0), Op::Equal(1, 1)]);
+    }
+
+    #[test]
+    fn test_myers_replace_middle() {
+        let old = ["a", "b", "c"];
+        let new = ["a", "x", "c"];
+        let ops = myers(&old, &new);
+
+        assert_eq!(ops.len(), 4);
+        assert!(ops.contains(&Op::Delete(1)));
+        assert!(ops.contains(&Op::Insert(1)));
+    }
+
+    #[test]
+    fn test_myers_empty_sides() {
+        assert_eq!(myers(&[], &["a"]), vec![Op::Insert(0)]);
+        assert_eq!(myers(&["a"], &[]), vec![Op::Delete(0)]);
+        assert!(myers(&[], &[]).is_empty());
+    }
+
+    #[test]
+    fn test_myers_large_rewrite() {
+        let old: Vec<String> = (0..20_000).map(|i| format!("old {}", i)).collect();
+        let new: Vec<String> = (0..20_000).map(|i| format!("new {}", i)).collect();
+        let old: Vec<&str> = old.iter().map(String::as_str).collect();
+        let new: Vec<&str> = new.iter().map(String::as_str).collect();
+
+        // Past the edit distance cap: one wholesale replacement
+        let ops = myers(&old, &new);
+        assert_eq!(ops.len(), 40_000);
+        assert_eq!(ops[0], Op::Delete(0));
+        assert_eq!(ops[19_999], Op::Delete(19_999));
+        assert_eq!(ops[20_000], Op::Insert(0));
+
+        let hunks = compute_hunks(&old.join("\n"), &new.join("\n"), 3);
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(count_changes(&hunks), (20_000, 20_000));
+    }
+
+    #[test]
+    fn test_myers_scattered_changes_within_cap() {
+        let old: Vec<String> = (0..5_000).map(|i| format!("line {}", i)).collect();
+        let mut new = old.clone();
+        for i in (0..5_000).step_by(50) {
+            new[i] = format!("changed {}", i);
+        }
+        let old: Vec<&str> = old.iter().map(String::as_str).collect();
+        let new: Vec<&str> = new.iter().map(String::as_str).collect();
+
+        let ops = myers(&old, &new);
+        let equal = ops.iter().filter(|op| matches!(op, Op::Equal(..))).count();
+        assert_eq!(equal, 4_900);
+        assert_eq!(ops.len(), 5_100);
+    }
+
+    // =============================================================================
+    // Hunk Tests
+    // =============================================================================
+
+    #[test]
+    fn test_compute_hunks_single_change() {
+        let old = "one\ntwo\nthree\nfour\nfive";
+        let new = "one\ntwo\nTHREE\nfour\nfive";
+        let hunks = compute_hunks(old, new, 1);
+
+        assert_eq!(
+            render(&hunks),
+            "@@ -2,3 +2,3 @@\n two\n-three\n+THREE\n four\n"
+        );
+    }
+
+    #[test]
+    fn test_compute_hunks_separate_and_merged() {
+        let old: String = (1..=20).map(|i| format!("line {}\n", i)).collect();
+        let new = old
+            .replace("line 2\n", "line two\n")
+            .replace("line 18\n", "line eighteen\n");
+
+        // Far-apart changes produce two hunks
+        let hunks = compute_hunks(&old, &new, 3);
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(hunks[0].header, "@@ -1,5 +1,5 @@");
+        assert_eq!(hunks[1].header, "@@ -15,6 +15,6 @@");
+
+        // Wide context merges them
+        assert_eq!(compute_hunks(&old, &new, 10).len(), 1);
+    }
+
+    #[test]
+    fn test_compute_hunks_new_file() {
+        let hunks = compute_hunks("", "a\nb", 3);
+
+        assert_eq!(render(&hunks), "@@ -0,0 +1,2 @@\n+a\n+b\n");
+        assert_eq!(count_changes(&hunks), (2, 0));
+    }
+
+    #[test]
+    fn test_compute_hunks_no_changes() {
+        assert!(compute_hunks("same\ntext", "same\ntext", 3).is_empty());
+    }
+
+    // =============================================================================
+    // Patch Rendering Tests
+    // =============================================================================
+
+    #[test]
+    fn test_render_file_patch_modified() {
+        let hunks = patch_hunks("a\nb\n", "a\nc\n");
+
+        assert_eq!(
+            render_file_patch("src/x.rs", false, &hunks),
+            "diff --git a/src/x.rs b/src/x.rs\n--- a/src/x.rs\n+++ b/src/x.rs\n\
+             @@ -1,2 +1,2 @@\n a\n-b\n+c\n"
+        );
+    }
+
+    #[test]
+    fn test_render_file_patch_new_file_without_newline() {
+        let hunks = patch_hunks("", "a\nb");
+
+        assert_eq!(
+            render_file_patch("new.txt", true, &hunks),
+            "diff --git a/new.txt b/new.txt\nnew file mode 100644\n--- /dev/null\n+++ b/new.txt\n\
+             @@ -0,0 +1,2 @@\n+a\n+b\n\\ No newline at end of file\n"
+        );
+    }
+
+    #[test]
+    fn test_patch_hunks_detects_added_final_newline() {
+        let hunks = patch_hunks("a", "a\n");
+
+        assert_eq!(count_changes(&hunks), (1, 1));
+    }
+
+    #[test]
+    fn test_diff_line_numbers() {
+        let hunks = compute_hunks("a\nb\nc", "a\nc\nd", 0);
+        let lines: Vec<(DiffLineKind, Option<u32>, Option<u32>)> = hunks
+            .iter()
+            .flat_map(|h| &h.lines)
+            .map(|l| (l.kind, l.old_line, l.new_line))
+            .collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                (DiffLineKind::Remove, Some(2), None),
+                (DiffLineKind::Add, None, Some(3)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_selected_hunks() {
+        let old = (1..=20)
+            .map(|i| format!("line {}\n", i))
+            .collect::<String>();
+        let new = old
+            .replace("line 2\n", "line two\n")
+            .replace("line 18\n", "line 18\nline 18b\n");
+        let hunks = patch_hunks(&old, &new);
+        assert_eq!(hunks.len(), 2);
+
+        assert_eq!(apply_selected_hunks(&old, &hunks, |_| true), new);
+        assert_eq!(apply_selected_hunks(&old, &hunks, |_| false), old);
+
+        let second_only = apply_selected_hunks(&old, &hunks, |i| i == 1);
+        assert!(second_only.contains("line 2\n"));
+        assert!(second_only.contains("line 18b\n"));
+    }
+
+    #[test]
+    fn test_apply_selected_hunks_into_empty_file() {
+        let hunks = patch_hunks("", "a\nb");
+        assert_eq!(apply_selected_hunks("", &hunks, |_| true), "a\nb");
+    }
+}
@@ -1,11 +1,28 @@
-//! Git integration for file diffs.
+//! Git integration for file diffs and repository state.
 //!
-//! Provides functionality to get file contents from HEAD and working directory
-//! for comparison in the diff viewer.
+//! Reads file contents, status, history, blame and worktrees for a project, and stages,
+//! commits, discards and stashes changes from the console.
 
-use git2::Repository;
+use git2::build::CheckoutBuilder;
+use git2::{
+    BlameOptions, BranchType, Commit, Delta, DiffFindOptions, DiffOptions, ErrorCode,
+    IndexAddOption, IndexEntry, IndexTime, Oid, Repository, Signature, Sort, StashFlags, Status,
+    StatusOptions, Tree,
+};
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::claude_code::{
+    discover_projects, get_session_file_contents, get_session_file_edits, get_session_git_branch,
+    get_session_slug, make_relative_path, FileEdit,
+};
+use crate::diff::{
+    apply_selected_hunks, compute_hunks, count_changes, patch_hunks, DiffHunk, DiffLineKind,
+};
+use crate::language::{detect_language, PLAINTEXT};
 
 /// Result of getting a git file diff - original (HEAD) and current content.
 #[derive(Clone, serde::Serialize)]
@@ -19,6 +36,14 @@ pub struct GitFileDiff {
     pub exists_at_head: bool,
     /// Whether the file exists in working directory
     pub exists_in_workdir: bool,
+    /// Kind of change from HEAD to the working directory (None when unchanged);
+    /// files missing from HEAD are diffed against empty content as additions
+    pub change_type: Option<GitChangeType>,
+    /// Whether the file is new and not yet staged
+    pub untracked: bool,
+    /// Path of the submodule containing the file, relative to the superproject root
+    /// (None when the file isn't in a submodule)
+    pub submodule: Option<String>,
 }
 
 /// Get the original (HEAD) and current content of a file for diff comparison.
@@ -29,7 +54,15 @@ pub struct GitFileDiff {
 ///
 /// This function discovers the git repository that actually contains the file,
 /// which may be different from project_path when editing files outside the project.
+/// Files inside a submodule are diffed against the submodule's own HEAD; the
+/// submodule directory itself diffs the recorded commit against the checked-out one.
 pub fn get_git_file_diff(project_path: &str, file_path: &str) -> Result<GitFileDiff, String> {
+    if let Ok(superproject) = open_repo(project_path) {
+        if let Some(diff) = submodule_file_diff(&superproject, project_path, file_path)? {
+            return Ok(diff);
+        }
+    }
+
     // Determine the actual file path on disk
     let actual_file_path = if Path::new(file_path).is_absolute() {
         Path::new(file_path).to_path_buf()
@@ -37,51 +70,49 @@ pub fn get_git_file_diff(project_path: &str, file_path: &str) -> Result<GitFileD
         Path::new(project_path).join(file_path)
     };
 
-    // Try to discover the git repository that contains this file
-    // This handles the case where the file is in a different repo than project_path
-    let (repo, relative_path) = if actual_file_path.exists() {
-        // Discover repo from the file's parent directory
-        let parent_dir = actual_file_path.parent().unwrap_or(Path::new("."));
-        match Repository::discover(parent_dir) {
-            Ok(repo) => {
-                // Get the repo's workdir to compute relative path
-                let workdir = repo
-                    .workdir()
-                    .ok_or_else(|| "Repository has no working directory".to_string())?;
-                let rel = actual_file_path
-                    .strip_prefix(workdir)
-                    .map(|p| p.to_path_buf())
-                    .unwrap_or_else(|_| Path::new(file_path).to_path_buf());
-                (repo, rel)
-            }
-            Err(_) => {
-                // No git repo found, fall back to project_path
-                let repo = Repository::open(project_path)
-                    .map_err(|e| format!("Failed to open repository: {}", e))?;
-                (repo, Path::new(file_path).to_path_buf())
-            }
-        }
-    } else {
-        // File doesn't exist, try project_path repo
-        let repo = Repository::open(project_path)
-            .map_err(|e| format!("Failed to open repository: {}", e))?;
-        (repo, Path::new(file_path).to_path_buf())
-    };
-
-    // Get HEAD commit
-    let head = repo.head().map_err(|e| format!("Failed to get HEAD: {}", e))?;
-    let head_commit = head
-        .peel_to_commit()
-        .map_err(|e| format!("Failed to get HEAD commit: {}", e))?;
-    let head_tree = head_commit
-        .tree()
-        .map_err(|e| format!("Failed to get HEAD tree: {}", e))?;
+    // Discover the repository that contains this file. This handles files in a
+    // different repo than project_path, and linked worktrees (whose HEAD differs
+    // from the main checkout). Deleted files resolve from their nearest existing
+    // directory.
+    let search_dir = actual_file_path
+        .ancestors()
+        .skip(1)
+        .find(|dir| dir.is_dir())
+        .unwrap_or(Path::new(project_path));
+    let repo = match Repository::discover(search_dir) {
+        Ok(repo) => repo,
+        // No git repo found, fall back to project_path
+        Err(_) => open_repo(project_path)?,
+    };
+    let (_, relative_path) = repo_paths(&repo, project_path, file_path)?;
+
+    head_and_workdir_diff(&repo, &relative_path, &actual_file_path)
+}
+
+/// Read a file's content at HEAD of `repo` and from the working directory.
+fn head_and_workdir_diff(
+    repo: &Repository,
+    relative_path: &Path,
+    actual_file_path: &Path,
+) -> Result<GitFileDiff, String> {
+    // An unborn branch (no commits yet) has nothing at HEAD, so every file is new
+    let head_tree = match repo.head() {
+        Ok(head) => Some(
+            head.peel_to_tree()
+                .map_err(|e| format!("Failed to get HEAD tree: {}", e))?,
+        ),
+        Err(e) if e.code() == ErrorCode::UnbornBranch => None,
+        Err(e) => return Err(format!("Failed to get HEAD: {}", e)),
+    };
 
     // Try to get file content from HEAD using the relative path
-    let (original, exists_at_head) = match head_tree.get_path(&relative_path) {
-        Ok(entry) => {
+    let head_entry = head_tree
+        .as_ref()
+        .and_then(|tree| tree.get_path(relative_path).ok());
+    let (original, exists_at_head) = match head_entry {
+        Some(entry) => {
             let obj = entry
-                .to_object(&repo)
+                .to_object(repo)
                 .map_err(|e| format!("Failed to get object: {}", e))?;
             let blob = obj
                 .as_blob()
@@ -89,7 +120,7 @@ pub fn get_git_file_diff(project_path: &str, file_path: &str) -> Result<GitFileD
             let content = String::from_utf8_lossy(blob.content()).to_string();
             (content, true)
         }
-        Err(_) => {
+        None => {
             // File doesn't exist at HEAD (new file)
             (String::new(), false)
         }
@@ -97,7 +128,7 @@ pub fn get_git_file_diff(project_path: &str, file_path: &str) -> Result<GitFileD
 
     // Get current file content from working directory
     let (current, exists_in_workdir) = if actual_file_path.exists() {
-        let content = fs::read_to_string(&actual_file_path)
+        let content = fs::read_to_string(actual_file_path)
             .map_err(|e| format!("Failed to read current file: {}", e))?;
         (content, true)
     } else {
@@ -105,10 +136,1828 @@ pub fn get_git_file_diff(project_path: &str, file_path: &str) -> Result<GitFileD
         (String::new(), false)
     };
 
+    // New files that were never staged are untracked
+    let untracked = !exists_at_head
+        && exists_in_workdir
+        && repo
+            .index()
+            .map(|index| index.get_path(relative_path, 0).is_none())
+            .unwrap_or(true);
+
     Ok(GitFileDiff {
+        change_type: file_change_type(exists_at_head, exists_in_workdir, &original, &current),
         original,
         current,
         exists_at_head,
         exists_in_workdir,
+        untracked,
+        submodule: None,
+    })
+}
+
+/// Classify a HEAD-vs-working-tree file comparison (None when unchanged).
+fn file_change_type(
+    exists_at_head: bool,
+    exists_in_workdir: bool,
+    original: &str,
+    current: &str,
+) -> Option<GitChangeType> {
+    match (exists_at_head, exists_in_workdir) {
+        (false, true) => Some(GitChangeType::Added),
+        (true, false) => Some(GitChangeType::Deleted),
+        (true, true) if original != current => Some(GitChangeType::Modified),
+        _ => None,
+    }
+}
+
+/// Diff a path inside (or at the root of) one of the repository's submodules.
+///
+/// Returns None when the path isn't in a submodule.
+fn submodule_file_diff(
+    repo: &Repository,
+    project_path: &str,
+    file_path: &str,
+) -> Result<Option<GitFileDiff>, String> {
+    let (actual_file_path, relative_path) = repo_paths(repo, project_path, file_path)?;
+    let submodules = match repo.submodules() {
+        Ok(submodules) => submodules,
+        Err(_) => return Ok(None),
+    };
+    let Some(submodule) = submodules
+        .into_iter()
+        .find(|sm| relative_path.starts_with(sm.path()))
+    else {
+        return Ok(None);
+    };
+    let submodule_path = submodule.path().to_string_lossy().to_string();
+
+    // The submodule directory itself changes by pointing at a different commit,
+    // shown the way `git diff` does
+    if relative_path == submodule.path() {
+        let subproject_commit = |id: Option<Oid>| {
+            id.map(|id| format!("Subproject commit {}\n", id))
+                .unwrap_or_default()
+        };
+        let original = subproject_commit(submodule.head_id());
+        let current = subproject_commit(submodule.workdir_id());
+        let exists_at_head = submodule.head_id().is_some();
+        let exists_in_workdir = submodule.workdir_id().is_some();
+        return Ok(Some(GitFileDiff {
+            change_type: file_change_type(exists_at_head, exists_in_workdir, &original, &current),
+            original,
+            current,
+            exists_at_head,
+            exists_in_workdir,
+            untracked: false,
+            submodule: Some(submodule_path),
+        }));
+    }
+
+    let inner = submodule
+        .open()
+        .map_err(|e| format!("Submodule {} is not initialized: {}", submodule_path, e))?;
+    let inner_relative = relative_path
+        .strip_prefix(submodule.path())
+        .map_err(|e| format!("Failed to resolve path in submodule: {}", e))?;
+    let mut diff = head_and_workdir_diff(&inner, inner_relative, &actual_file_path)?;
+    diff.submodule = Some(submodule_path);
+    Ok(Some(diff))
+}
+
+/// Open the repository containing a project directory.
+fn open_repo(project_path: &str) -> Result<Repository, String> {
+    Repository::discover(project_path).map_err(|e| format!("Failed to open repository: {}", e))
+}
+
+/// Resolve a file path (absolute or project-relative) to its on-disk path and
+/// its path relative to the repository root.
+fn repo_paths(
+    repo: &Repository,
+    project_path: &str,
+    file_path: &str,
+) -> Result<(PathBuf, PathBuf), String> {
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| "Repository has no working directory".to_string())?;
+    let actual_file_path = if Path::new(file_path).is_absolute() {
+        Path::new(file_path).to_path_buf()
+    } else {
+        Path::new(project_path).join(file_path)
+    };
+    // Repositories reached through a `.git` file (`gitdir:` indirection, as in linked
+    // worktrees and submodules) report their workdir with symlinks resolved, so fall
+    // back to comparing canonical paths before treating the path as repo-relative
+    let relative_path = actual_file_path
+        .strip_prefix(workdir)
+        .map(|p| p.to_path_buf())
+        .ok()
+        .or_else(|| {
+            let canonical_workdir = canonicalize_lenient(workdir)?;
+            let canonical_file = canonicalize_lenient(&actual_file_path)?;
+            canonical_file
+                .strip_prefix(canonical_workdir)
+                .map(|p| p.to_path_buf())
+                .ok()
+        })
+        .unwrap_or_else(|| Path::new(file_path).to_path_buf());
+    Ok((actual_file_path, relative_path))
+}
+
+/// Canonicalize a path that may not exist (e.g. a deleted file) by resolving its
+/// nearest existing ancestor.
+fn canonicalize_lenient(path: &Path) -> Option<PathBuf> {
+    path.ancestors().find_map(|ancestor| {
+        let canonical = ancestor.canonicalize().ok()?;
+        let rest = path.strip_prefix(ancestor).ok()?;
+        Some(if rest.as_os_str().is_empty() {
+            canonical
+        } else {
+            canonical.join(rest)
+        })
+    })
+}
+
+// =============================================================================
+// Working Tree Status
+// =============================================================================
+
+/// Kind of change to a file in the index or working tree.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GitChangeType {
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
+    Typechange,
+}
+
+/// A changed file in the index or working tree.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitStatusEntry {
+    /// Path relative to the repository root
+    pub path: String,
+    /// Kind of change
+    pub change_type: GitChangeType,
+    /// Previous path for renames
+    pub old_path: Option<String>,
+}
+
+/// Working-tree state of a repository.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitStatus {
+    /// Changes staged in the index (HEAD vs index)
+    pub staged: Vec<GitStatusEntry>,
+    /// Unstaged changes (index vs working tree)
+    pub unstaged: Vec<GitStatusEntry>,
+    /// Untracked files
+    pub untracked: Vec<String>,
+    /// Files with merge conflicts
+    pub conflicted: Vec<String>,
+}
+
+/// Get staged, unstaged, untracked, and conflicted files for a project.
+pub fn get_git_status(project_path: &str) -> Result<GitStatus, String> {
+    let repo = open_repo(project_path)?;
+
+    let mut options = StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .renames_head_to_index(true)
+        .renames_index_to_workdir(true);
+    let statuses = repo
+        .statuses(Some(&mut options))
+        .map_err(|e| format!("Failed to get status: {}", e))?;
+
+    let mut status = GitStatus {
+        staged: Vec::new(),
+        unstaged: Vec::new(),
+        untracked: Vec::new(),
+        conflicted: Vec::new(),
+    };
+
+    for entry in statuses.iter() {
+        let flags = entry.status();
+        let path = match entry.path() {
+            Some(p) => p.to_string(),
+            None => continue,
+        };
+
+        if flags.is_conflicted() {
+            status.conflicted.push(path);
+            continue;
+        }
+        if flags.is_wt_new() {
+            status.untracked.push(path.clone());
+        }
+
+        // Renames report the new path on the delta's new_file side
+        if let Some(change_type) = index_change_type(flags) {
+            let (path, old_path) = delta_paths(entry.head_to_index(), &path);
+            status.staged.push(GitStatusEntry {
+                path,
+                change_type,
+                old_path,
+            });
+        }
+        if let Some(change_type) = workdir_change_type(flags) {
+            let (path, old_path) = delta_paths(entry.index_to_workdir(), &path);
+            status.unstaged.push(GitStatusEntry {
+                path,
+                change_type,
+                old_path,
+            });
+        }
+    }
+
+    Ok(status)
+}
+
+/// Change type of the staged side of a status entry.
+fn index_change_type(flags: Status) -> Option<GitChangeType> {
+    if flags.is_index_new() {
+        Some(GitChangeType::Added)
+    } else if flags.is_index_modified() {
+        Some(GitChangeType::Modified)
+    } else if flags.is_index_deleted() {
+        Some(GitChangeType::Deleted)
+    } else if flags.is_index_renamed() {
+        Some(GitChangeType::Renamed)
+    } else if flags.is_index_typechange() {
+        Some(GitChangeType::Typechange)
+    } else {
+        None
+    }
+}
+
+/// Change type of the unstaged side of a status entry (untracked files excluded).
+fn workdir_change_type(flags: Status) -> Option<GitChangeType> {
+    if flags.is_wt_modified() {
+        Some(GitChangeType::Modified)
+    } else if flags.is_wt_deleted() {
+        Some(GitChangeType::Deleted)
+    } else if flags.is_wt_renamed() {
+        Some(GitChangeType::Renamed)
+    } else if flags.is_wt_typechange() {
+        Some(GitChangeType::Typechange)
+    } else {
+        None
+    }
+}
+
+/// Current path of a status delta, plus its old path if it was a rename.
+fn delta_paths(delta: Option<git2::DiffDelta<'_>>, fallback: &str) -> (String, Option<String>) {
+    let delta = match delta {
+        Some(d) => d,
+        None => return (fallback.to_string(), None),
+    };
+    let path_of = |file: git2::DiffFile<'_>| file.path().map(|p| p.to_string_lossy().to_string());
+
+    let path = path_of(delta.new_file()).unwrap_or_else(|| fallback.to_string());
+    let old_path = if delta.status() == Delta::Renamed {
+        path_of(delta.old_file())
+    } else {
+        None
+    };
+    (path, old_path)
+}
+
+// =============================================================================
+// Branches
+// =============================================================================
+
+/// A local branch and its relation to its upstream.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitBranch {
+    /// Branch name (e.g. "main")
+    pub name: String,
+    /// Whether HEAD points at this branch
+    pub is_current: bool,
+    /// Upstream branch name (e.g. "origin/main"), if tracking one
+    pub upstream: Option<String>,
+    /// Commits on this branch not on its upstream
+    pub ahead: usize,
+    /// Commits on the upstream not on this branch
+    pub behind: usize,
+}
+
+/// Local branches of a repository.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitBranches {
+    /// Current branch name, or None when HEAD is detached or unborn
+    pub current: Option<String>,
+    /// Whether HEAD is detached
+    pub detached: bool,
+    /// All local branches, sorted by name
+    pub branches: Vec<GitBranch>,
+}
+
+/// List local branches with ahead/behind counts versus their upstreams.
+pub fn get_git_branches(project_path: &str) -> Result<GitBranches, String> {
+    let repo = open_repo(project_path)?;
+    let detached = repo.head_detached().unwrap_or(false);
+
+    let mut branches = Vec::new();
+    let iter = repo
+        .branches(Some(BranchType::Local))
+        .map_err(|e| format!("Failed to list branches: {}", e))?;
+    for item in iter {
+        let (branch, _) = item.map_err(|e| format!("Failed to read branch: {}", e))?;
+        let name = match branch.name() {
+            Ok(Some(name)) => name.to_string(),
+            _ => continue,
+        };
+
+        let upstream = branch.upstream().ok();
+        let upstream_name = upstream
+            .as_ref()
+            .and_then(|u| u.name().ok().flatten())
+            .map(String::from);
+        // Ahead/behind are only meaningful when both sides resolve to commits
+        let (ahead, behind) = match (
+            branch.get().target(),
+            upstream.and_then(|u| u.get().target()),
+        ) {
+            (Some(local), Some(remote)) => repo.graph_ahead_behind(local, remote).unwrap_or((0, 0)),
+            _ => (0, 0),
+        };
+
+        branches.push(GitBranch {
+            name,
+            is_current: branch.is_head(),
+            upstream: upstream_name,
+            ahead,
+            behind,
+        });
+    }
+    branches.sort_by(|a, b| a.name.cmp(&b.name));
+
+    // An unborn HEAD (fresh repo) has no branch entry yet but still names one
+    let current = if detached {
+        None
+    } else {
+        branches
+            .iter()
+            .find(|b| b.is_current)
+            .map(|b| b.name.clone())
+            .or_else(|| unborn_branch_name(&repo))
+    };
+
+    Ok(GitBranches {
+        current,
+        detached,
+        branches,
+    })
+}
+
+/// The branch a session ran on compared with the branch checked out now.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitBranchCheck {
+    /// Branch recorded in the session's events (None if it didn't record one)
+    pub session_branch: Option<String>,
+    /// Branch checked out now (None when HEAD is detached)
+    pub current_branch: Option<String>,
+    /// Whether both branches are known and differ, e.g. the session's edits were
+    /// made on feature/x while main is checked out
+    pub mismatch: bool,
+}
+
+/// Compare the session's recorded git branch with the project's current branch.
+pub fn check_session_branch(
+    project_path: &str,
+    session_id: &str,
+) -> Result<GitBranchCheck, String> {
+    let repo = open_repo(project_path)?;
+    let current_branch = current_branch_name(&repo);
+    // Detached sessions record the literal "HEAD", which names no branch
+    let session_branch =
+        get_session_git_branch(project_path, session_id).filter(|branch| branch != "HEAD");
+
+    Ok(GitBranchCheck {
+        mismatch: matches!(
+            (&session_branch, &current_branch),
+            (Some(session), Some(current)) if session != current
+        ),
+        session_branch,
+        current_branch,
+    })
+}
+
+/// Get the branch checked out in the project (None when HEAD is detached).
+pub fn get_current_branch(project_path: &str) -> Result<Option<String>, String> {
+    let repo = open_repo(project_path)?;
+    Ok(current_branch_name(&repo))
+}
+
+/// Get the path of the project's HEAD file, inside its git directory (which for a
+/// linked worktree is `.git/worktrees/<name>` of the main repository).
+pub fn get_git_head_path(project_path: &str) -> Result<PathBuf, String> {
+    let repo = open_repo(project_path)?;
+    Ok(repo.path().join("HEAD"))
+}
+
+/// Branch HEAD points at, including unborn branches (None when HEAD is detached).
+fn current_branch_name(repo: &Repository) -> Option<String> {
+    if repo.head_detached().unwrap_or(false) {
+        return None;
+    }
+    match repo.head() {
+        Ok(head) => head.shorthand().map(String::from),
+        Err(_) => unborn_branch_name(repo),
+    }
+}
+
+/// Branch name HEAD refers to in a repository with no commits yet.
+fn unborn_branch_name(repo: &Repository) -> Option<String> {
+    let head = repo.find_reference("HEAD").ok()?;
+    let target = head.symbolic_target()?;
+    target.strip_prefix("refs/heads/").map(String::from)
+}
+
+// =============================================================================
+// Commit History
+// =============================================================================
+
+/// Default number of commits per log page.
+const DEFAULT_LOG_LIMIT: u32 = 50;
+
+/// A commit in the project history.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCommit {
+    /// Full commit hash
+    pub hash: String,
+    /// Author name
+    pub author: String,
+    /// Author email
+    pub author_email: String,
+    /// Commit time (RFC 3339)
+    pub date: String,
+    /// First line of the commit message
+    pub subject: String,
+    /// Paths changed relative to the first parent (or all files for a root commit)
+    pub files: Vec<String>,
+}
+
+/// A page of commit history.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitLogResponse {
+    /// Commits for the requested page, newest first
+    pub commits: Vec<GitCommit>,
+    /// Whether there are older commits after this page
+    pub has_more: bool,
+    /// Hash to pass as `cursor` for the next page, stable as new commits land
+    pub next_cursor: Option<String>,
+}
+
+/// Get a page of commit history reachable from HEAD, newest first.
+///
+/// `cursor` (a commit hash from a previous page's `next_cursor`) takes precedence
+/// over `offset`; both skip commits already shown.
+pub fn get_git_log(
+    project_path: &str,
+    offset: Option<u32>,
+    cursor: Option<&str>,
+    limit: Option<u32>,
+) -> Result<GitLogResponse, String> {
+    let repo = open_repo(project_path)?;
+    let limit = limit.unwrap_or(DEFAULT_LOG_LIMIT) as usize;
+
+    let empty = GitLogResponse {
+        commits: Vec::new(),
+        has_more: false,
+        next_cursor: None,
+    };
+    // A fresh repository has no history yet
+    if repo.head().is_err() {
+        return Ok(empty);
+    }
+
+    let mut revwalk = repo
+        .revwalk()
+        .map_err(|e| format!("Failed to walk history: {}", e))?;
+    revwalk
+        .set_sorting(Sort::TIME)
+        .map_err(|e| format!("Failed to walk history: {}", e))?;
+    revwalk
+        .push_head()
+        .map_err(|e| format!("Failed to walk history: {}", e))?;
+
+    let mut oids = revwalk.filter_map(Result::ok);
+    if let Some(cursor) = cursor {
+        let cursor = Oid::from_str(cursor).map_err(|e| format!("Invalid cursor: {}", e))?;
+        if !oids.by_ref().any(|oid| oid == cursor) {
+            return Ok(empty);
+        }
+    } else {
+        oids.by_ref()
+            .take(offset.unwrap_or(0) as usize)
+            .for_each(drop);
+    }
+
+    let mut commits = Vec::new();
+    let mut has_more = false;
+    for oid in oids {
+        if commits.len() == limit {
+            has_more = true;
+            break;
+        }
+        let commit = repo
+            .find_commit(oid)
+            .map_err(|e| format!("Failed to read commit: {}", e))?;
+        commits.push(commit_summary(&repo, &commit));
+    }
+
+    let next_cursor = if has_more {
+        commits.last().map(|c| c.hash.clone())
+    } else {
+        None
+    };
+    Ok(GitLogResponse {
+        commits,
+        has_more,
+        next_cursor,
+    })
+}
+
+/// Summarize a commit, including the files it touched.
+fn commit_summary(repo: &Repository, commit: &Commit<'_>) -> GitCommit {
+    let author = commit.author();
+    let date = chrono::DateTime::from_timestamp(commit.time().seconds(), 0)
+        .map(|dt| dt.to_rfc3339())
+        .unwrap_or_default();
+
+    GitCommit {
+        hash: commit.id().to_string(),
+        author: author.name().unwrap_or_default().to_string(),
+        author_email: author.email().unwrap_or_default().to_string(),
+        date,
+        subject: commit.summary().unwrap_or_default().to_string(),
+        files: commit_files(repo, commit),
+    }
+}
+
+/// Paths changed by a commit relative to its first parent.
+fn commit_files(repo: &Repository, commit: &Commit<'_>) -> Vec<String> {
+    let tree = match commit.tree() {
+        Ok(tree) => tree,
+        Err(_) => return Vec::new(),
+    };
+    let parent_tree = commit.parent(0).ok().and_then(|p| p.tree().ok());
+    let diff = match repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None) {
+        Ok(diff) => diff,
+        Err(_) => return Vec::new(),
+    };
+
+    diff.deltas()
+        .filter_map(|delta| delta.new_file().path().or_else(|| delta.old_file().path()))
+        .map(|p| p.to_string_lossy().to_string())
+        .collect()
+}
+
+// =============================================================================
+// Ref Diffs
+// =============================================================================
+
+/// Number of unchanged lines shown around each change in ref diffs.
+const REF_DIFF_CONTEXT_LINES: usize = 3;
+
+/// Structured diff of one file between two refs.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitRefFileDiff {
+    /// Path in the `to` tree (or the `from` tree for deletions)
+    pub path: String,
+    /// Path in the `from` tree when the file was renamed
+    pub old_path: Option<String>,
+    /// Kind of change
+    pub change_type: GitChangeType,
+    /// Monaco language identifier for the file
+    pub language: String,
+    /// Whether either side is binary (no hunks are produced)
+    pub is_binary: bool,
+    /// Number of added lines
+    pub additions: u32,
+    /// Number of removed lines
+    pub deletions: u32,
+    /// Unified hunks with line numbers relative to each side's whole file
+    pub hunks: Vec<DiffHunk>,
+}
+
+/// Diff two commits or refs (branches, tags, hashes, `HEAD~2`, ...).
+///
+/// Restricted to `file_path` (relative to the repository root) when given.
+pub fn get_git_ref_diff(
+    project_path: &str,
+    from: &str,
+    to: &str,
+    file_path: Option<&str>,
+) -> Result<Vec<GitRefFileDiff>, String> {
+    let repo = open_repo(project_path)?;
+    let from_tree = resolve_tree(&repo, from)?;
+    let to_tree = resolve_tree(&repo, to)?;
+
+    let mut options = DiffOptions::new();
+    if let Some(path) = file_path {
+        options.pathspec(path).disable_pathspec_match(true);
+    }
+    let mut diff = repo
+        .diff_tree_to_tree(Some(&from_tree), Some(&to_tree), Some(&mut options))
+        .map_err(|e| format!("Failed to diff {}..{}: {}", from, to, e))?;
+    // Rename detection needs the full tree, so skip it for single-file diffs
+    if file_path.is_none() {
+        diff.find_similar(Some(DiffFindOptions::new().renames(true)))
+            .map_err(|e| format!("Failed to detect renames: {}", e))?;
+    }
+
+    let mut files = Vec::new();
+    for delta in diff.deltas() {
+        let change_type = match delta.status() {
+            Delta::Added | Delta::Copied => GitChangeType::Added,
+            Delta::Deleted => GitChangeType::Deleted,
+            Delta::Modified => GitChangeType::Modified,
+            Delta::Renamed => GitChangeType::Renamed,
+            Delta::Typechange => GitChangeType::Typechange,
+            _ => continue,
+        };
+        let old_blob = blob_content(&repo, delta.old_file().id());
+        let new_blob = blob_content(&repo, delta.new_file().id());
+        let (path, old_path) = delta_paths(Some(delta), "");
+        let is_binary = matches!(&old_blob, Some(None)) || matches!(&new_blob, Some(None));
+
+        let (language, hunks) = if is_binary {
+            (PLAINTEXT.to_string(), Vec::new())
+        } else {
+            let old = old_blob.flatten().unwrap_or_default();
+            let new = new_blob.flatten().unwrap_or_default();
+            let language = detect_language(&path, Some(if new.is_empty() { &old } else { &new }));
+            (
+                language.to_string(),
+                compute_hunks(&old, &new, REF_DIFF_CONTEXT_LINES),
+            )
+        };
+        let (additions, deletions) = count_changes(&hunks);
+
+        files.push(GitRefFileDiff {
+            path,
+            old_path,
+            change_type,
+            language,
+            is_binary,
+            additions,
+            deletions,
+            hunks,
+        });
+    }
+
+    Ok(files)
+}
+
+/// Resolve a ref or revision expression to its tree.
+fn resolve_tree<'r>(repo: &'r Repository, spec: &str) -> Result<Tree<'r>, String> {
+    repo.revparse_single(spec)
+        .and_then(|obj| obj.peel_to_tree())
+        .map_err(|e| format!("Failed to resolve '{}': {}", spec, e))
+}
+
+/// Text of a blob: None if the side doesn't exist, Some(None) if it is binary.
+fn blob_content(repo: &Repository, id: Oid) -> Option<Option<String>> {
+    if id.is_zero() {
+        return None;
+    }
+    let blob = repo.find_blob(id).ok()?;
+    if blob.is_binary() {
+        return Some(None);
+    }
+    Some(Some(String::from_utf8_lossy(blob.content()).to_string()))
+}
+
+// =============================================================================
+// Blame
+// =============================================================================
+
+/// Commit-message markers left by coding agents (matched case-insensitively).
+const AGENT_COMMIT_MARKERS: &[&str] = &["co-authored-by: claude", "generated with [claude code]"];
+
+/// A run of consecutive lines last changed by the same commit.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitBlameHunk {
+    /// First line of the run (1-based, in the working-tree file)
+    pub start_line: u32,
+    /// Number of lines in the run
+    pub line_count: u32,
+    /// Commit hash (None for uncommitted lines)
+    pub hash: Option<String>,
+    /// Author name
+    pub author: Option<String>,
+    /// Author email
+    pub author_email: Option<String>,
+    /// Commit time (RFC 3339)
+    pub date: Option<String>,
+    /// First line of the commit message
+    pub subject: Option<String>,
+    /// Whether the commit carries an agent co-author trailer
+    pub agent_authored: bool,
+}
+
+/// Blame a file's working-tree content, optionally limited to a line range.
+///
+/// `start_line`/`end_line` are 1-based and inclusive. Lines changed since the
+/// last commit come back with no hash.
+pub fn get_git_blame(
+    project_path: &str,
+    file_path: &str,
+    start_line: Option<u32>,
+    end_line: Option<u32>,
+) -> Result<Vec<GitBlameHunk>, String> {
+    let repo = open_repo(project_path)?;
+    let (actual_file_path, relative_path) = repo_paths(&repo, project_path, file_path)?;
+
+    let committed = repo
+        .blame_file(&relative_path, Some(&mut BlameOptions::new()))
+        .map_err(|e| format!("Failed to blame {}: {}", relative_path.display(), e))?;
+
+    // Blame the on-disk content so uncommitted lines show up as such
+    let blame = match fs::read(&actual_file_path) {
+        Ok(buffer) => committed
+            .blame_buffer(&buffer)
+            .map_err(|e| format!("Failed to blame working tree: {}", e))?,
+        Err(_) => committed,
+    };
+
+    // The range refers to working-tree lines, so clip after blaming the buffer
+    let first = start_line.unwrap_or(1);
+    let last = end_line.unwrap_or(u32::MAX);
+
+    let mut hunks = Vec::new();
+    for hunk in blame.iter() {
+        let hunk_start = hunk.final_start_line() as u32;
+        let hunk_end = hunk_start + hunk.lines_in_hunk() as u32 - 1;
+        let start = hunk_start.max(first);
+        let end = hunk_end.min(last);
+        if start > end {
+            continue;
+        }
+
+        let id = hunk.final_commit_id();
+        let commit = if id.is_zero() {
+            None
+        } else {
+            repo.find_commit(id).ok()
+        };
+        let author = commit.as_ref().map(|c| c.author());
+
+        hunks.push(GitBlameHunk {
+            start_line: start,
+            line_count: end - start + 1,
+            hash: commit.as_ref().map(|c| c.id().to_string()),
+            author: author.as_ref().and_then(|a| a.name()).map(String::from),
+            author_email: author.as_ref().and_then(|a| a.email()).map(String::from),
+            date: commit
+                .as_ref()
+                .and_then(|c| chrono::DateTime::from_timestamp(c.time().seconds(), 0))
+                .map(|dt| dt.to_rfc3339()),
+            subject: commit.as_ref().and_then(|c| c.summary()).map(String::from),
+            agent_authored: commit.as_ref().is_some_and(is_agent_commit),
+        });
+    }
+
+    Ok(hunks)
+}
+
+/// Whether a commit message carries an agent co-author trailer.
+fn is_agent_commit(commit: &Commit<'_>) -> bool {
+    let message = commit.message().unwrap_or_default().to_lowercase();
+    AGENT_COMMIT_MARKERS
+        .iter()
+        .any(|marker| message.contains(marker))
+}
+
+// =============================================================================
+// Staging and Committing
+// =============================================================================
+
+/// Stage files (absolute or project-relative paths), including deletions.
+///
+/// Returns the working-tree status after staging.
+pub fn git_stage_files(project_path: &str, file_paths: &[String]) -> Result<GitStatus, String> {
+    let repo = open_repo(project_path)?;
+    let mut index = repo
+        .index()
+        .map_err(|e| format!("Failed to open index: {}", e))?;
+
+    for file_path in file_paths {
+        let (actual_file_path, relative_path) = repo_paths(&repo, project_path, file_path)?;
+        let result = if actual_file_path.exists() {
+            index.add_path(&relative_path)
+        } else {
+            index.remove_path(&relative_path)
+        };
+        result.map_err(|e| format!("Failed to stage {}: {}", relative_path.display(), e))?;
+    }
+    index
+        .write()
+        .map_err(|e| format!("Failed to write index: {}", e))?;
+
+    get_git_status(project_path)
+}
+
+/// An unstaged hunk (index vs working tree) that can be staged on its own.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitStageableHunk {
+    /// Position of the hunk in the file's unstaged diff (used to select it for staging)
+    pub index: usize,
+    /// Whether the hunk's changes match what the session left in the file
+    /// (None when no session was given or its content couldn't be reconstructed)
+    pub from_session: Option<bool>,
+    /// The hunk, with line terminators stripped for display
+    pub hunk: DiffHunk,
+}
+
+/// Get the unstaged hunks of a file, optionally flagging the ones that came from a session.
+pub fn get_unstaged_hunks(
+    project_path: &str,
+    file_path: &str,
+    session_id: Option<&str>,
+) -> Result<Vec<GitStageableHunk>, String> {
+    let repo = open_repo(project_path)?;
+    let (actual_file_path, relative_path) = repo_paths(&repo, project_path, file_path)?;
+    let (staged, working) = staged_and_working_content(&repo, &relative_path, &actual_file_path)?;
+    let session_content =
+        session_id.and_then(|id| session_file_content(project_path, id, file_path));
+
+    let hunks = patch_hunks(&staged, &working)
+        .into_iter()
+        .enumerate()
+        .map(|(index, mut hunk)| {
+            let from_session = session_content
+                .as_deref()
+                .map(|content| hunk_matches_session(&hunk, content));
+            for line in &mut hunk.lines {
+                let trimmed_len = line.content.trim_end_matches(['\n', '\r']).len();
+                line.content.truncate(trimmed_len);
+            }
+            GitStageableHunk {
+                index,
+                from_session,
+                hunk,
+            }
+        })
+        .collect();
+    Ok(hunks)
+}
+
+/// Stage selected unstaged hunks of a file, leaving the rest in the working tree.
+///
+/// Hunks are chosen by `hunk_indexes` (positions from `get_unstaged_hunks`), or, when
+/// that is None, every hunk that matches what the session left in the file.
+/// Returns the working-tree status after staging.
+pub fn git_stage_hunks(
+    project_path: &str,
+    file_path: &str,
+    hunk_indexes: Option<&[usize]>,
+    session_id: Option<&str>,
+) -> Result<GitStatus, String> {
+    let repo = open_repo(project_path)?;
+    let (actual_file_path, relative_path) = repo_paths(&repo, project_path, file_path)?;
+    let (staged, working) = staged_and_working_content(&repo, &relative_path, &actual_file_path)?;
+    let hunks = patch_hunks(&staged, &working);
+
+    let selected: Vec<bool> = match (hunk_indexes, session_id) {
+        (Some(indexes), _) => {
+            if let Some(&bad) = indexes.iter().find(|&&i| i >= hunks.len()) {
+                return Err(format!(
+                    "Hunk {} out of range ({} unstaged hunks)",
+                    bad,
+                    hunks.len()
+                ));
+            }
+            (0..hunks.len()).map(|i| indexes.contains(&i)).collect()
+        }
+        (None, Some(session_id)) => {
+            let content = session_file_content(project_path, session_id, file_path)
+                .ok_or_else(|| format!("Session content of {} is not available", file_path))?;
+            hunks
+                .iter()
+                .map(|hunk| hunk_matches_session(hunk, &content))
+                .collect()
+        }
+        (None, None) => return Err("Either hunk indexes or a session is required".to_string()),
+    };
+    if !selected.contains(&true) {
+        return Err("No hunks selected to stage".to_string());
+    }
+
+    let content = apply_selected_hunks(&staged, &hunks, |i| selected[i]);
+    let mut index = repo
+        .index()
+        .map_err(|e| format!("Failed to open index: {}", e))?;
+    let entry = index
+        .get_path(&relative_path, 0)
+        .unwrap_or_else(|| new_index_entry(&relative_path));
+    index
+        .add_frombuffer(&entry, content.as_bytes())
+        .map_err(|e| format!("Failed to stage {}: {}", relative_path.display(), e))?;
+    index
+        .write()
+        .map_err(|e| format!("Failed to write index: {}", e))?;
+
+    get_git_status(project_path)
+}
+
+/// Read a file's content in the index (empty if it isn't staged) and in the working tree.
+fn staged_and_working_content(
+    repo: &Repository,
+    relative_path: &Path,
+    actual_file_path: &Path,
+) -> Result<(String, String), String> {
+    let index = repo
+        .index()
+        .map_err(|e| format!("Failed to open index: {}", e))?;
+    let staged = match index.get_path(relative_path, 0) {
+        Some(entry) => {
+            let blob = repo
+                .find_blob(entry.id)
+                .map_err(|e| format!("Failed to read staged content: {}", e))?;
+            String::from_utf8_lossy(blob.content()).to_string()
+        }
+        None => String::new(),
+    };
+    let working = fs::read_to_string(actual_file_path)
+        .map_err(|e| format!("Failed to read current file: {}", e))?;
+    Ok((staged, working))
+}
+
+/// Content a session left in a file, if its edits can be replayed.
+fn session_file_content(project_path: &str, session_id: &str, file_path: &str) -> Option<String> {
+    get_session_file_contents(project_path, session_id, file_path)
+        .ok()
+        .map(|contents| contents.after)
+}
+
+/// Whether a hunk is the session's work: every added line is present in the session's
+/// result and every removed line is gone from it.
+fn hunk_matches_session(hunk: &DiffHunk, session_content: &str) -> bool {
+    let session_lines: HashSet<&str> = session_content.lines().collect();
+    hunk.lines.iter().all(|line| {
+        let text = line.content.trim_end_matches(['\n', '\r']);
+        match line.kind {
+            DiffLineKind::Add => session_lines.contains(text),
+            DiffLineKind::Remove => !session_lines.contains(text),
+            DiffLineKind::Context => true,
+        }
+    })
+}
+
+/// Index entry for a file that isn't staged yet (regular, non-executable file).
+fn new_index_entry(relative_path: &Path) -> IndexEntry {
+    IndexEntry {
+        ctime: IndexTime::new(0, 0),
+        mtime: IndexTime::new(0, 0),
+        dev: 0,
+        ino: 0,
+        mode: 0o100644,
+        uid: 0,
+        gid: 0,
+        file_size: 0,
+        id: Oid::zero(),
+        flags: 0,
+        flags_extended: 0,
+        path: relative_path
+            .to_string_lossy()
+            .replace('\\', "/")
+            .into_bytes(),
+    }
+}
+
+/// Commit the staged changes on the current branch with the user's git identity.
+pub fn git_commit(project_path: &str, message: &str) -> Result<GitCommit, String> {
+    if message.trim().is_empty() {
+        return Err("Commit message is empty".to_string());
+    }
+
+    let repo = open_repo(project_path)?;
+    let signature = repo
+        .signature()
+        .map_err(|e| format!("Git user.name/user.email not configured: {}", e))?;
+    let mut index = repo
+        .index()
+        .map_err(|e| format!("Failed to open index: {}", e))?;
+    let tree_id = index
+        .write_tree()
+        .map_err(|e| format!("Failed to write tree: {}", e))?;
+    let tree = repo
+        .find_tree(tree_id)
+        .map_err(|e| format!("Failed to read tree: {}", e))?;
+
+    // The first commit in a fresh repository has no parent
+    let parent = match repo.head() {
+        Ok(head) => Some(
+            head.peel_to_commit()
+                .map_err(|e| format!("Failed to get HEAD commit: {}", e))?,
+        ),
+        Err(e) if e.code() == ErrorCode::UnbornBranch => None,
+        Err(e) => return Err(format!("Failed to get HEAD: {}", e)),
+    };
+    if parent.as_ref().is_some_and(|p| p.tree_id() == tree_id) {
+        return Err("Nothing staged to commit".to_string());
+    }
+
+    let parents: Vec<&Commit<'_>> = parent.iter().collect();
+    let commit_id = repo
+        .commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .map_err(|e| format!("Failed to commit: {}", e))?;
+    let commit = repo
+        .find_commit(commit_id)
+        .map_err(|e| format!("Failed to read commit: {}", e))?;
+
+    Ok(commit_summary(&repo, &commit))
+}
+
+/// Restore a file to its HEAD content, discarding staged and unstaged changes.
+///
+/// Only files edited in the given session can be discarded, so a stray path can't
+/// throw away unrelated work. Files that don't exist at HEAD are unstaged and deleted.
+/// Returns the working-tree status after discarding.
+pub fn git_discard_file(
+    project_path: &str,
+    session_id: &str,
+    file_path: &str,
+) -> Result<GitStatus, String> {
+    let edits = get_session_file_edits(project_path, session_id);
+    check_edited_in_session(&edits, project_path, session_id, file_path)?;
+    discard_file(project_path, file_path)
+}
+
+/// Check that a file (or the path it was renamed from) is among a session's edits.
+fn check_edited_in_session(
+    edits: &[FileEdit],
+    project_path: &str,
+    session_id: &str,
+    file_path: &str,
+) -> Result<(), String> {
+    let session_path = make_relative_path(file_path, project_path);
+    let edited_in_session = edits.iter().any(|edit| {
+        edit.path == session_path || edit.renamed_from.as_deref() == Some(session_path.as_str())
+    });
+    if !edited_in_session {
+        return Err(format!(
+            "{} was not edited in session {}",
+            session_path, session_id
+        ));
+    }
+    Ok(())
+}
+
+/// Restore a file to HEAD, or unstage and delete it if it doesn't exist at HEAD.
+fn discard_file(project_path: &str, file_path: &str) -> Result<GitStatus, String> {
+    let repo = open_repo(project_path)?;
+    let (actual_file_path, relative_path) = repo_paths(&repo, project_path, file_path)?;
+    let head_tree = repo
+        .head()
+        .and_then(|head| head.peel_to_tree())
+        .map_err(|e| format!("Failed to get HEAD tree: {}", e))?;
+
+    if head_tree.get_path(&relative_path).is_ok() {
+        let mut checkout = CheckoutBuilder::new();
+        checkout
+            .force()
+            .disable_pathspec_match(true)
+            .path(&relative_path);
+        repo.checkout_head(Some(&mut checkout))
+            .map_err(|e| format!("Failed to restore {}: {}", relative_path.display(), e))?;
+    } else {
+        let mut index = repo
+            .index()
+            .map_err(|e| format!("Failed to open index: {}", e))?;
+        if index.get_path(&relative_path, 0).is_some() {
+            index
+                .remove_path(&relative_path)
+                .map_err(|e| format!("Failed to unstage {}: {}", relative_path.display(), e))?;
+            index
+                .write()
+                .map_err(|e| format!("Failed to write index: {}", e))?;
+        }
+        if actual_file_path.exists() {
+            fs::remove_file(&actual_file_path)
+                .map_err(|e| format!("Failed to delete {}: {}", relative_path.display(), e))?;
+        }
+    }
+
+    get_git_status(project_path)
+}
+
+// =============================================================================
+// Stashes
+// =============================================================================
+
+/// A stash entry.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitStash {
+    /// Position in the stash list (0 is the most recent)
+    pub index: usize,
+    /// Stash message (e.g. "On main: WIP")
+    pub message: String,
+    /// Hash of the stash commit
+    pub hash: String,
+}
+
+/// Stash uncommitted changes (optionally including untracked files).
+///
+/// Returns the new stash entry.
+pub fn git_stash_push(
+    project_path: &str,
+    message: Option<&str>,
+    include_untracked: bool,
+) -> Result<GitStash, String> {
+    let mut repo = open_repo(project_path)?;
+    let signature = repo
+        .signature()
+        .map_err(|e| format!("Git user.name/user.email not configured: {}", e))?;
+    let flags = if include_untracked {
+        StashFlags::INCLUDE_UNTRACKED
+    } else {
+        StashFlags::DEFAULT
+    };
+
+    repo.stash_save2(&signature, message, Some(flags))
+        .map_err(|e| match e.code() {
+            ErrorCode::NotFound => "No local changes to stash".to_string(),
+            _ => format!("Failed to stash: {}", e),
+        })?;
+
+    git_stash_list_in(&mut repo)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Stash was not recorded".to_string())
+}
+
+/// List stash entries, most recent first.
+pub fn git_stash_list(project_path: &str) -> Result<Vec<GitStash>, String> {
+    let mut repo = open_repo(project_path)?;
+    git_stash_list_in(&mut repo)
+}
+
+fn git_stash_list_in(repo: &mut Repository) -> Result<Vec<GitStash>, String> {
+    let mut stashes = Vec::new();
+    repo.stash_foreach(|index, message, oid| {
+        stashes.push(GitStash {
+            index,
+            message: message.to_string(),
+            hash: oid.to_string(),
+        });
+        true
+    })
+    .map_err(|e| format!("Failed to list stashes: {}", e))?;
+    Ok(stashes)
+}
+
+/// Apply and drop a stash entry (the most recent if `index` is None).
+///
+/// Returns the working-tree status after popping.
+pub fn git_stash_pop(project_path: &str, index: Option<usize>) -> Result<GitStatus, String> {
+    let mut repo = open_repo(project_path)?;
+    repo.stash_pop(index.unwrap_or(0), None)
+        .map_err(|e| match e.code() {
+            ErrorCode::NotFound => "No such stash entry".to_string(),
+            ErrorCode::Conflict => "Stash conflicts with local changes".to_string(),
+            _ => format!("Failed to pop stash: {}", e),
+        })?;
+    get_git_status(project_path)
+}
+
+// =============================================================================
+// Session Snapshots
+// =============================================================================
+
+/// Prefix for snapshot tag and branch names.
+const SNAPSHOT_REF_PREFIX: &str = "agent-console/";
+
+/// A tag or branch capturing the repository state at review time.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitSnapshot {
+    /// Tag or branch name (e.g. "agent-console/async-knitting-panda")
+    pub name: String,
+    /// Full ref name (e.g. "refs/tags/agent-console/async-knitting-panda")
+    pub ref_name: String,
+    /// Hash of the snapshot commit
+    pub hash: String,
+    /// Whether uncommitted changes were captured in a snapshot commit on top of HEAD
+    pub includes_uncommitted: bool,
+}
+
+/// Capture the current repository state, including uncommitted and untracked changes,
+/// under a lightweight tag (or a branch) named after the session.
+///
+/// Uncommitted changes are recorded in a commit on top of HEAD that no branch points
+/// to, leaving HEAD, the index, and the working tree untouched. Existing names get a
+/// numeric suffix rather than being overwritten.
+pub fn create_session_snapshot(
+    project_path: &str,
+    session_id: &str,
+    as_branch: bool,
+) -> Result<GitSnapshot, String> {
+    let repo = open_repo(project_path)?;
+    let head = match repo.head() {
+        Ok(head) => Some(
+            head.peel_to_commit()
+                .map_err(|e| format!("Failed to get HEAD commit: {}", e))?,
+        ),
+        Err(e) if e.code() == ErrorCode::UnbornBranch => None,
+        Err(e) => return Err(format!("Failed to get HEAD: {}", e)),
+    };
+
+    let tree_id = working_tree_id(&repo)?;
+    let (commit, includes_uncommitted) = match head {
+        Some(head) if head.tree_id() == tree_id => (head, false),
+        parent => {
+            let signature = repo
+                .signature()
+                .or_else(|_| Signature::now("Agent Console", "agent-console@localhost"))
+                .map_err(|e| format!("Failed to create signature: {}", e))?;
+            let tree = repo
+                .find_tree(tree_id)
+                .map_err(|e| format!("Failed to read tree: {}", e))?;
+            let parents: Vec<&Commit<'_>> = parent.iter().collect();
+            let message = format!("Agent Console snapshot of session {}", session_id);
+            let commit_id = repo
+                .commit(None, &signature, &signature, &message, &tree, &parents)
+                .map_err(|e| format!("Failed to create snapshot commit: {}", e))?;
+            let commit = repo
+                .find_commit(commit_id)
+                .map_err(|e| format!("Failed to read snapshot commit: {}", e))?;
+            (commit, true)
+        }
+    };
+
+    let slug = get_session_slug(project_path, session_id)
+        .unwrap_or_else(|| session_id.chars().take(8).collect());
+    let ref_kind = if as_branch { "heads" } else { "tags" };
+    let name = (1..)
+        .map(|n| match n {
+            1 => format!("{}{}", SNAPSHOT_REF_PREFIX, slug),
+            n => format!("{}{}-{}", SNAPSHOT_REF_PREFIX, slug, n),
+        })
+        .find(|name| {
+            repo.find_reference(&format!("refs/{}/{}", ref_kind, name))
+                .is_err()
+        })
+        .unwrap_or_default();
+
+    if as_branch {
+        repo.branch(&name, &commit, false)
+            .map_err(|e| format!("Failed to create branch {}: {}", name, e))?;
+    } else {
+        repo.tag_lightweight(&name, commit.as_object(), false)
+            .map_err(|e| format!("Failed to create tag {}: {}", name, e))?;
+    }
+
+    Ok(GitSnapshot {
+        ref_name: format!("refs/{}/{}", ref_kind, name),
+        name,
+        hash: commit.id().to_string(),
+        includes_uncommitted,
     })
 }
+
+/// Write a tree of the working directory (tracked changes, deletions, and untracked
+/// files that aren't ignored) without touching the index on disk.
+fn working_tree_id(repo: &Repository) -> Result<Oid, String> {
+    let mut index = repo
+        .index()
+        .map_err(|e| format!("Failed to open index: {}", e))?;
+    let result = index
+        .add_all(["*"], IndexAddOption::DEFAULT, None)
+        .and_then(|_| index.update_all(["*"], None))
+        .and_then(|_| index.write_tree());
+    // Drop the in-memory additions so the real index stays as the user left it
+    index
+        .read(true)
+        .map_err(|e| format!("Failed to reload index: {}", e))?;
+    result.map_err(|e| format!("Failed to snapshot working tree: {}", e))
+}
+
+// =============================================================================
+// Worktrees
+// =============================================================================
+
+/// A checkout of a repository: the main working tree or a linked worktree.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitWorktree {
+    /// Worktree name (None for the main working tree)
+    pub name: Option<String>,
+    /// Absolute path of the checkout
+    pub path: String,
+    /// Branch checked out, or None when detached
+    pub branch: Option<String>,
+    /// Hash of the checked-out commit (None before the first commit)
+    pub head: Option<String>,
+    /// Whether this is the main working tree
+    pub is_main: bool,
+    /// Whether the worktree is locked against pruning
+    pub is_locked: bool,
+    /// Sessions whose working directory is inside this checkout
+    pub session_ids: Vec<String>,
+}
+
+/// List the main working tree and linked worktrees of a project's repository,
+/// with the sessions that ran in each.
+pub fn get_git_worktrees(project_path: &str) -> Result<Vec<GitWorktree>, String> {
+    let repo = open_repo(project_path)?;
+    // Linked worktrees are only enumerable from the main repository
+    let main = if repo.is_worktree() {
+        Repository::open(repo.commondir())
+            .map_err(|e| format!("Failed to open main repository: {}", e))?
+    } else {
+        repo
+    };
+
+    let mut worktrees = Vec::new();
+    if let Some(workdir) = main.workdir() {
+        worktrees.push(worktree_entry(&main, None, workdir, false));
+    }
+
+    let names = main
+        .worktrees()
+        .map_err(|e| format!("Failed to list worktrees: {}", e))?;
+    for name in names.iter().flatten() {
+        let worktree = match main.find_worktree(name) {
+            Ok(w) => w,
+            Err(_) => continue,
+        };
+        let is_locked = matches!(
+            worktree.is_locked(),
+            Ok(git2::WorktreeLockStatus::Locked(_))
+        );
+        // Skip worktrees whose directory was removed without pruning
+        if let Ok(checkout) = Repository::open_from_worktree(&worktree) {
+            worktrees.push(worktree_entry(
+                &checkout,
+                Some(name.to_string()),
+                worktree.path(),
+                is_locked,
+            ));
+        }
+    }
+
+    assign_sessions(&mut worktrees);
+    Ok(worktrees)
+}
+
+/// Index of the worktree containing `cwd` (the deepest match, since linked
+/// worktrees may live inside the main checkout).
+pub fn worktree_for_path(worktrees: &[GitWorktree], cwd: &str) -> Option<usize> {
+    let cwd = Path::new(cwd);
+    worktrees
+        .iter()
+        .enumerate()
+        .filter(|(_, w)| cwd.starts_with(&w.path))
+        .max_by_key(|(_, w)| w.path.len())
+        .map(|(i, _)| i)
+}
+
+fn worktree_entry(
+    checkout: &Repository,
+    name: Option<String>,
+    path: &Path,
+    is_locked: bool,
+) -> GitWorktree {
+    let head = checkout.head().ok();
+    let branch = head
+        .as_ref()
+        .filter(|h| h.is_branch())
+        .and_then(|h| h.shorthand())
+        .map(String::from)
+        .or_else(|| unborn_branch_name(checkout));
+
+    GitWorktree {
+        is_main: name.is_none(),
+        name,
+        path: path
+            .to_string_lossy()
+            .trim_end_matches(['/', '\\'])
+            .to_string(),
+        branch,
+        head: head.and_then(|h| h.target()).map(|oid| oid.to_string()),
+        is_locked,
+        session_ids: Vec::new(),
+    }
+}
+
+/// Attach each known session to the worktree its working directory belongs to.
+fn assign_sessions(worktrees: &mut [GitWorktree]) {
+    for project in discover_projects() {
+        if let Some(index) = worktree_for_path(worktrees, &project.project_path) {
+            worktrees[index]
+                .session_ids
+                .extend(project.sessions.into_iter().map(|s| s.id));
+        }
+    }
+}
+
+// =============================================================================
+// Dirty Flag
+// =============================================================================
+
+/// How long a cached uncommitted-change count stays fresh.
+const DIRTY_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// Cached uncommitted-change counts per project, so refreshing the project list
+/// doesn't rescan every working tree.
+pub struct GitDirtyCache {
+    /// Map of project_path -> (when counted, count)
+    counts: Mutex<HashMap<String, (Instant, Option<u32>)>>,
+}
+
+impl GitDirtyCache {
+    pub fn new() -> Self {
+        Self {
+            counts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Number of uncommitted changes for a project, recounted when stale.
+    pub fn uncommitted_changes(&self, project_path: &str) -> Option<u32> {
+        if let Ok(counts) = self.counts.lock() {
+            if let Some((counted_at, count)) = counts.get(project_path) {
+                if counted_at.elapsed() < DIRTY_CACHE_TTL {
+                    return *count;
+                }
+            }
+        }
+
+        let count = count_uncommitted_changes(project_path);
+        if let Ok(mut counts) = self.counts.lock() {
+            counts.insert(project_path.to_string(), (Instant::now(), count));
+        }
+        count
+    }
+
+    /// Drop a project's cached count after a change to its working tree or index.
+    pub fn invalidate(&self, project_path: &str) {
+        if let Ok(mut counts) = self.counts.lock() {
+            counts.remove(project_path);
+        }
+    }
+}
+
+/// Count files with staged, unstaged, or untracked changes.
+///
+/// Untracked directories count once rather than being recursed into, which
+/// keeps this cheap on large repositories. Returns None for non-repositories.
+pub fn count_uncommitted_changes(project_path: &str) -> Option<u32> {
+    let repo = open_repo(project_path).ok()?;
+    let mut options = StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(false)
+        .exclude_submodules(true);
+    let statuses = repo.statuses(Some(&mut options)).ok()?;
+    Some(statuses.len() as u32)
+}
+
+// =============================================================================
+// Ignore Rules
+// =============================================================================
+
+/// Drop file edits the repository ignores (build artifacts, vendored files, ...).
+///
+/// Tracked files are kept even if an ignore rule matches them, as are files
+/// outside the repository. Edits pass through unchanged if the project isn't
+/// a git repository.
+pub fn filter_ignored_file_edits(project_path: &str, edits: Vec<FileEdit>) -> Vec<FileEdit> {
+    let repo = match open_repo(project_path) {
+        Ok(repo) => repo,
+        Err(_) => return edits,
+    };
+    let index = repo.index().ok();
+
+    edits
+        .into_iter()
+        .filter(|edit| {
+            let relative_path = match repo_paths(&repo, project_path, &edit.path) {
+                Ok((_, rel)) if rel.is_relative() => rel,
+                _ => return true,
+            };
+            let ignored = repo.is_path_ignored(&relative_path).unwrap_or(false);
+            let tracked = index
+                .as_ref()
+                .is_some_and(|i| i.get_path(&relative_path, 0).is_some());
+            !ignored || tracked
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::claude_code::FileEditType;
+
+    /// Create a repository in a temp directory with `files` committed.
+    fn init_repo(files: &[(&str, &str)]) -> (tempfile::TempDir, Repository) {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        for (path, content) in files {
+            write_file(&dir, path, content);
+        }
+        commit_all(&repo, "Initial commit");
+        (dir, repo)
+    }
+
+    /// Stage everything in the working tree and commit it on HEAD.
+    fn commit_all(repo: &Repository, message: &str) -> Oid {
+        let mut index = repo.index().unwrap();
+        index.add_all(["*"], IndexAddOption::DEFAULT, None).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = Signature::now("Test", "test@example.com").unwrap();
+        let parent = repo.head().ok().map(|head| head.peel_to_commit().unwrap());
+        let parents: Vec<&Commit<'_>> = parent.iter().collect();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .unwrap()
+    }
+
+    fn write_file(dir: &tempfile::TempDir, path: &str, content: &str) {
+        let path = dir.path().join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn read_file(dir: &tempfile::TempDir, path: &str) -> String {
+        fs::read_to_string(dir.path().join(path)).unwrap()
+    }
+
+    fn project_path(dir: &tempfile::TempDir) -> &str {
+        dir.path().to_str().unwrap()
+    }
+
+    // =============================================================================
+    // Discard Tests
+    // =============================================================================
+
+    fn file_edit(path: &str, renamed_from: Option<&str>) -> FileEdit {
+        FileEdit {
+            path: path.to_string(),
+            edit_type: FileEditType::Modified,
+            last_edited_at: None,
+            edited_by: None,
+            lines_added: 1,
+            lines_removed: 0,
+            edit_count: 1,
+            renamed_from: renamed_from.map(String::from),
+            is_generated: false,
+        }
+    }
+
+    #[test]
+    fn test_check_edited_in_session() {
+        let edits = vec![
+            file_edit("src/a.rs", None),
+            file_edit("src/new.rs", Some("src/old.rs")),
+        ];
+
+        assert!(check_edited_in_session(&edits, "/p", "s-1", "/p/src/a.rs").is_ok());
+        assert!(check_edited_in_session(&edits, "/p", "s-1", "src/old.rs").is_ok());
+        assert_eq!(
+            check_edited_in_session(&edits, "/p", "s-1", "/p/src/b.rs"),
+            Err("src/b.rs was not edited in session s-1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_discard_file_restores_tracked_file() {
+        let (dir, _repo) = init_repo(&[("a.txt", "one\n"), ("b.txt", "two\n")]);
+        write_file(&dir, "a.txt", "one changed\n");
+        write_file(&dir, "b.txt", "two changed\n");
+
+        let status = discard_file(project_path(&dir), "a.txt").unwrap();
+
+        assert_eq!(read_file(&dir, "a.txt"), "one\n");
+        assert_eq!(read_file(&dir, "b.txt"), "two changed\n");
+        let unstaged: Vec<&str> = status.unstaged.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(unstaged, vec!["b.txt"]);
+    }
+
+    #[test]
+    fn test_discard_file_deletes_new_file() {
+        let (dir, repo) = init_repo(&[("a.txt", "one\n")]);
+        write_file(&dir, "a.txt", "one changed\n");
+        write_file(&dir, "new.txt", "new\n");
+        write_file(&dir, "staged.txt", "staged\n");
+        write_file(&dir, "other.txt", "other\n");
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("staged.txt")).unwrap();
+        index.write().unwrap();
+
+        discard_file(project_path(&dir), "new.txt").unwrap();
+        let status = discard_file(project_path(&dir), "staged.txt").unwrap();
+
+        assert!(!dir.path().join("new.txt").exists());
+        assert!(!dir.path().join("staged.txt").exists());
+        assert!(status.staged.is_empty());
+        assert_eq!(status.untracked, vec!["other.txt"]);
+        assert_eq!(read_file(&dir, "a.txt"), "one changed\n");
+    }
+
+    // =============================================================================
+    // Hunk Staging Tests
+    // =============================================================================
+
+    /// The index as written on disk (the repository caches the one it last loaded).
+    fn disk_index(repo: &Repository) -> git2::Index {
+        let mut index = repo.index().unwrap();
+        index.read(true).unwrap();
+        index
+    }
+
+    /// Content of a file in the on-disk index, if it's staged.
+    fn staged_content(repo: &Repository, path: &str) -> Option<String> {
+        let entry = disk_index(repo).get_path(Path::new(path), 0)?;
+        let blob = repo.find_blob(entry.id).unwrap();
+        Some(String::from_utf8_lossy(blob.content()).to_string())
+    }
+
+    /// Twenty numbered lines, with the given line numbers replaced.
+    fn numbered_lines(changed: &[usize]) -> String {
+        (1..=20)
+            .map(|n| {
+                if changed.contains(&n) {
+                    format!("changed {}\n", n)
+                } else {
+                    format!("line {}\n", n)
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_git_stage_hunks_stages_only_selected_hunk() {
+        let (dir, repo) = init_repo(&[("f.txt", numbered_lines(&[]).as_str())]);
+        write_file(&dir, "f.txt", &numbered_lines(&[2, 18]));
+
+        let hunks = get_unstaged_hunks(project_path(&dir), "f.txt", None).unwrap();
+        assert_eq!(hunks.len(), 2);
+        assert!(hunks.iter().all(|h| h.from_session.is_none()));
+
+        git_stage_hunks(project_path(&dir), "f.txt", Some(&[0]), None).unwrap();
+
+        assert_eq!(
+            staged_content(&repo, "f.txt").as_deref(),
+            Some(numbered_lines(&[2]).as_str())
+        );
+        let remaining = get_unstaged_hunks(project_path(&dir), "f.txt", None).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert!(remaining[0]
+            .hunk
+            .lines
+            .iter()
+            .any(|line| line.kind == DiffLineKind::Add && line.content == "changed 18"));
+        assert_eq!(read_file(&dir, "f.txt"), numbered_lines(&[2, 18]));
+    }
+
+    #[test]
+    fn test_git_stage_hunks_rejects_out_of_range_index() {
+        let (dir, repo) = init_repo(&[("f.txt", numbered_lines(&[]).as_str())]);
+        write_file(&dir, "f.txt", &numbered_lines(&[2, 18]));
+
+        let result = git_stage_hunks(project_path(&dir), "f.txt", Some(&[0, 2]), None);
+
+        assert_eq!(
+            result.err().as_deref(),
+            Some("Hunk 2 out of range (2 unstaged hunks)")
+        );
+        assert_eq!(
+            staged_content(&repo, "f.txt").as_deref(),
+            Some(numbered_lines(&[]).as_str())
+        );
+    }
+
+    #[test]
+    fn test_git_stage_hunks_new_file() {
+        let (dir, repo) = init_repo(&[("a.txt", "one\n")]);
+        write_file(&dir, "new.txt", "first\nsecond\n");
+
+        let hunks = get_unstaged_hunks(project_path(&dir), "new.txt", None).unwrap();
+        assert_eq!(hunks.len(), 1);
+
+        git_stage_hunks(project_path(&dir), "new.txt", Some(&[0]), None).unwrap();
+
+        assert_eq!(
+            staged_content(&repo, "new.txt").as_deref(),
+            Some("first\nsecond\n")
+        );
+        let entry = disk_index(&repo).get_path(Path::new("new.txt"), 0).unwrap();
+        assert_eq!(entry.mode, 0o100644);
+        assert!(get_unstaged_hunks(project_path(&dir), "new.txt", None)
+            .unwrap()
+            .is_empty());
+    }
+
+    // =============================================================================
+    // Snapshot Tests
+    // =============================================================================
+
+    /// Content of a file in a tree, if present.
+    fn tree_file(repo: &Repository, tree: &Tree<'_>, path: &str) -> Option<String> {
+        let entry = tree.get_path(Path::new(path)).ok()?;
+        let blob = entry.to_object(repo).unwrap().peel_to_blob().unwrap();
+        Some(String::from_utf8_lossy(blob.content()).to_string())
+    }
+
+    #[test]
+    fn test_working_tree_id() {
+        let (dir, repo) = init_repo(&[
+            ("a.txt", "one\n"),
+            ("b.txt", "two\n"),
+            (".gitignore", "*.log\n"),
+        ]);
+        write_file(&dir, "a.txt", "one changed\n");
+        fs::remove_file(dir.path().join("b.txt")).unwrap();
+        write_file(&dir, "new.txt", "new\n");
+        write_file(&dir, "debug.log", "ignored\n");
+
+        let tree = repo.find_tree(working_tree_id(&repo).unwrap()).unwrap();
+
+        assert_eq!(
+            tree_file(&repo, &tree, "a.txt").as_deref(),
+            Some("one changed\n")
+        );
+        assert_eq!(tree_file(&repo, &tree, "b.txt"), None);
+        assert_eq!(tree_file(&repo, &tree, "new.txt").as_deref(), Some("new\n"));
+        assert_eq!(tree_file(&repo, &tree, "debug.log"), None);
+    }
+
+    #[test]
+    fn test_create_session_snapshot_leaves_repo_untouched() {
+        let (dir, repo) = init_repo(&[("a.txt", "one\n")]);
+        write_file(&dir, "a.txt", "one changed\n");
+        write_file(&dir, "new.txt", "new\n");
+        let git_dir = repo.path().to_path_buf();
+        let index_before = fs::read(git_dir.join("index")).unwrap();
+        let head_before = fs::read(git_dir.join("HEAD")).unwrap();
+        let head_commit = repo.head().unwrap().target().unwrap();
+
+        let snapshot = create_session_snapshot(project_path(&dir), "0a1b2c3d-e4f5", false).unwrap();
+
+        assert_eq!(snapshot.name, "agent-console/0a1b2c3d");
+        assert_eq!(snapshot.ref_name, "refs/tags/agent-console/0a1b2c3d");
+        assert!(snapshot.includes_uncommitted);
+        let commit = repo
+            .find_commit(Oid::from_str(&snapshot.hash).unwrap())
+            .unwrap();
+        assert_eq!(commit.parent_id(0).unwrap(), head_commit);
+        let tree = commit.tree().unwrap();
+        assert_eq!(
+            tree_file(&repo, &tree, "a.txt").as_deref(),
+            Some("one changed\n")
+        );
+        assert_eq!(tree_file(&repo, &tree, "new.txt").as_deref(), Some("new\n"));
+
+        assert_eq!(fs::read(git_dir.join("index")).unwrap(), index_before);
+        assert_eq!(fs::read(git_dir.join("HEAD")).unwrap(), head_before);
+        assert_eq!(repo.head().unwrap().target().unwrap(), head_commit);
+        assert_eq!(read_file(&dir, "a.txt"), "one changed\n");
+    }
+
+    #[test]
+    fn test_create_session_snapshot_suffixes_taken_names() {
+        let (dir, _repo) = init_repo(&[("a.txt", "one\n")]);
+
+        let first = create_session_snapshot(project_path(&dir), "0a1b2c3d-e4f5", false).unwrap();
+        let second = create_session_snapshot(project_path(&dir), "0a1b2c3d-e4f5", false).unwrap();
+        let branch = create_session_snapshot(project_path(&dir), "0a1b2c3d-e4f5", true).unwrap();
+
+        assert_eq!(first.name, "agent-console/0a1b2c3d");
+        assert!(!first.includes_uncommitted);
+        assert_eq!(second.name, "agent-console/0a1b2c3d-2");
+        assert_eq!(second.hash, first.hash);
+        // Branches and tags don't share a namespace
+        assert_eq!(branch.ref_name, "refs/heads/agent-console/0a1b2c3d");
+    }
+}
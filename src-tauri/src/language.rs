@@ -0,0 +1,219 @@
+//! Language detection and content classification for edited files.
+//!
+//! Maps file names to Monaco language identifiers so diff responses can tell
+//! the frontend which syntax highlighter to use, and spots binary or
+//! machine-generated files the UI should collapse.
+
+/// Fallback identifier when no language is recognized.
+pub const PLAINTEXT: &str = "plaintext";
+
+/// Detect the Monaco language identifier for a file.
+///
+/// Uses the extension (or well-known file names), falling back to the shebang
+/// line of `content` for extensionless scripts.
+pub fn detect_language(path: &str, content: Option<&str>) -> &'static str {
+    let file_name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+
+    if let Some(language) = language_for_file_name(file_name) {
+        return language;
+    }
+
+    if let Some((_, ext)) = file_name.rsplit_once('.') {
+        if let Some(language) = language_for_extension(&ext.to_lowercase()) {
+            return language;
+        }
+    }
+
+    content.and_then(language_for_shebang).unwrap_or(PLAINTEXT)
+}
+
+/// Languages for files identified by their whole name.
+fn language_for_file_name(file_name: &str) -> Option<&'static str> {
+    let language = match file_name.to_lowercase().as_str() {
+        "dockerfile" | "containerfile" => "dockerfile",
+        ".bashrc" | ".zshrc" | ".profile" | ".bash_profile" => "shell",
+        ".gitignore" | ".dockerignore" | ".env" => PLAINTEXT,
+        _ => return None,
+    };
+    Some(language)
+}
+
+/// Languages by file extension (kept in sync with the frontend's `getLanguageFromPath`).
+fn language_for_extension(ext: &str) -> Option<&'static str> {
+    let language = match ext {
+        "ts" | "tsx" | "mts" | "cts" => "typescript",
+        "js" | "jsx" | "mjs" | "cjs" => "javascript",
+        "rs" => "rust",
+        "py" | "pyi" => "python",
+        "go" => "go",
+        "java" => "java",
+        "c" | "h" => "c",
+        "cpp" | "cc" | "cxx" | "hpp" | "hh" => "cpp",
+        "cs" => "csharp",
+        "rb" => "ruby",
+        "php" => "php",
+        "swift" => "swift",
+        "kt" | "kts" => "kotlin",
+        "scala" => "scala",
+        "html" | "htm" => "html",
+        "css" => "css",
+        "scss" => "scss",
+        "less" => "less",
+        "json" | "jsonl" => "json",
+        "xml" | "svg" => "xml",
+        "yaml" | "yml" => "yaml",
+        "md" | "mdx" => "markdown",
+        "sql" => "sql",
+        "sh" | "bash" | "zsh" => "shell",
+        "ps1" => "powershell",
+        "dockerfile" => "dockerfile",
+        "toml" => "toml",
+        "ini" | "conf" => "ini",
+        "rego" => "rego",
+        "lua" => "lua",
+        "pl" | "pm" => "perl",
+        _ => return None,
+    };
+    Some(language)
+}
+
+/// Languages for scripts identified by their `#!` interpreter line.
+fn language_for_shebang(content: &str) -> Option<&'static str> {
+    let line = content.lines().next()?.strip_prefix("#!")?;
+    let mut words = line.split_whitespace();
+    let mut interpreter = words.next()?.rsplit('/').next()?;
+    // `#!/usr/bin/env -S deno run` style: the interpreter is the first non-flag argument
+    if interpreter == "env" {
+        interpreter = words.find(|w| !w.starts_with('-'))?;
+    }
+
+    let name = interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    let language = match name {
+        "sh" | "bash" | "zsh" | "dash" | "ksh" => "shell",
+        "python" => "python",
+        "node" | "nodejs" => "javascript",
+        "deno" | "bun" | "ts-node" | "tsx" => "typescript",
+        "ruby" => "ruby",
+        "perl" => "perl",
+        "php" => "php",
+        "lua" => "lua",
+        "pwsh" => "powershell",
+        _ => return None,
+    };
+    Some(language)
+}
+
+/// Lockfiles and other files that are always machine-written.
+const GENERATED_FILE_NAMES: &[&str] = &[
+    "cargo.lock",
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "bun.lockb",
+    "poetry.lock",
+    "pipfile.lock",
+    "uv.lock",
+    "gemfile.lock",
+    "composer.lock",
+    "go.sum",
+    "flake.lock",
+];
+
+/// How much of the content to inspect for binary/minified heuristics.
+const SNIFF_BYTES: usize = 8 * 1024;
+
+/// Lines longer than this on average suggest minified output.
+const MINIFIED_AVG_LINE_LEN: usize = 500;
+
+/// Whether a file looks binary or machine-generated, from its name and (optionally) content.
+pub fn looks_generated(path: &str, content: Option<&str>) -> bool {
+    let file_name = path
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or(path)
+        .to_lowercase();
+
+    if GENERATED_FILE_NAMES.contains(&file_name.as_str())
+        || file_name.ends_with(".min.js")
+        || file_name.ends_with(".min.css")
+        || file_name.ends_with(".map")
+    {
+        return true;
+    }
+
+    content.is_some_and(content_looks_generated)
+}
+
+/// Content heuristics: NUL bytes, very long lines, or a generated-code marker near the top.
+fn content_looks_generated(content: &str) -> bool {
+    let mut end = content.len().min(SNIFF_BYTES);
+    while !content.is_char_boundary(end) {
+        end -= 1;
+    }
+    let sample = &content[..end];
+
+    if sample.contains('\0') {
+        return true;
+    }
+
+    let lines = sample.lines().count().max(1);
+    if sample.len() / lines > MINIFIED_AVG_LINE_LEN {
+        return true;
+    }
+
+    sample
+        .lines()
+        .take(5)
+        .any(|line| line.contains("@generated") || line.contains("DO NOT EDIT"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_language_by_extension() {
+        assert_eq!(detect_language("src/main.rs", None), "rust");
+        assert_eq!(detect_language("/p/web/App.TSX", None), "typescript");
+        assert_eq!(detect_language("config.yml", None), "yaml");
+        assert_eq!(detect_language("notes.unknown", None), PLAINTEXT);
+    }
+
+    #[test]
+    fn test_detect_language_by_file_name() {
+        assert_eq!(detect_language("docker/Dockerfile", None), "dockerfile");
+        assert_eq!(detect_language(".zshrc", None), "shell");
+    }
+
+    #[test]
+    fn test_detect_language_by_shebang() {
+        assert_eq!(
+            detect_language("bin/run", Some("#!/usr/bin/env python3\nprint(1)")),
+            "python"
+        );
+        assert_eq!(detect_language("bin/tool", Some("#!/bin/bash\n")), "shell");
+        assert_eq!(
+            detect_language("bin/x", Some("#!/usr/bin/env -S deno run\n")),
+            "typescript"
+        );
+        // Extension wins over shebang
+        assert_eq!(detect_language("x.rb", Some("#!/bin/sh\n")), "ruby");
+        assert_eq!(detect_language("bin/x", Some("no shebang")), PLAINTEXT);
+    }
+
+    #[test]
+    fn test_looks_generated() {
+        assert!(looks_generated("Cargo.lock", None));
+        assert!(looks_generated("web/package-lock.json", None));
+        assert!(looks_generated("dist/app.min.js", None));
+        assert!(!looks_generated("src/main.rs", Some("fn main() {}\n")));
+
+        assert!(looks_generated("out/blob.bin", Some("PK\0\u{3}")));
+        assert!(looks_generated("dist/bundle.js", Some(&"x".repeat(2000))));
+        assert!(looks_generated(
+            "api.rs",
+            Some("// @generated by protoc\nfn f() {}")
+        ));
+    }
+}
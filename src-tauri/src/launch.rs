@@ -0,0 +1,565 @@
+//! Claude Code launch commands.
+//!
+//! This module builds the `claude` command line run in a terminal from structured
+//! launch options, quoting every user-supplied value for the shell that runs it.
+//! Environment variables are set on the command itself, so they don't leak into the
+//! terminal's shell. The command can also be wrapped to run in a container or on a
+//! remote host over SSH.
+//!
+//! Each launch is tagged with an ID in the claude process's environment and kept in a
+//! registry, so the process can be found again once the terminal has started it.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use crate::claude_code::is_uuid_format;
+use crate::process::{claude_processes, ClaudeProcess, ProcessMonitor};
+use crate::terminal::{Shell, TerminalType};
+
+/// Environment variable tagging a launched claude process with its launch ID.
+pub(crate) const LAUNCH_ID_ENV: &str = "AGENT_CONSOLE_LAUNCH_ID";
+
+/// Number of launches kept in the registry; older ones are dropped.
+const MAX_TRACKED_LAUNCHES: usize = 100;
+
+/// Permission mode to start Claude Code in (`--permission-mode`).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum PermissionMode {
+    /// Ask before using tools
+    Default,
+    /// Accept file edits without asking
+    AcceptEdits,
+    /// Plan only, without making changes
+    Plan,
+    /// Skip all permission prompts
+    BypassPermissions,
+}
+
+impl PermissionMode {
+    /// Value passed to `--permission-mode`.
+    fn as_arg(self) -> &'static str {
+        match self {
+            PermissionMode::Default => "default",
+            PermissionMode::AcceptEdits => "acceptEdits",
+            PermissionMode::Plan => "plan",
+            PermissionMode::BypassPermissions => "bypassPermissions",
+        }
+    }
+}
+
+/// Container to run Claude Code in.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ContainerTarget {
+    /// The project's devcontainer, started if needed (`devcontainer up` then `exec`)
+    Devcontainer,
+    /// A running Docker container (`docker exec`)
+    #[serde(rename_all = "camelCase")]
+    Docker {
+        /// Container name or ID
+        container: String,
+        /// Working directory in the container (defaults to the project path)
+        workdir: Option<String>,
+    },
+}
+
+/// Remote host to run Claude Code on over SSH.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteTarget {
+    /// SSH destination (`user@host` or a `~/.ssh/config` alias)
+    pub host: String,
+    /// Project directory on the remote host (defaults to the local project path)
+    pub path: Option<String>,
+}
+
+/// Options for launching Claude Code.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LaunchOptions {
+    /// Project to launch in
+    #[serde(skip)]
+    pub project_path: String,
+    /// Continue the most recent session (`--continue`)
+    #[serde(skip)]
+    pub continue_session: bool,
+    /// Skip permission prompts (`--dangerously-skip-permissions`)
+    #[serde(skip)]
+    pub yolo_mode: bool,
+    /// Session to resume (`--resume`), taking precedence over `continue_session`
+    #[serde(skip)]
+    pub resume_session_id: Option<String>,
+    /// Model alias or name (`--model`)
+    pub model: Option<String>,
+    /// Permission mode (`--permission-mode`)
+    pub permission_mode: Option<PermissionMode>,
+    /// Additional directories Claude may access (`--add-dir`)
+    pub add_dirs: Vec<String>,
+    /// Extra arguments appended as-is (each quoted as a single argument)
+    pub extra_args: Vec<String>,
+    /// Environment variables to set for the claude process
+    pub env: BTreeMap<String, String>,
+    /// Container to run claude in, instead of on the host
+    pub container: Option<ContainerTarget>,
+    /// Remote host to run claude on, over SSH
+    pub remote: Option<RemoteTarget>,
+}
+
+/// Build the `claude` command line for a set of launch options, quoted for `shell`.
+pub fn build_claude_command(options: &LaunchOptions, shell: Shell) -> Result<String, String> {
+    let Some(remote) = &options.remote else {
+        return build_local_command(options, shell);
+    };
+
+    // A leading dash would be read as an ssh option
+    let host = remote.host.trim();
+    if host.is_empty() || host.starts_with('-') {
+        return Err(format!("Invalid SSH host: {}", remote.host));
+    }
+    let path = remote
+        .path
+        .as_deref()
+        .filter(|path| !path.trim().is_empty())
+        .unwrap_or(&options.project_path);
+
+    // The command is run by the remote login shell, assumed to be POSIX-compatible
+    let remote_options = LaunchOptions {
+        project_path: path.to_string(),
+        remote: None,
+        ..options.clone()
+    };
+    let remote_command = format!(
+        "cd {} && {}",
+        Shell::Posix.quote(path),
+        build_local_command(&remote_options, Shell::Posix)?
+    );
+
+    Ok(format!(
+        "ssh -t {} {}",
+        shell.quote(host),
+        shell.quote(&remote_command)
+    ))
+}
+
+/// Build the command that runs claude on this machine (or in a container on it).
+fn build_local_command(options: &LaunchOptions, shell: Shell) -> Result<String, String> {
+    let mut args = vec!["claude".to_string()];
+
+    if let Some(session_id) = &options.resume_session_id {
+        if !is_uuid_format(session_id) {
+            return Err(format!("Invalid session ID: {}", session_id));
+        }
+        args.push("--resume".to_string());
+        args.push(session_id.clone());
+    } else if options.continue_session {
+        args.push("--continue".to_string());
+    }
+
+    if options.yolo_mode {
+        args.push("--dangerously-skip-permissions".to_string());
+    }
+
+    if let Some(model) = options.model.as_deref().map(str::trim) {
+        if !model.is_empty() {
+            args.push("--model".to_string());
+            args.push(shell.quote(model));
+        }
+    }
+
+    if let Some(mode) = options.permission_mode {
+        args.push("--permission-mode".to_string());
+        args.push(mode.as_arg().to_string());
+    }
+
+    for dir in options.add_dirs.iter().filter(|dir| !dir.trim().is_empty()) {
+        args.push("--add-dir".to_string());
+        args.push(shell.quote(dir));
+    }
+
+    args.extend(options.extra_args.iter().map(|arg| shell.quote(arg)));
+
+    if let Some(name) = options.env.keys().find(|name| !is_env_var_name(name)) {
+        return Err(format!("Invalid environment variable name: {}", name));
+    }
+
+    if let Some(container) = &options.container {
+        return Ok(container_command(container, options, &args, shell));
+    }
+
+    let command = args.join(" ");
+    if options.env.is_empty() {
+        return Ok(command);
+    }
+
+    let prefixed = match shell {
+        // `env` works whichever shell the terminal runs, unlike `NAME=value claude`
+        Shell::Posix => {
+            let assignments: Vec<String> = options
+                .env
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, shell.quote(value)))
+                .collect();
+            format!("env {} {}", assignments.join(" "), command)
+        }
+        // cmd.exe has no per-command assignments, so variables are set for its session
+        Shell::Cmd => {
+            let assignments: Vec<String> = options
+                .env
+                .iter()
+                .map(|(name, value)| format!("set {}", shell.quote(&format!("{}={}", name, value))))
+                .collect();
+            format!("{} && {}", assignments.join(" && "), command)
+        }
+    };
+
+    Ok(prefixed)
+}
+
+/// Wrap claude's arguments in the command that runs them in a container. Environment
+/// variables are passed into the container, as the host's environment doesn't reach it.
+fn container_command(
+    container: &ContainerTarget,
+    options: &LaunchOptions,
+    claude_args: &[String],
+    shell: Shell,
+) -> String {
+    let project = shell.quote(&options.project_path);
+    let mut args = Vec::new();
+
+    match container {
+        ContainerTarget::Devcontainer => {
+            args.extend(["devcontainer", "exec", "--workspace-folder"].map(String::from));
+            args.push(project.clone());
+            for (name, value) in &options.env {
+                args.push("--remote-env".to_string());
+                args.push(shell.quote(&format!("{}={}", name, value)));
+            }
+        }
+        ContainerTarget::Docker { container, workdir } => {
+            let workdir = workdir.as_deref().unwrap_or(&options.project_path);
+            args.extend(["docker", "exec", "-it", "-w"].map(String::from));
+            args.push(shell.quote(workdir));
+            for (name, value) in &options.env {
+                args.push("-e".to_string());
+                args.push(shell.quote(&format!("{}={}", name, value)));
+            }
+            args.push(shell.quote(container));
+        }
+    }
+    args.extend(claude_args.iter().cloned());
+
+    match container {
+        ContainerTarget::Devcontainer => format!(
+            "devcontainer up --workspace-folder {} && {}",
+            project,
+            args.join(" ")
+        ),
+        ContainerTarget::Docker { .. } => args.join(" "),
+    }
+}
+
+/// Whether a devcontainer is configured for a project.
+pub fn has_devcontainer(project_path: &Path) -> bool {
+    project_path
+        .join(".devcontainer/devcontainer.json")
+        .is_file()
+        || project_path.join(".devcontainer.json").is_file()
+}
+
+/// Whether a string is a valid environment variable name (letters, digits and
+/// underscores, not starting with a digit).
+fn is_env_var_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Status of a launched session.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LaunchStatus {
+    /// The terminal was launched but its claude process hasn't been seen yet
+    Starting,
+    /// The claude process is running
+    Running,
+    /// The claude process has exited
+    Exited,
+}
+
+/// A claude session launched from this app.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchedSession {
+    /// Launch ID, returned by `launch_claude`
+    pub id: String,
+    /// Project the session was launched in
+    pub project_path: String,
+    /// Terminal it was launched in
+    pub terminal_type: TerminalType,
+    /// When it was launched (RFC 3339)
+    pub launched_at: String,
+    /// Process ID of the claude process, once seen
+    pub pid: Option<u32>,
+    /// Current status
+    pub status: LaunchStatus,
+}
+
+/// Registry of sessions launched from this app, most recent last.
+pub struct LaunchRegistry {
+    launches: Mutex<Vec<LaunchedSession>>,
+    next_id: AtomicU64,
+}
+
+impl LaunchRegistry {
+    pub fn new() -> Self {
+        Self {
+            launches: Mutex::new(Vec::new()),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Create an ID for a new launch, unique across app restarts.
+    pub fn next_launch_id(&self) -> String {
+        let sequence = self.next_id.fetch_add(1, Ordering::Relaxed);
+        format!(
+            "{:x}-{:x}-{}",
+            chrono::Utc::now().timestamp_millis(),
+            std::process::id(),
+            sequence
+        )
+    }
+
+    /// Record a successful launch.
+    pub fn record(&self, id: String, project_path: String, terminal_type: TerminalType) {
+        let Ok(mut launches) = self.launches.lock() else {
+            return;
+        };
+        launches.push(LaunchedSession {
+            id,
+            project_path,
+            terminal_type,
+            launched_at: chrono::Utc::now().to_rfc3339(),
+            pid: None,
+            status: LaunchStatus::Starting,
+        });
+        if launches.len() > MAX_TRACKED_LAUNCHES {
+            let excess = launches.len() - MAX_TRACKED_LAUNCHES;
+            launches.drain(..excess);
+        }
+    }
+}
+
+/// Get sessions launched from this app, with their process IDs and current status.
+pub fn get_launched_sessions(
+    registry: &LaunchRegistry,
+    monitor: &ProcessMonitor,
+) -> Vec<LaunchedSession> {
+    let processes = claude_processes(monitor);
+    let Ok(mut launches) = registry.launches.lock() else {
+        return Vec::new();
+    };
+    for launch in launches.iter_mut() {
+        update_launch_status(launch, &processes);
+    }
+    launches.clone()
+}
+
+/// Update a launch from the running claude processes.
+fn update_launch_status(launch: &mut LaunchedSession, processes: &[ClaudeProcess]) {
+    let process = processes
+        .iter()
+        .find(|process| process.launch_id.as_deref() == Some(launch.id.as_str()));
+
+    match process {
+        Some(process) => {
+            launch.pid = Some(process.pid);
+            launch.status = LaunchStatus::Running;
+        }
+        // Only a process that was seen can have exited; until then it may be starting
+        None if launch.pid.is_some() => launch.status = LaunchStatus::Exited,
+        None => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SESSION: &str = "0b7e8f2a-3c4d-4e5f-8a9b-1c2d3e4f5a6b";
+
+    #[test]
+    fn test_build_claude_command_flags() {
+        let options = LaunchOptions {
+            continue_session: true,
+            resume_session_id: Some(SESSION.to_string()),
+            model: Some("opus".to_string()),
+            permission_mode: Some(PermissionMode::AcceptEdits),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            build_claude_command(&options, Shell::Posix),
+            Ok(format!(
+                "claude --resume {} --model opus --permission-mode acceptEdits",
+                SESSION
+            ))
+        );
+        assert_eq!(
+            build_claude_command(&LaunchOptions::default(), Shell::Posix),
+            Ok("claude".to_string())
+        );
+    }
+
+    #[test]
+    fn test_build_claude_command_quotes_user_values() {
+        let options = LaunchOptions {
+            add_dirs: vec!["/work/my lib".to_string(), " ".to_string()],
+            extra_args: vec!["--verbose".to_string(), "$(rm -rf ~)".to_string()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            build_claude_command(&options, Shell::Posix),
+            Ok("claude --add-dir '/work/my lib' --verbose '$(rm -rf ~)'".to_string())
+        );
+    }
+
+    #[test]
+    fn test_build_claude_command_env() {
+        let mut options = LaunchOptions {
+            continue_session: true,
+            ..Default::default()
+        };
+        options.env.insert("DEBUG".to_string(), "1".to_string());
+        options.env.insert(
+            "CLAUDE_CONFIG_DIR".to_string(),
+            "/work/my config".to_string(),
+        );
+
+        assert_eq!(
+            build_claude_command(&options, Shell::Posix),
+            Ok("env CLAUDE_CONFIG_DIR='/work/my config' DEBUG=1 claude --continue".to_string())
+        );
+        assert_eq!(
+            build_claude_command(&options, Shell::Cmd),
+            Ok(
+                "set \"CLAUDE_CONFIG_DIR=/work/my config\" && set \"DEBUG=1\" && claude --continue"
+                    .to_string()
+            )
+        );
+
+        options.env.insert("BAD NAME".to_string(), "x".to_string());
+        assert!(build_claude_command(&options, Shell::Posix).is_err());
+    }
+
+    #[test]
+    fn test_build_claude_command_in_container() {
+        let mut options = LaunchOptions {
+            project_path: "/work/my app".to_string(),
+            container: Some(ContainerTarget::Devcontainer),
+            ..Default::default()
+        };
+        options.env.insert("DEBUG".to_string(), "1".to_string());
+
+        assert_eq!(
+            build_claude_command(&options, Shell::Posix),
+            Ok(
+                "devcontainer up --workspace-folder '/work/my app' && devcontainer exec \
+                --workspace-folder '/work/my app' --remote-env 'DEBUG=1' claude"
+                    .to_string()
+            )
+        );
+
+        options.container = Some(ContainerTarget::Docker {
+            container: "app-dev".to_string(),
+            workdir: Some("/workspace".to_string()),
+        });
+        assert_eq!(
+            build_claude_command(&options, Shell::Posix),
+            Ok("docker exec -it -w /workspace -e 'DEBUG=1' app-dev claude".to_string())
+        );
+    }
+
+    #[test]
+    fn test_build_claude_command_over_ssh() {
+        let mut options = LaunchOptions {
+            project_path: "/Users/me/app".to_string(),
+            continue_session: true,
+            remote: Some(RemoteTarget {
+                host: "dev@build-box".to_string(),
+                path: Some("/home/dev/app".to_string()),
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            build_claude_command(&options, Shell::Posix),
+            Ok("ssh -t dev@build-box 'cd /home/dev/app && claude --continue'".to_string())
+        );
+
+        options.remote = Some(RemoteTarget {
+            host: "-oProxyCommand=evil".to_string(),
+            path: None,
+        });
+        assert!(build_claude_command(&options, Shell::Posix).is_err());
+    }
+
+    #[test]
+    fn test_has_devcontainer() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(!has_devcontainer(dir.path()));
+
+        std::fs::create_dir(dir.path().join(".devcontainer")).unwrap();
+        std::fs::write(dir.path().join(".devcontainer/devcontainer.json"), "{}").unwrap();
+        assert!(has_devcontainer(dir.path()));
+    }
+
+    #[test]
+    fn test_update_launch_status() {
+        let registry = LaunchRegistry::new();
+        let id = registry.next_launch_id();
+        registry.record(id.clone(), "/work/app".to_string(), TerminalType::Ghostty);
+        let mut launch = registry.launches.lock().unwrap()[0].clone();
+
+        update_launch_status(&mut launch, &[]);
+        assert_eq!(launch.status, LaunchStatus::Starting);
+
+        let mut process = ClaudeProcess {
+            pid: 42,
+            cwd: Some("/work/app".to_string()),
+            start_time: 0,
+            args: vec!["claude".to_string()],
+            cpu_percent: 0.0,
+            memory_bytes: 0,
+            uptime_secs: 0,
+            tmux: None,
+            terminal: None,
+            launch_id: Some(id),
+        };
+        update_launch_status(&mut launch, std::slice::from_ref(&process));
+        assert_eq!(
+            (launch.pid, launch.status),
+            (Some(42), LaunchStatus::Running)
+        );
+
+        process.launch_id = None;
+        update_launch_status(&mut launch, &[process]);
+        assert_eq!(launch.status, LaunchStatus::Exited);
+    }
+
+    #[test]
+    fn test_build_claude_command_rejects_invalid_session_id() {
+        let options = LaunchOptions {
+            resume_session_id: Some("abc; rm -rf ~".to_string()),
+            ..Default::default()
+        };
+
+        assert!(build_claude_command(&options, Shell::Posix).is_err());
+    }
+}
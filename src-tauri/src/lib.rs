@@ -1,23 +1,56 @@
+mod activity;
 mod claude_code;
+mod diff;
 mod git;
+mod language;
+mod launch;
 mod process;
 mod search;
 mod session_index;
 mod terminal;
 mod watcher;
 
-use claude_code::{FileDiff, FileEdit, PolicyEvaluation, Project, Session};
-use git::GitFileDiff;
-use session_index::{get_edit_context, EditContext, IndexStatus};
-use std::path::Path;
-use tauri::{AppHandle, State};
-use terminal::TerminalType;
-use watcher::WatcherState;
+use claude_code::{
+    ApplyEditResult, FileDiff, FileEdit, FileSnapshot, PolicyEvaluationCache,
+    PolicyEvaluationDetail, PolicyEvaluationFilter, PolicyEvaluationsResponse, PolicyFile,
+    PolicyFileContent, PreviewOptions, Project, Session,
+};
+use diff::{EditSearchMatch, FileDiffHunks, FileDrift, FileThreeWay, PatchExport};
+use git::{
+    GitBlameHunk, GitBranchCheck, GitBranches, GitCommit, GitDirtyCache, GitFileDiff,
+    GitLogResponse, GitRefFileDiff, GitSnapshot, GitStageableHunk, GitStash, GitStatus,
+    GitWorktree,
+};
+use launch::{LaunchOptions, LaunchRegistry, LaunchedSession};
+use process::{AgentSignal, ProcessMonitor, SignalResult};
+use session_index::{
+    get_edit_context, group_edits_by_turn, BranchPoint, EditContext, EditTurn, EventThread,
+    IndexStatus, SessionStats, SessionSummary, TimestampMatch,
+};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Manager, State};
+use terminal::{LaunchPreview, TerminalType};
+use watcher::{
+    SessionEventsAppendedPayload, WatcherFailure, WatcherState, WatcherStatus,
+    WatchersRestartedPayload,
+};
 
 /// Discover all Claude Code projects (lightweight - no session content parsing).
+/// Set `include_git_status` to also report uncommitted changes per project (cached).
 #[tauri::command]
-fn get_projects() -> Vec<Project> {
-    claude_code::discover_projects()
+fn get_projects(
+    include_git_status: Option<bool>,
+    dirty_cache: State<'_, GitDirtyCache>,
+) -> Vec<Project> {
+    let mut projects = claude_code::discover_projects();
+    if include_git_status.unwrap_or(false) {
+        for project in &mut projects {
+            project.uncommitted_changes = dirty_cache.uncommitted_changes(&project.project_path);
+        }
+    }
+    projects
 }
 
 /// Get full session details for a specific project (on-demand).
@@ -28,8 +61,30 @@ fn get_project_sessions(project_path: String) -> Vec<Session> {
 
 /// Get active Claude Code sessions (projects with running claude process).
 #[tauri::command]
-fn get_active_sessions() -> process::ActiveSessionsResult {
-    process::get_active_sessions()
+fn get_active_sessions(monitor: State<'_, ProcessMonitor>) -> process::ActiveSessionsResult {
+    process::get_active_sessions(&monitor)
+}
+
+/// Poll for running Claude processes every `interval_ms`, emitting
+/// "active-sessions-changed" when they change. Pass no interval to stop polling.
+#[tauri::command]
+fn set_active_sessions_polling(
+    app_handle: AppHandle,
+    state: State<'_, WatcherState>,
+    interval_ms: Option<u64>,
+) -> Result<(), String> {
+    watcher::set_active_sessions_polling(app_handle, &state, interval_ms.map(Duration::from_millis))
+}
+
+/// Send SIGINT, SIGTERM or SIGKILL to a running claude process, reporting whether it
+/// exited. Only claude processes can be signalled.
+#[tauri::command]
+fn signal_agent_process(
+    monitor: State<'_, ProcessMonitor>,
+    pid: u32,
+    signal: AgentSignal,
+) -> Result<SignalResult, String> {
+    process::signal_agent_process(&monitor, pid, signal)
 }
 
 /// Get available terminal emulators on this system.
@@ -38,14 +93,23 @@ fn get_available_terminals() -> Vec<TerminalType> {
     terminal::get_available_terminals()
 }
 
-/// Launch Claude Code in a terminal.
+/// Launch Claude Code in a terminal, returning an ID for tracking the launched session.
+/// `resume_session_id` reopens that session (taking precedence over `continue_session`).
+/// `options` adds a model, permission mode, extra directories and arguments.
+/// `custom_template` is the command template used when `terminal_type` is `Custom`, and
+/// `wsl_distro` the distribution used for `Wsl` (the default one if None).
 #[tauri::command]
 fn launch_claude(
     terminal_type: TerminalType,
     project_path: String,
     continue_session: bool,
     yolo_mode: bool,
-) -> Result<(), String> {
+    resume_session_id: Option<String>,
+    options: Option<LaunchOptions>,
+    custom_template: Option<String>,
+    wsl_distro: Option<String>,
+    registry: State<'_, LaunchRegistry>,
+) -> Result<String, String> {
     // Debug: write to a file to confirm function is called
     let debug_msg = format!(
         "launch_claude called: terminal={:?}, path={}, continue={}, yolo={}\n",
@@ -53,23 +117,118 @@ fn launch_claude(
     );
     let _ = std::fs::write("/tmp/launch_claude_debug.log", &debug_msg);
 
-    let mut cmd = String::from("claude");
+    let launch_id = registry.next_launch_id();
+    let mut options = LaunchOptions {
+        project_path: project_path.clone(),
+        continue_session,
+        yolo_mode,
+        resume_session_id,
+        ..options.unwrap_or_default()
+    };
+    options.env.insert(launch::LAUNCH_ID_ENV.to_string(), launch_id.clone());
+    let (cwd, cmd) = prepare_launch(&terminal_type, &options)?;
 
-    if continue_session {
-        cmd.push_str(" --continue");
+    match terminal_type {
+        TerminalType::Custom => {
+            let template = custom_template.unwrap_or_default();
+            terminal::launch_custom_terminal(&template, &cwd, &cmd)?;
+        }
+        TerminalType::Wsl => {
+            terminal::launch_wsl(wsl_distro.as_deref(), &cwd, &cmd)?;
+        }
+        _ => terminal::launch_terminal(&terminal_type, &cwd, &cmd)?,
     }
 
-    if yolo_mode {
-        cmd.push_str(" --dangerously-skip-permissions");
+    registry.record(launch_id.clone(), project_path, terminal_type);
+    Ok(launch_id)
+}
+
+/// Preview the command (and AppleScript, on macOS) `launch_claude` would run with the
+/// same arguments, without launching anything. The launch ID variable is left out, as
+/// no ID is assigned until a launch.
+#[tauri::command]
+fn preview_launch_command(
+    terminal_type: TerminalType,
+    project_path: String,
+    continue_session: bool,
+    yolo_mode: bool,
+    resume_session_id: Option<String>,
+    options: Option<LaunchOptions>,
+    custom_template: Option<String>,
+) -> Result<LaunchPreview, String> {
+    let options = LaunchOptions {
+        project_path,
+        continue_session,
+        yolo_mode,
+        resume_session_id,
+        ..options.unwrap_or_default()
+    };
+    let (cwd, cmd) = prepare_launch(&terminal_type, &options)?;
+
+    match terminal_type {
+        TerminalType::Custom => {
+            let template = custom_template.unwrap_or_default();
+            terminal::preview_custom_terminal(&template, &cwd, &cmd)
+        }
+        _ => terminal::preview_launch(&terminal_type, &cwd, &cmd),
     }
+}
+
+/// Build the claude command line for a terminal and the directory to start it in.
+fn prepare_launch(
+    terminal_type: &TerminalType,
+    options: &LaunchOptions,
+) -> Result<(String, String), String> {
+    let cmd = launch::build_claude_command(options, terminal_type.shell())?;
+
+    // Remote projects may not exist locally, so the terminal starts in the home directory
+    let project_path = &options.project_path;
+    let cwd = match dirs::home_dir() {
+        Some(home) if options.remote.is_some() && !Path::new(project_path).is_dir() => {
+            home.to_string_lossy().to_string()
+        }
+        _ => project_path.clone(),
+    };
+
+    Ok((cwd, cmd))
+}
+
+/// Check whether a project has a devcontainer to launch sessions in.
+#[tauri::command]
+fn has_devcontainer(project_path: String) -> bool {
+    launch::has_devcontainer(Path::new(&project_path))
+}
+
+/// List installed WSL distributions (empty outside Windows).
+#[tauri::command]
+fn get_wsl_distros() -> Vec<String> {
+    terminal::get_wsl_distros()
+}
 
-    terminal::launch_terminal(&terminal_type, &project_path, &cmd)
+/// Get sessions launched from this app, with their process IDs and whether they're
+/// still running.
+#[tauri::command]
+fn get_launched_sessions(
+    registry: State<'_, LaunchRegistry>,
+    monitor: State<'_, ProcessMonitor>,
+) -> Vec<LaunchedSession> {
+    launch::get_launched_sessions(&registry, &monitor)
 }
 
 /// Get file edits for a session (lightweight - just file list and types).
+/// Set `respect_gitignore` to drop untracked files the repository ignores.
 #[tauri::command]
-fn get_session_file_edits(project_path: String, session_id: String) -> Vec<FileEdit> {
-    claude_code::get_session_file_edits(&project_path, &session_id)
+fn get_session_file_edits(
+    project_path: String,
+    session_id: String,
+    patterns: Option<Vec<String>>,
+    respect_gitignore: Option<bool>,
+) -> Result<Vec<FileEdit>, String> {
+    let mut edits = claude_code::get_session_file_edits(&project_path, &session_id);
+    if respect_gitignore.unwrap_or(false) {
+        edits = git::filter_ignored_file_edits(&project_path, edits);
+    }
+    claude_code::filter_file_edits(edits, &patterns.unwrap_or_default())
 }
 
 /// Get all diffs for a specific file in a session.
@@ -78,22 +237,300 @@ fn get_file_diffs(project_path: String, session_id: String, file_path: String) -
     claude_code::get_file_diffs(&project_path, &session_id, &file_path)
 }
 
+/// Get unified diff hunks for every edit of a file in a session.
+#[tauri::command]
+fn get_file_diff_hunks(
+    project_path: String,
+    session_id: String,
+    file_path: String,
+    context_lines: Option<usize>,
+) -> Vec<FileDiffHunks> {
+    diff::get_file_diff_hunks(&project_path, &session_id, &file_path, context_lines)
+}
+
+/// Reconstruct a file's content as of a given edit in a session.
+#[tauri::command]
+fn get_file_snapshot(
+    project_path: String,
+    session_id: String,
+    file_path: String,
+    sequence: Option<u32>,
+) -> Result<FileSnapshot, String> {
+    claude_code::get_file_snapshot(&project_path, &session_id, &file_path, sequence)
+}
+
+/// Apply a session edit (or all edits for a file) to the working tree.
+#[tauri::command]
+fn apply_session_edit(
+    dirty_cache: State<'_, GitDirtyCache>,
+    project_path: String,
+    session_id: String,
+    file_path: String,
+    sequence: Option<u32>,
+) -> Result<ApplyEditResult, String> {
+    let result = claude_code::apply_session_edit(&project_path, &session_id, &file_path, sequence)?;
+    if result.written {
+        dirty_cache.invalidate(&project_path);
+    }
+    Ok(result)
+}
+
+/// Revert a single session edit in the working tree.
+#[tauri::command]
+fn revert_session_edit(
+    dirty_cache: State<'_, GitDirtyCache>,
+    project_path: String,
+    session_id: String,
+    file_path: String,
+    sequence: u32,
+) -> Result<ApplyEditResult, String> {
+    let result =
+        claude_code::revert_session_edit(&project_path, &session_id, &file_path, sequence)?;
+    if result.written {
+        dirty_cache.invalidate(&project_path);
+    }
+    Ok(result)
+}
+
+/// Export a session's edits as a git-applyable patch file.
+#[tauri::command]
+fn export_session_patch(
+    project_path: String,
+    session_id: String,
+    file_paths: Option<Vec<String>>,
+    output_path: String,
+) -> Result<PatchExport, String> {
+    diff::export_session_patch(
+        &project_path,
+        &session_id,
+        file_paths.as_deref(),
+        &output_path,
+    )
+}
+
+/// Compare each file's end-of-session content against the file on disk.
+#[tauri::command]
+fn get_session_drift(project_path: String, session_id: String) -> Vec<FileDrift> {
+    diff::get_session_drift(&project_path, &session_id)
+}
+
+/// Search the diffs of a session's file edits for text.
+#[tauri::command]
+fn search_file_edits(
+    project_path: String,
+    session_id: String,
+    query: String,
+    case_sensitive: Option<bool>,
+    limit: Option<usize>,
+) -> Vec<EditSearchMatch> {
+    diff::search_file_edits(
+        &project_path,
+        &session_id,
+        &query,
+        case_sensitive.unwrap_or(false),
+        limit,
+    )
+}
+
+/// Get a file's HEAD, end-of-session, and working-tree content with pairwise hunks.
+#[tauri::command]
+fn get_file_three_way(project_path: String, session_id: String, file_path: String) -> FileThreeWay {
+    diff::get_file_three_way(&project_path, &session_id, &file_path)
+}
+
 /// Get git diff for a file (HEAD vs working directory).
 #[tauri::command]
 fn get_git_file_diff(project_path: String, file_path: String) -> Result<GitFileDiff, String> {
     git::get_git_file_diff(&project_path, &file_path)
 }
 
+/// Get staged, unstaged, and untracked files for a project.
+#[tauri::command]
+fn get_git_status(project_path: String) -> Result<GitStatus, String> {
+    git::get_git_status(&project_path)
+}
+
+/// Get local branches, the current branch, and ahead/behind counts for a project.
+#[tauri::command]
+fn get_git_branches(project_path: String) -> Result<GitBranches, String> {
+    git::get_git_branches(&project_path)
+}
+
+/// Compare the branch a session recorded with the project's current branch.
+#[tauri::command]
+fn check_session_branch(
+    project_path: String,
+    session_id: String,
+) -> Result<GitBranchCheck, String> {
+    git::check_session_branch(&project_path, &session_id)
+}
+
+/// Get paginated commit history for a project (newest first).
+/// Pass the previous page's `nextCursor` as `cursor` to load older commits.
+#[tauri::command]
+fn get_git_log(
+    project_path: String,
+    offset: Option<u32>,
+    cursor: Option<String>,
+    limit: Option<u32>,
+) -> Result<GitLogResponse, String> {
+    git::get_git_log(&project_path, offset, cursor.as_deref(), limit)
+}
+
+/// Get structured hunks between two commits or refs, optionally for a single file.
+#[tauri::command]
+fn get_git_ref_diff(
+    project_path: String,
+    from: String,
+    to: String,
+    file_path: Option<String>,
+) -> Result<Vec<GitRefFileDiff>, String> {
+    git::get_git_ref_diff(&project_path, &from, &to, file_path.as_deref())
+}
+
+/// Get blame for a file, optionally limited to a 1-based inclusive line range.
+#[tauri::command]
+fn get_git_blame(
+    project_path: String,
+    file_path: String,
+    start_line: Option<u32>,
+    end_line: Option<u32>,
+) -> Result<Vec<GitBlameHunk>, String> {
+    git::get_git_blame(&project_path, &file_path, start_line, end_line)
+}
+
+/// Stage files (e.g. the ones a session edited), returning the updated status.
+#[tauri::command]
+fn git_stage_files(project_path: String, file_paths: Vec<String>) -> Result<GitStatus, String> {
+    git::git_stage_files(&project_path, &file_paths)
+}
+
+/// Get a file's unstaged hunks, flagging the ones that match the session's result.
+#[tauri::command]
+fn get_unstaged_hunks(
+    project_path: String,
+    file_path: String,
+    session_id: Option<String>,
+) -> Result<Vec<GitStageableHunk>, String> {
+    git::get_unstaged_hunks(&project_path, &file_path, session_id.as_deref())
+}
+
+/// Stage selected hunks of a file, or every hunk from the session when none are given.
+#[tauri::command]
+fn git_stage_hunks(
+    dirty_cache: State<'_, GitDirtyCache>,
+    project_path: String,
+    file_path: String,
+    hunk_indexes: Option<Vec<usize>>,
+    session_id: Option<String>,
+) -> Result<GitStatus, String> {
+    let status = git::git_stage_hunks(
+        &project_path,
+        &file_path,
+        hunk_indexes.as_deref(),
+        session_id.as_deref(),
+    );
+    dirty_cache.invalidate(&project_path);
+    status
+}
+
+/// Commit staged changes with the given message.
+#[tauri::command]
+fn git_commit(
+    dirty_cache: State<'_, GitDirtyCache>,
+    project_path: String,
+    message: String,
+) -> Result<GitCommit, String> {
+    let commit = git::git_commit(&project_path, &message);
+    dirty_cache.invalidate(&project_path);
+    commit
+}
+
+/// Restore a file edited in the session to its HEAD content.
+#[tauri::command]
+fn git_discard_file(
+    dirty_cache: State<'_, GitDirtyCache>,
+    project_path: String,
+    session_id: String,
+    file_path: String,
+) -> Result<GitStatus, String> {
+    let status = git::git_discard_file(&project_path, &session_id, &file_path);
+    dirty_cache.invalidate(&project_path);
+    status
+}
+
+/// Stash uncommitted changes in a project (untracked files included by default).
+#[tauri::command]
+fn git_stash_push(
+    dirty_cache: State<'_, GitDirtyCache>,
+    project_path: String,
+    message: Option<String>,
+    include_untracked: Option<bool>,
+) -> Result<GitStash, String> {
+    let stash = git::git_stash_push(
+        &project_path,
+        message.as_deref(),
+        include_untracked.unwrap_or(true),
+    );
+    dirty_cache.invalidate(&project_path);
+    stash
+}
+
+/// List stash entries for a project, most recent first.
+#[tauri::command]
+fn git_stash_list(project_path: String) -> Result<Vec<GitStash>, String> {
+    git::git_stash_list(&project_path)
+}
+
+/// Apply and drop a stash entry (the most recent by default).
+#[tauri::command]
+fn git_stash_pop(
+    dirty_cache: State<'_, GitDirtyCache>,
+    project_path: String,
+    index: Option<usize>,
+) -> Result<GitStatus, String> {
+    let status = git::git_stash_pop(&project_path, index);
+    dirty_cache.invalidate(&project_path);
+    status
+}
+
+/// Tag (or branch) the repository state at review time, including uncommitted changes.
+#[tauri::command]
+fn create_session_snapshot(
+    project_path: String,
+    session_id: String,
+    as_branch: Option<bool>,
+) -> Result<GitSnapshot, String> {
+    git::create_session_snapshot(&project_path, &session_id, as_branch.unwrap_or(false))
+}
+
+/// List a project's main checkout and linked worktrees with their sessions.
+#[tauri::command]
+fn get_git_worktrees(project_path: String) -> Result<Vec<GitWorktree>, String> {
+    git::get_git_worktrees(&project_path)
+}
+
 /// Get paginated events from a session for the log viewer.
 /// Events are returned in descending order (newest first).
+/// Pass the previous page's `nextCursor` as `cursor` for stable "load more" on live sessions.
+/// `preview` overrides the preview length and content precedence (text > thinking > tool).
 #[tauri::command]
 fn get_session_events(
     project_path: String,
     session_id: String,
     offset: Option<u32>,
+    cursor: Option<String>,
     limit: Option<u32>,
+    preview: Option<PreviewOptions>,
 ) -> claude_code::SessionEventsResponse {
-    claude_code::get_session_events(&project_path, &session_id, offset, limit)
+    claude_code::get_session_events(
+        &project_path,
+        &session_id,
+        offset,
+        cursor.as_deref(),
+        limit,
+        &preview.unwrap_or_default(),
+    )
 }
 
 /// Get the raw JSON for a specific event by its byte offset.
@@ -106,15 +543,84 @@ fn get_event_raw_json(
     claude_code::get_event_raw_json(&project_path, &session_id, byte_offset)
 }
 
+/// Get the raw JSON for an event prepared for display: optionally pretty-printed, with
+/// strings longer than max_string_chars cut, plus size metadata for large-event warnings.
+#[tauri::command]
+fn get_event_raw_json_formatted(
+    project_path: String,
+    session_id: String,
+    byte_offset: u64,
+    pretty: Option<bool>,
+    max_string_chars: Option<usize>,
+) -> Result<claude_code::RawEventJson, String> {
+    claude_code::get_event_raw_json_formatted(
+        &project_path,
+        &session_id,
+        byte_offset,
+        pretty.unwrap_or(false),
+        max_string_chars,
+    )
+}
+
+/// Get the complete content of a tool result by tool_use_id or event byte offset.
+/// Use max_chars to cap the returned content; `truncated` reports whether it was cut.
+#[tauri::command]
+fn get_tool_result_content(
+    project_path: String,
+    session_id: String,
+    tool_use_id: Option<String>,
+    byte_offset: Option<u64>,
+    max_chars: Option<usize>,
+) -> Result<claude_code::ToolResultContent, String> {
+    claude_code::get_tool_result_content(
+        &project_path,
+        &session_id,
+        tool_use_id.as_deref(),
+        byte_offset,
+        max_chars,
+    )
+}
+
+/// Get image and document attachments for an event by its byte offset.
+/// Large attachments are written to the app's cache directory and returned as file paths.
+#[tauri::command]
+fn get_event_attachments(
+    app_handle: AppHandle,
+    project_path: String,
+    session_id: String,
+    byte_offset: u64,
+) -> Result<Vec<claude_code::EventAttachment>, String> {
+    let cache_dir = attachment_cache_dir(&app_handle)?;
+    claude_code::get_event_attachments(&project_path, &session_id, byte_offset, &cache_dir)
+}
+
+/// Directory cached attachment files are written to.
+fn attachment_cache_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    app_handle
+        .path()
+        .app_cache_dir()
+        .map(|dir| dir.join("attachments"))
+        .map_err(|e| format!("Failed to resolve cache directory: {}", e))
+}
+
 /// Get paginated events from a sub-agent session for the log viewer.
 #[tauri::command]
 fn get_subagent_events(
     project_path: String,
     agent_id: String,
     offset: Option<u32>,
+    cursor: Option<String>,
     limit: Option<u32>,
+    preview: Option<PreviewOptions>,
 ) -> claude_code::SessionEventsResponse {
-    claude_code::get_subagent_events(&project_path, &agent_id, offset, limit)
+    claude_code::get_subagent_events(
+        &project_path,
+        &agent_id,
+        offset,
+        cursor.as_deref(),
+        limit,
+        &preview.unwrap_or_default(),
+    )
 }
 
 /// Get the raw JSON for a specific event in a sub-agent session.
@@ -183,6 +689,61 @@ fn unwatch_session(
     watcher::unwatch_session(&state, &project_path, &session_id)
 }
 
+/// Stream newly appended events for a watched session over a channel.
+/// Call after watch_session; the subscription ends when the session is unwatched.
+#[tauri::command]
+fn stream_session_events(
+    state: State<'_, WatcherState>,
+    project_path: String,
+    session_id: String,
+    on_events: Channel<SessionEventsAppendedPayload>,
+) -> Result<(), String> {
+    watcher::stream_session_events(&state, &project_path, &session_id, on_events)
+}
+
+/// Tear down session watchers not queried for `ttl_secs`, emitting "watch-expired".
+/// Pass no TTL to keep watchers until they are unwatched.
+#[tauri::command]
+fn set_watcher_idle_ttl(
+    app_handle: AppHandle,
+    state: State<'_, WatcherState>,
+    ttl_secs: Option<u64>,
+) -> Result<(), String> {
+    watcher::set_watcher_idle_ttl(app_handle, &state, ttl_secs.map(Duration::from_secs))
+}
+
+/// Set how long watchers wait for writes to settle before emitting, in milliseconds.
+/// Omitted intervals revert to their defaults (500ms sessions, 300ms telemetry).
+/// Returns the watchers dropped because they couldn't be recreated.
+#[tauri::command]
+fn set_watcher_debounce(
+    state: State<'_, WatcherState>,
+    session_ms: Option<u64>,
+    telemetry_ms: Option<u64>,
+) -> Result<Vec<WatcherFailure>, String> {
+    watcher::set_watcher_debounce(
+        &state,
+        session_ms.map(Duration::from_millis),
+        telemetry_ms.map(Duration::from_millis),
+    )
+}
+
+/// List active watchers with their last event time and any callback errors.
+#[tauri::command]
+fn get_watcher_status(state: State<'_, WatcherState>) -> Result<Vec<WatcherStatus>, String> {
+    watcher::get_watcher_status(&state)
+}
+
+/// Recreate all watchers (e.g. when live updates stall), returning the restarted keys
+/// and any watchers that couldn't be recreated.
+#[tauri::command]
+fn restart_watchers(
+    app_handle: AppHandle,
+    state: State<'_, WatcherState>,
+) -> Result<WatchersRestartedPayload, String> {
+    watcher::restart_watchers(&app_handle, &state, "manual")
+}
+
 /// Start watching a sub-agent file for changes.
 #[tauri::command]
 fn watch_subagent(
@@ -204,6 +765,37 @@ fn unwatch_subagent(
     watcher::unwatch_subagent(&state, &project_path, &agent_id)
 }
 
+/// Start watching `~/.claude/projects` for projects created while the app is open.
+#[tauri::command]
+fn watch_projects_root(
+    app_handle: AppHandle,
+    state: State<'_, WatcherState>,
+) -> Result<(), String> {
+    watcher::watch_projects_root(app_handle, &state)
+}
+
+/// Stop watching `~/.claude/projects` for new projects.
+#[tauri::command]
+fn unwatch_projects_root(state: State<'_, WatcherState>) -> Result<(), String> {
+    watcher::unwatch_projects_root(&state)
+}
+
+/// Start watching a project for new and removed sessions.
+#[tauri::command]
+fn watch_project(
+    app_handle: AppHandle,
+    state: State<'_, WatcherState>,
+    project_path: String,
+) -> Result<(), String> {
+    watcher::watch_project(app_handle, &state, project_path)
+}
+
+/// Stop watching a project for session changes.
+#[tauri::command]
+fn unwatch_project(state: State<'_, WatcherState>, project_path: String) -> Result<(), String> {
+    watcher::unwatch_project(&state, &project_path)
+}
+
 /// Start watching a project's telemetry directory for changes.
 #[tauri::command]
 fn watch_telemetry(
@@ -220,6 +812,59 @@ fn unwatch_telemetry(state: State<'_, WatcherState>, project_path: String) -> Re
     watcher::unwatch_telemetry(&state, &project_path)
 }
 
+/// Start watching a project's CLAUDE.md, .claude settings and .mcp.json for changes.
+#[tauri::command]
+fn watch_project_config(
+    app_handle: AppHandle,
+    state: State<'_, WatcherState>,
+    project_path: String,
+) -> Result<(), String> {
+    watcher::watch_project_config(app_handle, &state, project_path)
+}
+
+/// Stop watching a project's agent configuration files.
+#[tauri::command]
+fn unwatch_project_config(
+    state: State<'_, WatcherState>,
+    project_path: String,
+) -> Result<(), String> {
+    watcher::unwatch_project_config(&state, &project_path)
+}
+
+/// Start watching a project's git HEAD, emitting "branch-changed" on branch switches.
+#[tauri::command]
+fn watch_git_head(
+    app_handle: AppHandle,
+    state: State<'_, WatcherState>,
+    project_path: String,
+) -> Result<(), String> {
+    watcher::watch_git_head(app_handle, &state, project_path)
+}
+
+/// Stop watching a project's git HEAD.
+#[tauri::command]
+fn unwatch_git_head(state: State<'_, WatcherState>, project_path: String) -> Result<(), String> {
+    watcher::unwatch_git_head(&state, &project_path)
+}
+
+/// Start watching ~/.claude/todos, emitting "todos-changed" when a todo list changes.
+#[tauri::command]
+fn watch_todos(app_handle: AppHandle, state: State<'_, WatcherState>) -> Result<(), String> {
+    watcher::watch_todos(app_handle, &state)
+}
+
+/// Stop watching ~/.claude/todos.
+#[tauri::command]
+fn unwatch_todos(state: State<'_, WatcherState>) -> Result<(), String> {
+    watcher::unwatch_todos(&state)
+}
+
+/// Get the todo list Claude Code currently holds for a session (~/.claude/todos).
+#[tauri::command]
+fn get_persisted_todos(session_id: String) -> Vec<activity::TodoItem> {
+    activity::get_persisted_todos(&session_id)
+}
+
 /// Get the index status for a session.
 /// Returns ready state, event counts, and any errors.
 #[tauri::command]
@@ -233,18 +878,27 @@ fn get_index_status(
 
 /// Get file edits from the cached session index (O(1) lookup).
 /// Falls back to scanning if index not available.
+/// Set `respect_gitignore` to drop untracked files the repository ignores.
 #[tauri::command]
 fn get_indexed_file_edits(
     state: State<'_, WatcherState>,
     project_path: String,
     session_id: String,
-) -> Vec<FileEdit> {
+    patterns: Option<Vec<String>>,
+    respect_gitignore: Option<bool>,
+) -> Result<Vec<FileEdit>, String> {
     // Try to get from cached index first
-    if let Some(index) = state.get_index(&project_path, &session_id) {
-        return index.file_edits;
+    let mut edits = match state.get_index(&project_path, &session_id) {
+        Some(index) => {
+            claude_code::merge_subagent_file_edits(&project_path, &session_id, index.file_edits)
+        }
+        // Fallback to scanning (shouldn't happen if index is ready)
+        None => claude_code::get_session_file_edits(&project_path, &session_id),
+    };
+    if respect_gitignore.unwrap_or(false) {
+        edits = git::filter_ignored_file_edits(&project_path, edits);
     }
-    // Fallback to scanning (shouldn't happen if index is ready)
-    claude_code::get_session_file_edits(&project_path, &session_id)
+    claude_code::filter_file_edits(edits, &patterns.unwrap_or_default())
 }
 
 /// Get paginated events using cached line offsets (O(k) seeks instead of O(n) scan).
@@ -255,8 +909,12 @@ fn get_indexed_events(
     project_path: String,
     session_id: String,
     offset: Option<u32>,
+    cursor: Option<String>,
     limit: Option<u32>,
+    preview: Option<PreviewOptions>,
 ) -> claude_code::SessionEventsResponse {
+    let preview = preview.unwrap_or_default();
+
     // Try to get from cached index first
     if let Some(index) = state.get_index(&project_path, &session_id) {
         return claude_code::get_session_events_with_index(
@@ -264,11 +922,20 @@ fn get_indexed_events(
             &session_id,
             &index,
             offset,
+            cursor.as_deref(),
             limit,
+            &preview,
         );
     }
     // Fallback to scanning (shouldn't happen if index is ready)
-    claude_code::get_session_events(&project_path, &session_id, offset, limit)
+    claude_code::get_session_events(
+        &project_path,
+        &session_id,
+        offset,
+        cursor.as_deref(),
+        limit,
+        &preview,
+    )
 }
 
 /// Get the context for a file edit - the chain of events from the human message to the edit.
@@ -301,7 +968,7 @@ fn get_file_edit_context(
 
     // Get the session file path
     let home = dirs::home_dir().ok_or_else(|| "Cannot find home directory".to_string())?;
-    let encoded_name = project_path.replace('/', "-").replace(' ', "-");
+    let encoded_name = project_path.replace(['/', ' '], "-");
     let session_file = home
         .join(".claude")
         .join("projects")
@@ -316,10 +983,206 @@ fn get_file_edit_context(
     get_edit_context(&index, &session_file, edit_line)
 }
 
-/// Get list of policy evaluations for a project.
+/// Group a file's diffs by the human prompt that triggered them.
+#[tauri::command]
+fn get_file_edits_by_turn(
+    state: State<'_, WatcherState>,
+    project_path: String,
+    session_id: String,
+    file_path: String,
+) -> Result<Vec<EditTurn>, String> {
+    let index = state
+        .get_index(&project_path, &session_id)
+        .ok_or_else(|| "Session index not available".to_string())?;
+    let session_file = claude_code::get_session_file_path(&project_path, &session_id)
+        .ok_or_else(|| format!("Session file not found for {}", session_id))?;
+
+    let diffs = claude_code::get_file_diffs(&project_path, &session_id, &file_path);
+    group_edits_by_turn(&index, &session_file, &file_path, diffs)
+}
+
+/// Get a single event by its UUID using the cached session index.
+/// Used to follow deep links such as `leafUuid` and `logicalParentUuid` in summary events.
+#[tauri::command]
+fn get_event_by_uuid(
+    state: State<'_, WatcherState>,
+    project_path: String,
+    session_id: String,
+    uuid: String,
+) -> Result<claude_code::SessionEvent, String> {
+    let index = state
+        .get_index(&project_path, &session_id)
+        .ok_or_else(|| "Session index not available".to_string())?;
+
+    let session_file = claude_code::get_session_file_path(&project_path, &session_id)
+        .ok_or_else(|| format!("Session file not found for {}", session_id))?;
+
+    session_index::get_event_by_uuid(&index, &session_file, &uuid)
+}
+
+/// Get the thread for any event - the parent chain from the triggering human message.
+/// The target is identified by UUID or by sequence (UUID takes precedence).
+#[tauri::command]
+fn get_event_thread(
+    state: State<'_, WatcherState>,
+    project_path: String,
+    session_id: String,
+    uuid: Option<String>,
+    sequence: Option<u32>,
+) -> Result<EventThread, String> {
+    let index = state
+        .get_index(&project_path, &session_id)
+        .ok_or_else(|| "Session index not available".to_string())?;
+
+    let target_line = match (uuid, sequence) {
+        (Some(uuid), _) => index
+            .line_for_uuid(&uuid)
+            .ok_or_else(|| format!("No event found for UUID: {}", uuid))?,
+        (None, Some(sequence)) => sequence,
+        (None, None) => return Err("Either uuid or sequence is required".to_string()),
+    };
+
+    let session_file = claude_code::get_session_file_path(&project_path, &session_id)
+        .ok_or_else(|| format!("Session file not found for {}", session_id))?;
+
+    session_index::get_event_thread(&index, &session_file, target_line)
+}
+
+/// Get branch points in a session (parents with multiple children), so the log viewer
+/// can offer "branch 2 of 3" navigation for resumed/edited turns and sidechains.
+#[tauri::command]
+fn get_session_branches(
+    state: State<'_, WatcherState>,
+    project_path: String,
+    session_id: String,
+) -> Result<Vec<BranchPoint>, String> {
+    let index = state
+        .get_index(&project_path, &session_id)
+        .ok_or_else(|| "Session index not available".to_string())?;
+
+    Ok(session_index::get_session_branches(&index))
+}
+
+/// Get every generated summary in a session, with each leafUuid resolved to a sequence,
+/// so users can skim a long session and jump to the point each summary covers.
+#[tauri::command]
+fn get_session_summaries(
+    state: State<'_, WatcherState>,
+    project_path: String,
+    session_id: String,
+) -> Result<Vec<SessionSummary>, String> {
+    let index = state
+        .get_index(&project_path, &session_id)
+        .ok_or_else(|| "Session index not available".to_string())?;
+
+    let session_file = claude_code::get_session_file_path(&project_path, &session_id)
+        .ok_or_else(|| format!("Session file not found for {}", session_id))?;
+
+    session_index::get_session_summaries(&index, &session_file)
+}
+
+/// Get summary statistics for a session (counts by type and tool, turns, duration).
+/// Computed from counters maintained by the index, so this is O(1) after indexing.
 #[tauri::command]
-fn get_policy_evaluations(project_path: String) -> Vec<PolicyEvaluation> {
-    claude_code::get_policy_evaluations(&project_path)
+fn get_session_stats(
+    state: State<'_, WatcherState>,
+    project_path: String,
+    session_id: String,
+) -> Result<SessionStats, String> {
+    let index = state
+        .get_index(&project_path, &session_id)
+        .ok_or_else(|| "Session index not available".to_string())?;
+
+    Ok(index.to_stats())
+}
+
+/// Find the event nearest to a timestamp (ISO 8601), for scrubbing through long sessions.
+/// Returns None if the session has no timestamped events.
+#[tauri::command]
+fn find_event_at_timestamp(
+    state: State<'_, WatcherState>,
+    project_path: String,
+    session_id: String,
+    timestamp: String,
+) -> Result<Option<TimestampMatch>, String> {
+    let index = state
+        .get_index(&project_path, &session_id)
+        .ok_or_else(|| "Session index not available".to_string())?;
+
+    session_index::find_event_at_timestamp(&index, &timestamp)
+}
+
+/// Get the agent's todo list (from TodoWrite calls) with its change history.
+#[tauri::command]
+fn get_session_todos(project_path: String, session_id: String) -> activity::SessionTodos {
+    activity::get_session_todos(&project_path, &session_id)
+}
+
+/// Get plans proposed via ExitPlanMode and whether each was approved.
+#[tauri::command]
+fn get_session_plans(project_path: String, session_id: String) -> Vec<activity::SessionPlan> {
+    activity::get_session_plans(&project_path, &session_id)
+}
+
+/// Get tool uses that required approval and were refused: rejected prompts,
+/// interrupted tool uses, and permission_denials from headless runs.
+#[tauri::command]
+fn get_permission_events(
+    project_path: String,
+    session_id: String,
+) -> Vec<activity::PermissionEvent> {
+    activity::get_permission_events(&project_path, &session_id)
+}
+
+/// Get API errors, failed tool results, and non-zero Bash exits in one list.
+#[tauri::command]
+fn get_session_errors(project_path: String, session_id: String) -> Vec<activity::SessionError> {
+    activity::get_session_errors(&project_path, &session_id)
+}
+
+/// Get WebSearch and WebFetch calls with queries, URLs, and result sizes.
+#[tauri::command]
+fn get_session_web_activity(
+    project_path: String,
+    session_id: String,
+) -> Vec<activity::WebActivity> {
+    activity::get_session_web_activity(&project_path, &session_id)
+}
+
+/// Get MCP tool calls (`mcp__<server>__<tool>`) grouped per server with failure counts.
+#[tauri::command]
+fn get_session_mcp_usage(
+    project_path: String,
+    session_id: String,
+) -> Vec<activity::McpServerUsage> {
+    activity::get_session_mcp_usage(&project_path, &session_id)
+}
+
+/// Get extended-thinking blocks with full text, in order, for reading the agent's reasoning.
+#[tauri::command]
+fn get_session_thinking(project_path: String, session_id: String) -> Vec<activity::ThinkingBlock> {
+    activity::get_session_thinking(&project_path, &session_id)
+}
+
+/// Suggest a conventional-commit message from a session's summary, prompts, and edited files.
+#[tauri::command]
+fn suggest_commit_message(project_path: String, session_id: String) -> Result<String, String> {
+    activity::suggest_commit_message(&project_path, &session_id)
+}
+
+/// Get a page of policy evaluations for a project, newest first.
+/// `offset` defaults to 0 and `limit` to 100. `filter` narrows the list by decision,
+/// tool, event type, time range, or trace ID.
+#[tauri::command]
+fn get_policy_evaluations(
+    project_path: String,
+    offset: Option<u32>,
+    limit: Option<u32>,
+    filter: Option<PolicyEvaluationFilter>,
+    cache: State<'_, PolicyEvaluationCache>,
+) -> Result<PolicyEvaluationsResponse, String> {
+    let filter = filter.unwrap_or_default();
+    claude_code::get_policy_evaluations(&project_path, offset, limit, &filter, &cache)
 }
 
 /// Get raw JSON for a specific policy evaluation.
@@ -328,6 +1191,28 @@ fn get_policy_evaluation(project_path: String, filename: String) -> Option<Strin
     claude_code::get_policy_evaluation(&project_path, &filename)
 }
 
+/// Get the parsed phases, rule results, and violation messages of a policy evaluation.
+#[tauri::command]
+fn get_policy_evaluation_detail(
+    project_path: String,
+    filename: String,
+) -> Option<PolicyEvaluationDetail> {
+    claude_code::get_policy_evaluation_detail(&project_path, &filename)
+}
+
+/// List a project's Cupcake policies (`.cupcake/policies/**/*.rego`) and
+/// guidebook/rulebook configuration.
+#[tauri::command]
+fn get_policy_files(project_path: String) -> Vec<PolicyFile> {
+    claude_code::get_policy_files(&project_path)
+}
+
+/// Get a Cupcake policy or configuration file's contents and metadata.
+#[tauri::command]
+fn get_policy_file(project_path: String, path: String) -> Result<PolicyFileContent, String> {
+    claude_code::get_policy_file(&project_path, &path)
+}
+
 /// Reveal a path in the system file manager.
 /// - macOS: Finder
 /// - Windows: Explorer
@@ -382,17 +1267,67 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .manage(WatcherState::new())
+        .manage(GitDirtyCache::new())
+        .manage(PolicyEvaluationCache::new())
+        .manage(ProcessMonitor::new())
+        .manage(LaunchRegistry::new())
+        .setup(|app| {
+            watcher::start_wake_monitor(app.handle().clone());
+            // Drop attachments cached by earlier runs once they've gone stale
+            if let Ok(cache_dir) = attachment_cache_dir(app.handle()) {
+                std::thread::spawn(move || {
+                    claude_code::prune_attachment_cache(
+                        &cache_dir,
+                        claude_code::ATTACHMENT_CACHE_TTL,
+                    );
+                });
+            }
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             get_projects,
             get_project_sessions,
             get_active_sessions,
+            signal_agent_process,
+            set_active_sessions_polling,
             get_available_terminals,
             launch_claude,
+            preview_launch_command,
+            get_launched_sessions,
+            get_wsl_distros,
+            has_devcontainer,
             get_session_file_edits,
             get_file_diffs,
+            get_file_diff_hunks,
+            get_file_snapshot,
+            apply_session_edit,
+            revert_session_edit,
+            export_session_patch,
+            get_session_drift,
+            search_file_edits,
+            get_file_three_way,
             get_git_file_diff,
+            get_git_status,
+            get_git_branches,
+            check_session_branch,
+            get_git_log,
+            get_git_ref_diff,
+            get_git_blame,
+            git_stage_files,
+            get_unstaged_hunks,
+            git_stage_hunks,
+            git_commit,
+            git_discard_file,
+            git_stash_push,
+            git_stash_list,
+            git_stash_pop,
+            create_session_snapshot,
+            get_git_worktrees,
             get_session_events,
             get_event_raw_json,
+            get_event_raw_json_formatted,
+            get_tool_result_content,
+            get_event_attachments,
             get_subagent_events,
             get_subagent_raw_json,
             search_session_events,
@@ -400,16 +1335,50 @@ pub fn run() {
             get_events_by_offsets,
             watch_session,
             unwatch_session,
+            stream_session_events,
+            set_watcher_idle_ttl,
+            set_watcher_debounce,
+            get_watcher_status,
+            restart_watchers,
             watch_subagent,
             unwatch_subagent,
+            watch_projects_root,
+            unwatch_projects_root,
+            watch_project,
+            unwatch_project,
             watch_telemetry,
             unwatch_telemetry,
+            watch_project_config,
+            unwatch_project_config,
+            watch_git_head,
+            unwatch_git_head,
+            watch_todos,
+            unwatch_todos,
+            get_persisted_todos,
             get_index_status,
             get_indexed_file_edits,
             get_indexed_events,
             get_file_edit_context,
+            get_file_edits_by_turn,
+            get_event_by_uuid,
+            get_event_thread,
+            get_session_branches,
+            get_session_summaries,
+            get_session_stats,
+            find_event_at_timestamp,
+            get_session_todos,
+            get_session_plans,
+            get_permission_events,
+            get_session_errors,
+            get_session_web_activity,
+            get_session_mcp_usage,
+            get_session_thinking,
+            suggest_commit_message,
             get_policy_evaluations,
             get_policy_evaluation,
+            get_policy_evaluation_detail,
+            get_policy_files,
+            get_policy_file,
             reveal_in_file_manager
         ])
         .run(tauri::generate_context!())
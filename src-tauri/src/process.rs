@@ -1,11 +1,18 @@
 //! Process detection for active Claude Code sessions.
 //!
-//! This module provides cross-platform detection of running Claude Code processes
-//! and their working directories.
+//! Finds running Claude Code processes with the `sysinfo` crate and matches each to the
+//! project and session it is working on.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::process::Command;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, UNIX_EPOCH};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, Signal, System, UpdateKind};
+
+use crate::claude_code::{get_session_modified_times, is_uuid_format};
+use crate::launch::LAUNCH_ID_ENV;
+use crate::terminal::TerminalType;
 
 /// Result of active session detection.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,137 +22,518 @@ pub struct ActiveSessionsResult {
     pub supported: bool,
     /// Set of project paths with active Claude sessions.
     pub active_paths: HashSet<String>,
+    /// Number of running claude processes per project path.
+    pub active_counts: HashMap<String, u32>,
+    /// Each running claude process and the session it is writing.
+    pub sessions: Vec<RunningSession>,
+}
+
+/// A running claude process matched to its session.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RunningSession {
+    /// Process ID
+    pub pid: u32,
+    /// Project path (the process's working directory)
+    pub project_path: String,
+    /// Session the process is writing (None if it couldn't be determined)
+    pub session_id: Option<String>,
+    /// CPU usage since the previous check (100.0 = one full core)
+    pub cpu_percent: f32,
+    /// Resident memory in bytes
+    pub memory_bytes: u64,
+    /// Seconds since the process started
+    pub uptime_secs: u64,
+    /// When the process started (ISO 8601)
+    pub started_at: Option<String>,
+    /// Whether the process has been running longer than `LONG_RUNNING_AFTER`
+    pub long_running: bool,
+    /// tmux pane the process runs in, if any
+    pub tmux: Option<TmuxPane>,
+    /// Whether this is a non-interactive run (`claude -p`), which can't be continued
+    /// in a terminal
+    pub headless: bool,
+    /// Terminal application the process runs in (None inside tmux or if unrecognized)
+    pub terminal: Option<HostTerminal>,
+    /// ID of the launch that started the process, if started from this app
+    pub launch_id: Option<String>,
+}
+
+/// A terminal application hosting a claude process.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HostTerminal {
+    /// Display name (e.g. "iTerm2", "VS Code")
+    pub name: String,
+    /// PID of the terminal's process
+    pub pid: u32,
+    /// Matching launchable terminal, if any
+    pub terminal_type: Option<TerminalType>,
+}
+
+/// A tmux pane hosting a claude process.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TmuxPane {
+    /// Pane ID (e.g. "%3"), usable as a tmux target
+    pub pane_id: String,
+    /// Socket of the tmux server the pane belongs to
+    pub socket_path: Option<String>,
+    /// Session name (None if tmux couldn't be queried)
+    pub session_name: Option<String>,
+    /// Window index within the session
+    pub window_index: Option<u32>,
+    /// Pane index within the window
+    pub pane_index: Option<u32>,
+}
+
+/// Uptime after which a process is flagged as running unusually long.
+const LONG_RUNNING_AFTER: Duration = Duration::from_secs(4 * 60 * 60);
+
+/// Keeps the process table between checks, since CPU usage is measured over the
+/// interval since the previous refresh.
+pub struct ProcessMonitor {
+    system: Mutex<Option<System>>,
+    /// Map of (pid, start time) -> described tmux pane, so tmux is queried once per process
+    tmux_panes: Mutex<HashMap<(u32, u64), TmuxPane>>,
+}
+
+impl ProcessMonitor {
+    pub fn new() -> Self {
+        Self {
+            system: Mutex::new(None),
+            tmux_panes: Mutex::new(HashMap::new()),
+        }
+    }
 }
 
 /// Detect active Claude Code sessions and return their working directories.
 ///
 /// # Platform Support
-/// - **macOS**: Full support via `ps` and `lsof`
-/// - **Linux**: Full support via `ps` and `/proc`
-/// - **Windows**: Not currently supported (returns supported=false)
-pub fn get_active_sessions() -> ActiveSessionsResult {
-    #[cfg(target_os = "macos")]
-    {
-        ActiveSessionsResult {
-            supported: true,
-            active_paths: detect_macos_sessions(),
-        }
+/// Supported wherever `sysinfo` can list processes (macOS, Linux, Windows, FreeBSD);
+/// elsewhere returns supported=false.
+pub fn get_active_sessions(monitor: &ProcessMonitor) -> ActiveSessionsResult {
+    let processes = claude_processes(monitor);
+    let mut active_counts = HashMap::new();
+    for cwd in processes.iter().filter_map(|p| p.cwd.clone()) {
+        *active_counts.entry(cwd).or_insert(0) += 1;
     }
 
-    #[cfg(target_os = "linux")]
-    {
-        ActiveSessionsResult {
-            supported: true,
-            active_paths: detect_linux_sessions(),
-        }
+    ActiveSessionsResult {
+        supported: sysinfo::IS_SUPPORTED_SYSTEM,
+        active_paths: active_counts.keys().cloned().collect(),
+        active_counts,
+        sessions: running_sessions(&processes),
     }
+}
 
-    #[cfg(target_os = "windows")]
-    {
-        ActiveSessionsResult {
-            supported: false,
-            active_paths: HashSet::new(),
-        }
-    }
+/// A running "claude" process.
+#[derive(Debug, Clone)]
+pub(crate) struct ClaudeProcess {
+    /// Process ID
+    pub pid: u32,
+    /// Working directory (None if it couldn't be read, e.g. another user's process)
+    pub cwd: Option<String>,
+    /// Start time in seconds since the Unix epoch
+    pub start_time: u64,
+    /// Command-line arguments, including the executable
+    pub args: Vec<String>,
+    /// CPU usage since the previous refresh (100.0 = one full core)
+    pub cpu_percent: f32,
+    /// Resident memory in bytes
+    pub memory_bytes: u64,
+    /// Seconds since the process started
+    pub uptime_secs: u64,
+    /// tmux pane the process runs in, if any
+    pub tmux: Option<TmuxPane>,
+    /// Terminal application the process runs in, if recognized
+    pub terminal: Option<HostTerminal>,
+    /// ID of the launch that started the process, if started from this app
+    pub launch_id: Option<String>,
+}
 
-    #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
-    {
-        ActiveSessionsResult {
-            supported: false,
-            active_paths: HashSet::new(),
-        }
+/// List running "claude" processes with their working directories and resource usage.
+pub(crate) fn claude_processes(monitor: &ProcessMonitor) -> Vec<ClaudeProcess> {
+    let Ok(mut guard) = monitor.system.lock() else {
+        return Vec::new();
+    };
+    let refresh_kind = ProcessRefreshKind::nothing()
+        .with_cwd(UpdateKind::Always)
+        .with_cmd(UpdateKind::Always)
+        .with_cpu()
+        .with_memory();
+
+    // CPU usage needs two samples, so the first check takes one ahead of time
+    if guard.is_none() {
+        let mut system = System::new();
+        system.refresh_processes_specifics(ProcessesToUpdate::All, true, refresh_kind);
+        std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+        *guard = Some(system);
     }
+    let Some(system) = guard.as_mut() else {
+        return Vec::new();
+    };
+    system.refresh_processes_specifics(ProcessesToUpdate::All, true, refresh_kind);
+
+    // A process's environment doesn't change, so it's only read once per claude process
+    let claude_pids: Vec<Pid> = system
+        .processes()
+        .iter()
+        .filter(|(_, process)| is_claude_process_name(&process.name().to_string_lossy()))
+        .map(|(pid, _)| *pid)
+        .collect();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&claude_pids),
+        false,
+        ProcessRefreshKind::nothing().with_environ(UpdateKind::OnlyIfNotSet),
+    );
+
+    let Ok(mut tmux_panes) = monitor.tmux_panes.lock() else {
+        return Vec::new();
+    };
+    let processes: Vec<ClaudeProcess> = claude_pids
+        .iter()
+        .filter_map(|pid| Some((pid, system.process(*pid)?)))
+        .map(|(pid, process)| ClaudeProcess {
+            pid: pid.as_u32(),
+            cwd: process
+                .cwd()
+                .map(|cwd| cwd.to_string_lossy().to_string())
+                .filter(|cwd| !cwd.is_empty()),
+            start_time: process.start_time(),
+            args: process
+                .cmd()
+                .iter()
+                .map(|arg| arg.to_string_lossy().to_string())
+                .collect(),
+            cpu_percent: process.cpu_usage(),
+            memory_bytes: process.memory(),
+            uptime_secs: process.run_time(),
+            tmux: tmux_pane(process.environ()).map(|pane| {
+                tmux_panes
+                    .entry((pid.as_u32(), process.start_time()))
+                    .or_insert_with(|| describe_tmux_pane(pane))
+                    .clone()
+            }),
+            terminal: host_terminal(system, *pid),
+            launch_id: environ_var(process.environ(), LAUNCH_ID_ENV),
+        })
+        .collect();
+
+    // Forget the panes of processes that have exited
+    tmux_panes.retain(|&(pid, start_time), _| {
+        processes
+            .iter()
+            .any(|p| p.pid == pid && p.start_time == start_time)
+    });
+
+    processes
 }
 
-/// Detect Claude sessions on macOS.
-#[cfg(target_os = "macos")]
-fn detect_macos_sessions() -> HashSet<String> {
-    let mut paths = HashSet::new();
+/// Maximum number of ancestors checked when looking for the hosting terminal.
+const MAX_PARENT_DEPTH: usize = 32;
 
-    for pid in get_claude_pids() {
-        if let Some(cwd) = get_process_cwd_macos(pid) {
-            paths.insert(cwd);
+/// Walk a process's ancestors to find the terminal application it runs in.
+fn host_terminal(system: &System, pid: Pid) -> Option<HostTerminal> {
+    let mut current = system.process(pid)?.parent();
+    // Bounded in case of a parent cycle between refreshes
+    for _ in 0..MAX_PARENT_DEPTH {
+        let process = system.process(current?)?;
+        let name = process.name().to_string_lossy();
+        if name == "tmux" || name.starts_with("tmux:") {
+            // The tmux server isn't attached to any one terminal
+            return None;
+        }
+        if let Some((name, terminal_type)) = identify_terminal(&name) {
+            return Some(HostTerminal {
+                name: name.to_string(),
+                pid: process.pid().as_u32(),
+                terminal_type,
+            });
         }
+        current = process.parent();
     }
+    None
+}
 
-    paths
+/// Identify a terminal application by process name. Names are matched by prefix, as
+/// helper processes (e.g. "Code Helper") and truncated names (Linux keeps 15
+/// characters, e.g. "gnome-terminal-") host the shell rather than the main app.
+fn identify_terminal(process_name: &str) -> Option<(&'static str, Option<TerminalType>)> {
+    let name = process_name.trim_end_matches(".exe");
+    let lower = name.to_lowercase();
+
+    let terminal = if name == "Terminal" {
+        ("Terminal", Some(TerminalType::MacosTerminal))
+    } else if lower.starts_with("iterm") {
+        ("iTerm2", Some(TerminalType::Iterm2))
+    } else if lower.starts_with("ghostty") {
+        ("Ghostty", Some(TerminalType::Ghostty))
+    } else if lower.starts_with("windowsterminal") || lower == "openconsole" {
+        ("Windows Terminal", Some(TerminalType::WindowsTerminal))
+    } else if lower.starts_with("gnome-terminal") {
+        ("GNOME Terminal", Some(TerminalType::GnomeTerminal))
+    } else if lower.starts_with("konsole") {
+        ("Konsole", Some(TerminalType::Konsole))
+    } else if lower.starts_with("alacritty") {
+        ("Alacritty", Some(TerminalType::Alacritty))
+    } else if lower == "warp" || lower == "stable" || lower.starts_with("warp-terminal") {
+        ("Warp", Some(TerminalType::Warp))
+    } else if lower.starts_with("cursor") {
+        ("Cursor", Some(TerminalType::Cursor))
+    } else if name == "Code" || name.starts_with("Code Helper") || lower == "code" {
+        ("VS Code", Some(TerminalType::Vscode))
+    } else if lower.starts_with("wezterm") {
+        ("WezTerm", Some(TerminalType::Wezterm))
+    } else if lower == "kitty" {
+        ("kitty", Some(TerminalType::Kitty))
+    } else {
+        return None;
+    };
+    Some(terminal)
 }
 
-/// Detect Claude sessions on Linux.
-#[cfg(target_os = "linux")]
-fn detect_linux_sessions() -> HashSet<String> {
-    let mut paths = HashSet::new();
+/// Get the tmux pane from a process environment (`TMUX_PANE`, plus the server socket
+/// from `TMUX`, formatted "<socket>,<server pid>,<session>").
+fn tmux_pane<S: AsRef<std::ffi::OsStr>>(environ: &[S]) -> Option<TmuxPane> {
+    Some(TmuxPane {
+        pane_id: environ_var(environ, "TMUX_PANE").filter(|pane| !pane.is_empty())?,
+        socket_path: environ_var(environ, "TMUX")
+            .and_then(|tmux| tmux.split(',').next().map(String::from))
+            .filter(|socket| !socket.is_empty()),
+        session_name: None,
+        window_index: None,
+        pane_index: None,
+    })
+}
 
-    for pid in get_claude_pids() {
-        if let Some(cwd) = get_process_cwd_linux(pid) {
-            paths.insert(cwd);
-        }
+/// Look up a variable in a process environment ("NAME=value" entries).
+fn environ_var<S: AsRef<std::ffi::OsStr>>(environ: &[S], name: &str) -> Option<String> {
+    environ.iter().find_map(|entry| {
+        let entry = entry.as_ref().to_str()?;
+        entry
+            .strip_prefix(name)
+            .and_then(|rest| rest.strip_prefix('='))
+            .map(String::from)
+    })
+}
+
+/// Fill in a pane's session name and window/pane indices by asking its tmux server.
+fn describe_tmux_pane(mut pane: TmuxPane) -> TmuxPane {
+    let mut command = Command::new("tmux");
+    if let Some(socket) = &pane.socket_path {
+        command.args(["-S", socket]);
+    }
+    let output = command
+        .args(["display-message", "-p", "-t", &pane.pane_id])
+        .arg("#{session_name}\t#{window_index}\t#{pane_index}")
+        .output();
+
+    if let Some(output) = output.ok().filter(|o| o.status.success()) {
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut fields = stdout.trim_end().split('\t');
+        pane.session_name = fields.next().map(String::from);
+        pane.window_index = fields.next().and_then(|i| i.parse().ok());
+        pane.pane_index = fields.next().and_then(|i| i.parse().ok());
     }
 
-    paths
+    pane
 }
 
-/// Get PIDs of all running "claude" processes.
-#[cfg(any(target_os = "macos", target_os = "linux"))]
-fn get_claude_pids() -> Vec<u32> {
-    // Use ps which is more reliable than pgrep across systems
-    let output = Command::new("ps")
-        .args(["-eo", "pid,comm"])
-        .output()
-        .ok();
+/// Match each process to the session it is writing.
+///
+/// A session named on the command line (`--resume <id>`) is used directly. Otherwise,
+/// processes in the same project take the most recently modified sessions written since
+/// they started, newest process first. Claude doesn't hold its transcript open between
+/// writes, so open-file inspection can't be relied on.
+fn running_sessions(processes: &[ClaudeProcess]) -> Vec<RunningSession> {
+    let mut by_project: HashMap<&str, Vec<&ClaudeProcess>> = HashMap::new();
+    for process in processes {
+        if let Some(cwd) = &process.cwd {
+            by_project.entry(cwd.as_str()).or_default().push(process);
+        }
+    }
 
-    let Some(output) = output else {
-        return Vec::new();
-    };
+    let mut running = Vec::new();
+    for (project_path, project_processes) in by_project {
+        let sessions: Vec<(String, u64)> = get_session_modified_times(project_path)
+            .into_iter()
+            .map(|(id, modified)| {
+                let secs = modified
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0);
+                (id, secs)
+            })
+            .collect();
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
+        for (process, session_id) in assign_sessions(&project_processes, &sessions) {
+            running.push(RunningSession {
+                pid: process.pid,
+                project_path: project_path.to_string(),
+                session_id,
+                cpu_percent: process.cpu_percent,
+                memory_bytes: process.memory_bytes,
+                uptime_secs: process.uptime_secs,
+                started_at: chrono::DateTime::from_timestamp(process.start_time as i64, 0)
+                    .map(|dt| dt.to_rfc3339()),
+                long_running: process.uptime_secs > LONG_RUNNING_AFTER.as_secs(),
+                tmux: process.tmux.clone(),
+                headless: is_headless_args(&process.args),
+                terminal: process.terminal.clone(),
+                launch_id: process.launch_id.clone(),
+            });
+        }
+    }
+    running.sort_by(|a, b| (&a.project_path, a.pid).cmp(&(&b.project_path, b.pid)));
+    running
+}
 
-    stdout
-        .lines()
-        .filter_map(|line| {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 2 && parts[1] == "claude" {
-                parts[0].parse::<u32>().ok()
-            } else {
-                None
-            }
-        })
-        .collect()
-}
-
-/// Get the current working directory of a process by PID on macOS.
-#[cfg(target_os = "macos")]
-fn get_process_cwd_macos(pid: u32) -> Option<String> {
-    let output = Command::new("lsof")
-        .args(["-p", &pid.to_string()])
-        .output()
-        .ok()?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-
-    for line in stdout.lines() {
-        if line.contains("cwd") {
-            // lsof output format: COMMAND PID USER FD TYPE DEVICE SIZE/OFF NODE NAME
-            // The NAME field (9th column) can contain spaces, so we can't just split_whitespace
-            // Skip the first 8 fields and join the rest
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 9 {
-                // Everything from index 8 onwards is the path (may have spaces)
-                return Some(parts[8..].join(" "));
+/// Assign sessions (ID, modified secs; most recent first) to one project's processes.
+fn assign_sessions<'a>(
+    processes: &[&'a ClaudeProcess],
+    sessions: &[(String, u64)],
+) -> Vec<(&'a ClaudeProcess, Option<String>)> {
+    let mut assigned: HashSet<String> = HashSet::new();
+    let mut result = Vec::new();
+
+    // Explicit session IDs first, so inference can't hand them to another process
+    let mut inferred: Vec<&ClaudeProcess> = Vec::new();
+    for &process in processes {
+        match session_id_from_args(&process.args) {
+            Some(session_id) => {
+                assigned.insert(session_id.clone());
+                result.push((process, Some(session_id)));
             }
+            None => inferred.push(process),
         }
     }
 
+    inferred.sort_by(|a, b| b.start_time.cmp(&a.start_time));
+    for process in inferred {
+        let session_id = sessions
+            .iter()
+            .find(|(id, modified)| *modified >= process.start_time && !assigned.contains(id))
+            .map(|(id, _)| id.clone());
+        if let Some(id) = &session_id {
+            assigned.insert(id.clone());
+        }
+        result.push((process, session_id));
+    }
+
+    result
+}
+
+/// Session ID named on a claude command line (`--resume <id>`, `-r <id>`,
+/// `--session-id <id>` or their `=` forms).
+fn session_id_from_args(args: &[String]) -> Option<String> {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let value = match arg.split_once('=') {
+            Some((flag, value)) if is_session_flag(flag) => Some(value),
+            _ if is_session_flag(arg) => iter.next().map(String::as_str),
+            _ => None,
+        };
+        // `--resume` without an ID opens a picker, so the next argument may be a flag
+        if let Some(id) = value.filter(|id| is_uuid_format(id)) {
+            return Some(id.to_string());
+        }
+    }
     None
 }
 
-/// Get the current working directory of a process by PID on Linux.
-#[cfg(target_os = "linux")]
-fn get_process_cwd_linux(pid: u32) -> Option<String> {
-    let proc_path = format!("/proc/{}/cwd", pid);
-    std::fs::read_link(&proc_path)
-        .ok()
-        .and_then(|p| p.to_str().map(|s| s.to_string()))
+/// Whether the arguments start a non-interactive run (`-p`/`--print`, or an
+/// `--output-format`, which only applies to print mode).
+fn is_headless_args(args: &[String]) -> bool {
+    args.iter().skip(1).any(|arg| {
+        let flag = arg.split_once('=').map_or(arg.as_str(), |(flag, _)| flag);
+        matches!(flag, "-p" | "--print" | "--output-format")
+    })
+}
+
+/// Whether a flag names the session to resume or use.
+fn is_session_flag(flag: &str) -> bool {
+    matches!(flag, "--resume" | "-r" | "--session-id")
+}
+
+/// Whether a process name is the Claude Code CLI ("claude", or "claude.exe" on Windows).
+fn is_claude_process_name(name: &str) -> bool {
+    name == "claude" || name.eq_ignore_ascii_case("claude.exe")
+}
+
+/// Signal to send to an agent process.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AgentSignal {
+    /// SIGINT - what Ctrl+C sends; Claude stops the current turn
+    Interrupt,
+    /// SIGTERM - ask the process to exit
+    Terminate,
+    /// SIGKILL - force the process to exit
+    Kill,
+}
+
+/// Outcome of signalling an agent process.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignalResult {
+    /// Whether the signal was delivered
+    pub delivered: bool,
+    /// Whether the process exited within the wait period
+    pub exited: bool,
+}
+
+/// How long to wait for a signalled process to exit before reporting it still running.
+const SIGNAL_EXIT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Send a signal to a running claude process and report whether it exited.
+///
+/// Refuses PIDs that aren't running claude processes, so a stale PID that the OS has
+/// reused for another program is never signalled.
+pub fn signal_agent_process(
+    monitor: &ProcessMonitor,
+    pid: u32,
+    signal: AgentSignal,
+) -> Result<SignalResult, String> {
+    let mut guard = monitor.system.lock().map_err(|e| e.to_string())?;
+    let system = guard.get_or_insert_with(System::new);
+    let pid = Pid::from_u32(pid);
+    system.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+
+    let process = system
+        .process(pid)
+        .filter(|p| is_claude_process_name(&p.name().to_string_lossy()))
+        .ok_or_else(|| format!("Process {} is not a running claude process", pid))?;
+
+    let sys_signal = match signal {
+        AgentSignal::Interrupt => Signal::Interrupt,
+        AgentSignal::Terminate => Signal::Term,
+        AgentSignal::Kill => Signal::Kill,
+    };
+    let delivered = process
+        .kill_with(sys_signal)
+        .ok_or_else(|| format!("{:?} is not supported on this platform", signal))?;
+    // An interrupt only stops the current turn, so don't wait for it to exit
+    if !delivered || signal == AgentSignal::Interrupt {
+        return Ok(SignalResult {
+            delivered,
+            exited: false,
+        });
+    }
+
+    let started = Instant::now();
+    let exited = loop {
+        system.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+        if system.process(pid).is_none() {
+            break true;
+        }
+        if started.elapsed() >= SIGNAL_EXIT_TIMEOUT {
+            break false;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    };
+
+    Ok(SignalResult { delivered, exited })
 }
 
 #[cfg(test)]
@@ -154,12 +542,129 @@ mod tests {
 
     #[test]
     fn test_get_active_sessions_returns_result() {
-        let result = get_active_sessions();
+        let result = get_active_sessions(&ProcessMonitor::new());
 
-        #[cfg(any(target_os = "macos", target_os = "linux"))]
+        #[cfg(any(target_os = "macos", target_os = "linux", target_os = "windows"))]
         assert!(result.supported);
+    }
+
+    fn process(pid: u32, start_time: u64, args: &[&str]) -> ClaudeProcess {
+        ClaudeProcess {
+            pid,
+            cwd: Some("/project".to_string()),
+            start_time,
+            args: args.iter().map(|a| a.to_string()).collect(),
+            cpu_percent: 0.0,
+            memory_bytes: 0,
+            uptime_secs: 0,
+            tmux: None,
+            terminal: None,
+            launch_id: None,
+        }
+    }
+
+    const SESSION_A: &str = "aaaaaaaa-1111-2222-3333-444444444444";
+    const SESSION_B: &str = "bbbbbbbb-1111-2222-3333-444444444444";
+
+    #[test]
+    fn test_session_id_from_args() {
+        let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            session_id_from_args(&args(&["claude", "--resume", SESSION_A])),
+            Some(SESSION_A.to_string())
+        );
+        let with_equals = format!("--session-id={}", SESSION_B);
+        assert_eq!(
+            session_id_from_args(&args(&["claude", &with_equals])),
+            Some(SESSION_B.to_string())
+        );
+        assert_eq!(
+            session_id_from_args(&args(&["claude", "-r", "--verbose"])),
+            None
+        );
+        assert_eq!(session_id_from_args(&args(&["claude"])), None);
+    }
+
+    #[test]
+    fn test_is_headless_args() {
+        let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert!(is_headless_args(&args(&["claude", "-p", "fix the build"])));
+        assert!(is_headless_args(&args(&[
+            "claude",
+            "--output-format=stream-json",
+            "--verbose"
+        ])));
+        assert!(!is_headless_args(&args(&["claude", "--resume", SESSION_A])));
+        assert!(!is_headless_args(&args(&["claude"])));
+    }
 
-        #[cfg(target_os = "windows")]
-        assert!(!result.supported);
+    #[test]
+    fn test_assign_sessions() {
+        let resumed = process(1, 100, &["claude", "--resume", SESSION_A]);
+        let fresh = process(2, 200, &["claude"]);
+        let stale = process(3, 500, &["claude"]);
+        let sessions = [(SESSION_A.to_string(), 300), (SESSION_B.to_string(), 250)];
+
+        let assigned: Vec<(u32, Option<String>)> =
+            assign_sessions(&[&resumed, &fresh, &stale], &sessions)
+                .into_iter()
+                .map(|(process, session_id)| (process.pid, session_id))
+                .collect();
+
+        assert_eq!(
+            assigned,
+            [
+                (1, Some(SESSION_A.to_string())),
+                // Started after every session was last written
+                (3, None),
+                (2, Some(SESSION_B.to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tmux_pane_from_environ() {
+        let pane = tmux_pane(&[
+            "HOME=/home/me",
+            "TMUX=/tmp/tmux-501/default,4242,0",
+            "TMUX_PANE=%3",
+        ]);
+
+        assert_eq!(
+            pane,
+            Some(TmuxPane {
+                pane_id: "%3".to_string(),
+                socket_path: Some("/tmp/tmux-501/default".to_string()),
+                session_name: None,
+                window_index: None,
+                pane_index: None,
+            })
+        );
+        assert_eq!(tmux_pane(&["HOME=/home/me", "TMUX_PANEL=%1"]), None);
+    }
+
+    #[test]
+    fn test_identify_terminal() {
+        let name = |process: &str| identify_terminal(process).map(|(name, _)| name);
+
+        assert_eq!(
+            identify_terminal("iTerm2"),
+            Some(("iTerm2", Some(TerminalType::Iterm2)))
+        );
+        assert_eq!(name("gnome-terminal-"), Some("GNOME Terminal"));
+        assert_eq!(name("Code Helper (Plugin)"), Some("VS Code"));
+        assert_eq!(name("WindowsTerminal.exe"), Some("Windows Terminal"));
+        assert_eq!(name("zsh"), None);
+        assert_eq!(name("login"), None);
+    }
+
+    #[test]
+    fn test_is_claude_process_name() {
+        assert!(is_claude_process_name("claude"));
+        assert!(is_claude_process_name("Claude.exe"));
+        assert!(!is_claude_process_name("claude-helper"));
+        assert!(!is_claude_process_name("node"));
     }
 }
@@ -358,10 +358,8 @@ fn build_snippet(text: &str, terms: &[String], context_chars: usize) -> String {
         }
     }
 
-    let pos = match earliest_pos {
-        Some(p) => p,
-        None => 0, // Fallback to start if no term found (shouldn't happen)
-    };
+    // Fallback to start if no term found (shouldn't happen)
+    let pos = earliest_pos.unwrap_or_default();
 
     // Calculate snippet bounds (ensure valid UTF-8 boundaries)
     let start = floor_char_boundary(text, pos.saturating_sub(context_chars));
@@ -9,9 +9,12 @@ use std::fs::{self, File};
 use std::io::{BufRead, BufReader};
 use std::path::Path;
 
-use crate::claude_code::{FileEdit, FileEditType};
+use crate::claude_code::{
+    apply_rename, multi_edits, renamed_edit_type, tool_use_edit_stats, tool_use_renames, EditStats,
+    FileEdit, FileEditType,
+};
 
-use super::types::{EditMetadata, SessionIndex};
+use super::types::{parse_timestamp_millis, EditMetadata, SessionIndex};
 
 /// Build a complete session index from a JSONL file.
 ///
@@ -41,6 +44,8 @@ pub fn build_session_index(session_file: &Path, project_path: &str) -> Result<Se
     let mut file_operations: HashMap<String, FileEditType> = HashMap::new();
     let mut files_with_prior_content: HashSet<String> = HashSet::new();
     let mut file_timestamps: HashMap<String, String> = HashMap::new();
+    let mut file_stats: HashMap<String, EditStats> = HashMap::new();
+    let mut renamed_from: HashMap<String, String> = HashMap::new();
 
     let mut byte_offset: u64 = 0;
 
@@ -67,6 +72,32 @@ pub fn build_session_index(session_file: &Path, project_path: &str) -> Result<Se
                 }
             }
 
+            // Track sidechain events (for branch visualization)
+            if entry.is_sidechain == Some(true) {
+                index.sidechain_lines.insert(seq);
+            }
+
+            // Track summary events (for the summaries list)
+            if entry.entry_type.as_deref() == Some("summary") {
+                index.summary_lines.push(seq);
+            }
+
+            // Track timestamps (for jumping to a point in time)
+            if let Some(millis) = entry.timestamp.as_deref().and_then(parse_timestamp_millis) {
+                index.timestamps.push((seq, millis));
+            }
+
+            // Count events by type (for session stats)
+            if let Some(ref entry_type) = entry.entry_type {
+                *index
+                    .event_type_counts
+                    .entry(entry_type.clone())
+                    .or_insert(0) += 1;
+            }
+            if entry.subtype.as_deref() == Some("compact_boundary") {
+                index.compaction_count += 1;
+            }
+
             // Check if this is a human message boundary
             if is_human_message(&entry) {
                 index.human_message_lines.push(seq);
@@ -75,21 +106,40 @@ pub fn build_session_index(session_file: &Path, project_path: &str) -> Result<Se
             // Extract file edits from assistant messages
             if entry.entry_type.as_deref() == Some("assistant") {
                 if let Some(ref message) = entry.message {
-                    if let Some(ref content) = message.content {
-                        if let Value::Array(items) = content {
-                            for item in items {
-                                process_tool_use(
-                                    item,
-                                    project_path,
-                                    seq,
-                                    byte_offset,
-                                    entry.uuid.as_deref(),
-                                    entry.parent_uuid.as_deref(),
+                    if let Some(Value::Array(items)) = &message.content {
+                        for item in items {
+                            if let Some(name) = tool_use_name(item) {
+                                *index.tool_counts.entry(name.to_string()).or_insert(0) += 1;
+                            }
+                            process_tool_use(
+                                item,
+                                project_path,
+                                seq,
+                                byte_offset,
+                                entry.uuid.as_deref(),
+                                entry.parent_uuid.as_deref(),
+                                entry.timestamp.as_deref(),
+                                &mut index,
+                                &mut file_operations,
+                                &mut files_with_prior_content,
+                                &mut file_timestamps,
+                            );
+                            if let Some((file_path, stats)) = tool_use_edit_stats(item) {
+                                file_stats
+                                    .entry(make_relative_path(file_path, project_path))
+                                    .or_default()
+                                    .add(stats);
+                            }
+                            for (source, dest) in tool_use_renames(item, project_path) {
+                                apply_rename(
+                                    &source,
+                                    &dest,
                                     entry.timestamp.as_deref(),
-                                    &mut index,
                                     &mut file_operations,
                                     &mut files_with_prior_content,
                                     &mut file_timestamps,
+                                    &mut file_stats,
+                                    &mut renamed_from,
                                 );
                             }
                         }
@@ -107,6 +157,8 @@ pub fn build_session_index(session_file: &Path, project_path: &str) -> Result<Se
         file_operations,
         files_with_prior_content,
         file_timestamps,
+        file_stats,
+        renamed_from,
     );
 
     // Sort human message lines for binary search
@@ -129,13 +181,11 @@ fn is_human_message(entry: &JsonEntry) -> bool {
 
     // Must NOT be a tool result
     if let Some(ref message) = entry.message {
-        if let Some(ref content) = message.content {
-            // Tool results have content as array with type: "tool_result"
-            if let Value::Array(items) = content {
-                for item in items {
-                    if item.get("type").and_then(|v| v.as_str()) == Some("tool_result") {
-                        return false;
-                    }
+        // Tool results have content as array with type: "tool_result"
+        if let Some(Value::Array(items)) = &message.content {
+            for item in items {
+                if item.get("type").and_then(|v| v.as_str()) == Some("tool_result") {
+                    return false;
                 }
             }
         }
@@ -155,6 +205,7 @@ fn is_human_message(entry: &JsonEntry) -> bool {
 }
 
 /// Process a potential tool_use entry for file edits.
+#[allow(clippy::too_many_arguments)]
 fn process_tool_use(
     item: &Value,
     project_path: &str,
@@ -219,6 +270,41 @@ fn process_tool_use(
                     .push(sequence);
             }
         }
+        "MultiEdit" => {
+            if let Some(file_path) = input.get("file_path").and_then(|v| v.as_str()) {
+                let rel_path = make_relative_path(file_path, project_path);
+                let edits = multi_edits(input);
+
+                let has_prior_content = edits.iter().any(|edit| {
+                    edit.get("old_string")
+                        .and_then(|v| v.as_str())
+                        .is_some_and(|s| !s.is_empty())
+                });
+                if has_prior_content {
+                    files_with_prior_content.insert(rel_path.clone());
+                }
+
+                file_operations.insert(rel_path.clone(), FileEditType::Modified);
+
+                if let Some(ts) = timestamp {
+                    file_timestamps.insert(rel_path.clone(), ts.to_string());
+                }
+
+                index.edit_metadata.insert(
+                    sequence,
+                    EditMetadata {
+                        uuid: uuid.map(String::from),
+                    },
+                );
+
+                // One line entry per sub-edit so edit indexes match get_file_diffs
+                index
+                    .file_to_edit_lines
+                    .entry(rel_path)
+                    .or_default()
+                    .extend(edits.iter().map(|_| sequence));
+            }
+        }
         "Write" => {
             if let Some(file_path) = input.get("file_path").and_then(|v| v.as_str()) {
                 let rel_path = make_relative_path(file_path, project_path);
@@ -249,6 +335,32 @@ fn process_tool_use(
                     .push(sequence);
             }
         }
+        "NotebookEdit" => {
+            if let Some(notebook_path) = input.get("notebook_path").and_then(|v| v.as_str()) {
+                let rel_path = make_relative_path(notebook_path, project_path);
+
+                // NotebookEdit only works on existing notebooks
+                files_with_prior_content.insert(rel_path.clone());
+                file_operations.insert(rel_path.clone(), FileEditType::Modified);
+
+                if let Some(ts) = timestamp {
+                    file_timestamps.insert(rel_path.clone(), ts.to_string());
+                }
+
+                index.edit_metadata.insert(
+                    sequence,
+                    EditMetadata {
+                        uuid: uuid.map(String::from),
+                    },
+                );
+
+                index
+                    .file_to_edit_lines
+                    .entry(rel_path)
+                    .or_default()
+                    .push(sequence);
+            }
+        }
         _ => {}
     }
 }
@@ -259,6 +371,8 @@ fn finalize_file_edits(
     file_operations: HashMap<String, FileEditType>,
     files_with_prior_content: HashSet<String>,
     file_timestamps: HashMap<String, String>,
+    file_stats: HashMap<String, EditStats>,
+    renamed_from: HashMap<String, String>,
 ) {
     let mut edits: Vec<FileEdit> = file_operations
         .into_iter()
@@ -268,10 +382,18 @@ fn finalize_file_edits(
                 edit_type = FileEditType::Added;
             }
             let last_edited_at = file_timestamps.get(&path).cloned();
+            let stats = file_stats.get(&path).copied().unwrap_or_default();
+            let renamed_from = renamed_from.get(&path).cloned();
             FileEdit {
+                edit_type: renamed_edit_type(edit_type, renamed_from.as_deref(), stats.edit_count),
                 path,
-                edit_type,
                 last_edited_at,
+                edited_by: None,
+                lines_added: stats.lines_added,
+                lines_removed: stats.lines_removed,
+                edit_count: stats.edit_count,
+                renamed_from,
+                is_generated: stats.generated,
             }
         })
         .collect();
@@ -284,13 +406,18 @@ fn finalize_file_edits(
 /// Convert an absolute file path to a relative path from the project root.
 fn make_relative_path(file_path: &str, project_path: &str) -> String {
     let project = project_path.trim_end_matches('/');
-    if file_path.starts_with(project) {
-        file_path[project.len()..]
-            .trim_start_matches('/')
-            .to_string()
-    } else {
-        file_path.to_string()
+    match file_path.strip_prefix(project) {
+        Some(rest) => rest.trim_start_matches('/').to_string(),
+        None => file_path.to_string(),
+    }
+}
+
+/// Get the tool name if a content item is a tool_use block.
+fn tool_use_name(item: &Value) -> Option<&str> {
+    if item.get("type").and_then(|t| t.as_str()) != Some("tool_use") {
+        return None;
     }
+    item.get("name").and_then(|n| n.as_str())
 }
 
 // === JSON Parsing Structures ===
@@ -299,6 +426,7 @@ fn make_relative_path(file_path: &str, project_path: &str) -> String {
 struct JsonEntry {
     #[serde(rename = "type")]
     entry_type: Option<String>,
+    subtype: Option<String>,
     uuid: Option<String>,
     #[serde(rename = "parentUuid")]
     parent_uuid: Option<String>,
@@ -308,6 +436,8 @@ struct JsonEntry {
     is_compact_summary: Option<bool>,
     #[serde(rename = "isMeta")]
     is_meta: Option<bool>,
+    #[serde(rename = "isSidechain")]
+    is_sidechain: Option<bool>,
     message: Option<JsonMessage>,
     timestamp: Option<String>,
 }
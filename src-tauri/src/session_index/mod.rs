@@ -37,6 +37,10 @@ mod updater;
 
 // Re-export public API
 pub use builder::build_session_index;
-pub use queries::{get_edit_context, EditContext};
-pub use types::{IndexStatus, SessionIndex};
+pub use queries::{
+    find_event_at_timestamp, get_edit_context, get_event_by_uuid, get_event_thread,
+    get_events_in_range, get_session_branches, get_session_summaries, group_edits_by_turn,
+    BranchPoint, EditContext, EditTurn, EventThread, SessionSummary, TimestampMatch,
+};
+pub use types::{IndexStatus, SessionIndex, SessionStats};
 pub use updater::{update_index_incremental, UpdateResult};
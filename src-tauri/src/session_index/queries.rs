@@ -2,13 +2,14 @@
 //!
 //! Provides efficient lookups using the pre-built index.
 
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::{BufRead, BufReader, Seek, SeekFrom};
 use std::path::Path;
 
-use crate::claude_code::{parse_session_event, SessionEvent};
+use crate::claude_code::{parse_session_event, FileDiff, SessionEvent};
 
-use super::types::SessionIndex;
+use super::types::{format_timestamp_millis, parse_timestamp_millis, SessionIndex};
 
 /// Context for a file edit - all events from the triggering user message to the edit.
 #[derive(Debug, Clone, serde::Serialize)]
@@ -22,6 +23,74 @@ pub struct EditContext {
     pub edit_line: u32,
 }
 
+/// Thread of events leading up to an arbitrary event.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventThread {
+    /// Events in order from the triggering human message to the target event
+    pub events: Vec<SessionEvent>,
+    /// Line number of the triggering human message
+    pub trigger_line: u32,
+    /// Line number of the target event
+    pub target_line: u32,
+}
+
+/// A point in the conversation where multiple children share the same parent.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchPoint {
+    /// UUID of the shared parent event
+    pub parent_uuid: String,
+    /// Line number of the shared parent (None if the parent isn't in this file)
+    pub parent_line: Option<u32>,
+    /// Branches starting at this point, ordered by first line
+    pub branches: Vec<Branch>,
+}
+
+/// A single branch leaving a branch point.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Branch {
+    /// UUID of the first event on this branch
+    pub uuid: String,
+    /// Line number of the first event on this branch
+    pub start_line: u32,
+    /// Highest line number reachable on this branch (its latest event)
+    pub end_line: u32,
+    /// Number of events on this branch, including nested branches
+    pub event_count: u32,
+    /// Whether this branch is a sidechain exploration (isSidechain: true)
+    pub is_sidechain: bool,
+}
+
+/// The event nearest to a requested point in time.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimestampMatch {
+    /// Sequence number (line index) of the nearest event
+    pub sequence: u32,
+    /// Byte offset of the nearest event
+    pub byte_offset: u64,
+    /// Timestamp of the nearest event (ISO 8601)
+    pub timestamp: String,
+}
+
+/// A generated summary with its leaf event resolved to a position in the session.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSummary {
+    /// Sequence number of the summary event
+    pub sequence: u32,
+    /// Summary text
+    pub summary: String,
+    /// UUID of the last event the summary covers
+    pub leaf_uuid: Option<String>,
+    /// Sequence number of the leaf event (None if it isn't in this session file)
+    pub leaf_sequence: Option<u32>,
+    /// Byte offset of the leaf event (None if it isn't in this session file)
+    pub leaf_byte_offset: Option<u64>,
+}
+
 /// Get the context for a file edit.
 ///
 /// Walks the parent chain backwards from the edit until it finds a human message boundary.
@@ -37,9 +106,131 @@ pub fn get_edit_context(
         .get(&edit_line)
         .ok_or_else(|| format!("No edit metadata found for line {}", edit_line))?;
 
-    // Walk backwards via parent chain to find the triggering human message
-    let mut lines_in_context: Vec<u32> = vec![edit_line];
-    let mut current_uuid = edit_meta.uuid.clone();
+    let (lines_in_context, trigger_line) =
+        walk_to_human_message(index, edit_line, edit_meta.uuid.clone());
+
+    // Load the actual events
+    let events = load_events_for_lines(index, session_file, &lines_in_context)?;
+
+    Ok(EditContext {
+        events,
+        trigger_line,
+        edit_line,
+    })
+}
+
+/// Get the thread for any event - the chain of events from the triggering human message.
+///
+/// Unlike `get_edit_context`, the target can be any line in the session, not only a file edit.
+/// The UUID of the target is read from the file since the index only maps UUIDs to lines.
+pub fn get_event_thread(
+    index: &SessionIndex,
+    session_file: &Path,
+    target_line: u32,
+) -> Result<EventThread, String> {
+    let target = load_events_for_lines(index, session_file, &[target_line])?
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("No event found at line {}", target_line))?;
+
+    let (lines_in_thread, trigger_line) = walk_to_human_message(index, target_line, target.uuid);
+
+    let events = load_events_for_lines(index, session_file, &lines_in_thread)?;
+
+    Ok(EventThread {
+        events,
+        trigger_line,
+        target_line,
+    })
+}
+
+/// A file's diffs grouped under the human prompt that triggered them.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditTurn {
+    /// Line number of the triggering human message (None if the edit couldn't be traced)
+    pub trigger_line: Option<u32>,
+    /// Preview of the human prompt
+    pub prompt: Option<String>,
+    /// Timestamp of the prompt (ISO 8601)
+    pub timestamp: Option<String>,
+    /// Diffs made in response to the prompt, in session order
+    pub diffs: Vec<FileDiff>,
+}
+
+/// Group a file's diffs by the human prompt that triggered each one.
+///
+/// `diffs` must be in the order returned by `get_file_diffs`; its main-session diffs match
+/// the index's `file_to_edit_lines` for the file. Sub-agent diffs aren't traced.
+pub fn group_edits_by_turn(
+    index: &SessionIndex,
+    session_file: &Path,
+    file_path: &str,
+    diffs: Vec<FileDiff>,
+) -> Result<Vec<EditTurn>, String> {
+    let edit_lines = index
+        .file_to_edit_lines
+        .get(file_path)
+        .map(Vec::as_slice)
+        .unwrap_or(&[]);
+
+    let mut turns: Vec<EditTurn> = Vec::new();
+    let mut main_lines = edit_lines.iter();
+    for diff in diffs {
+        let edit_line = match diff.edited_by {
+            Some(_) => None,
+            None => main_lines.next(),
+        };
+        let trigger_line = edit_line.map(|&line| {
+            let uuid = index.edit_metadata.get(&line).and_then(|m| m.uuid.clone());
+            walk_to_human_message(index, line, uuid).1
+        });
+
+        match turns.iter_mut().find(|t| t.trigger_line == trigger_line) {
+            Some(turn) => turn.diffs.push(diff),
+            None => turns.push(EditTurn {
+                trigger_line,
+                prompt: None,
+                timestamp: None,
+                diffs: vec![diff],
+            }),
+        }
+    }
+
+    // Fill in the prompts
+    let trigger_lines: Vec<u32> = turns.iter().filter_map(|t| t.trigger_line).collect();
+    let prompts: HashMap<u32, SessionEvent> =
+        load_events_for_lines(index, session_file, &trigger_lines)?
+            .into_iter()
+            .map(|event| (event.sequence, event))
+            .collect();
+    for turn in &mut turns {
+        if let Some(event) = turn.trigger_line.and_then(|line| prompts.get(&line)) {
+            turn.prompt = Some(event.preview.clone());
+            turn.timestamp = event.timestamp.clone();
+        }
+    }
+
+    Ok(turns)
+}
+
+/// Walk the parent chain backwards from a line until a human message boundary is reached.
+///
+/// Returns the visited lines in chronological order and the line of the triggering
+/// human message.
+fn walk_to_human_message(
+    index: &SessionIndex,
+    start_line: u32,
+    start_uuid: Option<String>,
+) -> (Vec<u32>, u32) {
+    let mut lines: Vec<u32> = vec![start_line];
+
+    // A human message is its own trigger
+    if index.is_human_message(start_line) {
+        return (lines, start_line);
+    }
+
+    let mut current_uuid = start_uuid;
 
     // Walk parent chain
     while let Some(ref uuid) = current_uuid {
@@ -47,10 +238,10 @@ pub fn get_edit_context(
             if let Some(parent_line) = index.line_for_uuid(parent_uuid) {
                 // Check if this parent is a human message boundary
                 if index.is_human_message(parent_line) {
-                    lines_in_context.push(parent_line);
+                    lines.push(parent_line);
                     break;
                 }
-                lines_in_context.push(parent_line);
+                lines.push(parent_line);
                 current_uuid = Some(parent_uuid.clone());
             } else {
                 break;
@@ -61,26 +252,218 @@ pub fn get_edit_context(
     }
 
     // Reverse to get chronological order
-    lines_in_context.reverse();
+    lines.reverse();
 
     // If we didn't find a human message, use the boundary finder
-    let trigger_line = if lines_in_context.len() > 1
-        && index.is_human_message(lines_in_context[0])
-    {
-        lines_in_context[0]
+    let trigger_line = if lines.len() > 1 && index.is_human_message(lines[0]) {
+        lines[0]
     } else {
-        // Fallback: find the most recent human message before the edit
-        index.find_human_boundary(edit_line).unwrap_or(0)
+        // Fallback: find the most recent human message before the start line
+        index.find_human_boundary(start_line).unwrap_or(0)
     };
 
-    // Load the actual events
-    let events = load_events_for_lines(index, session_file, &lines_in_context)?;
+    (lines, trigger_line)
+}
 
-    Ok(EditContext {
-        events,
-        trigger_line,
-        edit_line,
-    })
+/// Look up a single event by its UUID.
+///
+/// Resolves the UUID to a line via the index and loads only that event, so deep links
+/// such as `leafUuid` or `logicalParentUuid` can be followed without scanning the file.
+pub fn get_event_by_uuid(
+    index: &SessionIndex,
+    session_file: &Path,
+    uuid: &str,
+) -> Result<SessionEvent, String> {
+    let line = index
+        .line_for_uuid(uuid)
+        .ok_or_else(|| format!("No event found for UUID: {}", uuid))?;
+
+    load_events_for_lines(index, session_file, &[line])?
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("Failed to parse event at line {}", line))
+}
+
+/// Find the event whose timestamp is nearest to the given time.
+///
+/// Binary-searches the index's timestamps (events are appended in time order),
+/// then picks the closer of the two neighbours around the insertion point.
+/// Returns None if the session has no timestamped events.
+pub fn find_event_at_timestamp(
+    index: &SessionIndex,
+    timestamp: &str,
+) -> Result<Option<TimestampMatch>, String> {
+    let target = parse_timestamp_millis(timestamp)
+        .ok_or_else(|| format!("Invalid timestamp: {}", timestamp))?;
+
+    let timestamps = &index.timestamps;
+    if timestamps.is_empty() {
+        return Ok(None);
+    }
+
+    let pos = timestamps.partition_point(|&(_, millis)| millis < target);
+    let nearest = if pos == 0 {
+        timestamps[0]
+    } else if pos == timestamps.len() {
+        timestamps[pos - 1]
+    } else {
+        let before = timestamps[pos - 1];
+        let after = timestamps[pos];
+        if target - before.1 <= after.1 - target {
+            before
+        } else {
+            after
+        }
+    };
+
+    let (sequence, millis) = nearest;
+    let byte_offset = index
+        .line_offsets
+        .get(sequence as usize)
+        .map(|&(offset, _)| offset)
+        .ok_or_else(|| format!("Line {} out of range", sequence))?;
+    let timestamp = format_timestamp_millis(millis).unwrap_or_default();
+
+    Ok(Some(TimestampMatch {
+        sequence,
+        byte_offset,
+        timestamp,
+    }))
+}
+
+/// Get every summary event in a session, with leafUuid resolved to a sequence.
+///
+/// Summaries of resumed sessions may point at leaves in another session file;
+/// those are returned with no leaf position.
+pub fn get_session_summaries(
+    index: &SessionIndex,
+    session_file: &Path,
+) -> Result<Vec<SessionSummary>, String> {
+    let events = load_events_for_lines(index, session_file, &index.summary_lines)?;
+
+    let summaries = events
+        .into_iter()
+        .map(|event| {
+            let leaf_sequence = event
+                .leaf_uuid
+                .as_deref()
+                .and_then(|uuid| index.line_for_uuid(uuid));
+            let leaf_byte_offset = leaf_sequence.and_then(|line| {
+                index
+                    .line_offsets
+                    .get(line as usize)
+                    .map(|&(offset, _)| offset)
+            });
+
+            SessionSummary {
+                sequence: event.sequence,
+                summary: event.summary.unwrap_or_default(),
+                leaf_uuid: event.leaf_uuid,
+                leaf_sequence,
+                leaf_byte_offset,
+            }
+        })
+        .collect();
+
+    Ok(summaries)
+}
+
+/// Find all branch points in a session.
+///
+/// A branch point is a parent with more than one child - produced by resumed or edited
+/// turns and by sidechain explorations. Returned in file order of the parent.
+pub fn get_session_branches(index: &SessionIndex) -> Vec<BranchPoint> {
+    // Invert the parent map: parent UUID -> child lines
+    let mut children: HashMap<&str, Vec<u32>> = HashMap::new();
+    for (child, parent) in &index.parent_map {
+        if let Some(child_line) = index.line_for_uuid(child) {
+            children
+                .entry(parent.as_str())
+                .or_default()
+                .push(child_line);
+        }
+    }
+
+    // Line -> UUID for the children we need to describe
+    let mut line_to_uuid: HashMap<u32, &str> = HashMap::new();
+    for (uuid, line) in &index.uuid_to_line {
+        line_to_uuid.insert(*line, uuid.as_str());
+    }
+
+    let mut points: Vec<BranchPoint> = children
+        .iter()
+        .filter(|(_, lines)| lines.len() > 1)
+        .map(|(parent, lines)| {
+            let mut lines = lines.clone();
+            lines.sort();
+
+            let branches = lines
+                .iter()
+                .filter_map(|&start_line| {
+                    let uuid = line_to_uuid.get(&start_line)?;
+                    let (end_line, event_count) =
+                        measure_subtree(start_line, &children, &line_to_uuid);
+                    Some(Branch {
+                        uuid: uuid.to_string(),
+                        start_line,
+                        end_line,
+                        event_count,
+                        is_sidechain: index.sidechain_lines.contains(&start_line),
+                    })
+                })
+                .collect();
+
+            BranchPoint {
+                parent_uuid: parent.to_string(),
+                parent_line: index.line_for_uuid(parent),
+                branches,
+            }
+        })
+        .collect();
+
+    points.sort_by_key(|p| {
+        (
+            p.parent_line.unwrap_or(0),
+            p.branches.first().map(|b| b.start_line),
+        )
+    });
+    points
+}
+
+/// Walk the subtree rooted at a line, returning (highest line, number of events).
+fn measure_subtree(
+    root_line: u32,
+    children: &HashMap<&str, Vec<u32>>,
+    line_to_uuid: &HashMap<u32, &str>,
+) -> (u32, u32) {
+    let mut end_line = root_line;
+    let mut count = 0u32;
+    let mut stack = vec![root_line];
+
+    while let Some(line) = stack.pop() {
+        count += 1;
+        end_line = end_line.max(line);
+        if let Some(uuid) = line_to_uuid.get(&line) {
+            if let Some(child_lines) = children.get(uuid) {
+                stack.extend(child_lines.iter().copied());
+            }
+        }
+    }
+
+    (end_line, count)
+}
+
+/// Load events for a range of lines (start inclusive, end exclusive), oldest first.
+/// Used to stream newly appended events without re-reading the file tail.
+pub fn get_events_in_range(
+    index: &SessionIndex,
+    session_file: &Path,
+    start_line: u32,
+    end_line: u32,
+) -> Result<Vec<SessionEvent>, String> {
+    let end_line = end_line.min(index.total_events());
+    let lines: Vec<u32> = (start_line..end_line).collect();
+    load_events_for_lines(index, session_file, &lines)
 }
 
 /// Load SessionEvent objects for specific line numbers.
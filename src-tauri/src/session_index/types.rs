@@ -4,7 +4,7 @@
 //! which provides fast lookups into session JSONL files.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::time::SystemTime;
 
 use crate::claude_code::FileEdit;
@@ -32,6 +32,24 @@ pub struct SessionIndex {
     // === Parent Chain (for edit context) ===
     /// UUID → parentUuid (for walking the conversation chain)
     pub parent_map: HashMap<String, String>,
+    /// Sequence numbers of sidechain events (isSidechain: true)
+    pub sidechain_lines: HashSet<u32>,
+
+    // === Summaries (for skimming long sessions) ===
+    /// Sequence numbers of summary events, in file order
+    pub summary_lines: Vec<u32>,
+
+    // === Timestamps (for time scrubbing) ===
+    /// (sequence, unix millis) for each event with a parseable timestamp, in file order
+    pub timestamps: Vec<(u32, i64)>,
+
+    // === Aggregate Counts (for session stats) ===
+    /// Event type ("user", "assistant", ...) → number of events
+    pub event_type_counts: HashMap<String, u32>,
+    /// Tool name → number of tool_use calls
+    pub tool_counts: HashMap<String, u32>,
+    /// Number of compact_boundary system events
+    pub compaction_count: u32,
 
     // === Human Message Boundaries (for edit context) ===
     /// Sequence numbers of "me" messages (actual human input, not tool results)
@@ -82,6 +100,12 @@ impl SessionIndex {
             line_offsets: Vec::new(),
             uuid_to_line: HashMap::new(),
             parent_map: HashMap::new(),
+            sidechain_lines: HashSet::new(),
+            summary_lines: Vec::new(),
+            timestamps: Vec::new(),
+            event_type_counts: HashMap::new(),
+            tool_counts: HashMap::new(),
+            compaction_count: 0,
             human_message_lines: Vec::new(),
             file_edits: Vec::new(),
             file_to_edit_lines: HashMap::new(),
@@ -118,6 +142,23 @@ impl SessionIndex {
         }
     }
 
+    /// Create SessionStats for frontend (all counts are maintained during indexing).
+    pub fn to_stats(&self) -> SessionStats {
+        let first = self.timestamps.first().map(|&(_, millis)| millis);
+        let last = self.timestamps.last().map(|&(_, millis)| millis);
+
+        SessionStats {
+            total_events: self.total_events(),
+            event_type_counts: self.event_type_counts.clone(),
+            tool_counts: self.tool_counts.clone(),
+            human_turns: self.human_message_lines.len() as u32,
+            compactions: self.compaction_count,
+            first_timestamp: first.and_then(format_timestamp_millis),
+            last_timestamp: last.and_then(format_timestamp_millis),
+            duration_ms: first.zip(last).map(|(first, last)| last - first),
+        }
+    }
+
     /// Create IndexStatus for frontend.
     pub fn to_status(&self) -> IndexStatus {
         IndexStatus {
@@ -130,6 +171,41 @@ impl SessionIndex {
     }
 }
 
+/// Summary statistics for a session, returned to frontend.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionStats {
+    /// Total number of events (lines) in the session
+    pub total_events: u32,
+    /// Event type → count
+    pub event_type_counts: HashMap<String, u32>,
+    /// Tool name → number of calls
+    pub tool_counts: HashMap<String, u32>,
+    /// Number of human turns (actual user input)
+    pub human_turns: u32,
+    /// Number of context compactions
+    pub compactions: u32,
+    /// Timestamp of the first timestamped event (ISO 8601)
+    pub first_timestamp: Option<String>,
+    /// Timestamp of the last timestamped event (ISO 8601)
+    pub last_timestamp: Option<String>,
+    /// Milliseconds between first and last timestamps
+    pub duration_ms: Option<i64>,
+}
+
+/// Parse an RFC 3339 event timestamp into unix milliseconds.
+pub fn parse_timestamp_millis(timestamp: &str) -> Option<i64> {
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|dt| dt.timestamp_millis())
+}
+
+/// Format unix milliseconds as an RFC 3339 timestamp.
+pub fn format_timestamp_millis(millis: i64) -> Option<String> {
+    chrono::DateTime::from_timestamp_millis(millis)
+        .map(|dt| dt.to_rfc3339_opts(chrono::SecondsFormat::Millis, true))
+}
+
 impl IndexStatus {
     /// Create a "building" status.
     pub fn building() -> Self {
@@ -9,10 +9,13 @@ use std::fs::{self, File};
 use std::io::{BufRead, BufReader, Seek, SeekFrom};
 use std::path::Path;
 
-use crate::claude_code::FileEditType;
+use crate::claude_code::{
+    apply_rename, multi_edits, renamed_edit_type, tool_use_edit_stats, tool_use_renames, EditStats,
+    FileEditType,
+};
 
 use super::builder::build_session_index;
-use super::types::{EditMetadata, SessionIndex};
+use super::types::{parse_timestamp_millis, EditMetadata, SessionIndex};
 
 /// Result of an incremental update.
 pub enum UpdateResult {
@@ -66,6 +69,10 @@ pub fn update_index_incremental(
     let mut new_file_operations: HashMap<String, FileEditType> = HashMap::new();
     let mut new_files_with_prior_content: HashSet<String> = HashSet::new();
     let mut new_file_timestamps: HashMap<String, String> = HashMap::new();
+    let mut new_file_stats: HashMap<String, EditStats> = HashMap::new();
+    let mut new_renamed_from: HashMap<String, String> = HashMap::new();
+    // Renames of files already in the index, applied to its entries on merge
+    let mut index_renames: Vec<(String, String, Option<String>)> = Vec::new();
 
     for (rel_seq, line_result) in reader.lines().enumerate() {
         let line = match line_result {
@@ -90,6 +97,32 @@ pub fn update_index_incremental(
                 }
             }
 
+            // Track sidechain events (for branch visualization)
+            if entry.is_sidechain == Some(true) {
+                index.sidechain_lines.insert(sequence);
+            }
+
+            // Track summary events (for the summaries list)
+            if entry.entry_type.as_deref() == Some("summary") {
+                index.summary_lines.push(sequence);
+            }
+
+            // Track timestamps (for jumping to a point in time)
+            if let Some(millis) = entry.timestamp.as_deref().and_then(parse_timestamp_millis) {
+                index.timestamps.push((sequence, millis));
+            }
+
+            // Count events by type (for session stats)
+            if let Some(ref entry_type) = entry.entry_type {
+                *index
+                    .event_type_counts
+                    .entry(entry_type.clone())
+                    .or_insert(0) += 1;
+            }
+            if entry.subtype.as_deref() == Some("compact_boundary") {
+                index.compaction_count += 1;
+            }
+
             // Check if this is a human message boundary
             if is_human_message(&entry) {
                 // Insert in sorted order
@@ -102,22 +135,47 @@ pub fn update_index_incremental(
             // Extract file edits from assistant messages
             if entry.entry_type.as_deref() == Some("assistant") {
                 if let Some(ref message) = entry.message {
-                    if let Some(ref content) = message.content {
-                        if let Value::Array(items) = content {
-                            for item in items {
-                                process_tool_use_incremental(
-                                    item,
-                                    project_path,
-                                    sequence,
-                                    byte_offset,
-                                    entry.uuid.as_deref(),
-                                    entry.parent_uuid.as_deref(),
-                                    entry.timestamp.as_deref(),
-                                    index,
-                                    &mut new_file_operations,
-                                    &mut new_files_with_prior_content,
-                                    &mut new_file_timestamps,
-                                );
+                    if let Some(Value::Array(items)) = &message.content {
+                        for item in items {
+                            if let Some(name) = tool_use_name(item) {
+                                *index.tool_counts.entry(name.to_string()).or_insert(0) += 1;
+                            }
+                            process_tool_use_incremental(
+                                item,
+                                project_path,
+                                sequence,
+                                byte_offset,
+                                entry.uuid.as_deref(),
+                                entry.parent_uuid.as_deref(),
+                                entry.timestamp.as_deref(),
+                                index,
+                                &mut new_file_operations,
+                                &mut new_files_with_prior_content,
+                                &mut new_file_timestamps,
+                            );
+                            if let Some((file_path, stats)) = tool_use_edit_stats(item) {
+                                new_file_stats
+                                    .entry(make_relative_path(file_path, project_path))
+                                    .or_default()
+                                    .add(stats);
+                            }
+                            for (source, dest) in tool_use_renames(item, project_path) {
+                                let in_index = index.file_edits.iter().any(|e| e.path == source);
+                                if !in_index || new_file_operations.contains_key(&source) {
+                                    apply_rename(
+                                        &source,
+                                        &dest,
+                                        entry.timestamp.as_deref(),
+                                        &mut new_file_operations,
+                                        &mut new_files_with_prior_content,
+                                        &mut new_file_timestamps,
+                                        &mut new_file_stats,
+                                        &mut new_renamed_from,
+                                    );
+                                }
+                                if in_index {
+                                    index_renames.push((source, dest, entry.timestamp.clone()));
+                                }
                             }
                         }
                     }
@@ -134,6 +192,9 @@ pub fn update_index_incremental(
         new_file_operations,
         new_files_with_prior_content,
         new_file_timestamps,
+        new_file_stats,
+        new_renamed_from,
+        index_renames,
     );
 
     // Update file state
@@ -154,12 +215,10 @@ fn is_human_message(entry: &JsonEntry) -> bool {
     }
 
     if let Some(ref message) = entry.message {
-        if let Some(ref content) = message.content {
-            if let Value::Array(items) = content {
-                for item in items {
-                    if item.get("type").and_then(|v| v.as_str()) == Some("tool_result") {
-                        return false;
-                    }
+        if let Some(Value::Array(items)) = &message.content {
+            for item in items {
+                if item.get("type").and_then(|v| v.as_str()) == Some("tool_result") {
+                    return false;
                 }
             }
         }
@@ -177,6 +236,7 @@ fn is_human_message(entry: &JsonEntry) -> bool {
 }
 
 /// Process a tool_use for incremental updates.
+#[allow(clippy::too_many_arguments)]
 fn process_tool_use_incremental(
     item: &Value,
     project_path: &str,
@@ -235,6 +295,41 @@ fn process_tool_use_incremental(
                     .push(sequence);
             }
         }
+        "MultiEdit" => {
+            if let Some(file_path) = input.get("file_path").and_then(|v| v.as_str()) {
+                let rel_path = make_relative_path(file_path, project_path);
+                let edits = multi_edits(input);
+
+                let has_prior_content = edits.iter().any(|edit| {
+                    edit.get("old_string")
+                        .and_then(|v| v.as_str())
+                        .is_some_and(|s| !s.is_empty())
+                });
+                if has_prior_content {
+                    new_files_with_prior_content.insert(rel_path.clone());
+                }
+
+                new_file_operations.insert(rel_path.clone(), FileEditType::Modified);
+
+                if let Some(ts) = timestamp {
+                    new_file_timestamps.insert(rel_path.clone(), ts.to_string());
+                }
+
+                index.edit_metadata.insert(
+                    sequence,
+                    EditMetadata {
+                        uuid: uuid.map(String::from),
+                    },
+                );
+
+                // One line entry per sub-edit so edit indexes match get_file_diffs
+                index
+                    .file_to_edit_lines
+                    .entry(rel_path)
+                    .or_default()
+                    .extend(edits.iter().map(|_| sequence));
+            }
+        }
         "Write" => {
             if let Some(file_path) = input.get("file_path").and_then(|v| v.as_str()) {
                 let rel_path = make_relative_path(file_path, project_path);
@@ -263,6 +358,32 @@ fn process_tool_use_incremental(
                     .push(sequence);
             }
         }
+        "NotebookEdit" => {
+            if let Some(notebook_path) = input.get("notebook_path").and_then(|v| v.as_str()) {
+                let rel_path = make_relative_path(notebook_path, project_path);
+
+                // NotebookEdit only works on existing notebooks
+                new_files_with_prior_content.insert(rel_path.clone());
+                new_file_operations.insert(rel_path.clone(), FileEditType::Modified);
+
+                if let Some(ts) = timestamp {
+                    new_file_timestamps.insert(rel_path.clone(), ts.to_string());
+                }
+
+                index.edit_metadata.insert(
+                    sequence,
+                    EditMetadata {
+                        uuid: uuid.map(String::from),
+                    },
+                );
+
+                index
+                    .file_to_edit_lines
+                    .entry(rel_path)
+                    .or_default()
+                    .push(sequence);
+            }
+        }
         _ => {}
     }
 }
@@ -273,16 +394,46 @@ fn merge_new_file_edits(
     new_file_operations: HashMap<String, FileEditType>,
     new_files_with_prior_content: HashSet<String>,
     new_file_timestamps: HashMap<String, String>,
+    new_file_stats: HashMap<String, EditStats>,
+    new_renamed_from: HashMap<String, String>,
+    index_renames: Vec<(String, String, Option<String>)>,
 ) {
     use crate::claude_code::FileEdit;
 
+    // Move existing entries to their new paths first
+    for (source, dest, timestamp) in index_renames {
+        if source == dest || !index.file_edits.iter().any(|e| e.path == source) {
+            continue;
+        }
+        index.file_edits.retain(|e| e.path != dest);
+        if let Some(existing) = index.file_edits.iter_mut().find(|e| e.path == source) {
+            if existing.edit_type != FileEditType::Added {
+                let original = existing.renamed_from.take().unwrap_or(source);
+                existing.renamed_from = (original != dest).then_some(original);
+            }
+            existing.path = dest;
+            if timestamp.is_some() {
+                existing.last_edited_at = timestamp;
+            }
+        }
+    }
+
     for (path, edit_type) in new_file_operations {
+        let stats = new_file_stats.get(&path).copied().unwrap_or_default();
+
         // Find existing edit for this path
         if let Some(existing) = index.file_edits.iter_mut().find(|e| e.path == path) {
             // Update timestamp
             if let Some(ts) = new_file_timestamps.get(&path) {
                 existing.last_edited_at = Some(ts.clone());
             }
+            existing.lines_added += stats.lines_added;
+            existing.lines_removed += stats.lines_removed;
+            existing.edit_count += stats.edit_count;
+            existing.is_generated |= stats.generated;
+            if existing.renamed_from.is_none() && existing.edit_type != FileEditType::Added {
+                existing.renamed_from = new_renamed_from.get(&path).cloned();
+            }
             // If it was added before and now has prior content, it's modified
             if new_files_with_prior_content.contains(&path) {
                 existing.edit_type = FileEditType::Modified;
@@ -299,10 +450,25 @@ fn merge_new_file_edits(
                 path: path.clone(),
                 edit_type: final_type,
                 last_edited_at: new_file_timestamps.get(&path).cloned(),
+                edited_by: None,
+                lines_added: stats.lines_added,
+                lines_removed: stats.lines_removed,
+                edit_count: stats.edit_count,
+                renamed_from: new_renamed_from.get(&path).cloned(),
+                is_generated: stats.generated,
             });
         }
     }
 
+    // Moves without content edits are renames
+    for edit in &mut index.file_edits {
+        edit.edit_type = renamed_edit_type(
+            edit.edit_type.clone(),
+            edit.renamed_from.as_deref(),
+            edit.edit_count,
+        );
+    }
+
     // Re-sort file edits
     index.file_edits.sort_by(|a, b| a.path.cmp(&b.path));
 }
@@ -310,13 +476,18 @@ fn merge_new_file_edits(
 /// Convert an absolute file path to a relative path from the project root.
 fn make_relative_path(file_path: &str, project_path: &str) -> String {
     let project = project_path.trim_end_matches('/');
-    if file_path.starts_with(project) {
-        file_path[project.len()..]
-            .trim_start_matches('/')
-            .to_string()
-    } else {
-        file_path.to_string()
+    match file_path.strip_prefix(project) {
+        Some(rest) => rest.trim_start_matches('/').to_string(),
+        None => file_path.to_string(),
+    }
+}
+
+/// Get the tool name if a content item is a tool_use block.
+fn tool_use_name(item: &Value) -> Option<&str> {
+    if item.get("type").and_then(|t| t.as_str()) != Some("tool_use") {
+        return None;
     }
+    item.get("name").and_then(|n| n.as_str())
 }
 
 // === JSON Parsing Structures ===
@@ -325,6 +496,7 @@ fn make_relative_path(file_path: &str, project_path: &str) -> String {
 struct JsonEntry {
     #[serde(rename = "type")]
     entry_type: Option<String>,
+    subtype: Option<String>,
     uuid: Option<String>,
     #[serde(rename = "parentUuid")]
     parent_uuid: Option<String>,
@@ -334,6 +506,8 @@ struct JsonEntry {
     is_compact_summary: Option<bool>,
     #[serde(rename = "isMeta")]
     is_meta: Option<bool>,
+    #[serde(rename = "isSidechain")]
+    is_sidechain: Option<bool>,
     message: Option<JsonMessage>,
     timestamp: Option<String>,
 }
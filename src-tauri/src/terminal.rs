@@ -3,6 +3,8 @@
 //! This module provides cross-platform terminal launching with command execution.
 
 use serde::{Deserialize, Serialize};
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
 use std::process::Command;
 
 /// Escape a string for safe use in shell commands.
@@ -32,10 +34,175 @@ pub enum TerminalType {
     Warp,
     /// Cursor
     Cursor,
+    /// WezTerm
+    Wezterm,
+    /// kitty
+    Kitty,
+    /// Visual Studio Code (opens the project; its CLI can't run a command in the
+    /// integrated terminal)
+    Vscode,
+    /// A pane in a zellij session
+    Zellij,
+    /// PowerShell console (Windows)
+    Powershell,
+    /// Command Prompt console (Windows)
+    Cmd,
+    /// Shell in a WSL distribution (Windows; see `launch_wsl`)
+    Wsl,
+    /// User-defined command template (see `launch_custom_terminal`)
+    Custom,
+}
+
+/// Shell that runs a launched command.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shell {
+    /// sh and compatible shells
+    Posix,
+    /// Windows cmd.exe
+    Cmd,
 }
 
-/// Get available terminals for the current platform.
+impl Shell {
+    /// Shell that runs launched commands on this platform.
+    pub fn native() -> Self {
+        if cfg!(target_os = "windows") {
+            Shell::Cmd
+        } else {
+            Shell::Posix
+        }
+    }
+
+    /// Quote a value as a single argument. Plain words are left as they are.
+    pub fn quote(self, s: &str) -> String {
+        let is_plain = !s.is_empty()
+            && s.chars()
+                .all(|c| c.is_ascii_alphanumeric() || "-_./:@+".contains(c));
+        if is_plain {
+            return s.to_string();
+        }
+
+        match self {
+            Shell::Posix => shell_escape(s),
+            Shell::Cmd => format!("\"{}\"", s.replace('"', "\"\"")),
+        }
+    }
+}
+
+impl TerminalType {
+    /// Shell that runs commands launched in this terminal.
+    pub fn shell(&self) -> Shell {
+        match self {
+            TerminalType::Wsl => Shell::Posix,
+            _ => Shell::native(),
+        }
+    }
+}
+
+/// Get available terminals for the current platform. The user's default terminal
+/// comes first, so it's preselected, and a custom command template is always offered
+/// after the detected terminals.
 pub fn get_available_terminals() -> Vec<TerminalType> {
+    let mut terminals = detect_terminals();
+    if let Some(default) = detect_default_terminal() {
+        if let Some(index) = terminals.iter().position(|t| *t == default) {
+            let terminal = terminals.remove(index);
+            terminals.insert(0, terminal);
+        }
+    }
+    terminals.push(TerminalType::Custom);
+    terminals
+}
+
+/// Detect the user's default terminal from the LaunchServices handler for shell
+/// scripts.
+#[cfg(target_os = "macos")]
+fn detect_default_terminal() -> Option<TerminalType> {
+    let plist = dirs::home_dir()?
+        .join("Library/Preferences/com.apple.LaunchServices/com.apple.launchservices.secure.plist");
+    let output = Command::new("plutil")
+        .args(["-convert", "json", "-o", "-"])
+        .arg(&plist)
+        .output()
+        .ok()?;
+    let preferences: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+
+    terminal_for_bundle_id(shell_handler_bundle_id(&preferences)?)
+}
+
+/// Get the bundle ID handling `public.unix-executable` from LaunchServices preferences.
+#[cfg(target_os = "macos")]
+fn shell_handler_bundle_id(preferences: &serde_json::Value) -> Option<&str> {
+    let handler = preferences["LSHandlers"]
+        .as_array()?
+        .iter()
+        .find(|handler| {
+            handler["LSHandlerContentType"].as_str() == Some("public.unix-executable")
+        })?;
+    handler["LSHandlerRoleShell"]
+        .as_str()
+        .or_else(|| handler["LSHandlerRoleAll"].as_str())
+}
+
+/// Map an app bundle ID to a terminal.
+#[cfg(target_os = "macos")]
+fn terminal_for_bundle_id(bundle_id: &str) -> Option<TerminalType> {
+    let terminal = match bundle_id.to_lowercase().as_str() {
+        "com.apple.terminal" => TerminalType::MacosTerminal,
+        "com.mitchellh.ghostty" => TerminalType::Ghostty,
+        "com.googlecode.iterm2" => TerminalType::Iterm2,
+        "org.alacritty" | "io.alacritty" => TerminalType::Alacritty,
+        "dev.warp.warp-stable" => TerminalType::Warp,
+        "com.todesktop.230313mzl4w4u92" => TerminalType::Cursor,
+        "com.github.wez.wezterm" => TerminalType::Wezterm,
+        "net.kovidgoyal.kitty" => TerminalType::Kitty,
+        "com.microsoft.vscode" => TerminalType::Vscode,
+        _ => return None,
+    };
+    Some(terminal)
+}
+
+/// Detect the user's default terminal from `$TERMINAL`, falling back to the
+/// `x-terminal-emulator` alternative (Debian and derivatives).
+#[cfg(target_os = "linux")]
+fn detect_default_terminal() -> Option<TerminalType> {
+    if let Some(terminal) = std::env::var("TERMINAL")
+        .ok()
+        .and_then(|command| terminal_for_binary(&command))
+    {
+        return Some(terminal);
+    }
+
+    // Resolves the alternatives symlinks, e.g. to /usr/bin/gnome-terminal.wrapper
+    let target = std::fs::canonicalize("/usr/bin/x-terminal-emulator").ok()?;
+    terminal_for_binary(&target.to_string_lossy())
+}
+
+/// Map a terminal command or binary path to a terminal.
+#[cfg(target_os = "linux")]
+fn terminal_for_binary(command: &str) -> Option<TerminalType> {
+    let program = command.split_whitespace().next()?;
+    let name = std::path::Path::new(program).file_name()?.to_str()?;
+    let terminal = match name.trim_end_matches(".wrapper") {
+        "gnome-terminal" => TerminalType::GnomeTerminal,
+        "konsole" => TerminalType::Konsole,
+        "alacritty" => TerminalType::Alacritty,
+        "ghostty" => TerminalType::Ghostty,
+        "warp-terminal" => TerminalType::Warp,
+        "wezterm" | "wezterm-gui" => TerminalType::Wezterm,
+        "kitty" => TerminalType::Kitty,
+        _ => return None,
+    };
+    Some(terminal)
+}
+
+/// Default terminals aren't detected on this platform.
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn detect_default_terminal() -> Option<TerminalType> {
+    None
+}
+
+/// Detect installed terminals for the current platform.
+fn detect_terminals() -> Vec<TerminalType> {
     #[cfg(target_os = "macos")]
     {
         let mut terminals = vec![TerminalType::MacosTerminal];
@@ -65,6 +232,31 @@ pub fn get_available_terminals() -> Vec<TerminalType> {
             terminals.push(TerminalType::Cursor);
         }
 
+        // Check if WezTerm is installed
+        if std::path::Path::new(WEZTERM_MACOS_PATH).exists() {
+            terminals.push(TerminalType::Wezterm);
+        }
+
+        // Check if kitty is installed
+        if std::path::Path::new(KITTY_MACOS_DIR).exists() {
+            terminals.push(TerminalType::Kitty);
+        }
+
+        // Check if VS Code is installed
+        if std::path::Path::new("/Applications/Visual Studio Code.app").exists() {
+            terminals.push(TerminalType::Vscode);
+        }
+
+        // zellij is a CLI tool, so check PATH
+        if Command::new("which")
+            .arg("zellij")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+        {
+            terminals.push(TerminalType::Zellij);
+        }
+
         terminals
     }
 
@@ -127,12 +319,81 @@ pub fn get_available_terminals() -> Vec<TerminalType> {
             terminals.push(TerminalType::Cursor);
         }
 
+        if Command::new("which")
+            .arg("wezterm")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+        {
+            terminals.push(TerminalType::Wezterm);
+        }
+
+        if Command::new("which")
+            .arg("kitty")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+        {
+            terminals.push(TerminalType::Kitty);
+        }
+
+        if Command::new("which")
+            .arg("code")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+        {
+            terminals.push(TerminalType::Vscode);
+        }
+
+        if Command::new("which")
+            .arg("zellij")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+        {
+            terminals.push(TerminalType::Zellij);
+        }
+
         terminals
     }
 
     #[cfg(target_os = "windows")]
     {
-        vec![TerminalType::WindowsTerminal]
+        let mut terminals = vec![
+            TerminalType::WindowsTerminal,
+            TerminalType::Powershell,
+            TerminalType::Cmd,
+        ];
+
+        if Command::new("where")
+            .arg("wsl")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+        {
+            terminals.push(TerminalType::Wsl);
+        }
+
+        if Command::new("where")
+            .arg("wezterm")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+        {
+            terminals.push(TerminalType::Wezterm);
+        }
+
+        if Command::new("where")
+            .arg("code")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+        {
+            terminals.push(TerminalType::Vscode);
+        }
+
+        terminals
     }
 
     #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
@@ -141,6 +402,86 @@ pub fn get_available_terminals() -> Vec<TerminalType> {
     }
 }
 
+/// What launching a terminal would run, without running it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchPreview {
+    /// Directory the terminal starts in
+    pub cwd: String,
+    /// Shell command the terminal runs (None when it only opens the project, e.g. an IDE
+    /// without terminal automation on this platform)
+    pub command: Option<String>,
+    /// AppleScript passed to `osascript`, for terminals driven by AppleScript (macOS)
+    pub apple_script: Option<String>,
+}
+
+/// Preview the command (and AppleScript) `launch_terminal` would run for a terminal,
+/// with the same escaping.
+pub fn preview_launch(
+    terminal: &TerminalType,
+    cwd: &str,
+    command: &str,
+) -> Result<LaunchPreview, String> {
+    #[cfg(target_os = "macos")]
+    let (command, apple_script) = {
+        let full_command = macos_full_command(cwd, command);
+        match terminal {
+            TerminalType::MacosTerminal => {
+                let script = terminal_app_script(&full_command);
+                (Some(full_command), Some(script))
+            }
+            TerminalType::Iterm2 => {
+                let script = iterm_script(&full_command);
+                (Some(full_command), Some(script))
+            }
+            TerminalType::Warp => (Some(command.to_string()), None),
+            TerminalType::Ghostty | TerminalType::Alacritty => (Some(full_command), None),
+            TerminalType::Zellij | TerminalType::Wezterm | TerminalType::Kitty => {
+                (Some(command.to_string()), None)
+            }
+            TerminalType::Cursor | TerminalType::Vscode => (None, None),
+            _ => return Err(format!("Terminal {:?} not supported on macOS", terminal)),
+        }
+    };
+
+    #[cfg(target_os = "linux")]
+    let (command, apple_script) = match terminal {
+        TerminalType::GnomeTerminal
+        | TerminalType::Konsole
+        | TerminalType::Alacritty
+        | TerminalType::Ghostty
+        | TerminalType::Warp => (Some(linux_full_command(cwd, command)), None),
+        TerminalType::Zellij | TerminalType::Wezterm | TerminalType::Kitty => {
+            (Some(command.to_string()), None)
+        }
+        TerminalType::Cursor | TerminalType::Vscode => (None, None),
+        _ => return Err(format!("Terminal {:?} not supported on Linux", terminal)),
+    };
+
+    #[cfg(target_os = "windows")]
+    let (command, apple_script) = match terminal {
+        TerminalType::WindowsTerminal
+        | TerminalType::Wezterm
+        | TerminalType::Cmd
+        | TerminalType::Wsl => (Some(command.to_string()), None),
+        TerminalType::Powershell => (Some(powershell_script(command)), None),
+        TerminalType::Vscode => (None, None),
+        _ => return Err(format!("Terminal {:?} not supported on Windows", terminal)),
+    };
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+    let (command, apple_script): (Option<String>, Option<String>) = {
+        let _ = (terminal, command);
+        Err("Terminal launching not supported on this platform".to_string())?
+    };
+
+    Ok(LaunchPreview {
+        cwd: cwd.to_string(),
+        command,
+        apple_script,
+    })
+}
+
 /// Launch a terminal with a command in a specific directory.
 pub fn launch_terminal(
     terminal: &TerminalType,
@@ -168,27 +509,176 @@ pub fn launch_terminal(
     }
 }
 
+/// WezTerm's CLI inside the macOS app bundle.
+#[cfg(target_os = "macos")]
+const WEZTERM_MACOS_PATH: &str = "/Applications/WezTerm.app/Contents/MacOS/wezterm";
+
+/// Launch a program in WezTerm. `wezterm cli spawn` opens a window in the running
+/// instance; if WezTerm isn't running, `wezterm start` launches a new one.
+#[cfg(any(target_os = "macos", target_os = "linux", target_os = "windows"))]
+fn launch_wezterm(wezterm: &str, cwd: &str, program: &[&str]) -> Result<(), String> {
+    let spawned = Command::new(wezterm)
+        .args(["cli", "spawn", "--new-window", "--cwd", cwd, "--"])
+        .args(program)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false);
+
+    if !spawned {
+        Command::new(wezterm)
+            .args(["start", "--cwd", cwd, "--"])
+            .args(program)
+            .spawn()
+            .map_err(|e| format!("Failed to launch WezTerm: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Directory holding kitty's binaries inside the macOS app bundle.
+#[cfg(target_os = "macos")]
+const KITTY_MACOS_DIR: &str = "/Applications/kitty.app/Contents/MacOS";
+
+/// Launch a command in kitty. `kitten @ launch` opens a window in the running
+/// instance when remote control is enabled; otherwise a new kitty is started.
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn launch_kitty(kitty: &str, kitten: &str, cwd: &str, command: &str) -> Result<(), String> {
+    let launched = Command::new(kitten)
+        .args(["@", "launch", "--type=os-window", "--cwd", cwd])
+        .args(["sh", "-c", command])
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false);
+
+    if !launched {
+        Command::new(kitty)
+            .args(["--directory", cwd, "sh", "-c", command])
+            .spawn()
+            .map_err(|e| format!("Failed to launch kitty: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Session created when no zellij session is running.
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+const ZELLIJ_SESSION_NAME: &str = "agent-console";
+
+/// Run a command in a new pane of the running zellij session. If none is running, a
+/// background session is created for it, ready to attach to.
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn launch_zellij(cwd: &str, command: &str) -> Result<(), String> {
+    let running = Command::new("zellij")
+        .args(["list-sessions", "--no-formatting"])
+        .output()
+        .map(|o| zellij_running_sessions(&String::from_utf8_lossy(&o.stdout)))
+        .map_err(|e| format!("Failed to list zellij sessions: {}", e))?;
+
+    let session = match running.into_iter().next() {
+        Some(session) => session,
+        None => {
+            let output = Command::new("zellij")
+                .args(["attach", "--create-background", ZELLIJ_SESSION_NAME])
+                .output()
+                .map_err(|e| format!("Failed to start zellij session: {}", e))?;
+            if !output.status.success() {
+                return Err(format!(
+                    "Failed to start zellij session: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ));
+            }
+            ZELLIJ_SESSION_NAME.to_string()
+        }
+    };
+
+    Command::new("zellij")
+        .args(["--session", &session, "run", "--name", "claude"])
+        .args(["--cwd", cwd, "--", "sh", "-c", command])
+        .spawn()
+        .map_err(|e| format!("Failed to launch zellij pane: {}", e))?;
+
+    Ok(())
+}
+
+/// Names of live sessions in `zellij list-sessions --no-formatting` output, skipping
+/// exited ones (listed as "name [Created ...] (EXITED - attach to resurrect)").
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn zellij_running_sessions(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter(|line| !line.contains("(EXITED"))
+        .filter_map(|line| line.split_whitespace().next())
+        .map(String::from)
+        .collect()
+}
+
+/// Launch a terminal using a user-defined command template, e.g.
+/// `foot -D {cwd} sh -c {cmd}`. `{cwd}` and `{cmd}` are replaced with the quoted
+/// directory and command, and the result is run through the system shell from `cwd`.
+pub fn launch_custom_terminal(template: &str, cwd: &str, command: &str) -> Result<(), String> {
+    let expanded = expand_terminal_template(template, cwd, command)?;
+
+    #[cfg(target_os = "windows")]
+    let mut shell = {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C").arg(&expanded);
+        shell
+    };
+
+    #[cfg(not(target_os = "windows"))]
+    let mut shell = {
+        let mut shell = Command::new("sh");
+        shell.arg("-c").arg(&expanded);
+        shell
+    };
+
+    shell
+        .current_dir(cwd)
+        .spawn()
+        .map_err(|e| format!("Failed to launch custom terminal: {}", e))?;
+
+    Ok(())
+}
+
+/// Preview the command `launch_custom_terminal` would run.
+pub fn preview_custom_terminal(
+    template: &str,
+    cwd: &str,
+    command: &str,
+) -> Result<LaunchPreview, String> {
+    Ok(LaunchPreview {
+        cwd: cwd.to_string(),
+        command: Some(expand_terminal_template(template, cwd, command)?),
+        apple_script: None,
+    })
+}
+
+/// Fill in a custom terminal template's `{cwd}` and `{cmd}` placeholders.
+fn expand_terminal_template(template: &str, cwd: &str, command: &str) -> Result<String, String> {
+    if template.trim().is_empty() {
+        return Err("No custom terminal command is configured".to_string());
+    }
+    if !template.contains("{cmd}") {
+        return Err("Custom terminal command must contain {cmd}".to_string());
+    }
+
+    let shell = Shell::native();
+    Ok(template
+        .replace("{cwd}", &shell.quote(cwd))
+        .replace("{cmd}", &shell.quote(command)))
+}
+
 #[cfg(target_os = "macos")]
 fn launch_terminal_macos(
     terminal: &TerminalType,
     cwd: &str,
     command: &str,
 ) -> Result<(), String> {
-    // Escape single quotes in paths and commands for AppleScript
-    let escaped_cwd = cwd.replace('\\', "\\\\").replace('"', "\\\"");
-    let escaped_cmd = command.replace('\\', "\\\\").replace('"', "\\\"");
-
-    let full_command = format!("cd \"{}\" && {}", escaped_cwd, escaped_cmd);
+    let full_command = macos_full_command(cwd, command);
 
     match terminal {
         TerminalType::MacosTerminal => {
-            let script = format!(
-                r#"tell application "Terminal"
-                    activate
-                    do script "{}"
-                end tell"#,
-                full_command.replace('"', "\\\"")
-            );
+            let script = terminal_app_script(&full_command);
 
             Command::new("osascript")
                 .arg("-e")
@@ -210,26 +700,9 @@ fn launch_terminal_macos(
                 .map_err(|e| format!("Failed to launch Ghostty: {}", e))?;
         }
         TerminalType::Iterm2 => {
-            // Copy command to clipboard first for reliable execution
-            Command::new("sh")
-                .arg("-c")
-                .arg(format!("printf '%s' {} | pbcopy", shell_escape(&full_command)))
-                .output()
-                .map_err(|e| format!("Failed to copy to clipboard: {}", e))?;
-
-            // Create window and paste command
-            let script = r#"tell application "iTerm"
-                activate
-                create window with default profile
-                tell current session of current window
-                    delay 0.2
-                    write text (the clipboard)
-                end tell
-            end tell"#;
-
             Command::new("osascript")
                 .arg("-e")
-                .arg(script)
+                .arg(iterm_script(&full_command))
                 .spawn()
                 .map_err(|e| format!("Failed to launch iTerm2: {}", e))?;
         }
@@ -246,80 +719,34 @@ fn launch_terminal_macos(
                 .map_err(|e| format!("Failed to launch Alacritty: {}", e))?;
         }
         TerminalType::Warp => {
-            // Use Warp's URL scheme to open at the correct directory,
-            // then paste the command via AppleScript
-
-            // Copy command to clipboard first
-            Command::new("sh")
-                .arg("-c")
-                .arg(format!("printf '%s' {} | pbcopy", shell_escape(command)))
-                .output()
-                .map_err(|e| format!("Failed to copy to clipboard: {}", e))?;
-
-            // URL-encode the path for the warp:// scheme
-            let encoded_path = urlencoding::encode(cwd);
-            let warp_url = format!("warp://action/new_window?path={}", encoded_path);
-
-            // Open Warp at the correct directory using URL scheme
-            Command::new("open")
-                .arg(&warp_url)
-                .spawn()
-                .map_err(|e| format!("Failed to launch Warp: {}", e))?;
-
-            // Give Warp time to open and focus the new window
-            std::thread::sleep(std::time::Duration::from_millis(800));
-
-            // Paste command and execute using AppleScript
-            let script = r#"tell application "System Events"
-                tell process "Warp"
-                    keystroke "v" using command down
-                    delay 0.1
-                    keystroke return
-                end tell
-            end tell"#;
-
-            Command::new("osascript")
-                .arg("-e")
-                .arg(script)
-                .spawn()
-                .map_err(|e| format!("Failed to paste command in Warp: {}", e))?;
+            launch_warp_macos(cwd, command)?;
+        }
+        TerminalType::Zellij => {
+            launch_zellij(cwd, command)?;
+        }
+        TerminalType::Wezterm => {
+            // The app bundle's CLI isn't on PATH unless the user linked it
+            launch_wezterm(WEZTERM_MACOS_PATH, cwd, &["sh", "-c", command])?;
+        }
+        TerminalType::Kitty => {
+            launch_kitty(
+                &format!("{}/kitty", KITTY_MACOS_DIR),
+                &format!("{}/kitten", KITTY_MACOS_DIR),
+                cwd,
+                command,
+            )?;
         }
         TerminalType::Cursor => {
-            // Cursor is an IDE with an integrated terminal
-            // Open project in Cursor and use the terminal
-            Command::new("open")
-                .arg("-a")
-                .arg("Cursor")
-                .arg(cwd)
-                .spawn()
-                .map_err(|e| format!("Failed to launch Cursor: {}", e))?;
-
-            // Copy command to clipboard first
-            Command::new("sh")
-                .arg("-c")
-                .arg(format!("printf '%s' {} | pbcopy", shell_escape(&full_command)))
-                .output()
-                .map_err(|e| format!("Failed to copy to clipboard: {}", e))?;
-
-            // Give Cursor time to open
-            std::thread::sleep(std::time::Duration::from_millis(1000));
-
-            // Open integrated terminal and paste command
-            let script = r#"tell application "System Events"
-                tell process "Cursor"
-                    keystroke "`" using control down
-                    delay 0.5
-                    keystroke "v" using command down
-                    delay 0.1
-                    keystroke return
-                end tell
-            end tell"#;
-
-            Command::new("osascript")
-                .arg("-e")
-                .arg(script)
-                .spawn()
-                .map_err(|e| format!("Failed to open Cursor terminal: {}", e))?;
+            // Open Cursor at the project directory (its CLI can't run the command, and
+            // driving its terminal would mean typing into whichever app is frontmost)
+            open_ide_macos(macos_ide_commands("cursor", "Cursor", cwd), "Cursor")?;
+        }
+        TerminalType::Vscode => {
+            // Open VS Code at the project directory, as for Cursor
+            open_ide_macos(
+                macos_ide_commands("code", "Visual Studio Code", cwd),
+                "VS Code",
+            )?;
         }
         _ => {
             return Err(format!("Terminal {:?} not supported on macOS", terminal));
@@ -329,13 +756,139 @@ fn launch_terminal_macos(
     Ok(())
 }
 
+/// Commands opening a project in an IDE on macOS: its CLI, then `open -a` for when the
+/// CLI isn't on PATH (it usually isn't for apps started from Finder).
+#[cfg(any(target_os = "macos", test))]
+fn macos_ide_commands(cli: &str, app: &str, cwd: &str) -> [Command; 2] {
+    let mut via_cli = Command::new(cli);
+    via_cli.arg(cwd);
+    let mut via_open = Command::new("open");
+    via_open.args(["-a", app, cwd]);
+    [via_cli, via_open]
+}
+
+/// Spawn the first of `commands` that starts.
+#[cfg(target_os = "macos")]
+fn open_ide_macos(commands: [Command; 2], app: &str) -> Result<(), String> {
+    let mut last_error = None;
+    for mut command in commands {
+        match command.spawn() {
+            Ok(_) => return Ok(()),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(format!(
+        "Failed to launch {}: {}",
+        app,
+        last_error.map(|e| e.to_string()).unwrap_or_default()
+    ))
+}
+
+/// Open a Warp window running a command, through a launch configuration, which Warp
+/// runs itself once the window's shell is ready.
+///
+/// Each launch writes its own configuration, so quick launches don't overwrite each
+/// other's before Warp reads them, and removes it once Warp has had time to.
+#[cfg(target_os = "macos")]
+fn launch_warp_macos(cwd: &str, command: &str) -> Result<(), String> {
+    let dir = dirs::home_dir()
+        .ok_or("Could not find home directory")?
+        .join(".warp")
+        .join("launch_configurations");
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create Warp launch configuration: {}", e))?;
+    let path = dir.join(format!(
+        "{}{}.yaml",
+        WARP_LAUNCH_CONFIG_PREFIX,
+        uuid::Uuid::new_v4()
+    ));
+    std::fs::write(&path, warp_launch_config(cwd, command))
+        .map_err(|e| format!("Failed to write Warp launch configuration: {}", e))?;
+
+    let warp_url = format!(
+        "warp://launch/{}",
+        urlencoding::encode(&path.to_string_lossy())
+    );
+    if let Err(e) = Command::new("open").arg(&warp_url).spawn() {
+        let _ = std::fs::remove_file(&path);
+        return Err(format!("Failed to launch Warp: {}", e));
+    }
+
+    std::thread::spawn(move || {
+        std::thread::sleep(WARP_LAUNCH_CONFIG_TTL);
+        let _ = std::fs::remove_file(&path);
+    });
+
+    Ok(())
+}
+
+/// File name prefix of the Warp launch configurations written for launches.
+#[cfg(target_os = "macos")]
+const WARP_LAUNCH_CONFIG_PREFIX: &str = "agent-console-";
+
+/// How long a Warp launch configuration is kept for Warp to read it.
+#[cfg(target_os = "macos")]
+const WARP_LAUNCH_CONFIG_TTL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Warp launch configuration opening one window in `cwd` that runs `command`.
+#[cfg(any(target_os = "macos", test))]
+fn warp_launch_config(cwd: &str, command: &str) -> String {
+    // JSON strings are valid double-quoted YAML scalars
+    let quote = |s: &str| serde_json::Value::String(s.to_string()).to_string();
+    format!(
+        "---\nname: Agent Console\nwindows:\n  - tabs:\n      - layout:\n          \
+         cwd: {}\n          commands:\n            - exec: {}\n",
+        quote(cwd),
+        quote(command)
+    )
+}
+
+/// `cd` into `cwd` and run `command`.
+#[cfg(target_os = "macos")]
+fn macos_full_command(cwd: &str, command: &str) -> String {
+    format!("cd {} && {}", shell_escape(cwd), command)
+}
+
+/// Quote a string as an AppleScript string literal.
+#[cfg(any(target_os = "macos", test))]
+fn applescript_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// AppleScript running a command in a new Terminal.app window.
+#[cfg(target_os = "macos")]
+fn terminal_app_script(full_command: &str) -> String {
+    format!(
+        r#"tell application "Terminal"
+                    activate
+                    do script {}
+                end tell"#,
+        applescript_string(full_command)
+    )
+}
+
+/// AppleScript opening an iTerm2 window and running a command in it.
+#[cfg(target_os = "macos")]
+fn iterm_script(full_command: &str) -> String {
+    format!(
+        r#"tell application "iTerm"
+                activate
+                set newWindow to (create window with default profile)
+                tell current session of newWindow
+                    write text {}
+                end tell
+            end tell"#,
+        applescript_string(full_command)
+    )
+}
+
 #[cfg(target_os = "linux")]
 fn launch_terminal_linux(
     terminal: &TerminalType,
     cwd: &str,
     command: &str,
 ) -> Result<(), String> {
-    let full_command = format!("cd '{}' && {}", cwd, command);
+    let full_command = linux_full_command(cwd, command);
 
     match terminal {
         TerminalType::GnomeTerminal => {
@@ -390,6 +943,22 @@ fn launch_terminal_linux(
                 .spawn()
                 .map_err(|e| format!("Failed to launch cursor: {}", e))?;
         }
+        TerminalType::Zellij => {
+            launch_zellij(cwd, command)?;
+        }
+        TerminalType::Vscode => {
+            // Open VS Code at the project directory
+            Command::new("code")
+                .arg(cwd)
+                .spawn()
+                .map_err(|e| format!("Failed to launch code: {}", e))?;
+        }
+        TerminalType::Wezterm => {
+            launch_wezterm("wezterm", cwd, &["sh", "-c", command])?;
+        }
+        TerminalType::Kitty => {
+            launch_kitty("kitty", "kitten", cwd, command)?;
+        }
         _ => {
             return Err(format!("Terminal {:?} not supported on Linux", terminal));
         }
@@ -398,6 +967,90 @@ fn launch_terminal_linux(
     Ok(())
 }
 
+/// `cd` into `cwd` and run `command`, for terminals started with `sh -c`.
+#[cfg(target_os = "linux")]
+fn linux_full_command(cwd: &str, command: &str) -> String {
+    format!("cd {} && {}", shell_escape(cwd), command)
+}
+
+/// Process creation flag giving a console program its own window.
+#[cfg(target_os = "windows")]
+const CREATE_NEW_CONSOLE: u32 = 0x0000_0010;
+
+/// Encode a script for `powershell -EncodedCommand` (base64 of UTF-16LE), which
+/// avoids quoting it on the command line.
+#[cfg(target_os = "windows")]
+fn encode_powershell(script: &str) -> String {
+    use base64::Engine;
+
+    let bytes: Vec<u8> = script.encode_utf16().flat_map(u16::to_le_bytes).collect();
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+/// PowerShell script running a cmd-syntax command. `--%` passes the rest of the line
+/// to cmd as-is.
+#[cfg(target_os = "windows")]
+fn powershell_script(command: &str) -> String {
+    format!("cmd /c --% {}", command)
+}
+
+/// Launch a command in a WSL distribution (the default one if `distro` is None),
+/// starting in `cwd`, which WSL translates from a Windows path. `command` must be
+/// quoted for a POSIX shell (see `TerminalType::shell`).
+pub fn launch_wsl(distro: Option<&str>, cwd: &str, command: &str) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        let mut wsl = Command::new("wsl");
+        if let Some(distro) = distro.filter(|d| !d.is_empty()) {
+            wsl.args(["-d", distro]);
+        }
+        // A login shell, so PATH includes where claude is installed (e.g. via nvm)
+        wsl.args(["--cd", cwd, "--", "bash", "-lic", command])
+            .creation_flags(CREATE_NEW_CONSOLE)
+            .spawn()
+            .map_err(|e| format!("Failed to launch WSL: {}", e))?;
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (distro, cwd, command);
+        Err("WSL is only available on Windows".to_string())
+    }
+}
+
+/// List installed WSL distributions.
+pub fn get_wsl_distros() -> Vec<String> {
+    #[cfg(target_os = "windows")]
+    {
+        Command::new("wsl")
+            .args(["--list", "--quiet"])
+            .output()
+            .map(|o| parse_wsl_distros(&o.stdout))
+            .unwrap_or_default()
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        Vec::new()
+    }
+}
+
+/// Parse `wsl --list --quiet` output, which is UTF-16LE.
+#[cfg(any(target_os = "windows", test))]
+fn parse_wsl_distros(output: &[u8]) -> Vec<String> {
+    let units: Vec<u16> = output
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect();
+    String::from_utf16_lossy(&units)
+        .lines()
+        .map(|line| line.trim_matches(|c: char| c.is_whitespace() || c == '\0'))
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect()
+}
+
 #[cfg(target_os = "windows")]
 fn launch_terminal_windows(
     terminal: &TerminalType,
@@ -415,6 +1068,35 @@ fn launch_terminal_windows(
                 .spawn()
                 .map_err(|e| format!("Failed to launch Windows Terminal: {}", e))?;
         }
+        TerminalType::Wezterm => {
+            launch_wezterm("wezterm", cwd, &["cmd", "/c", command])?;
+        }
+        TerminalType::Vscode => {
+            // `code` is a batch script, so it's run through cmd
+            Command::new("cmd")
+                .args(["/c", "code", cwd])
+                .spawn()
+                .map_err(|e| format!("Failed to launch VS Code: {}", e))?;
+        }
+        TerminalType::Powershell => {
+            let script = powershell_script(command);
+            Command::new("powershell")
+                .args(["-NoExit", "-EncodedCommand", &encode_powershell(&script)])
+                .current_dir(cwd)
+                .creation_flags(CREATE_NEW_CONSOLE)
+                .spawn()
+                .map_err(|e| format!("Failed to launch PowerShell: {}", e))?;
+        }
+        TerminalType::Cmd => {
+            // Passed raw, as the command is already quoted for cmd
+            Command::new("cmd")
+                .arg("/k")
+                .raw_arg(command)
+                .current_dir(cwd)
+                .creation_flags(CREATE_NEW_CONSOLE)
+                .spawn()
+                .map_err(|e| format!("Failed to launch Command Prompt: {}", e))?;
+        }
         _ => {
             return Err(format!("Terminal {:?} not supported on Windows", terminal));
         }
@@ -433,5 +1115,156 @@ mod tests {
         // Should return at least one terminal on supported platforms
         #[cfg(any(target_os = "macos", target_os = "windows"))]
         assert!(!terminals.is_empty());
+        assert_eq!(terminals.last(), Some(&TerminalType::Custom));
+    }
+
+    #[test]
+    #[cfg(target_os = "macos")]
+    fn test_shell_handler_bundle_id() {
+        let preferences = serde_json::json!({
+            "LSHandlers": [
+                { "LSHandlerURLScheme": "https", "LSHandlerRoleAll": "com.apple.safari" },
+                {
+                    "LSHandlerContentType": "public.unix-executable",
+                    "LSHandlerRoleShell": "com.googlecode.iterm2"
+                }
+            ]
+        });
+
+        let bundle_id = shell_handler_bundle_id(&preferences);
+        assert_eq!(bundle_id, Some("com.googlecode.iterm2"));
+        assert_eq!(
+            bundle_id.and_then(terminal_for_bundle_id),
+            Some(TerminalType::Iterm2)
+        );
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_terminal_for_binary() {
+        assert_eq!(
+            terminal_for_binary("/usr/bin/gnome-terminal.wrapper"),
+            Some(TerminalType::GnomeTerminal)
+        );
+        assert_eq!(
+            terminal_for_binary("kitty --single-instance"),
+            Some(TerminalType::Kitty)
+        );
+        assert_eq!(terminal_for_binary("/usr/bin/xterm"), None);
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(Shell::Posix.quote("--verbose"), "--verbose");
+        assert_eq!(Shell::Posix.quote("it's here"), "'it'\\''s here'");
+        assert_eq!(Shell::Cmd.quote("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(Shell::Posix.quote(""), "''");
+    }
+
+    #[test]
+    fn test_parse_wsl_distros() {
+        let output: Vec<u8> = "Ubuntu\r\ndocker-desktop\r\n\r\n"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+
+        assert_eq!(parse_wsl_distros(&output), vec!["Ubuntu", "docker-desktop"]);
+    }
+
+    #[test]
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    fn test_zellij_running_sessions() {
+        let output = "work [Created 2h ago] (current)\n\
+                      old [Created 3days ago] (EXITED - attach to resurrect)\n\
+                      scratch [Created 10m ago]\n";
+
+        assert_eq!(zellij_running_sessions(output), vec!["work", "scratch"]);
+        assert!(zellij_running_sessions("").is_empty());
+    }
+
+    #[test]
+    fn test_applescript_string() {
+        assert_eq!(applescript_string("claude"), "\"claude\"");
+        assert_eq!(
+            applescript_string(r#"cd '/tmp/a "b"\c' && claude"#),
+            r#""cd '/tmp/a \"b\"\\c' && claude""#
+        );
+    }
+
+    #[test]
+    fn test_macos_ide_commands() {
+        let args = |command: &Command| -> Vec<String> {
+            command
+                .get_args()
+                .map(|arg| arg.to_string_lossy().to_string())
+                .collect()
+        };
+        let [via_cli, via_open] = macos_ide_commands("cursor", "Cursor", "/tmp/my proj");
+
+        assert_eq!(via_cli.get_program(), "cursor");
+        assert_eq!(args(&via_cli), vec!["/tmp/my proj"]);
+        assert_eq!(via_open.get_program(), "open");
+        assert_eq!(args(&via_open), vec!["-a", "Cursor", "/tmp/my proj"]);
+
+        let [via_cli, via_open] = macos_ide_commands("code", "Visual Studio Code", "/tmp/p");
+        assert_eq!(via_cli.get_program(), "code");
+        assert_eq!(args(&via_cli), vec!["/tmp/p"]);
+        assert_eq!(args(&via_open), vec!["-a", "Visual Studio Code", "/tmp/p"]);
+    }
+
+    #[test]
+    fn test_warp_launch_config() {
+        assert_eq!(
+            warp_launch_config("/tmp/my \"app\"", "claude --model 'opus'"),
+            "---\nname: Agent Console\nwindows:\n  - tabs:\n      - layout:\n          \
+             cwd: \"/tmp/my \\\"app\\\"\"\n          commands:\n            \
+             - exec: \"claude --model 'opus'\"\n"
+        );
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_preview_launch() {
+        let preview = preview_launch(&TerminalType::GnomeTerminal, "/tmp/proj", "claude -c");
+        assert_eq!(
+            preview,
+            Ok(LaunchPreview {
+                cwd: "/tmp/proj".to_string(),
+                command: Some("cd '/tmp/proj' && claude -c".to_string()),
+                apple_script: None,
+            })
+        );
+        assert_eq!(
+            preview_launch(&TerminalType::Cursor, "/tmp/proj", "claude").map(|p| p.command),
+            Ok(None)
+        );
+        assert_eq!(
+            preview_launch(&TerminalType::Vscode, "/tmp/proj", "claude").map(|p| p.command),
+            Ok(None)
+        );
+        assert!(preview_launch(&TerminalType::Iterm2, "/tmp/proj", "claude").is_err());
+
+        // A quote in the path can't end the quoting and inject a command
+        assert_eq!(
+            linux_full_command("/tmp/it's'; touch pwned; '", "claude"),
+            r#"cd '/tmp/it'\''s'\''; touch pwned; '\''' && claude"#
+        );
+
+        let custom = preview_custom_terminal("foot sh -c {cmd}", "/tmp/proj", "claude");
+        assert_eq!(
+            custom.map(|p| p.command),
+            Ok(Some("foot sh -c claude".to_string()))
+        );
+    }
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn test_expand_terminal_template() {
+        assert_eq!(
+            expand_terminal_template("foot -D {cwd} sh -c {cmd}", "/tmp/it's", "claude -c"),
+            Ok("foot -D '/tmp/it'\\''s' sh -c 'claude -c'".to_string())
+        );
+        assert!(expand_terminal_template("st -e sh", "/tmp", "claude").is_err());
+        assert!(expand_terminal_template("  ", "/tmp", "claude").is_err());
     }
 }
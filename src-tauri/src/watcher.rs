@@ -1,17 +1,28 @@
-//! Session file watcher for real-time edit updates.
+//! File watchers for real-time updates.
 //!
-//! Watches Claude Code session JSONL files and emits Tauri events when changes occur.
-//! Also manages session indices for fast lookups.
+//! Watches session transcripts, project directories and related files, emitting Tauri
+//! events when they change, and maintains session indices for fast lookups.
 
 use notify_debouncer_mini::{new_debouncer, notify::RecursiveMode, DebouncedEventKind};
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
-use tauri::{AppHandle, Emitter};
+use std::time::{Duration, Instant, SystemTime};
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Emitter, Manager};
 
+use crate::activity::{get_claude_todos_dir, read_todo_file, todo_file_owner, TodoItem};
+use crate::claude_code::{
+    find_task_launch, get_claude_projects_dir, get_sessions_for_project, project_from_dir,
+    read_subagent_origin, Project, Session, SessionEvent,
+};
+use crate::git::{get_current_branch, get_git_head_path};
+use crate::process::{get_active_sessions, ProcessMonitor, RunningSession};
 use crate::session_index::{
-    build_session_index, update_index_incremental, IndexStatus, SessionIndex, UpdateResult,
+    build_session_index, get_events_in_range, update_index_incremental, IndexStatus, SessionIndex,
+    UpdateResult,
 };
 
 /// Event payload sent to the frontend when a session file changes.
@@ -20,8 +31,20 @@ use crate::session_index::{
 pub struct SessionChangedPayload {
     pub project_path: String,
     pub session_id: String,
+    /// First sequence appended by this change (None if the index wasn't updated)
+    pub appended_from: Option<u32>,
+    /// Total number of events after the change (None if the index wasn't updated)
+    pub total_count: Option<u32>,
+    /// True if the index was rebuilt (file shrank) - the frontend should re-fetch
+    pub reset: bool,
+    /// Appended events, oldest first, included when there are few enough to inline
+    pub events: Option<Vec<SessionEvent>>,
 }
 
+/// Most appended events inlined in a "session-changed" payload; larger appends only
+/// report their sequence range.
+const MAX_INLINE_CHANGED_EVENTS: u32 = 50;
+
 /// Event payload sent to the frontend when a sub-agent file changes.
 #[derive(Clone, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -30,6 +53,42 @@ pub struct SubagentChangedPayload {
     pub agent_id: String,
 }
 
+/// Event payload sent to the frontend when a new project appears under `~/.claude/projects`.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectAddedPayload {
+    pub project: Project,
+}
+
+/// Event payload sent to the frontend when a new session appears in a watched project.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionAddedPayload {
+    pub project_path: String,
+    pub session: Session,
+}
+
+/// Event payload sent to the frontend when a session disappears from a watched project.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionRemovedPayload {
+    pub project_path: String,
+    pub session_id: String,
+}
+
+/// Event payload sent to the frontend when a watched session launches a sub-agent.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubagentSpawnedPayload {
+    pub project_path: String,
+    pub session_id: String,
+    pub agent_id: String,
+    /// ID of the Task tool_use that launched the agent (None if it couldn't be matched)
+    pub tool_use_id: Option<String>,
+    /// Description given to the Task tool
+    pub description: Option<String>,
+}
+
 /// Event payload sent to the frontend when the session index is ready.
 #[derive(Clone, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -39,25 +98,191 @@ pub struct IndexReadyPayload {
     pub status: IndexStatus,
 }
 
+/// Payload streamed over a session's event channel when new lines are appended.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionEventsAppendedPayload {
+    /// Newly appended events, oldest first
+    pub events: Vec<SessionEvent>,
+    /// Total number of events after the append
+    pub total_count: u32,
+    /// True if the index was rebuilt (file shrank) - the frontend should re-fetch
+    pub reset: bool,
+}
+
+/// Handler a shared directory watcher dispatches each debounced batch to.
+type BatchHandler = Box<dyn FnMut(&[notify_debouncer_mini::DebouncedEvent]) + Send>;
+
+/// Subscriptions of a shared directory watcher, keyed like `WatcherState::watchers`.
+type Subscriptions = Arc<Mutex<HashMap<String, BatchHandler>>>;
+
+/// A single watcher on a project directory, shared by every subscription in it.
+struct DirWatcher {
+    // The debouncer is kept alive by holding this reference
+    _debouncer: notify_debouncer_mini::Debouncer<notify::RecommendedWatcher>,
+    subscriptions: Subscriptions,
+}
+
+/// Last event and callback error seen by a watcher, for status introspection.
+#[derive(Clone, Default)]
+struct WatcherActivity {
+    /// When the watcher last handled a relevant event (ISO 8601)
+    last_event: Option<String>,
+    /// Most recent error raised while handling events
+    last_error: Option<String>,
+}
+
+/// Map of watcher key -> recent activity, shared with watcher callbacks.
+type ActivityLog = Arc<Mutex<HashMap<String, WatcherActivity>>>;
+
+/// Record that the watcher `key` handled an event.
+fn record_event(activity: &ActivityLog, key: &str) {
+    if let Ok(mut activity) = activity.lock() {
+        activity.entry(key.to_string()).or_default().last_event =
+            Some(chrono::Utc::now().to_rfc3339());
+    }
+}
+
+/// Record an error raised in the watcher `key`'s callback.
+fn record_error(activity: &ActivityLog, key: &str, error: String) {
+    if let Ok(mut activity) = activity.lock() {
+        activity.entry(key.to_string()).or_default().last_error = Some(error);
+    }
+}
+
+/// Set when a watcher reports a notify error; the wake monitor then re-arms watchers.
+static WATCH_FAILED: AtomicBool = AtomicBool::new(false);
+
+/// Record a notify error for the watcher `key` and request that watchers be re-armed.
+fn record_watch_error(activity: &ActivityLog, key: &str, error: &notify::Error) {
+    record_error(activity, key, error.to_string());
+    WATCH_FAILED.store(true, Ordering::SeqCst);
+}
+
+/// Status of an active watcher, for debugging live updates.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatcherStatus {
+    /// Watcher key ("project_path:session_id", "project_path:agent:agent_id", ...)
+    pub key: String,
+    /// "session", "subagent", "project", "telemetry", "config", "git-head", "todos" or
+    /// "projects-root"
+    pub kind: String,
+    /// Project directory whose shared watcher this one is subscribed to
+    pub directory: Option<String>,
+    /// Whether the session index has been built (session watchers only)
+    pub index_ready: Option<bool>,
+    /// When the watcher last handled a relevant event (ISO 8601)
+    pub last_event: Option<String>,
+    /// Most recent error raised while handling events
+    pub last_error: Option<String>,
+    /// Seconds since the watcher was started or its session last queried
+    pub idle_secs: u64,
+}
+
 /// Global state for managing file watchers and session indices.
 pub struct WatcherState {
     /// Map of "project_path:session_id" -> watcher handle (for cleanup)
     watchers: Mutex<HashMap<String, WatcherHandle>>,
+    /// Map of project directory -> shared watcher dispatching to its subscriptions
+    dir_watchers: Mutex<HashMap<PathBuf, DirWatcher>>,
+    /// Map of watcher key -> last event and callback error
+    activity: ActivityLog,
     /// Map of "project_path:session_id" -> session index (for fast lookups)
     /// Wrapped in Arc so it can be shared with background indexing threads
     indices: Arc<Mutex<HashMap<String, SessionIndex>>>,
+    /// Map of "project_path:session_id" -> channel for streaming appended events
+    event_channels: Arc<Mutex<HashMap<String, Channel<SessionEventsAppendedPayload>>>>,
+    /// Session watchers not queried for this long are torn down (None keeps them forever)
+    idle_ttl: Mutex<Option<Duration>>,
+    /// Debounce intervals for newly created watchers
+    debounce: Mutex<DebounceIntervals>,
+    /// Whether the idle reaper thread has been started
+    reaper_started: AtomicBool,
+    /// Bumped whenever active-session polling is reconfigured, retiring older pollers
+    active_poll_generation: AtomicU64,
 }
 
 struct WatcherHandle {
-    // The debouncer is kept alive by holding this reference
-    _debouncer: notify_debouncer_mini::Debouncer<notify::RecommendedWatcher>,
+    // The debouncer is kept alive by holding this reference (None for subscriptions)
+    _debouncer: Option<notify_debouncer_mini::Debouncer<notify::RecommendedWatcher>>,
+    /// Directory whose shared watcher this handle is subscribed to
+    directory: Option<PathBuf>,
+    /// (project_path, session_id) for session watchers, which expire when idle
+    session: Option<(String, String)>,
+    /// When the watched session was last watched or queried
+    last_accessed: Instant,
+}
+
+impl WatcherHandle {
+    fn new(debouncer: notify_debouncer_mini::Debouncer<notify::RecommendedWatcher>) -> Self {
+        Self {
+            _debouncer: Some(debouncer),
+            directory: None,
+            session: None,
+            last_accessed: Instant::now(),
+        }
+    }
+
+    fn subscribed(directory: &Path) -> Self {
+        Self {
+            _debouncer: None,
+            directory: Some(directory.to_path_buf()),
+            session: None,
+            last_accessed: Instant::now(),
+        }
+    }
+
+    fn for_session(directory: &Path, project_path: &str, session_id: &str) -> Self {
+        Self {
+            session: Some((project_path.to_string(), session_id.to_string())),
+            ..Self::subscribed(directory)
+        }
+    }
+}
+
+/// How long watchers wait for writes to settle before emitting.
+#[derive(Clone, Copy)]
+struct DebounceIntervals {
+    /// Session, sub-agent, session list, projects root, project config, git HEAD and
+    /// todo watchers
+    session: Duration,
+    /// Telemetry directory watchers
+    telemetry: Duration,
+}
+
+impl Default for DebounceIntervals {
+    fn default() -> Self {
+        Self {
+            session: Duration::from_millis(500),
+            telemetry: Duration::from_millis(300),
+        }
+    }
+}
+
+/// How often the idle reaper checks for expired session watchers.
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Event payload sent to the frontend when an idle session watcher is torn down.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchExpiredPayload {
+    pub project_path: String,
+    pub session_id: String,
 }
 
 impl WatcherState {
     pub fn new() -> Self {
         Self {
             watchers: Mutex::new(HashMap::new()),
+            dir_watchers: Mutex::new(HashMap::new()),
+            activity: Arc::new(Mutex::new(HashMap::new())),
             indices: Arc::new(Mutex::new(HashMap::new())),
+            event_channels: Arc::new(Mutex::new(HashMap::new())),
+            idle_ttl: Mutex::new(None),
+            debounce: Mutex::new(DebounceIntervals::default()),
+            reaper_started: AtomicBool::new(false),
+            active_poll_generation: AtomicU64::new(0),
         }
     }
 
@@ -66,9 +291,115 @@ impl WatcherState {
         Arc::clone(&self.indices)
     }
 
+    /// Get a clone of the activity log Arc for sharing with watcher callbacks.
+    fn activity_arc(&self) -> ActivityLog {
+        Arc::clone(&self.activity)
+    }
+
+    /// Get a clone of the event channels Arc for sharing with watcher callbacks.
+    fn event_channels_arc(
+        &self,
+    ) -> Arc<Mutex<HashMap<String, Channel<SessionEventsAppendedPayload>>>> {
+        Arc::clone(&self.event_channels)
+    }
+
+    /// Current debounce intervals.
+    fn debounce(&self) -> DebounceIntervals {
+        self.debounce.lock().map(|d| *d).unwrap_or_default()
+    }
+
+    /// Register `handler` under `key` with the shared watcher for `dir`, creating the
+    /// watcher on first use.
+    fn subscribe(&self, dir: &Path, key: &str, handler: BatchHandler) -> Result<(), String> {
+        let mut dir_watchers = self.dir_watchers.lock().map_err(|e| e.to_string())?;
+        if let Some(watcher) = dir_watchers.get(dir) {
+            let mut subscriptions = watcher.subscriptions.lock().map_err(|e| e.to_string())?;
+            subscriptions.insert(key.to_string(), handler);
+            return Ok(());
+        }
+
+        let subscriptions: Subscriptions =
+            Arc::new(Mutex::new(HashMap::from([(key.to_string(), handler)])));
+        let debouncer =
+            watch_project_dir(dir, &subscriptions, &self.activity, self.debounce().session)?;
+
+        dir_watchers.insert(
+            dir.to_path_buf(),
+            DirWatcher {
+                _debouncer: debouncer,
+                subscriptions,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Remove a watcher handle, dropping its shared directory watcher once nothing
+    /// else is subscribed to it.
+    fn remove_watcher(&self, key: &str) -> Result<(), String> {
+        let handle = self.watchers.lock().map_err(|e| e.to_string())?.remove(key);
+        if let Ok(mut activity) = self.activity.lock() {
+            activity.remove(key);
+        }
+        let Some(dir) = handle.and_then(|h| h.directory) else {
+            return Ok(());
+        };
+
+        let mut dir_watchers = self.dir_watchers.lock().map_err(|e| e.to_string())?;
+        let unused = match dir_watchers.get(&dir) {
+            Some(watcher) => {
+                let mut subscriptions = watcher.subscriptions.lock().map_err(|e| e.to_string())?;
+                subscriptions.remove(key);
+                subscriptions.is_empty()
+            }
+            None => false,
+        };
+        if unused {
+            dir_watchers.remove(&dir);
+        }
+
+        Ok(())
+    }
+
+    /// Whether a watcher is registered under `key`.
+    fn is_watching(&self, key: &str) -> bool {
+        self.watchers
+            .lock()
+            .map(|watchers| watchers.contains_key(key))
+            .unwrap_or(false)
+    }
+
+    /// Record that a watched session was queried, postponing its idle expiry.
+    fn touch(&self, key: &str) {
+        if let Ok(mut watchers) = self.watchers.lock() {
+            if let Some(handle) = watchers.get_mut(key) {
+                handle.last_accessed = Instant::now();
+            }
+        }
+    }
+
+    /// Sessions whose watchers have been idle longer than the TTL.
+    fn idle_sessions(&self) -> Vec<(String, String)> {
+        let ttl = match self.idle_ttl.lock().ok().and_then(|ttl| *ttl) {
+            Some(ttl) => ttl,
+            None => return Vec::new(),
+        };
+        let watchers = match self.watchers.lock() {
+            Ok(w) => w,
+            Err(_) => return Vec::new(),
+        };
+
+        watchers
+            .values()
+            .filter(|handle| handle.last_accessed.elapsed() > ttl)
+            .filter_map(|handle| handle.session.clone())
+            .collect()
+    }
+
     /// Get the index for a session, if it exists.
     pub fn get_index(&self, project_path: &str, session_id: &str) -> Option<SessionIndex> {
         let key = format!("{}:{}", project_path, session_id);
+        self.touch(&key);
         let indices = self.indices.lock().ok()?;
         indices.get(&key).cloned()
     }
@@ -76,6 +407,7 @@ impl WatcherState {
     /// Get the index status for a session.
     pub fn get_index_status(&self, project_path: &str, session_id: &str) -> IndexStatus {
         let key = format!("{}:{}", project_path, session_id);
+        self.touch(&key);
         let indices = match self.indices.lock() {
             Ok(i) => i,
             Err(_) => return IndexStatus::error("Failed to lock indices"),
@@ -88,10 +420,53 @@ impl WatcherState {
     }
 }
 
+/// Create a debounced watcher on a project directory that dispatches each batch to
+/// every subscription.
+fn watch_project_dir(
+    dir: &Path,
+    subscriptions: &Subscriptions,
+    activity: &ActivityLog,
+    interval: Duration,
+) -> Result<notify_debouncer_mini::Debouncer<notify::RecommendedWatcher>, String> {
+    let dispatch = Arc::clone(subscriptions);
+    let activity = Arc::clone(activity);
+
+    let mut debouncer = new_debouncer(
+        interval,
+        move |result: Result<Vec<notify_debouncer_mini::DebouncedEvent>, notify::Error>| {
+            let Ok(mut subscriptions) = dispatch.lock() else {
+                return;
+            };
+            match result {
+                Ok(events) => {
+                    for handler in subscriptions.values_mut() {
+                        handler(&events);
+                    }
+                }
+                // A watch error affects every subscription in the directory
+                Err(e) => {
+                    for key in subscriptions.keys() {
+                        record_watch_error(&activity, key, &e);
+                    }
+                }
+            }
+        },
+    )
+    .map_err(|e| format!("Failed to create watcher: {}", e))?;
+
+    // Sessions and sub-agents are direct children of the project directory
+    debouncer
+        .watcher()
+        .watch(dir, RecursiveMode::NonRecursive)
+        .map_err(|e| format!("Failed to watch project dir: {}", e))?;
+
+    Ok(debouncer)
+}
+
 /// Get the session file path for watching.
 fn get_session_file_path(project_path: &str, session_id: &str) -> Option<PathBuf> {
     let home = dirs::home_dir()?;
-    let encoded_name = project_path.replace('/', "-").replace(' ', "-");
+    let encoded_name = project_path.replace(['/', ' '], "-");
     let session_file = home
         .join(".claude")
         .join("projects")
@@ -105,7 +480,17 @@ fn get_session_file_path(project_path: &str, session_id: &str) -> Option<PathBuf
     }
 }
 
+/// Get the agent ID from a sub-agent transcript path (`agent-<id>.jsonl`).
+fn subagent_id_from_path(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_str()?;
+    let agent_id = name.strip_prefix("agent-")?.strip_suffix(".jsonl")?;
+    Some(agent_id.to_string())
+}
+
 /// Start watching a session file for changes.
+/// Also reports sub-agents the session launches ("subagent-spawned"). If the session file
+/// is deleted or renamed away, stops watching it and emits "session-removed", unless
+/// `watch_project` is watching the project and reports the removal itself.
 /// Spawns a background thread to build the session index, emitting "index-ready" when done.
 pub fn watch_session(
     app_handle: AppHandle,
@@ -115,10 +500,11 @@ pub fn watch_session(
 ) -> Result<(), String> {
     let key = format!("{}:{}", project_path, session_id);
 
-    // Check if already watching
+    // Check if already watching (re-watching counts as activity)
     {
-        let watchers = state.watchers.lock().map_err(|e| e.to_string())?;
-        if watchers.contains_key(&key) {
+        let mut watchers = state.watchers.lock().map_err(|e| e.to_string())?;
+        if let Some(handle) = watchers.get_mut(&key) {
+            handle.last_accessed = Instant::now();
             return Ok(()); // Already watching
         }
     }
@@ -126,86 +512,144 @@ pub fn watch_session(
     let session_file = get_session_file_path(&project_path, &session_id)
         .ok_or_else(|| format!("Session file not found for {}", session_id))?;
 
+    // Sub-agent transcripts are written next to the session file
+    let project_dir = session_file
+        .parent()
+        .map(PathBuf::from)
+        .ok_or_else(|| "Session file has no parent directory".to_string())?;
+    let mut known_agents: HashSet<String> = std::fs::read_dir(&project_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| subagent_id_from_path(&entry.path()))
+                .collect()
+        })
+        .unwrap_or_default();
+
     // Clone data for the file watcher closure
-    let watcher_app_handle = app_handle.clone();
-    let watcher_project_path = project_path.clone();
-    let watcher_session_id = session_id.clone();
-    let watcher_session_file = session_file.clone();
-    let watcher_indices = state.indices_arc();
-    let watcher_key = key.clone();
-
-    // Create debounced watcher with 500ms debounce
-    let mut debouncer = new_debouncer(
-        Duration::from_millis(500),
-        move |result: Result<Vec<notify_debouncer_mini::DebouncedEvent>, notify::Error>| {
-            if let Ok(events) = result {
-                for event in events {
-                    if event.kind == DebouncedEventKind::Any {
-                        // Update the index incrementally
-                        if let Ok(mut indices) = watcher_indices.lock() {
-                            if let Some(index) = indices.get_mut(&watcher_key) {
-                                match update_index_incremental(
-                                    index,
-                                    &watcher_session_file,
-                                    &watcher_project_path,
-                                ) {
-                                    Ok(UpdateResult::Updated) => {
-                                        println!(
-                                            "[session_index] Incremental update: now {} events",
-                                            index.total_events()
-                                        );
-                                    }
-                                    Ok(UpdateResult::Rebuilt) => {
-                                        println!(
-                                            "[session_index] Index rebuilt: {} events",
-                                            index.total_events()
-                                        );
-                                    }
-                                    Ok(UpdateResult::Unchanged) => {
-                                        // No logging for unchanged
-                                    }
-                                    Err(e) => {
-                                        eprintln!("[session_index] Incremental update failed: {}", e);
-                                    }
-                                }
-                            }
-                        }
+    let updater = SessionUpdater {
+        app_handle: app_handle.clone(),
+        indices: state.indices_arc(),
+        channels: state.event_channels_arc(),
+        activity: state.activity_arc(),
+        key: key.clone(),
+        session_file: session_file.clone(),
+        project_path: project_path.clone(),
+        session_id: session_id.clone(),
+    };
+    let mut removed = false;
+    let mut throttle = UpdateThrottle::new(MIN_SESSION_UPDATE_INTERVAL);
+
+    let handler: BatchHandler =
+        Box::new(move |events: &[notify_debouncer_mini::DebouncedEvent]| {
+            // Report sub-agents this session launched since the last batch
+            for event in events {
+                let Some(agent_id) = subagent_id_from_path(&event.path) else {
+                    continue;
+                };
+                if known_agents.contains(&agent_id) {
+                    continue;
+                }
+                let origin = read_subagent_origin(&event.path);
+                // An empty new file has no origin yet; retry on its next write
+                let Some(parent_session) = origin.session_id else {
+                    continue;
+                };
+                known_agents.insert(agent_id.clone());
+                if parent_session != updater.session_id {
+                    continue;
+                }
+
+                let launch = origin
+                    .prompt
+                    .as_deref()
+                    .and_then(|prompt| find_task_launch(&updater.session_file, prompt));
+                let (tool_use_id, description) = match launch {
+                    Some((id, description)) => (Some(id), description),
+                    None => (None, None),
+                };
+                let _ = updater.app_handle.emit(
+                    "subagent-spawned",
+                    SubagentSpawnedPayload {
+                        project_path: updater.project_path.clone(),
+                        session_id: updater.session_id.clone(),
+                        agent_id,
+                        tool_use_id,
+                        description,
+                    },
+                );
+            }
+
+            for event in events {
+                // Other files in the project directory share this watcher
+                if event.path.file_name() != updater.session_file.file_name() {
+                    continue;
+                }
 
-                        // Emit event to frontend
-                        let _ = watcher_app_handle.emit(
-                            "session-changed",
-                            SessionChangedPayload {
-                                project_path: watcher_project_path.clone(),
-                                session_id: watcher_session_id.clone(),
-                            },
+                // Deleted or renamed away (cleanup scripts, claude housekeeping)
+                if !updater.session_file.exists() {
+                    if !removed {
+                        removed = true;
+                        record_event(&updater.activity, &updater.key);
+                        // The project's session-list watcher reports it when active
+                        let project_key = project_watcher_key(&updater.project_path);
+                        let state = updater.app_handle.state::<WatcherState>();
+                        if !state.is_watching(&project_key) {
+                            let _ = updater.app_handle.emit(
+                                "session-removed",
+                                SessionRemovedPayload {
+                                    project_path: updater.project_path.clone(),
+                                    session_id: updater.session_id.clone(),
+                                },
+                            );
+                        }
+                        unwatch_session_later(
+                            updater.app_handle.clone(),
+                            updater.project_path.clone(),
+                            updater.session_id.clone(),
                         );
-                        break; // Only emit once per batch
                     }
+                    return;
+                }
+
+                // Sustained writes arrive as AnyContinuous batches before the final Any
+                if matches!(
+                    event.kind,
+                    DebouncedEventKind::Any | DebouncedEventKind::AnyContinuous
+                ) {
+                    match throttle.request(Instant::now()) {
+                        ThrottledUpdate::Now => updater.apply(),
+                        ThrottledUpdate::After(delay) => {
+                            let flush_updater = updater.clone();
+                            let flush_pending = throttle.flush_pending();
+                            std::thread::spawn(move || {
+                                std::thread::sleep(delay);
+                                flush_pending.store(false, Ordering::SeqCst);
+                                flush_updater.apply();
+                            });
+                        }
+                        ThrottledUpdate::Covered => {}
+                    }
+                    break; // Only update once per batch
                 }
             }
-        },
-    )
-    .map_err(|e| format!("Failed to create watcher: {}", e))?;
+        });
 
-    // Watch the session file
-    debouncer
-        .watcher()
-        .watch(&session_file, RecursiveMode::NonRecursive)
-        .map_err(|e| format!("Failed to watch file: {}", e))?;
+    // The project directory's watcher sees both the session file and new sub-agents
+    state.subscribe(&project_dir, &key, handler)?;
 
     // Store the watcher handle immediately (so cleanup works)
     {
         let mut watchers = state.watchers.lock().map_err(|e| e.to_string())?;
         watchers.insert(
             key.clone(),
-            WatcherHandle {
-                _debouncer: debouncer,
-            },
+            WatcherHandle::for_session(&project_dir, &project_path, &session_id),
         );
     }
 
     // Clone data for the background indexing thread
     let indices = state.indices_arc();
+    let index_activity = state.activity_arc();
     let index_app_handle = app_handle;
     let index_project_path = project_path;
     let index_session_id = session_id;
@@ -236,6 +680,7 @@ pub fn watch_session(
             }
             Err(err) => {
                 eprintln!("[session_index] Failed to build index: {}", err);
+                record_error(&index_activity, &index_key, err.clone());
                 IndexStatus::error(err)
             }
         };
@@ -263,10 +708,7 @@ pub fn unwatch_session(
     let key = format!("{}:{}", project_path, session_id);
 
     // Remove the watcher
-    {
-        let mut watchers = state.watchers.lock().map_err(|e| e.to_string())?;
-        watchers.remove(&key);
-    }
+    state.remove_watcher(&key)?;
 
     // Remove the index
     {
@@ -274,106 +716,717 @@ pub fn unwatch_session(
         indices.remove(&key);
     }
 
+    // Remove the event channel
+    {
+        let mut channels = state.event_channels.lock().map_err(|e| e.to_string())?;
+        channels.remove(&key);
+    }
+
     Ok(())
 }
 
-/// Get the sub-agent file path for watching.
-fn get_subagent_file_path(project_path: &str, agent_id: &str) -> Option<PathBuf> {
-    let home = dirs::home_dir()?;
-    let encoded_name = project_path.replace('/', "-").replace(' ', "-");
-    let agent_file = home
-        .join(".claude")
-        .join("projects")
-        .join(&encoded_name)
-        .join(format!("agent-{}.jsonl", agent_id));
+/// Minimum time between incremental updates of one session; changes arriving sooner
+/// are merged into a single trailing update.
+const MIN_SESSION_UPDATE_INTERVAL: Duration = Duration::from_millis(250);
 
-    if agent_file.exists() {
-        Some(agent_file)
-    } else {
-        None
+/// When a requested session update should run.
+#[derive(Debug, PartialEq)]
+enum ThrottledUpdate {
+    /// Apply it right away
+    Now,
+    /// Schedule a trailing flush after this delay
+    After(Duration),
+    /// Skip it: a pending flush re-reads the file, so it covers this change
+    Covered,
+}
+
+/// Spaces out a session's updates, merging changes during write bursts into one
+/// trailing update.
+struct UpdateThrottle {
+    interval: Duration,
+    last_update: Option<Instant>,
+    /// Set while a trailing flush is scheduled; the flush clears it before applying
+    flush_pending: Arc<AtomicBool>,
+}
+
+impl UpdateThrottle {
+    fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_update: None,
+            flush_pending: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Decide how to handle a change seen at `now`.
+    fn request(&mut self, now: Instant) -> ThrottledUpdate {
+        match self.last_update.map(|t| t + self.interval) {
+            Some(next) if next > now => {
+                if self.flush_pending.swap(true, Ordering::SeqCst) {
+                    return ThrottledUpdate::Covered;
+                }
+                self.last_update = Some(next);
+                ThrottledUpdate::After(next - now)
+            }
+            _ => {
+                self.last_update = Some(now);
+                ThrottledUpdate::Now
+            }
+        }
+    }
+
+    /// Flag a scheduled flush must clear when it runs.
+    fn flush_pending(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.flush_pending)
     }
 }
 
-/// Start watching a sub-agent file for changes.
-pub fn watch_subagent(
+/// Everything a session watcher needs to apply a change, cloneable into a deferred flush.
+#[derive(Clone)]
+struct SessionUpdater {
     app_handle: AppHandle,
-    state: &WatcherState,
+    indices: Arc<Mutex<HashMap<String, SessionIndex>>>,
+    channels: Arc<Mutex<HashMap<String, Channel<SessionEventsAppendedPayload>>>>,
+    activity: ActivityLog,
+    key: String,
+    session_file: PathBuf,
     project_path: String,
-    agent_id: String,
-) -> Result<(), String> {
-    let key = format!("{}:agent:{}", project_path, agent_id);
+    session_id: String,
+}
 
-    // Check if already watching
-    {
-        let watchers = state.watchers.lock().map_err(|e| e.to_string())?;
-        if watchers.contains_key(&key) {
-            return Ok(()); // Already watching
-        }
-    }
+impl SessionUpdater {
+    /// Update the session index and emit "session-changed". Skips the emit when the
+    /// index is built and nothing was appended.
+    fn apply(&self) {
+        let mut payload = SessionChangedPayload {
+            project_path: self.project_path.clone(),
+            session_id: self.session_id.clone(),
+            appended_from: None,
+            total_count: None,
+            reset: false,
+            events: None,
+        };
 
-    let agent_file = get_subagent_file_path(&project_path, &agent_id)
-        .ok_or_else(|| format!("Sub-agent file not found for {}", agent_id))?;
+        // Update the index incrementally
+        if let Ok(mut indices) = self.indices.lock() {
+            if let Some(index) = indices.get_mut(&self.key) {
+                let previous_count = index.total_events();
+                let result =
+                    update_index_incremental(index, &self.session_file, &self.project_path);
 
-    let project_path_clone = project_path.clone();
-    let agent_id_clone = agent_id.clone();
+                // Stream appended events to a subscribed channel, if any
+                if let Ok(ref update) = result {
+                    send_appended_events(
+                        &self.channels,
+                        &self.key,
+                        index,
+                        &self.session_file,
+                        previous_count,
+                        update,
+                    );
+                }
 
-    // Create debounced watcher with 500ms debounce
-    let mut debouncer = new_debouncer(
-        Duration::from_millis(500),
-        move |result: Result<Vec<notify_debouncer_mini::DebouncedEvent>, notify::Error>| {
-            if let Ok(events) = result {
-                for event in events {
-                    if event.kind == DebouncedEventKind::Any {
-                        // Emit event to frontend
-                        let _ = app_handle.emit(
-                            "subagent-changed",
-                            SubagentChangedPayload {
-                                project_path: project_path_clone.clone(),
-                                agent_id: agent_id_clone.clone(),
-                            },
+                match result {
+                    Ok(UpdateResult::Updated) => {
+                        println!(
+                            "[session_index] Incremental update: now {} events",
+                            index.total_events()
+                        );
+                        let total = index.total_events();
+                        let appended = total.saturating_sub(previous_count);
+                        payload.appended_from = Some(previous_count);
+                        payload.total_count = Some(total);
+                        if appended <= MAX_INLINE_CHANGED_EVENTS {
+                            payload.events = get_events_in_range(
+                                index,
+                                &self.session_file,
+                                previous_count,
+                                total,
+                            )
+                            .ok();
+                        }
+                    }
+                    Ok(UpdateResult::Rebuilt) => {
+                        println!(
+                            "[session_index] Index rebuilt: {} events",
+                            index.total_events()
+                        );
+                        payload.total_count = Some(index.total_events());
+                        payload.reset = true;
+                    }
+                    Ok(UpdateResult::Unchanged) => {
+                        // Redundant write (e.g. merged into an earlier update)
+                        return;
+                    }
+                    Err(e) => {
+                        eprintln!("[session_index] Incremental update failed: {}", e);
+                        record_error(
+                            &self.activity,
+                            &self.key,
+                            format!("Incremental update failed: {}", e),
                         );
-                        break; // Only emit once per batch
                     }
                 }
             }
-        },
-    )
-    .map_err(|e| format!("Failed to create watcher: {}", e))?;
-
-    // Watch the agent file
-    debouncer
-        .watcher()
-        .watch(&agent_file, RecursiveMode::NonRecursive)
-        .map_err(|e| format!("Failed to watch file: {}", e))?;
+        }
 
-    // Store the watcher handle
-    {
-        let mut watchers = state.watchers.lock().map_err(|e| e.to_string())?;
-        watchers.insert(
-            key,
-            WatcherHandle {
-                _debouncer: debouncer,
-            },
-        );
+        // Emit event to frontend
+        record_event(&self.activity, &self.key);
+        let _ = self.app_handle.emit("session-changed", payload);
     }
+}
 
-    Ok(())
+/// Stop watching a session from a separate thread. Session handlers run while their
+/// directory watcher's subscriptions are locked, so they can't unsubscribe themselves.
+fn unwatch_session_later(app_handle: AppHandle, project_path: String, session_id: String) {
+    std::thread::spawn(move || {
+        let state = app_handle.state::<WatcherState>();
+        if let Err(e) = unwatch_session(&state, &project_path, &session_id) {
+            eprintln!("[watcher] Failed to unwatch removed session: {}", e);
+        }
+    });
 }
 
-/// Stop watching a sub-agent file.
-pub fn unwatch_subagent(
+/// Set how long a watched session may go unqueried before its watcher and index are
+/// torn down, emitting "watch-expired". Pass None to keep watchers until unwatched.
+pub fn set_watcher_idle_ttl(
+    app_handle: AppHandle,
     state: &WatcherState,
-    project_path: &str,
-    agent_id: &str,
+    ttl: Option<Duration>,
 ) -> Result<(), String> {
-    let key = format!("{}:agent:{}", project_path, agent_id);
-
-    let mut watchers = state.watchers.lock().map_err(|e| e.to_string())?;
-    watchers.remove(&key);
+    *state.idle_ttl.lock().map_err(|e| e.to_string())? = ttl;
+
+    if ttl.is_some() && !state.reaper_started.swap(true, Ordering::SeqCst) {
+        std::thread::spawn(move || loop {
+            std::thread::sleep(IDLE_CHECK_INTERVAL);
+            let state = app_handle.state::<WatcherState>();
+            for (project_path, session_id) in state.idle_sessions() {
+                if unwatch_session(&state, &project_path, &session_id).is_ok() {
+                    let _ = app_handle.emit(
+                        "watch-expired",
+                        WatchExpiredPayload {
+                            project_path,
+                            session_id,
+                        },
+                    );
+                }
+            }
+        });
+    }
+
+    Ok(())
+}
+
+/// Set the debounce intervals watchers wait for writes to settle before emitting.
+/// Pass None to restore an interval's default (500ms for sessions, 300ms for telemetry).
+///
+/// Project directory watchers are recreated with the new session interval; projects
+/// root and telemetry watchers pick up the change the next time they are started.
+/// Returns the watchers dropped because their directory couldn't be watched again.
+pub fn set_watcher_debounce(
+    state: &WatcherState,
+    session: Option<Duration>,
+    telemetry: Option<Duration>,
+) -> Result<Vec<WatcherFailure>, String> {
+    if session.is_some_and(|d| d.is_zero()) || telemetry.is_some_and(|d| d.is_zero()) {
+        return Err("Debounce interval must be greater than zero".to_string());
+    }
+
+    let defaults = DebounceIntervals::default();
+    let intervals = DebounceIntervals {
+        session: session.unwrap_or(defaults.session),
+        telemetry: telemetry.unwrap_or(defaults.telemetry),
+    };
+    *state.debounce.lock().map_err(|e| e.to_string())? = intervals;
+
+    rearm_dir_watchers(state, intervals.session)
+}
+
+/// Watchers that were dropped because they couldn't be recreated.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatcherFailure {
+    /// Keys of the dropped watchers
+    pub keys: Vec<String>,
+    /// Why the watcher couldn't be recreated
+    pub error: String,
+}
+
+/// Recreate every shared project directory watcher, keeping its subscriptions.
+///
+/// A directory that can't be watched again (e.g. it was deleted) doesn't stop the
+/// others: its watcher and subscriptions are dropped and reported as a failure.
+fn rearm_dir_watchers(
+    state: &WatcherState,
+    interval: Duration,
+) -> Result<Vec<WatcherFailure>, String> {
+    let mut failures = Vec::new();
+    {
+        let mut dir_watchers = state.dir_watchers.lock().map_err(|e| e.to_string())?;
+        dir_watchers.retain(|dir, watcher| {
+            match watch_project_dir(dir, &watcher.subscriptions, &state.activity, interval) {
+                Ok(debouncer) => {
+                    watcher._debouncer = debouncer;
+                    true
+                }
+                Err(error) => {
+                    eprintln!("[watcher] Failed to re-arm {}: {}", dir.display(), error);
+                    let mut keys: Vec<String> = match watcher.subscriptions.lock() {
+                        Ok(subscriptions) => subscriptions.keys().cloned().collect(),
+                        Err(_) => Vec::new(),
+                    };
+                    keys.sort();
+                    failures.push(WatcherFailure { keys, error });
+                    false
+                }
+            }
+        });
+    }
+
+    // The directory watchers are gone, so this only drops the handles and session state
+    for key in failures.iter().flat_map(|failure| &failure.keys) {
+        let session = match state.watchers.lock() {
+            Ok(watchers) => watchers.get(key).and_then(|handle| handle.session.clone()),
+            Err(_) => None,
+        };
+        let result = match session {
+            Some((project_path, session_id)) => unwatch_session(state, &project_path, &session_id),
+            None => state.remove_watcher(key),
+        };
+        if let Err(e) = result {
+            eprintln!("[watcher] Failed to drop {}: {}", key, e);
+        }
+    }
+
+    Ok(failures)
+}
+
+/// Kind of watcher a handle belongs to, derived from its key.
+fn watcher_kind(key: &str, handle: &WatcherHandle) -> &'static str {
+    if handle.session.is_some() {
+        "session"
+    } else if key == PROJECTS_ROOT_KEY {
+        "projects-root"
+    } else if key == TODOS_KEY {
+        "todos"
+    } else if key.ends_with(":project") {
+        "project"
+    } else if key.ends_with(":telemetry") {
+        "telemetry"
+    } else if key.ends_with(":config") {
+        "config"
+    } else if key.ends_with(":git-head") {
+        "git-head"
+    } else {
+        "subagent"
+    }
+}
+
+/// List active watchers with their last event and any callback error, so a session
+/// that stops updating live can be diagnosed.
+pub fn get_watcher_status(state: &WatcherState) -> Result<Vec<WatcherStatus>, String> {
+    let watchers = state.watchers.lock().map_err(|e| e.to_string())?;
+    let indices = state.indices.lock().map_err(|e| e.to_string())?;
+    let activity = state.activity.lock().map_err(|e| e.to_string())?;
+
+    let mut statuses: Vec<WatcherStatus> = watchers
+        .iter()
+        .map(|(key, handle)| {
+            let kind = watcher_kind(key, handle);
+            let recent = activity.get(key).cloned().unwrap_or_default();
+
+            WatcherStatus {
+                key: key.clone(),
+                kind: kind.to_string(),
+                directory: handle
+                    .directory
+                    .as_ref()
+                    .map(|d| d.to_string_lossy().to_string()),
+                index_ready: handle.session.as_ref().map(|_| indices.contains_key(key)),
+                last_event: recent.last_event,
+                last_error: recent.last_error,
+                idle_secs: handle.last_accessed.elapsed().as_secs(),
+            }
+        })
+        .collect();
+    statuses.sort_by(|a, b| a.key.cmp(&b.key));
+
+    Ok(statuses)
+}
+
+/// Subscribe a channel to a watched session's appended events.
+///
+/// While the session is watched, each incremental index update sends the newly
+/// parsed events through the channel, so live tailing doesn't re-read the file.
+/// Replaces any previous channel for the session.
+pub fn stream_session_events(
+    state: &WatcherState,
+    project_path: &str,
+    session_id: &str,
+    channel: Channel<SessionEventsAppendedPayload>,
+) -> Result<(), String> {
+    let key = format!("{}:{}", project_path, session_id);
+    state.touch(&key);
+
+    let mut channels = state.event_channels.lock().map_err(|e| e.to_string())?;
+    channels.insert(key, channel);
+
+    Ok(())
+}
+
+/// Send events appended since `previous_count` to the session's channel, if subscribed.
+fn send_appended_events(
+    channels: &Mutex<HashMap<String, Channel<SessionEventsAppendedPayload>>>,
+    key: &str,
+    index: &SessionIndex,
+    session_file: &Path,
+    previous_count: u32,
+    update: &UpdateResult,
+) {
+    let channels = match channels.lock() {
+        Ok(c) => c,
+        Err(_) => return,
+    };
+    let channel = match channels.get(key) {
+        Some(c) => c,
+        None => return,
+    };
+
+    let payload = match update {
+        UpdateResult::Unchanged => return,
+        UpdateResult::Rebuilt => SessionEventsAppendedPayload {
+            events: Vec::new(),
+            total_count: index.total_events(),
+            reset: true,
+        },
+        UpdateResult::Updated => {
+            let events = match get_events_in_range(
+                index,
+                session_file,
+                previous_count,
+                index.total_events(),
+            ) {
+                Ok(events) => events,
+                Err(e) => {
+                    eprintln!("[watcher] Failed to load appended events: {}", e);
+                    return;
+                }
+            };
+            SessionEventsAppendedPayload {
+                events,
+                total_count: index.total_events(),
+                reset: false,
+            }
+        }
+    };
+
+    if let Err(e) = channel.send(payload) {
+        eprintln!("[watcher] Failed to send appended events: {}", e);
+    }
+}
+
+/// Get the sub-agent file path for watching.
+fn get_subagent_file_path(project_path: &str, agent_id: &str) -> Option<PathBuf> {
+    let home = dirs::home_dir()?;
+    let encoded_name = project_path.replace(['/', ' '], "-");
+    let agent_file = home
+        .join(".claude")
+        .join("projects")
+        .join(&encoded_name)
+        .join(format!("agent-{}.jsonl", agent_id));
+
+    if agent_file.exists() {
+        Some(agent_file)
+    } else {
+        None
+    }
+}
+
+/// Start watching a sub-agent file for changes.
+pub fn watch_subagent(
+    app_handle: AppHandle,
+    state: &WatcherState,
+    project_path: String,
+    agent_id: String,
+) -> Result<(), String> {
+    let key = format!("{}:agent:{}", project_path, agent_id);
+
+    // Check if already watching
+    {
+        let watchers = state.watchers.lock().map_err(|e| e.to_string())?;
+        if watchers.contains_key(&key) {
+            return Ok(()); // Already watching
+        }
+    }
+
+    let agent_file = get_subagent_file_path(&project_path, &agent_id)
+        .ok_or_else(|| format!("Sub-agent file not found for {}", agent_id))?;
+    let project_dir = agent_file
+        .parent()
+        .map(PathBuf::from)
+        .ok_or_else(|| "Sub-agent file has no parent directory".to_string())?;
+
+    let project_path_clone = project_path.clone();
+    let agent_id_clone = agent_id.clone();
+    let activity = state.activity_arc();
+    let activity_key = key.clone();
+
+    let handler: BatchHandler =
+        Box::new(move |events: &[notify_debouncer_mini::DebouncedEvent]| {
+            for event in events {
+                // Other files in the project directory share this watcher
+                if event.path.file_name() != agent_file.file_name() {
+                    continue;
+                }
+                if event.kind == DebouncedEventKind::Any {
+                    // Emit event to frontend
+                    record_event(&activity, &activity_key);
+                    let _ = app_handle.emit(
+                        "subagent-changed",
+                        SubagentChangedPayload {
+                            project_path: project_path_clone.clone(),
+                            agent_id: agent_id_clone.clone(),
+                        },
+                    );
+                    break; // Only emit once per batch
+                }
+            }
+        });
+
+    // Subscribe to the project directory's watcher
+    state.subscribe(&project_dir, &key, handler)?;
+
+    // Store the watcher handle
+    {
+        let mut watchers = state.watchers.lock().map_err(|e| e.to_string())?;
+        watchers.insert(key, WatcherHandle::subscribed(&project_dir));
+    }
+
+    Ok(())
+}
+
+/// Stop watching a sub-agent file.
+pub fn unwatch_subagent(
+    state: &WatcherState,
+    project_path: &str,
+    agent_id: &str,
+) -> Result<(), String> {
+    let key = format!("{}:agent:{}", project_path, agent_id);
+    state.remove_watcher(&key)
+}
+
+/// Key for the global watcher on `~/.claude/projects`.
+const PROJECTS_ROOT_KEY: &str = "projects-root";
+
+/// Start watching `~/.claude/projects` for new projects, emitting "project-added".
+///
+/// A new directory usually appears before its first session is written, so the root
+/// is watched recursively and a directory is reported once it holds a session.
+pub fn watch_projects_root(app_handle: AppHandle, state: &WatcherState) -> Result<(), String> {
+    // Check if already watching
+    {
+        let watchers = state.watchers.lock().map_err(|e| e.to_string())?;
+        if watchers.contains_key(PROJECTS_ROOT_KEY) {
+            return Ok(()); // Already watching
+        }
+    }
+
+    let projects_dir = get_claude_projects_dir()
+        .filter(|dir| dir.is_dir())
+        .ok_or_else(|| "Claude projects directory not found".to_string())?;
+
+    // Directories present at startup are already on the dashboard
+    let mut settled_dirs: HashSet<OsString> = std::fs::read_dir(&projects_dir)
+        .map(|entries| entries.flatten().map(|e| e.file_name()).collect())
+        .unwrap_or_default();
+    let root = projects_dir.clone();
+    let activity = state.activity_arc();
+
+    // Create debounced watcher
+    let mut debouncer = new_debouncer(
+        state.debounce().session,
+        move |result: Result<Vec<notify_debouncer_mini::DebouncedEvent>, notify::Error>| {
+            let events = match result {
+                Ok(events) => events,
+                Err(e) => {
+                    record_watch_error(&activity, PROJECTS_ROOT_KEY, &e);
+                    return;
+                }
+            };
+            record_event(&activity, PROJECTS_ROOT_KEY);
+
+            let paths = events.iter().map(|event| event.path.as_path());
+            for dir_name in unsettled_dirs(paths, &root, &settled_dirs) {
+                if let Some(project) = project_from_dir(&root.join(&dir_name)) {
+                    let _ = app_handle.emit("project-added", ProjectAddedPayload { project });
+                    settled_dirs.insert(dir_name);
+                }
+            }
+        },
+    )
+    .map_err(|e| format!("Failed to create watcher: {}", e))?;
+
+    // Watch the projects root, including directories created later
+    debouncer
+        .watcher()
+        .watch(&projects_dir, RecursiveMode::Recursive)
+        .map_err(|e| format!("Failed to watch projects dir: {}", e))?;
+
+    // Store the watcher handle
+    {
+        let mut watchers = state.watchers.lock().map_err(|e| e.to_string())?;
+        watchers.insert(PROJECTS_ROOT_KEY.to_string(), WatcherHandle::new(debouncer));
+    }
+
+    Ok(())
+}
+
+/// Top-level directories under `root` touched by some paths that aren't known
+/// projects yet.
+fn unsettled_dirs<'a>(
+    paths: impl IntoIterator<Item = &'a Path>,
+    root: &Path,
+    settled_dirs: &HashSet<OsString>,
+) -> HashSet<OsString> {
+    paths
+        .into_iter()
+        .filter_map(|path| path.strip_prefix(root).ok())
+        .filter_map(|relative| relative.components().next())
+        .map(|component| component.as_os_str().to_os_string())
+        .filter(|name| !settled_dirs.contains(name))
+        .collect()
+}
+
+/// Stop watching `~/.claude/projects` for new projects.
+pub fn unwatch_projects_root(state: &WatcherState) -> Result<(), String> {
+    state.remove_watcher(PROJECTS_ROOT_KEY)
+}
+
+/// Get a project's directory in `~/.claude/projects` for watching.
+fn get_project_dir_path(project_path: &str) -> Option<PathBuf> {
+    let home = dirs::home_dir()?;
+    let encoded_name = project_path.replace(['/', ' '], "-");
+    let project_dir = home.join(".claude").join("projects").join(&encoded_name);
+
+    if project_dir.is_dir() {
+        Some(project_dir)
+    } else {
+        None
+    }
+}
+
+/// Key of a project's session-list watcher.
+fn project_watcher_key(project_path: &str) -> String {
+    format!("{}:project", project_path)
+}
+
+/// Start watching a project's directory for sessions being added or removed.
+/// Emits "session-added" with the new session's metadata and "session-removed" with its ID.
+pub fn watch_project(
+    app_handle: AppHandle,
+    state: &WatcherState,
+    project_path: String,
+) -> Result<(), String> {
+    let key = project_watcher_key(&project_path);
+
+    // Check if already watching
+    {
+        let watchers = state.watchers.lock().map_err(|e| e.to_string())?;
+        if watchers.contains_key(&key) {
+            return Ok(()); // Already watching
+        }
+    }
+
+    let project_dir = get_project_dir_path(&project_path)
+        .ok_or_else(|| format!("Project directory not found for {}", project_path))?;
+
+    // Sessions already present, so only changes are reported
+    let mut known_sessions: HashSet<String> = get_sessions_for_project(&project_path)
+        .into_iter()
+        .map(|s| s.id)
+        .collect();
+    let project_path_clone = project_path.clone();
+    let activity = state.activity_arc();
+    let activity_key = key.clone();
+
+    let handler: BatchHandler =
+        Box::new(move |events: &[notify_debouncer_mini::DebouncedEvent]| {
+            // Sub-agent files and other writes don't change the session list
+            if !events.iter().any(|event| is_session_file(&event.path)) {
+                return;
+            }
+            record_event(&activity, &activity_key);
+
+            let sessions = get_sessions_for_project(&project_path_clone);
+            let current: HashSet<String> = sessions.iter().map(|s| s.id.clone()).collect();
+            let (added, removed) = diff_sessions(&known_sessions, sessions);
+
+            for session in added {
+                let _ = app_handle.emit(
+                    "session-added",
+                    SessionAddedPayload {
+                        project_path: project_path_clone.clone(),
+                        session,
+                    },
+                );
+            }
+            for session_id in removed {
+                let _ = app_handle.emit(
+                    "session-removed",
+                    SessionRemovedPayload {
+                        project_path: project_path_clone.clone(),
+                        session_id,
+                    },
+                );
+            }
+
+            known_sessions = current;
+        });
+
+    // Subscribe to the project directory's watcher (sessions are direct children)
+    state.subscribe(&project_dir, &key, handler)?;
+
+    // Store the watcher handle
+    {
+        let mut watchers = state.watchers.lock().map_err(|e| e.to_string())?;
+        watchers.insert(key, WatcherHandle::subscribed(&project_dir));
+    }
 
     Ok(())
 }
 
+/// Whether a path is a session transcript (not a sub-agent's).
+fn is_session_file(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == "jsonl")
+        && !path
+            .file_name()
+            .is_some_and(|n| n.to_string_lossy().starts_with("agent-"))
+}
+
+/// Split a project's current sessions into those not known yet and the IDs of known
+/// sessions that are gone (sorted).
+fn diff_sessions(known: &HashSet<String>, sessions: Vec<Session>) -> (Vec<Session>, Vec<String>) {
+    let current: HashSet<&str> = sessions.iter().map(|s| s.id.as_str()).collect();
+    let mut removed: Vec<String> = known
+        .iter()
+        .filter(|id| !current.contains(id.as_str()))
+        .cloned()
+        .collect();
+    removed.sort();
+    let added = sessions
+        .into_iter()
+        .filter(|session| !known.contains(&session.id))
+        .collect();
+
+    (added, removed)
+}
+
+/// Stop watching a project's directory for session changes.
+pub fn unwatch_project(state: &WatcherState, project_path: &str) -> Result<(), String> {
+    let key = project_watcher_key(project_path);
+    state.remove_watcher(&key)
+}
+
 /// Event payload sent to the frontend when telemetry files change.
 #[derive(Clone, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -413,11 +1466,16 @@ pub fn watch_telemetry(
     }
 
     let project_path_clone = project_path.clone();
+    let activity = state.activity_arc();
+    let activity_key = key.clone();
 
-    // Create debounced watcher with 300ms debounce
+    // Create debounced watcher
     let mut debouncer = new_debouncer(
-        Duration::from_millis(300),
+        state.debounce().telemetry,
         move |result: Result<Vec<notify_debouncer_mini::DebouncedEvent>, notify::Error>| {
+            if let Err(ref e) = result {
+                record_watch_error(&activity, &activity_key, e);
+            }
             if let Ok(events) = result {
                 for event in events {
                     if event.kind == DebouncedEventKind::Any {
@@ -428,6 +1486,7 @@ pub fn watch_telemetry(
                             .map(|e| e == "json")
                             .unwrap_or(false)
                         {
+                            record_event(&activity, &activity_key);
                             let _ = app_handle.emit(
                                 "telemetry-changed",
                                 TelemetryChangedPayload {
@@ -452,12 +1511,7 @@ pub fn watch_telemetry(
     // Store the watcher handle
     {
         let mut watchers = state.watchers.lock().map_err(|e| e.to_string())?;
-        watchers.insert(
-            key,
-            WatcherHandle {
-                _debouncer: debouncer,
-            },
-        );
+        watchers.insert(key, WatcherHandle::new(debouncer));
     }
 
     Ok(())
@@ -466,9 +1520,740 @@ pub fn watch_telemetry(
 /// Stop watching a project's telemetry directory.
 pub fn unwatch_telemetry(state: &WatcherState, project_path: &str) -> Result<(), String> {
     let key = format!("{}:telemetry", project_path);
+    state.remove_watcher(&key)
+}
+
+/// Event payload sent to the frontend when a project's agent configuration changes.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectConfigChangedPayload {
+    pub project_path: String,
+    /// Changed file, relative to the project root (e.g. ".claude/settings.json")
+    pub file: String,
+}
+
+/// Agent configuration files watched by `watch_project_config`, relative to the project.
+const PROJECT_CONFIG_FILES: [&str; 4] = [
+    "CLAUDE.md",
+    ".claude/settings.json",
+    ".claude/settings.local.json",
+    ".mcp.json",
+];
+
+/// Start watching a project's CLAUDE.md, `.claude` settings and `.mcp.json`, emitting
+/// "project-config-changed" for each file that changes.
+///
+/// The project root and its `.claude` directory are watched non-recursively, so files
+/// created later are picked up; a `.claude` directory created after watching starts is
+/// not.
+pub fn watch_project_config(
+    app_handle: AppHandle,
+    state: &WatcherState,
+    project_path: String,
+) -> Result<(), String> {
+    let key = format!("{}:config", project_path);
+
+    // Check if already watching
+    {
+        let watchers = state.watchers.lock().map_err(|e| e.to_string())?;
+        if watchers.contains_key(&key) {
+            return Ok(()); // Already watching
+        }
+    }
+
+    let project_root = PathBuf::from(&project_path);
+    if !project_root.is_dir() {
+        return Err(format!("Project directory not found: {}", project_path));
+    }
+    let config_files: Vec<PathBuf> = PROJECT_CONFIG_FILES
+        .iter()
+        .map(|file| project_root.join(file))
+        .collect();
+
+    let project_path_clone = project_path.clone();
+    let root = project_root.clone();
+    let activity = state.activity_arc();
+    let activity_key = key.clone();
+
+    // Create debounced watcher
+    let mut debouncer = new_debouncer(
+        state.debounce().session,
+        move |result: Result<Vec<notify_debouncer_mini::DebouncedEvent>, notify::Error>| {
+            let events = match result {
+                Ok(events) => events,
+                Err(e) => {
+                    record_watch_error(&activity, &activity_key, &e);
+                    return;
+                }
+            };
+
+            // Each changed config file is reported once per batch
+            let changed: HashSet<&PathBuf> = events
+                .iter()
+                .filter_map(|event| config_files.iter().find(|file| **file == event.path))
+                .collect();
+            if !changed.is_empty() {
+                record_event(&activity, &activity_key);
+            }
+
+            for file in changed {
+                let relative = file.strip_prefix(&root).unwrap_or(file);
+                let _ = app_handle.emit(
+                    "project-config-changed",
+                    ProjectConfigChangedPayload {
+                        project_path: project_path_clone.clone(),
+                        file: relative.to_string_lossy().to_string(),
+                    },
+                );
+            }
+        },
+    )
+    .map_err(|e| format!("Failed to create watcher: {}", e))?;
+
+    // Watch the project root and its .claude directory (config files are direct children)
+    debouncer
+        .watcher()
+        .watch(&project_root, RecursiveMode::NonRecursive)
+        .map_err(|e| format!("Failed to watch project root: {}", e))?;
+    let claude_dir = project_root.join(".claude");
+    if claude_dir.is_dir() {
+        debouncer
+            .watcher()
+            .watch(&claude_dir, RecursiveMode::NonRecursive)
+            .map_err(|e| format!("Failed to watch .claude dir: {}", e))?;
+    }
+
+    // Store the watcher handle
+    {
+        let mut watchers = state.watchers.lock().map_err(|e| e.to_string())?;
+        watchers.insert(key, WatcherHandle::new(debouncer));
+    }
+
+    Ok(())
+}
+
+/// Stop watching a project's agent configuration files.
+pub fn unwatch_project_config(state: &WatcherState, project_path: &str) -> Result<(), String> {
+    let key = format!("{}:config", project_path);
+    state.remove_watcher(&key)
+}
+
+/// Event payload sent to the frontend when a project's checked-out branch changes.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchChangedPayload {
+    pub project_path: String,
+    /// Branch checked out before the change (None when HEAD was detached)
+    pub previous_branch: Option<String>,
+    /// Branch checked out now (None when HEAD is detached)
+    pub branch: Option<String>,
+}
+
+/// Start watching a project's git HEAD, emitting "branch-changed" when the user
+/// switches branches.
+///
+/// Git replaces HEAD by renaming `HEAD.lock` over it, so the git directory is watched
+/// rather than the file itself.
+pub fn watch_git_head(
+    app_handle: AppHandle,
+    state: &WatcherState,
+    project_path: String,
+) -> Result<(), String> {
+    let key = format!("{}:git-head", project_path);
+
+    // Check if already watching
+    {
+        let watchers = state.watchers.lock().map_err(|e| e.to_string())?;
+        if watchers.contains_key(&key) {
+            return Ok(()); // Already watching
+        }
+    }
+
+    let head_path = get_git_head_path(&project_path)?;
+    let git_dir = head_path
+        .parent()
+        .map(PathBuf::from)
+        .ok_or_else(|| "HEAD has no parent directory".to_string())?;
+
+    let mut branch = get_current_branch(&project_path)?;
+    let project_path_clone = project_path.clone();
+    let activity = state.activity_arc();
+    let activity_key = key.clone();
+
+    // Create debounced watcher
+    let mut debouncer = new_debouncer(
+        state.debounce().session,
+        move |result: Result<Vec<notify_debouncer_mini::DebouncedEvent>, notify::Error>| {
+            let events = match result {
+                Ok(events) => events,
+                Err(e) => {
+                    record_watch_error(&activity, &activity_key, &e);
+                    return;
+                }
+            };
+            // Index, ref and object writes share the directory
+            if !events
+                .iter()
+                .any(|event| event.path.file_name() == head_path.file_name())
+            {
+                return;
+            }
+            record_event(&activity, &activity_key);
+
+            let current = match get_current_branch(&project_path_clone) {
+                Ok(current) => current,
+                Err(e) => {
+                    record_error(&activity, &activity_key, e);
+                    return;
+                }
+            };
+            if current == branch {
+                return;
+            }
+
+            let previous_branch = std::mem::replace(&mut branch, current.clone());
+            let _ = app_handle.emit(
+                "branch-changed",
+                BranchChangedPayload {
+                    project_path: project_path_clone.clone(),
+                    previous_branch,
+                    branch: current,
+                },
+            );
+        },
+    )
+    .map_err(|e| format!("Failed to create watcher: {}", e))?;
+
+    // Watch the git directory (HEAD is a direct child)
+    debouncer
+        .watcher()
+        .watch(&git_dir, RecursiveMode::NonRecursive)
+        .map_err(|e| format!("Failed to watch git dir: {}", e))?;
+
+    // Store the watcher handle
+    {
+        let mut watchers = state.watchers.lock().map_err(|e| e.to_string())?;
+        watchers.insert(key, WatcherHandle::new(debouncer));
+    }
+
+    Ok(())
+}
+
+/// Stop watching a project's git HEAD.
+pub fn unwatch_git_head(state: &WatcherState, project_path: &str) -> Result<(), String> {
+    let key = format!("{}:git-head", project_path);
+    state.remove_watcher(&key)
+}
+
+/// Event payload sent to the frontend when the set of running Claude sessions changes.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveSessionsChangedPayload {
+    /// Project paths with a running claude process
+    pub active_paths: HashSet<String>,
+    /// Paths that gained a running process since the last poll
+    pub started: Vec<String>,
+    /// Paths whose processes exited since the last poll
+    pub stopped: Vec<String>,
+}
+
+/// Event payload sent to the frontend when a claude process starts ("agent-started")
+/// or exits ("agent-stopped").
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentLifecyclePayload {
+    pub project_path: String,
+    pub pid: u32,
+    /// Session the process was writing, if known
+    pub session_id: Option<String>,
+    /// Seconds the process had been running when last seen
+    pub uptime_secs: u64,
+}
+
+/// Poll for running Claude processes every `interval`, emitting "active-sessions-changed"
+/// only when the set of active project paths changes, and "agent-started" /
+/// "agent-stopped" for each process that appears or exits. Pass None to stop polling.
+///
+/// Replaces the frontend polling `get_active_sessions` on a timer; the first poll is
+/// compared against the state when polling starts, so fetch that with the command.
+pub fn set_active_sessions_polling(
+    app_handle: AppHandle,
+    state: &WatcherState,
+    interval: Option<Duration>,
+) -> Result<(), String> {
+    if interval.is_some_and(|d| d.is_zero()) {
+        return Err("Polling interval must be greater than zero".to_string());
+    }
+
+    // Any running poller sees the new generation and exits
+    let generation = state.active_poll_generation.fetch_add(1, Ordering::SeqCst) + 1;
+    let Some(interval) = interval else {
+        return Ok(());
+    };
+
+    std::thread::spawn(move || {
+        let initial = get_active_sessions(&app_handle.state::<ProcessMonitor>());
+        let mut active = initial.active_paths;
+        let mut processes = processes_by_pid(initial.sessions);
+        loop {
+            std::thread::sleep(interval);
+            let state = app_handle.state::<WatcherState>();
+            if state.active_poll_generation.load(Ordering::SeqCst) != generation {
+                return;
+            }
+
+            let result = get_active_sessions(&app_handle.state::<ProcessMonitor>());
+            let current_processes = processes_by_pid(result.sessions);
+            emit_agent_lifecycle(&app_handle, &processes, &current_processes);
+            processes = current_processes;
+
+            let current = result.active_paths;
+            if current == active {
+                continue;
+            }
+            let mut started: Vec<String> = current.difference(&active).cloned().collect();
+            let mut stopped: Vec<String> = active.difference(&current).cloned().collect();
+            started.sort();
+            stopped.sort();
+
+            let _ = app_handle.emit(
+                "active-sessions-changed",
+                ActiveSessionsChangedPayload {
+                    active_paths: current.clone(),
+                    started,
+                    stopped,
+                },
+            );
+            active = current;
+        }
+    });
+
+    Ok(())
+}
+
+/// Event payload sent to the frontend after watchers are re-armed.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchersRestartedPayload {
+    /// Why the watchers were restarted ("wake", "error" or "manual")
+    pub reason: String,
+    /// Keys of the watchers that were recreated
+    pub keys: Vec<String>,
+    /// Watchers that couldn't be recreated and were dropped
+    pub failed: Vec<WatcherFailure>,
+}
+
+/// How often the wake monitor checks for sleep and watcher errors.
+const WAKE_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Wall-clock time passing this much faster than the monotonic clock means the system
+/// was asleep (the monotonic clock stops during sleep).
+const SLEEP_DETECTION_THRESHOLD: Duration = Duration::from_secs(5);
 
-    let mut watchers = state.watchers.lock().map_err(|e| e.to_string())?;
-    watchers.remove(&key);
+/// Start a background thread that re-arms all watchers after the system wakes from
+/// sleep (FSEvents streams can die across sleep/wake) or after a watcher reports an
+/// error, emitting "watchers-restarted".
+pub fn start_wake_monitor(app_handle: AppHandle) {
+    std::thread::spawn(move || {
+        let mut wall = SystemTime::now();
+        let mut monotonic = Instant::now();
+        loop {
+            std::thread::sleep(WAKE_CHECK_INTERVAL);
+            let slept = wall.elapsed().unwrap_or_default()
+                > monotonic.elapsed() + SLEEP_DETECTION_THRESHOLD;
+            wall = SystemTime::now();
+            monotonic = Instant::now();
+
+            let failed = WATCH_FAILED.swap(false, Ordering::SeqCst);
+            if !slept && !failed {
+                continue;
+            }
+            let reason = if slept { "wake" } else { "error" };
+            let state = app_handle.state::<WatcherState>();
+            if let Err(e) = restart_watchers(&app_handle, &state, reason) {
+                eprintln!("[watcher] Failed to restart watchers: {}", e);
+            }
+        }
+    });
+}
+
+/// Recreate every registered watcher, emitting "watchers-restarted" with their keys.
+///
+/// Shared project directory watchers are rebuilt in place, so session indices and
+/// subscriptions survive; standalone watchers are started again from their keys.
+/// Watchers that can't be recreated are dropped and listed in the payload's `failed`.
+pub fn restart_watchers(
+    app_handle: &AppHandle,
+    state: &WatcherState,
+    reason: &str,
+) -> Result<WatchersRestartedPayload, String> {
+    let mut failed = rearm_dir_watchers(state, state.debounce().session)?;
+
+    let (mut keys, standalone): (Vec<String>, Vec<(String, &'static str)>) = {
+        let watchers = state.watchers.lock().map_err(|e| e.to_string())?;
+        let subscribed = watchers
+            .iter()
+            .filter(|(_, handle)| handle.directory.is_some())
+            .map(|(key, _)| key.clone())
+            .collect();
+        let standalone = watchers
+            .iter()
+            .filter(|(_, handle)| handle.directory.is_none())
+            .map(|(key, handle)| (key.clone(), watcher_kind(key, handle)))
+            .collect();
+        (subscribed, standalone)
+    };
+
+    for (key, kind) in standalone {
+        // Drop the old watcher so watching the key again doesn't short-circuit
+        {
+            let mut watchers = state.watchers.lock().map_err(|e| e.to_string())?;
+            watchers.remove(&key);
+        }
+
+        let project_path = key.rsplit_once(':').map(|(path, _)| path.to_string());
+        let result = match (kind, project_path) {
+            ("projects-root", _) => watch_projects_root(app_handle.clone(), state),
+            ("todos", _) => watch_todos(app_handle.clone(), state),
+            ("telemetry", Some(path)) => watch_telemetry(app_handle.clone(), state, path),
+            ("config", Some(path)) => watch_project_config(app_handle.clone(), state, path),
+            ("git-head", Some(path)) => watch_git_head(app_handle.clone(), state, path),
+            _ => continue,
+        };
+        match result {
+            Ok(()) => keys.push(key),
+            Err(error) => {
+                eprintln!("[watcher] Failed to restart {}: {}", key, error);
+                failed.push(WatcherFailure {
+                    keys: vec![key],
+                    error,
+                });
+            }
+        }
+    }
+    keys.sort();
+
+    let payload = WatchersRestartedPayload {
+        reason: reason.to_string(),
+        keys,
+        failed,
+    };
+    let _ = app_handle.emit("watchers-restarted", payload.clone());
+
+    Ok(payload)
+}
+
+/// Key for the global watcher on `~/.claude/todos`.
+const TODOS_KEY: &str = "todos";
+
+/// Event payload sent to the frontend when Claude Code rewrites a todo list.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TodosChangedPayload {
+    pub session_id: String,
+    /// Agent the list belongs to (equal to the session ID for the main agent)
+    pub agent_id: String,
+    /// Full list after the change (empty if the file was removed)
+    pub todos: Vec<TodoItem>,
+}
+
+/// Start watching `~/.claude/todos`, emitting "todos-changed" with the parsed list each
+/// time Claude Code rewrites a session's todo file.
+pub fn watch_todos(app_handle: AppHandle, state: &WatcherState) -> Result<(), String> {
+    // Check if already watching
+    {
+        let watchers = state.watchers.lock().map_err(|e| e.to_string())?;
+        if watchers.contains_key(TODOS_KEY) {
+            return Ok(()); // Already watching
+        }
+    }
+
+    let todos_dir = get_claude_todos_dir().ok_or_else(|| "Home directory not found".to_string())?;
+
+    // Create the directory if it doesn't exist (so we can watch it)
+    if !todos_dir.exists() {
+        std::fs::create_dir_all(&todos_dir)
+            .map_err(|e| format!("Failed to create todos dir: {}", e))?;
+    }
+
+    let activity = state.activity_arc();
+
+    // Create debounced watcher
+    let mut debouncer = new_debouncer(
+        state.debounce().session,
+        move |result: Result<Vec<notify_debouncer_mini::DebouncedEvent>, notify::Error>| {
+            let events = match result {
+                Ok(events) => events,
+                Err(e) => {
+                    record_watch_error(&activity, TODOS_KEY, &e);
+                    return;
+                }
+            };
+
+            // Each changed list is reported once per batch
+            let changed: HashSet<&PathBuf> = events.iter().map(|event| &event.path).collect();
+            for path in changed {
+                let Some((session_id, agent_id)) = todo_file_owner(path) else {
+                    continue;
+                };
+                record_event(&activity, TODOS_KEY);
+                let _ = app_handle.emit(
+                    "todos-changed",
+                    TodosChangedPayload {
+                        session_id,
+                        agent_id,
+                        todos: read_todo_file(path),
+                    },
+                );
+            }
+        },
+    )
+    .map_err(|e| format!("Failed to create watcher: {}", e))?;
+
+    // Watch the todos directory
+    debouncer
+        .watcher()
+        .watch(&todos_dir, RecursiveMode::NonRecursive)
+        .map_err(|e| format!("Failed to watch todos dir: {}", e))?;
+
+    // Store the watcher handle
+    {
+        let mut watchers = state.watchers.lock().map_err(|e| e.to_string())?;
+        watchers.insert(TODOS_KEY.to_string(), WatcherHandle::new(debouncer));
+    }
 
     Ok(())
 }
+
+/// Stop watching `~/.claude/todos`.
+pub fn unwatch_todos(state: &WatcherState) -> Result<(), String> {
+    state.remove_watcher(TODOS_KEY)
+}
+
+/// Index running processes by PID.
+fn processes_by_pid(sessions: Vec<RunningSession>) -> HashMap<u32, RunningSession> {
+    sessions.into_iter().map(|s| (s.pid, s)).collect()
+}
+
+/// Emit "agent-started" / "agent-stopped" for processes that appeared or exited between
+/// two polls.
+fn emit_agent_lifecycle(
+    app_handle: &AppHandle,
+    previous: &HashMap<u32, RunningSession>,
+    current: &HashMap<u32, RunningSession>,
+) {
+    for (event, pid) in lifecycle_changes(previous, current) {
+        let process = match event {
+            "agent-started" => &current[&pid],
+            _ => &previous[&pid],
+        };
+        let _ = app_handle.emit(
+            event,
+            AgentLifecyclePayload {
+                project_path: process.project_path.clone(),
+                pid: process.pid,
+                session_id: process.session_id.clone(),
+                uptime_secs: process.uptime_secs,
+            },
+        );
+    }
+}
+
+/// PIDs that appeared ("agent-started") or exited ("agent-stopped") between two polls,
+/// started first and each in PID order.
+fn lifecycle_changes<T>(
+    previous: &HashMap<u32, T>,
+    current: &HashMap<u32, T>,
+) -> Vec<(&'static str, u32)> {
+    let changes = [
+        ("agent-started", current, previous),
+        ("agent-stopped", previous, current),
+    ];
+    let mut events = Vec::new();
+    for (event, from, other) in changes {
+        let mut pids: Vec<u32> = from
+            .keys()
+            .filter(|pid| !other.contains_key(pid))
+            .copied()
+            .collect();
+        pids.sort();
+        events.extend(pids.into_iter().map(|pid| (event, pid)));
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(id: &str) -> Session {
+        Session {
+            id: id.to_string(),
+            slug: None,
+            summary: None,
+            model: None,
+            version: None,
+            git_branch: None,
+            started_at: None,
+            last_activity: String::new(),
+            message_count: 0,
+        }
+    }
+
+    #[test]
+    fn test_subagent_id_from_path() {
+        assert_eq!(
+            subagent_id_from_path(Path::new("/p/agent-a1b2c3.jsonl")).as_deref(),
+            Some("a1b2c3")
+        );
+        assert_eq!(
+            subagent_id_from_path(Path::new("/p/agent-a1b2c3.json")),
+            None
+        );
+        assert_eq!(
+            subagent_id_from_path(Path::new("/p/040f5516-2ff1.jsonl")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_watcher_kind() {
+        let dir = Path::new("/p");
+        let subscribed = WatcherHandle::subscribed(dir);
+        let session = WatcherHandle::for_session(dir, "/proj", "s1");
+
+        assert_eq!(watcher_kind("/proj:s1", &session), "session");
+        assert_eq!(
+            watcher_kind(PROJECTS_ROOT_KEY, &subscribed),
+            "projects-root"
+        );
+        assert_eq!(watcher_kind(TODOS_KEY, &subscribed), "todos");
+        assert_eq!(
+            watcher_kind(&project_watcher_key("/proj"), &subscribed),
+            "project"
+        );
+        assert_eq!(watcher_kind("/proj:telemetry", &subscribed), "telemetry");
+        assert_eq!(watcher_kind("/proj:config", &subscribed), "config");
+        assert_eq!(watcher_kind("/proj:git-head", &subscribed), "git-head");
+        assert_eq!(watcher_kind("/proj:agent-a1", &subscribed), "subagent");
+    }
+
+    #[test]
+    fn test_unsettled_dirs() {
+        let root = Path::new("/home/u/.claude/projects");
+        let settled: HashSet<OsString> = [OsString::from("-old")].into_iter().collect();
+        let paths = [
+            root.join("-old/s1.jsonl"),
+            root.join("-new/s2.jsonl"),
+            root.join("-new/agent-a1.jsonl"),
+            root.join("-newer"),
+            PathBuf::from("/elsewhere/-other/s3.jsonl"),
+        ];
+
+        let dirs = unsettled_dirs(paths.iter().map(PathBuf::as_path), root, &settled);
+        let expected: HashSet<OsString> = [OsString::from("-new"), OsString::from("-newer")]
+            .into_iter()
+            .collect();
+        assert_eq!(dirs, expected);
+    }
+
+    #[test]
+    fn test_is_session_file() {
+        assert!(is_session_file(Path::new("/p/040f5516.jsonl")));
+        assert!(!is_session_file(Path::new("/p/agent-a1.jsonl")));
+        assert!(!is_session_file(Path::new("/p/notes.json")));
+    }
+
+    #[test]
+    fn test_diff_sessions() {
+        let known: HashSet<String> = ["s1", "s2", "s3"].iter().map(|s| s.to_string()).collect();
+        let (added, removed) = diff_sessions(&known, vec![session("s2"), session("s4")]);
+
+        let added: Vec<&str> = added.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(added, vec!["s4"]);
+        assert_eq!(removed, vec!["s1", "s3"]);
+
+        let (added, removed) =
+            diff_sessions(&known, vec![session("s1"), session("s2"), session("s3")]);
+        assert!(added.is_empty());
+        assert!(removed.is_empty());
+    }
+
+    #[test]
+    fn test_lifecycle_changes() {
+        let previous: HashMap<u32, ()> = [(1, ()), (2, ()), (5, ())].into_iter().collect();
+        let current: HashMap<u32, ()> = [(2, ()), (4, ()), (3, ())].into_iter().collect();
+
+        assert_eq!(
+            lifecycle_changes(&previous, &current),
+            vec![
+                ("agent-started", 3),
+                ("agent-started", 4),
+                ("agent-stopped", 1),
+                ("agent-stopped", 5),
+            ]
+        );
+        assert!(lifecycle_changes(&current, &current).is_empty());
+    }
+
+    #[test]
+    fn test_update_throttle_merges_updates_within_interval() {
+        let interval = Duration::from_millis(250);
+        let mut throttle = UpdateThrottle::new(interval);
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+
+        assert_eq!(throttle.request(at(0)), ThrottledUpdate::Now);
+
+        // Two changes inside the interval reparse once, when the interval ends
+        let reparses = [at(100), at(200)]
+            .into_iter()
+            .filter(|&now| throttle.request(now) != ThrottledUpdate::Covered)
+            .count();
+        assert_eq!(reparses, 1);
+        assert_eq!(
+            throttle.request(at(210)),
+            ThrottledUpdate::Covered,
+            "a scheduled flush covers later changes"
+        );
+
+        // Once the flush has run, the next change waits out the following interval
+        throttle.flush_pending().store(false, Ordering::SeqCst);
+        assert_eq!(
+            throttle.request(at(300)),
+            ThrottledUpdate::After(Duration::from_millis(200))
+        );
+        throttle.flush_pending().store(false, Ordering::SeqCst);
+        assert_eq!(throttle.request(at(800)), ThrottledUpdate::Now);
+    }
+
+    #[test]
+    fn test_rearm_dir_watchers_drops_dead_directories() {
+        let state = WatcherState::new();
+        let live = tempfile::tempdir().unwrap();
+        let dead = tempfile::tempdir().unwrap();
+        for (dir, key) in [(live.path(), "/live:s-1"), (dead.path(), "/dead:s-2")] {
+            let handler: BatchHandler = Box::new(|_: &[notify_debouncer_mini::DebouncedEvent]| {});
+            state.subscribe(dir, key, handler).unwrap();
+            state
+                .watchers
+                .lock()
+                .unwrap()
+                .insert(key.to_string(), WatcherHandle::subscribed(dir));
+        }
+        let dead_path = dead.path().to_path_buf();
+        dead.close().unwrap();
+
+        let failures = rearm_dir_watchers(&state, Duration::from_millis(50)).unwrap();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].keys, vec!["/dead:s-2"]);
+
+        let dir_watchers = state.dir_watchers.lock().unwrap();
+        assert!(dir_watchers.contains_key(live.path()));
+        assert!(!dir_watchers.contains_key(&dead_path));
+        drop(dir_watchers);
+        assert!(state.is_watching("/live:s-1"));
+        assert!(!state.is_watching("/dead:s-2"));
+    }
+}
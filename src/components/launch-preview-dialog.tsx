@@ -0,0 +1,101 @@
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Button } from "@/components/ui/button";
+import {
+  Dialog,
+  DialogBody,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from "@/components/ui/dialog";
+import { loadLaunchOptions } from "@/lib/launch-options";
+import { loadRemoteHost } from "@/lib/remote-hosts";
+import type { LaunchPreview, TerminalType } from "@/lib/types";
+import { CUSTOM_TERMINAL_STORAGE_KEY } from "@/pages/settings";
+
+interface LaunchPreviewDialogProps {
+  /** Project to preview a new session for (null when closed) */
+  projectPath: string | null;
+  terminalType: TerminalType;
+  onClose: () => void;
+}
+
+export function LaunchPreviewDialog({
+  projectPath,
+  terminalType,
+  onClose,
+}: LaunchPreviewDialogProps) {
+  const [preview, setPreview] = useState<LaunchPreview | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!projectPath) return;
+    setPreview(null);
+    setError(null);
+    invoke<LaunchPreview>("preview_launch_command", {
+      terminalType,
+      projectPath,
+      continueSession: false,
+      yoloMode: false,
+      options: { ...loadLaunchOptions(), remote: loadRemoteHost(projectPath) },
+      customTemplate: localStorage.getItem(CUSTOM_TERMINAL_STORAGE_KEY),
+    })
+      .then(setPreview)
+      .catch((err) => setError(String(err)));
+  }, [projectPath, terminalType]);
+
+  return (
+    <Dialog
+      open={projectPath !== null}
+      onOpenChange={(open) => !open && onClose()}
+    >
+      <DialogContent className="max-w-2xl">
+        <DialogHeader>
+          <DialogTitle>Launch Command</DialogTitle>
+          <DialogDescription>
+            What a new session would run, exactly as escaped. Nothing is
+            launched.
+          </DialogDescription>
+        </DialogHeader>
+        <DialogBody className="space-y-3">
+          {error && <p className="text-sm text-destructive">{error}</p>}
+          {preview && (
+            <>
+              <PreviewBlock label="Working directory" value={preview.cwd} />
+              <PreviewBlock
+                label="Shell command"
+                value={
+                  preview.command ??
+                  "None: this terminal only opens the project"
+                }
+              />
+              {preview.appleScript && (
+                <PreviewBlock label="AppleScript" value={preview.appleScript} />
+              )}
+            </>
+          )}
+        </DialogBody>
+        <DialogFooter>
+          <Button variant="ghost" size="sm" onClick={onClose}>
+            Close
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+}
+
+function PreviewBlock({ label, value }: { label: string; value: string }) {
+  return (
+    <div>
+      <div className="text-xs font-medium text-muted-foreground mb-1">
+        {label}
+      </div>
+      <pre className="text-xs bg-muted/30 p-2 rounded overflow-auto max-h-48 font-mono whitespace-pre-wrap break-all select-text">
+        {value}
+      </pre>
+    </div>
+  );
+}
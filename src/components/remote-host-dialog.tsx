@@ -0,0 +1,93 @@
+import { useState, useEffect } from "react";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { Field, FieldGroup, FieldLabel } from "@/components/ui/field";
+import {
+  Dialog,
+  DialogBody,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from "@/components/ui/dialog";
+import { loadRemoteHost, saveRemoteHost } from "@/lib/remote-hosts";
+
+interface RemoteHostDialogProps {
+  /** Project being configured (null when closed) */
+  projectPath: string | null;
+  onClose: () => void;
+}
+
+export function RemoteHostDialog({
+  projectPath,
+  onClose,
+}: RemoteHostDialogProps) {
+  const [host, setHost] = useState("");
+  const [path, setPath] = useState("");
+
+  // Load the current settings whenever the dialog opens for a project
+  useEffect(() => {
+    if (!projectPath) return;
+    const remote = loadRemoteHost(projectPath);
+    setHost(remote?.host ?? "");
+    setPath(remote?.path ?? "");
+  }, [projectPath]);
+
+  const handleSave = () => {
+    if (!projectPath) return;
+    const trimmedHost = host.trim();
+    saveRemoteHost(
+      projectPath,
+      trimmedHost ? { host: trimmedHost, path: path.trim() || null } : null
+    );
+    onClose();
+  };
+
+  return (
+    <Dialog
+      open={projectPath !== null}
+      onOpenChange={(open) => !open && onClose()}
+    >
+      <DialogContent className="max-w-md">
+        <DialogHeader>
+          <DialogTitle>Remote Host</DialogTitle>
+          <DialogDescription>
+            Run Claude sessions for this project on a remote host over SSH.
+            Leave the host empty to launch locally.
+          </DialogDescription>
+        </DialogHeader>
+        <DialogBody>
+          <FieldGroup>
+            <Field>
+              <FieldLabel htmlFor="remote-host">SSH host</FieldLabel>
+              <Input
+                id="remote-host"
+                value={host}
+                onChange={(e) => setHost(e.target.value)}
+                placeholder="user@dev-server"
+              />
+            </Field>
+            <Field>
+              <FieldLabel htmlFor="remote-path">Remote project path</FieldLabel>
+              <Input
+                id="remote-path"
+                value={path}
+                onChange={(e) => setPath(e.target.value)}
+                placeholder={projectPath ?? ""}
+              />
+            </Field>
+          </FieldGroup>
+        </DialogBody>
+        <DialogFooter>
+          <Button variant="ghost" size="sm" onClick={onClose}>
+            Cancel
+          </Button>
+          <Button size="sm" onClick={handleSave}>
+            Save
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+}
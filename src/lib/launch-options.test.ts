@@ -0,0 +1,41 @@
+import { describe, it, expect, beforeEach } from "vitest";
+import {
+  LAUNCH_OPTIONS_STORAGE_KEY,
+  loadLaunchOptions,
+  saveLaunchOptions,
+} from "./launch-options";
+
+describe("launch options", () => {
+  beforeEach(() => {
+    localStorage.clear();
+  });
+
+  it("should default to no options", () => {
+    expect(loadLaunchOptions()).toEqual({
+      model: null,
+      permissionMode: null,
+      addDirs: [],
+      extraArgs: [],
+      env: {},
+    });
+  });
+
+  it("should round-trip saved options", () => {
+    const options = {
+      model: "opus",
+      permissionMode: "plan" as const,
+      addDirs: ["/work/lib"],
+      extraArgs: ["--verbose"],
+      env: { HTTPS_PROXY: "http://proxy:8080" },
+    };
+    saveLaunchOptions(options);
+
+    expect(loadLaunchOptions()).toEqual(options);
+  });
+
+  it("should ignore invalid saved data", () => {
+    localStorage.setItem(LAUNCH_OPTIONS_STORAGE_KEY, "{not json");
+
+    expect(loadLaunchOptions().extraArgs).toEqual([]);
+  });
+});
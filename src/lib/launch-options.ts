@@ -0,0 +1,27 @@
+import type { LaunchOptions } from "./types";
+
+export const LAUNCH_OPTIONS_STORAGE_KEY = "agent-console:launch-options";
+
+const emptyLaunchOptions: LaunchOptions = {
+  model: null,
+  permissionMode: null,
+  addDirs: [],
+  extraArgs: [],
+  env: {},
+};
+
+// Load the saved launch options, falling back to none for missing or invalid data
+export function loadLaunchOptions(): LaunchOptions {
+  try {
+    const saved = localStorage.getItem(LAUNCH_OPTIONS_STORAGE_KEY);
+    return saved
+      ? { ...emptyLaunchOptions, ...(JSON.parse(saved) as Partial<LaunchOptions>) }
+      : emptyLaunchOptions;
+  } catch {
+    return emptyLaunchOptions;
+  }
+}
+
+export function saveLaunchOptions(options: LaunchOptions): void {
+  localStorage.setItem(LAUNCH_OPTIONS_STORAGE_KEY, JSON.stringify(options));
+}
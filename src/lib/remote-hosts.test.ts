@@ -0,0 +1,25 @@
+import { describe, it, expect, beforeEach } from "vitest";
+import { loadRemoteHost, saveRemoteHost } from "./remote-hosts";
+
+describe("remote hosts", () => {
+  beforeEach(() => {
+    localStorage.clear();
+  });
+
+  it("should store a host per project", () => {
+    saveRemoteHost("/work/app", { host: "dev@box", path: "/srv/app" });
+
+    expect(loadRemoteHost("/work/app")).toEqual({
+      host: "dev@box",
+      path: "/srv/app",
+    });
+    expect(loadRemoteHost("/work/other")).toBeNull();
+  });
+
+  it("should clear a host", () => {
+    saveRemoteHost("/work/app", { host: "dev@box", path: null });
+    saveRemoteHost("/work/app", null);
+
+    expect(loadRemoteHost("/work/app")).toBeNull();
+  });
+});
@@ -0,0 +1,31 @@
+import type { RemoteTarget } from "./types";
+
+export const REMOTE_HOSTS_STORAGE_KEY = "agent-console:remote-hosts";
+
+function loadRemoteHosts(): Record<string, RemoteTarget> {
+  try {
+    const saved = localStorage.getItem(REMOTE_HOSTS_STORAGE_KEY);
+    return saved ? (JSON.parse(saved) as Record<string, RemoteTarget>) : {};
+  } catch {
+    return {};
+  }
+}
+
+// Get the SSH host configured for a project, if any
+export function loadRemoteHost(projectPath: string): RemoteTarget | null {
+  return loadRemoteHosts()[projectPath] ?? null;
+}
+
+// Set or clear (with null) the SSH host for a project
+export function saveRemoteHost(
+  projectPath: string,
+  remote: RemoteTarget | null
+): void {
+  const hosts = loadRemoteHosts();
+  if (remote) {
+    hosts[projectPath] = remote;
+  } else {
+    delete hosts[projectPath];
+  }
+  localStorage.setItem(REMOTE_HOSTS_STORAGE_KEY, JSON.stringify(hosts));
+}
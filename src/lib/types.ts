@@ -41,6 +41,8 @@ export interface Project {
   lastActivity: string;
   /** Individual sessions (sorted by last activity, descending) */
   sessions: Session[];
+  /** Number of uncommitted changes (null unless requested, or not a git repository) */
+  uncommittedChanges: number | null;
 }
 
 export interface ActiveSessionsResult {
@@ -48,6 +50,62 @@ export interface ActiveSessionsResult {
   supported: boolean;
   /** Set of project paths with active Claude sessions */
   activePaths: string[];
+  /** Number of running claude processes per project path */
+  activeCounts: Record<string, number>;
+  /** Each running claude process and the session it is writing */
+  sessions: RunningSession[];
+}
+
+/** A running claude process matched to its session */
+export interface RunningSession {
+  /** Process ID */
+  pid: number;
+  /** Project path (the process's working directory) */
+  projectPath: string;
+  /** Session the process is writing (null if it couldn't be determined) */
+  sessionId: string | null;
+  /** CPU usage since the previous check (100 = one full core) */
+  cpuPercent: number;
+  /** Resident memory in bytes */
+  memoryBytes: number;
+  /** Seconds since the process started */
+  uptimeSecs: number;
+  /** When the process started (ISO 8601) */
+  startedAt: string | null;
+  /** Whether the process has been running unusually long (over 4 hours) */
+  longRunning: boolean;
+  /** tmux pane the process runs in (null outside tmux) */
+  tmux: TmuxPane | null;
+  /** Whether this is a non-interactive run (`claude -p`), which can't be continued in a terminal */
+  headless: boolean;
+  /** Terminal application the process runs in (null inside tmux or if unrecognized) */
+  terminal: HostTerminal | null;
+  /** ID of the launch that started the process, if started from this app */
+  launchId: string | null;
+}
+
+/** A terminal application hosting a claude process */
+export interface HostTerminal {
+  /** Display name (e.g. "iTerm2", "VS Code") */
+  name: string;
+  /** PID of the terminal's process */
+  pid: number;
+  /** Matching launchable terminal, if any */
+  terminalType: TerminalType | null;
+}
+
+/** A tmux pane hosting a claude process */
+export interface TmuxPane {
+  /** Pane ID (e.g. "%3"), usable as a tmux target */
+  paneId: string;
+  /** Socket of the tmux server the pane belongs to */
+  socketPath: string | null;
+  /** Session name (null if tmux couldn't be queried) */
+  sessionName: string | null;
+  /** Window index within the session */
+  windowIndex: number | null;
+  /** Pane index within the window */
+  paneIndex: number | null;
 }
 
 export type TerminalType =
@@ -59,7 +117,15 @@ export type TerminalType =
   | "konsole"
   | "alacritty"
   | "warp"
-  | "cursor";
+  | "cursor"
+  | "wezterm"
+  | "kitty"
+  | "vscode"
+  | "zellij"
+  | "powershell"
+  | "cmd"
+  | "wsl"
+  | "custom";
 
 export const terminalDisplayNames: Record<TerminalType, string> = {
   "macos-terminal": "Terminal",
@@ -71,10 +137,82 @@ export const terminalDisplayNames: Record<TerminalType, string> = {
   alacritty: "Alacritty",
   warp: "Warp",
   cursor: "Cursor",
+  wezterm: "WezTerm",
+  kitty: "kitty",
+  vscode: "VS Code",
+  zellij: "zellij",
+  powershell: "PowerShell",
+  cmd: "Command Prompt",
+  wsl: "WSL",
+  custom: "Custom Command",
 };
 
+// Launch types - matches Rust structs in launch.rs
+export type PermissionMode =
+  | "default"
+  | "acceptEdits"
+  | "plan"
+  | "bypassPermissions";
+
+export interface LaunchOptions {
+  /** Model alias or name (--model) */
+  model: string | null;
+  /** Permission mode (--permission-mode) */
+  permissionMode: PermissionMode | null;
+  /** Additional directories Claude may access (--add-dir) */
+  addDirs: string[];
+  /** Extra arguments, each passed as a single argument */
+  extraArgs: string[];
+  /** Environment variables to set for the claude process */
+  env: Record<string, string>;
+  /** Container to run claude in, instead of on the host */
+  container?: ContainerTarget | null;
+  /** SSH host to run claude on, instead of locally */
+  remote?: RemoteTarget | null;
+}
+
+export type ContainerTarget =
+  | { kind: "devcontainer" }
+  | { kind: "docker"; container: string; workdir: string | null };
+
+/** An SSH host a project's sessions run on */
+export interface RemoteTarget {
+  /** SSH destination, e.g. user@host or an alias from ~/.ssh/config */
+  host: string;
+  /** Project directory on the remote host (defaults to the local path) */
+  path: string | null;
+}
+
+export type LaunchStatus = "starting" | "running" | "exited";
+
+/** A claude session launched from this app */
+export interface LaunchedSession {
+  /** Launch ID, returned by launch_claude */
+  id: string;
+  /** Project the session was launched in */
+  projectPath: string;
+  /** Terminal it was launched in */
+  terminalType: TerminalType;
+  /** When it was launched (ISO 8601) */
+  launchedAt: string;
+  /** Process ID of the claude process, once seen */
+  pid: number | null;
+  /** Current status */
+  status: LaunchStatus;
+}
+
+/** What launch_claude would run, from preview_launch_command */
+export interface LaunchPreview {
+  /** Directory the terminal starts in */
+  cwd: string;
+  /** Shell command the terminal runs (null when it only opens the project) */
+  command: string | null;
+  /** AppleScript passed to osascript (macOS terminals driven by AppleScript) */
+  appleScript: string | null;
+}
+
 // File edit types - matches Rust structs in claude_code.rs
-export type FileEditType = "added" | "modified" | "deleted";
+export type FileEditType = "added" | "modified" | "deleted" | "renamed";
 
 export interface FileEdit {
   /** Relative path from project root */
@@ -83,6 +221,18 @@ export interface FileEdit {
   editType: FileEditType;
   /** Timestamp of the last edit to this file (ISO 8601) */
   lastEditedAt: string | null;
+  /** ID of the sub-agent that made the edits (null when the main session edited the file) */
+  editedBy: string | null;
+  /** Lines added across all edits */
+  linesAdded: number;
+  /** Lines removed across all edits */
+  linesRemoved: number;
+  /** Number of edit operations (each MultiEdit sub-edit counts) */
+  editCount: number;
+  /** Path the file had before being moved in the session */
+  renamedFrom: string | null;
+  /** Binary or machine-generated content (lockfiles, minified bundles) */
+  isGenerated: boolean;
 }
 
 export interface FileDiff {
@@ -94,6 +244,18 @@ export interface FileDiff {
   sequence: number;
   /** Timestamp of the change (ISO 8601) */
   timestamp: string | null;
+  /** ID of the sub-agent that made the change (null when the main session made it) */
+  editedBy: string | null;
+  /** ID of the notebook cell that was changed (NotebookEdit only) */
+  cellId: string | null;
+  /** Notebook edit mode: "replace", "insert", or "delete" (NotebookEdit only) */
+  cellEditMode: string | null;
+  /** Monaco language identifier detected from the file name or shebang */
+  language: string;
+  /** Whether the edit would apply to the file currently on disk (null if unknown) */
+  appliesCleanly: boolean | null;
+  /** Whether the edit could be reverted on the file currently on disk (null if unknown) */
+  revertsCleanly: boolean | null;
 }
 
 export interface GitFileDiff {
@@ -105,6 +267,12 @@ export interface GitFileDiff {
   existsAtHead: boolean;
   /** Whether the file exists in working directory */
   existsInWorkdir: boolean;
+  /** Kind of change from HEAD to the working directory (null when unchanged) */
+  changeType: "added" | "modified" | "deleted" | "renamed" | "typechange" | null;
+  /** Whether the file is new and not yet staged */
+  untracked: boolean;
+  /** Path of the submodule containing the file (null when not in a submodule) */
+  submodule: string | null;
 }
 
 // Session Event Log types
@@ -117,6 +285,18 @@ export interface CompactMetadata {
   preTokens: number;
 }
 
+/** Token usage reported on an assistant event */
+export interface TokenUsage {
+  /** Uncached input tokens */
+  inputTokens: number;
+  /** Output tokens generated */
+  outputTokens: number;
+  /** Input tokens read from the prompt cache */
+  cacheReadInputTokens: number;
+  /** Input tokens written to the prompt cache */
+  cacheCreationInputTokens: number;
+}
+
 /** A single event in the session log */
 export interface SessionEvent {
   /** Sequence number (line number in file, 0-indexed) */
@@ -161,6 +341,19 @@ export interface SessionEvent {
   isToolResult: boolean;
   /** Whether this is a meta/context injection (isMeta: true) */
   isMeta: boolean;
+  /** Token usage (only for assistant events that report it) */
+  usage: TokenUsage | null;
+}
+
+/** Content kind used for event previews */
+export type PreviewSource = "text" | "thinking" | "tool";
+
+/** Options for building event previews (all fields optional) */
+export interface PreviewOptions {
+  /** Maximum preview length in characters (default 500) */
+  maxChars?: number;
+  /** Content kinds to try, in order (default text, thinking, tool) */
+  precedence?: PreviewSource[];
 }
 
 /** Paginated response for session events */
@@ -173,6 +366,8 @@ export interface SessionEventsResponse {
   offset: number;
   /** Whether there are more events after this page */
   hasMore: boolean;
+  /** Opaque cursor for the next (older) page, stable while the file grows */
+  nextCursor: string | null;
 }
 
 // =============================================================================
@@ -197,6 +392,121 @@ export interface PolicyEvaluation {
   traceId: string;
 }
 
+/** Filters for get_policy_evaluations; unset fields match everything */
+export interface PolicyEvaluationFilter {
+  /** Final decision, case-insensitive (e.g., "Deny") */
+  decision?: string | null;
+  /** Tool name, case-insensitive (e.g., "Bash") */
+  toolName?: string | null;
+  /** Event type, case-insensitive (e.g., "PreToolUse") */
+  eventType?: string | null;
+  /** Only evaluations at or after this time (ISO 8601) */
+  since?: string | null;
+  /** Only evaluations at or before this time (ISO 8601) */
+  until?: string | null;
+  /** Trace ID, or a prefix of it */
+  traceId?: string | null;
+}
+
+/** A page of policy evaluations, newest first */
+export interface PolicyEvaluationsResponse {
+  /** Evaluations for the requested page (unreadable telemetry files are skipped) */
+  evaluations: PolicyEvaluation[];
+  /** Total number of telemetry files */
+  totalCount: number;
+  /** Current offset */
+  offset: number;
+  /** Whether there are more evaluations after this page */
+  hasMore: boolean;
+}
+
+/** A policy evaluation's phases and results (matches Rust PolicyEvaluationDetail) */
+export interface PolicyEvaluationDetail {
+  /** Summary shown in the evaluation list */
+  evaluation: PolicyEvaluation;
+  /** Harness that sent the event (e.g., "ClaudeCode") */
+  harness: string | null;
+  /** Reason given with the final decision */
+  reason: string | null;
+  /** Messages returned to the agent with the final decision */
+  agentMessages: string[];
+  /** Policy phases in evaluation order (global, catalogs, project) */
+  phases: PolicyPhaseDetail[];
+  /** Errors encountered during evaluation */
+  errors: string[];
+}
+
+/** One phase of a policy evaluation */
+export interface PolicyPhaseDetail {
+  /** Phase name (e.g., "global", "project", "catalog:xyz") */
+  name: string;
+  /** When the phase started, in milliseconds after the evaluation started */
+  startOffsetMs: number;
+  /** Duration in milliseconds */
+  durationMs: number;
+  /** Whether the event was routed to this phase's policies */
+  routed: boolean;
+  /** Names of policies that matched the event */
+  matchedPolicies: string[];
+  /** Reason the phase exited early, if it did */
+  exitReason: string | null;
+  /** The phase's decision (e.g., "Allow", "Deny") */
+  decision: string | null;
+  /** Rule results that contributed to the decision */
+  results: PolicyRuleResult[];
+  /** Signals run to gather input for the policies */
+  signals: PolicySignalResult[];
+}
+
+/** A policy rule's verdict, such as a denial and its reason */
+export interface PolicyRuleResult {
+  /** Verdict kind */
+  kind: "halt" | "deny" | "block" | "ask";
+  /** ID of the rule that produced it */
+  ruleId: string;
+  /** Violation message */
+  reason: string;
+  /** Severity level */
+  severity: string | null;
+}
+
+/** A signal run during a policy phase */
+export interface PolicySignalResult {
+  /** Signal name */
+  name: string;
+  /** Command that was run */
+  command: string;
+  /** Duration in milliseconds */
+  durationMs: number | null;
+  /** Exit code, if the signal ran a process */
+  exitCode: number | null;
+}
+
+/** Kind of file in a project's .cupcake directory */
+export type PolicyFileKind = "policy" | "guidebook" | "rulebook";
+
+/** A Cupcake policy or configuration file (matches Rust PolicyFile) */
+export interface PolicyFile {
+  /** Path relative to the .cupcake directory (e.g., "policies/no_force_push.rego") */
+  path: string;
+  /** Kind of file */
+  kind: PolicyFileKind;
+  /** Rego package (e.g., "cupcake.policies.no_force_push"), for policies */
+  package: string | null;
+  /** Size in bytes */
+  size: number;
+  /** Last modified (ISO 8601) */
+  modified: string;
+}
+
+/** A Cupcake policy or configuration file with its contents */
+export interface PolicyFileContent {
+  /** File metadata */
+  file: PolicyFile;
+  /** File contents */
+  content: string;
+}
+
 /** Harness type that generated the event */
 export type HarnessType = "ClaudeCode" | "Cursor" | "OpenCode" | "Factory";
 
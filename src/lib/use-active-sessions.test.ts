@@ -23,6 +23,8 @@ describe("useActiveSessions", () => {
     vi.mocked(invoke).mockResolvedValue({
       supported: true,
       activePaths: [],
+      activeCounts: {},
+      sessions: [],
     });
 
     const { result } = renderHook(() => useActiveSessions());
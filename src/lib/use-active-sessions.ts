@@ -9,6 +9,10 @@ interface UseActiveSessionsResult {
   supported: boolean;
   /** Check if a project path has an active session */
   isActive: (projectPath: string) => boolean;
+  /** Number of running claude processes in a project */
+  activeCount: (projectPath: string) => number;
+  /** Check if a specific session is being written by a running process */
+  isSessionRunning: (sessionId: string) => boolean;
   /** Refresh the active sessions */
   refresh: () => void;
 }
@@ -17,6 +21,12 @@ const POLL_INTERVAL_MS = 5000; // Poll every 5 seconds
 
 export function useActiveSessions(): UseActiveSessionsResult {
   const [activePaths, setActivePaths] = useState<Set<string>>(new Set());
+  const [activeCounts, setActiveCounts] = useState<Record<string, number>>(
+    {}
+  );
+  const [runningSessionIds, setRunningSessionIds] = useState<Set<string>>(
+    new Set()
+  );
   const [supported, setSupported] = useState(true);
 
   const fetchActiveSessions = useCallback(async () => {
@@ -25,6 +35,12 @@ export function useActiveSessions(): UseActiveSessionsResult {
       setSupported(result.supported);
       if (result.supported) {
         setActivePaths(new Set(result.activePaths));
+        setActiveCounts(result.activeCounts);
+        setRunningSessionIds(
+          new Set(
+            result.sessions.flatMap((s) => (s.sessionId ? [s.sessionId] : []))
+          )
+        );
       }
     } catch {
       // If the command fails, assume not supported
@@ -47,10 +63,22 @@ export function useActiveSessions(): UseActiveSessionsResult {
     [activePaths]
   );
 
+  const activeCount = useCallback(
+    (projectPath: string) => activeCounts[projectPath] ?? 0,
+    [activeCounts]
+  );
+
+  const isSessionRunning = useCallback(
+    (sessionId: string) => runningSessionIds.has(sessionId),
+    [runningSessionIds]
+  );
+
   return {
     activePaths,
     supported,
     isActive,
+    activeCount,
+    isSessionRunning,
     refresh: fetchActiveSessions,
   };
 }
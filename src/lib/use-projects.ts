@@ -18,7 +18,9 @@ export function useProjects(): UseProjectsResult {
     setLoading(true);
     setError(null);
     try {
-      const result = await invoke<Project[]>("get_projects");
+      const result = await invoke<Project[]>("get_projects", {
+        includeGitStatus: true,
+      });
       setProjects(result);
     } catch (err) {
       setError(err instanceof Error ? err.message : String(err));
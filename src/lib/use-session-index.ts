@@ -1,7 +1,7 @@
 import { useState, useEffect, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
-import type { IndexStatus } from "./types";
+import type { IndexStatus, SessionEvent } from "./types";
 
 export type IndexState = "idle" | "indexing" | "ready" | "error";
 
@@ -21,6 +21,14 @@ interface UseSessionIndexResult {
 interface SessionChangedPayload {
   projectPath: string;
   sessionId: string;
+  /** First sequence appended by this change (null if the index wasn't updated) */
+  appendedFrom: number | null;
+  /** Total number of events after the change */
+  totalCount: number | null;
+  /** True if the index was rebuilt and cached pages are stale */
+  reset: boolean;
+  /** Appended events, inlined when the append is small */
+  events: SessionEvent[] | null;
 }
 
 interface IndexReadyPayload {
@@ -304,6 +304,9 @@ export function EditViewer({
                             {dirPath && (
                               <span className="text-muted-foreground"> | {dirPath}</span>
                             )}
+                            {edit.renamedFrom && (
+                              <span className="text-muted-foreground"> ← {edit.renamedFrom}</span>
+                            )}
                           </span>
                           {edit.lastEditedAt && (
                             <span className="shrink-0 text-[0.65rem] text-muted-foreground">
@@ -417,7 +420,9 @@ export function EditViewer({
                     <div className="bg-muted/50 px-3 py-1.5 text-xs font-medium border-b border-border flex items-center justify-between">
                       <span>HEAD vs Current</span>
                       <span className="text-muted-foreground font-normal">
-                        {!gitDiff.existsAtHead && "(new file)"}
+                        {gitDiff.submodule && `(submodule ${gitDiff.submodule}) `}
+                        {!gitDiff.existsAtHead &&
+                          (gitDiff.untracked ? "(new file, untracked)" : "(new file)")}
                         {!gitDiff.existsInWorkdir && "(deleted)"}
                       </span>
                     </div>
@@ -490,7 +495,7 @@ export function EditViewer({
                                 </div>
                                 <DiffEditor
                                   height={height}
-                                  language={getLanguageFromPath(selectedFile)}
+                                  language={diff.language}
                                   original={diff.oldString}
                                   modified={diff.newString}
                                   theme={monacoTheme}
@@ -512,13 +517,19 @@ export function EditViewer({
                                     </div>
                                   }
                                 />
-                                {/* Show what led to this edit */}
-                                <EditContextView
-                                  projectPath={projectPath}
-                                  sessionId={sessionId}
-                                  filePath={selectedFile}
-                                  editIndex={diff.sequence}
-                                />
+                                {/* Show what led to this edit (the index only traces main-session edits) */}
+                                {diff.editedBy === null && (
+                                  <EditContextView
+                                    projectPath={projectPath}
+                                    sessionId={sessionId}
+                                    filePath={selectedFile}
+                                    editIndex={
+                                      diffs.filter(
+                                        (d) => d.editedBy === null && d.sequence < diff.sequence
+                                      ).length
+                                    }
+                                  />
+                                )}
                               </div>
                             );
                           })}
@@ -0,0 +1,102 @@
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { cn } from "@/lib/utils";
+import type { PolicyFile, PolicyFileContent } from "@/lib/types";
+import { formatRelativeTime } from "../utils";
+
+interface PolicyFilesProps {
+  projectPath: string;
+}
+
+/** The project's Cupcake policies and configuration, with a file viewer */
+export function PolicyFiles({ projectPath }: PolicyFilesProps) {
+  const [files, setFiles] = useState<PolicyFile[]>([]);
+  const [loading, setLoading] = useState(true);
+  const [selectedPath, setSelectedPath] = useState<string | null>(null);
+  const [selectedFile, setSelectedFile] = useState<PolicyFileContent | null>(
+    null
+  );
+
+  useEffect(() => {
+    setLoading(true);
+    invoke<PolicyFile[]>("get_policy_files", { projectPath })
+      .then(setFiles)
+      .catch((err) => console.error("Failed to load policy files:", err))
+      .finally(() => setLoading(false));
+  }, [projectPath]);
+
+  useEffect(() => {
+    if (!selectedPath) {
+      setSelectedFile(null);
+      return;
+    }
+    invoke<PolicyFileContent>("get_policy_file", {
+      projectPath,
+      path: selectedPath,
+    })
+      .then(setSelectedFile)
+      .catch((err) => {
+        console.error("Failed to load policy file:", err);
+        setSelectedFile(null);
+      });
+  }, [projectPath, selectedPath]);
+
+  if (loading) {
+    return (
+      <div className="h-full flex items-center justify-center text-muted-foreground">
+        <p className="text-sm">Loading policies...</p>
+      </div>
+    );
+  }
+
+  if (files.length === 0) {
+    return (
+      <div className="h-full flex items-center justify-center text-muted-foreground">
+        <p className="text-sm">Select an evaluation to view trace</p>
+      </div>
+    );
+  }
+
+  return (
+    <div className="h-full flex flex-col">
+      <div className="shrink-0 px-4 py-2 border-b border-border text-xs text-muted-foreground">
+        Select an evaluation to view its trace, or a policy to view its source
+      </div>
+      <div className="flex-1 min-h-0 flex">
+        <div className="w-64 shrink-0 overflow-auto border-r border-border py-1">
+          {files.map((file) => (
+            <button
+              key={file.path}
+              onClick={() => setSelectedPath(file.path)}
+              className={cn(
+                "w-full flex flex-col gap-0.5 px-3 py-1.5 text-left text-xs transition-colors",
+                selectedPath === file.path
+                  ? "bg-accent text-accent-foreground"
+                  : "hover:bg-muted"
+              )}
+            >
+              <span className="font-mono truncate">{file.path}</span>
+              <span className="text-[0.65rem] text-muted-foreground truncate">
+                {file.package ?? file.kind} ·{" "}
+                {formatRelativeTime(file.modified)}
+              </span>
+            </button>
+          ))}
+        </div>
+        <div className="flex-1 min-w-0 overflow-auto">
+          {selectedFile ? (
+            <pre className="p-4 text-xs font-mono whitespace-pre select-text">
+              {selectedFile.content}
+            </pre>
+          ) : (
+            <div className="h-full flex items-center justify-center text-muted-foreground">
+              <p className="text-sm">
+                {files.length} {files.length === 1 ? "file" : "files"}
+              </p>
+            </div>
+          )}
+        </div>
+      </div>
+    </div>
+  );
+}
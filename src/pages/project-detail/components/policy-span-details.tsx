@@ -1,14 +1,25 @@
 import { cn } from "@/lib/utils";
-import type { CupcakeSpan, SignalExecution, DecisionResult } from "@/lib/types";
+import type {
+  CupcakeSpan,
+  SignalExecution,
+  DecisionResult,
+  PolicyEvaluationDetail,
+} from "@/lib/types";
 import { type TraceSpan, getDecisionBadgeClass, formatDuration, getSpanColor } from "../policy-utils";
 import { JsonViewer } from "./json-viewer";
 
 interface PolicySpanDetailsProps {
   span: TraceSpan | null;
   cupcakeSpan: CupcakeSpan | null;
+  /** Parsed evaluation, summarized when no span is selected */
+  detail?: PolicyEvaluationDetail | null;
 }
 
-export function PolicySpanDetails({ span, cupcakeSpan }: PolicySpanDetailsProps) {
+export function PolicySpanDetails({ span, cupcakeSpan, detail }: PolicySpanDetailsProps) {
+  if (!span && detail) {
+    return <EvaluationOverview detail={detail} />;
+  }
+
   if (!span) {
     return (
       <div className="h-full flex items-center justify-center text-muted-foreground p-4 border-t border-border bg-background">
@@ -95,6 +106,72 @@ export function PolicySpanDetails({ span, cupcakeSpan }: PolicySpanDetailsProps)
   );
 }
 
+/** Final decision and rule violations of a whole evaluation */
+function EvaluationOverview({ detail }: { detail: PolicyEvaluationDetail }) {
+  const phasesWithResults = detail.phases.filter((p) => p.results.length > 0);
+
+  return (
+    <div className="h-full overflow-y-auto p-4 space-y-3 bg-background border-t border-border">
+      <div className="flex items-center gap-2">
+        <span
+          className={cn(
+            "px-1.5 py-0.5 rounded text-[0.65rem] font-medium",
+            getDecisionBadgeClass(detail.evaluation.decision)
+          )}
+        >
+          {detail.evaluation.decision || "?"}
+        </span>
+        {detail.reason && <span className="text-xs">{detail.reason}</span>}
+      </div>
+
+      {detail.agentMessages.length > 0 && (
+        <div className="space-y-1">
+          <p className="text-[0.65rem] font-medium text-muted-foreground">
+            Messages to agent
+          </p>
+          {detail.agentMessages.map((message, i) => (
+            <p key={i} className="text-xs">
+              {message}
+            </p>
+          ))}
+        </div>
+      )}
+
+      {phasesWithResults.map((phase) => (
+        <div key={phase.name} className="space-y-1">
+          <p className="text-[0.65rem] font-medium text-muted-foreground">
+            {phase.name} ({formatDuration(phase.durationMs)})
+          </p>
+          <DecisionList
+            label="Violations"
+            decisions={phase.results.map((r) => ({
+              ruleId: r.ruleId,
+              reason: r.reason,
+              severity: r.severity ?? "",
+            }))}
+            color={phase.results.every((r) => r.kind === "ask") ? "yellow" : "red"}
+          />
+        </div>
+      ))}
+
+      {detail.errors.length > 0 && (
+        <div className="space-y-1">
+          <p className="text-[0.65rem] font-medium text-red-500">Errors</p>
+          {detail.errors.map((error, i) => (
+            <p key={i} className="text-xs text-red-500">
+              {error}
+            </p>
+          ))}
+        </div>
+      )}
+
+      <p className="text-xs text-muted-foreground">
+        Select a span to view details
+      </p>
+    </div>
+  );
+}
+
 function RootDetails({
   data,
   cupcakeSpan,
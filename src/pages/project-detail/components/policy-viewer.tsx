@@ -16,8 +16,23 @@ import {
   TooltipContent,
   TooltipTrigger,
 } from "@/components/ui/tooltip";
+import { Input } from "@/components/ui/input";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
 import { cn } from "@/lib/utils";
-import type { PolicyEvaluation, CupcakeSpan } from "@/lib/types";
+import type {
+  PolicyEvaluation,
+  PolicyEvaluationDetail,
+  PolicyEvaluationFilter,
+  PolicyEvaluationsResponse,
+  CupcakeSpan,
+  FinalDecisionType,
+} from "@/lib/types";
 import { formatRelativeTime } from "../utils";
 import {
   flattenCupcakeSpan,
@@ -28,6 +43,35 @@ import {
 } from "../policy-utils";
 import { PolicyTraceChart } from "./policy-trace-chart";
 import { PolicySpanDetails } from "./policy-span-details";
+import { PolicyFiles } from "./policy-files";
+
+const PAGE_SIZE = 100;
+
+const ALL = "all";
+
+const DECISIONS: FinalDecisionType[] = [
+  "Allow",
+  "Block",
+  "Deny",
+  "Halt",
+  "Ask",
+  "Modify",
+];
+
+type TimeRange = typeof ALL | "hour" | "day" | "week";
+
+const timeRangeMs: Record<Exclude<TimeRange, typeof ALL>, number> = {
+  hour: 60 * 60 * 1000,
+  day: 24 * 60 * 60 * 1000,
+  week: 7 * 24 * 60 * 60 * 1000,
+};
+
+const timeRangeLabels: Record<TimeRange, string> = {
+  all: "Any time",
+  hour: "Last hour",
+  day: "Last day",
+  week: "Last week",
+};
 
 interface PolicyViewerProps {
   projectPath: string;
@@ -36,9 +80,16 @@ interface PolicyViewerProps {
 export function PolicyViewer({ projectPath }: PolicyViewerProps) {
   const [evaluations, setEvaluations] = useState<PolicyEvaluation[]>([]);
   const [loading, setLoading] = useState(true);
+  const [totalCount, setTotalCount] = useState(0);
+  const [hasMore, setHasMore] = useState(false);
+  const [loadingMore, setLoadingMore] = useState(false);
+  // Number of evaluations loaded, kept when the list refreshes
+  const loadedCountRef = useRef(PAGE_SIZE);
   const [selectedEvaluation, setSelectedEvaluation] =
     useState<PolicyEvaluation | null>(null);
   const [cupcakeSpan, setCupcakeSpan] = useState<CupcakeSpan | null>(null);
+  const [evaluationDetail, setEvaluationDetail] =
+    useState<PolicyEvaluationDetail | null>(null);
   const [spanLoading, setSpanLoading] = useState(false);
   const [selectedSpan, setSelectedSpan] = useState<TraceSpan | null>(null);
   const [sidebarCollapsed, setSidebarCollapsed] = useState(false);
@@ -47,14 +98,38 @@ export function PolicyViewer({ projectPath }: PolicyViewerProps) {
     new Set()
   );
   const prevTimestampsRef = useRef<Map<string, string>>(new Map());
+  const [decisionFilter, setDecisionFilter] = useState<string>(ALL);
+  const [timeRange, setTimeRange] = useState<TimeRange>(ALL);
+  const [toolFilter, setToolFilter] = useState("");
+
+  const buildFilter = useCallback((): PolicyEvaluationFilter => {
+    const tool = toolFilter.trim();
+    return {
+      decision: decisionFilter === ALL ? null : decisionFilter,
+      toolName: tool || null,
+      since:
+        timeRange === ALL
+          ? null
+          : new Date(Date.now() - timeRangeMs[timeRange]).toISOString(),
+    };
+  }, [decisionFilter, timeRange, toolFilter]);
 
-  // Load evaluations
+  // Load evaluations (as many as are already shown, so refreshes keep the list)
   const loadEvaluations = useCallback(async () => {
     try {
-      const evals = await invoke<PolicyEvaluation[]>("get_policy_evaluations", {
-        projectPath,
-      });
+      const response = await invoke<PolicyEvaluationsResponse>(
+        "get_policy_evaluations",
+        {
+          projectPath,
+          offset: 0,
+          limit: loadedCountRef.current,
+          filter: buildFilter(),
+        }
+      );
+      const evals = response.evaluations;
       setEvaluations(evals);
+      setTotalCount(response.totalCount);
+      setHasMore(response.hasMore);
 
       // Track new evaluations for flash animation
       const newFlashing = new Set<string>();
@@ -79,10 +154,36 @@ export function PolicyViewer({ projectPath }: PolicyViewerProps) {
     } finally {
       setLoading(false);
     }
-  }, [projectPath]);
+  }, [projectPath, buildFilter]);
+
+  // Load the next page
+  const loadMore = useCallback(async () => {
+    setLoadingMore(true);
+    try {
+      const response = await invoke<PolicyEvaluationsResponse>(
+        "get_policy_evaluations",
+        {
+          projectPath,
+          offset: loadedCountRef.current,
+          limit: PAGE_SIZE,
+          filter: buildFilter(),
+        }
+      );
+      loadedCountRef.current += PAGE_SIZE;
+      setEvaluations((prev) => [...prev, ...response.evaluations]);
+      setTotalCount(response.totalCount);
+      setHasMore(response.hasMore);
+    } catch (err) {
+      console.error("Failed to load policy evaluations:", err);
+    } finally {
+      setLoadingMore(false);
+    }
+  }, [projectPath, buildFilter]);
 
-  // Initial load
+  // Initial load, and reload from the first page when filters change
   useEffect(() => {
+    loadedCountRef.current = PAGE_SIZE;
+    prevTimestampsRef.current = new Map();
     loadEvaluations();
   }, [loadEvaluations]);
 
@@ -122,6 +223,7 @@ export function PolicyViewer({ projectPath }: PolicyViewerProps) {
   useEffect(() => {
     if (!selectedEvaluation) {
       setCupcakeSpan(null);
+      setEvaluationDetail(null);
       setSelectedSpan(null);
       return;
     }
@@ -130,10 +232,15 @@ export function PolicyViewer({ projectPath }: PolicyViewerProps) {
       setSpanLoading(true);
       setSelectedSpan(null);
       try {
-        const rawJson = await invoke<string | null>("get_policy_evaluation", {
-          projectPath,
-          filename: selectedEvaluation!.filename,
-        });
+        const args = { projectPath, filename: selectedEvaluation!.filename };
+        const [rawJson, detail] = await Promise.all([
+          invoke<string | null>("get_policy_evaluation", args),
+          invoke<PolicyEvaluationDetail | null>(
+            "get_policy_evaluation_detail",
+            args
+          ),
+        ]);
+        setEvaluationDetail(detail);
         if (rawJson) {
           const rawSpan = JSON.parse(rawJson);
           const span = snakeToCamelKeys<CupcakeSpan>(rawSpan);
@@ -144,6 +251,7 @@ export function PolicyViewer({ projectPath }: PolicyViewerProps) {
       } catch (err) {
         console.error("Failed to load span:", err);
         setCupcakeSpan(null);
+        setEvaluationDetail(null);
       } finally {
         setSpanLoading(false);
       }
@@ -152,6 +260,9 @@ export function PolicyViewer({ projectPath }: PolicyViewerProps) {
     loadSpan();
   }, [projectPath, selectedEvaluation]);
 
+  const filtersActive =
+    decisionFilter !== ALL || timeRange !== ALL || toolFilter.trim() !== "";
+
   // Flatten span for chart
   const traceSpans = cupcakeSpan ? flattenCupcakeSpan(cupcakeSpan) : [];
 
@@ -235,16 +346,74 @@ export function PolicyViewer({ projectPath }: PolicyViewerProps) {
                   <IconChevronLeft className="size-3.5" />
                 </button>
                 <span className="text-xs font-medium text-muted-foreground">
-                  Evaluations ({evaluations.length})
+                  Evaluations ({totalCount})
                 </span>
                 {loading && <span className="text-xs text-muted-foreground animate-pulse">...</span>}
               </div>
 
+              {/* Filters */}
+              <div className="shrink-0 px-3 py-2 border-b border-border flex flex-col gap-1.5">
+                <div className="flex gap-1.5">
+                  <Select
+                    value={decisionFilter}
+                    onValueChange={setDecisionFilter}
+                  >
+                    <SelectTrigger
+                      size="sm"
+                      className="flex-1 text-xs"
+                      aria-label="Decision"
+                    >
+                      <SelectValue />
+                    </SelectTrigger>
+                    <SelectContent>
+                      <SelectItem value={ALL}>Any decision</SelectItem>
+                      {DECISIONS.map((decision) => (
+                        <SelectItem key={decision} value={decision}>
+                          {decision}
+                        </SelectItem>
+                      ))}
+                    </SelectContent>
+                  </Select>
+                  <Select
+                    value={timeRange}
+                    onValueChange={(value) => setTimeRange(value as TimeRange)}
+                  >
+                    <SelectTrigger
+                      size="sm"
+                      className="flex-1 text-xs"
+                      aria-label="Time range"
+                    >
+                      <SelectValue />
+                    </SelectTrigger>
+                    <SelectContent>
+                      {(Object.keys(timeRangeLabels) as TimeRange[]).map(
+                        (range) => (
+                          <SelectItem key={range} value={range}>
+                            {timeRangeLabels[range]}
+                          </SelectItem>
+                        )
+                      )}
+                    </SelectContent>
+                  </Select>
+                </div>
+                <Input
+                  value={toolFilter}
+                  onChange={(e) => setToolFilter(e.target.value)}
+                  placeholder="Tool name"
+                  className="h-7 text-xs"
+                  aria-label="Tool name"
+                />
+              </div>
+
               <div className="flex-1 overflow-auto">
                 {loading && evaluations.length === 0 ? (
                   <div className="px-3 py-4 text-xs text-muted-foreground">
                     Loading evaluations...
                   </div>
+                ) : evaluations.length === 0 && filtersActive ? (
+                  <div className="px-3 py-4 text-xs text-muted-foreground">
+                    No evaluations match these filters.
+                  </div>
                 ) : evaluations.length === 0 ? (
                   <div className="px-3 py-4 text-xs text-muted-foreground">
                     <p className="mb-2">No policy evaluations found.</p>
@@ -287,6 +456,17 @@ export function PolicyViewer({ projectPath }: PolicyViewerProps) {
                         </div>
                       </button>
                     ))}
+                    {hasMore && (
+                      <button
+                        onClick={loadMore}
+                        disabled={loadingMore}
+                        className="w-full px-3 py-2 text-xs text-muted-foreground hover:text-foreground hover:bg-muted transition-colors"
+                      >
+                        {loadingMore
+                          ? "Loading..."
+                          : `Load more (${totalCount - evaluations.length} remaining)`}
+                      </button>
+                    )}
                   </div>
                 )}
               </div>
@@ -306,9 +486,7 @@ export function PolicyViewer({ projectPath }: PolicyViewerProps) {
       {/* Content area */}
       <Panel defaultSize={75} minSize={30}>
         {!selectedEvaluation ? (
-          <div className="h-full flex items-center justify-center text-muted-foreground">
-            <p className="text-sm">Select an evaluation to view trace</p>
-          </div>
+          <PolicyFiles projectPath={projectPath} />
         ) : spanLoading ? (
           <div className="h-full flex items-center justify-center text-muted-foreground">
             <p className="text-sm">Loading trace...</p>
@@ -338,6 +516,7 @@ export function PolicyViewer({ projectPath }: PolicyViewerProps) {
               <PolicySpanDetails
                 span={selectedSpan}
                 cupcakeSpan={cupcakeSpan}
+                detail={evaluationDetail}
               />
             </Panel>
           </PanelGroup>
@@ -4,6 +4,7 @@ import {
   IconCheck,
   IconChevronDown,
   IconLoader2,
+  IconPlayerPlay,
 } from "@tabler/icons-react";
 import {
   DropdownMenu,
@@ -14,7 +15,13 @@ import {
 import { Button } from "@/components/ui/button";
 import { cn } from "@/lib/utils";
 import { useSessionIndex } from "@/lib/use-session-index";
-import type { Session, ActiveSessionsResult, FileEdit, FileDiff, SessionEvent, SessionEventsResponse, SearchResponse } from "@/lib/types";
+import { loadLaunchOptions } from "@/lib/launch-options";
+import type { Session, ActiveSessionsResult, FileEdit, FileDiff, SessionEvent, SessionEventsResponse, SearchResponse, TerminalType } from "@/lib/types";
+import {
+  CUSTOM_TERMINAL_STORAGE_KEY,
+  TERMINAL_STORAGE_KEY,
+  WSL_DISTRO_STORAGE_KEY,
+} from "@/pages/settings";
 import { formatRelativeTime, truncateUuid } from "./utils";
 import { EditViewer } from "./components/edit-viewer";
 import { EventLogViewer } from "./components/event-log-viewer";
@@ -46,6 +53,7 @@ export function ProjectDetailPage({ projectPath }: ProjectDetailPageProps) {
   const [eventsLoadingMore, setEventsLoadingMore] = useState(false);
   const [eventsTotalCount, setEventsTotalCount] = useState(0);
   const [eventsHasMore, setEventsHasMore] = useState(false);
+  const [eventsCursor, setEventsCursor] = useState<string | null>(null);
   const [eventFilter, setEventFilter] = useState<string>("all");
   const [eventFilterMode, setEventFilterMode] = useState<EventFilterMode>("filter");
   const [selectedSubagentId, setSelectedSubagentId] = useState<string | null>(null);
@@ -146,6 +154,30 @@ export function ProjectDetailPage({ projectPath }: ProjectDetailPageProps) {
 
   const selectedSession = sessions.find((s) => s.id === selectedSessionId);
 
+  // Reopen the selected session in the preferred terminal
+  const resumeSession = useCallback(async () => {
+    if (!selectedSessionId) return;
+    try {
+      const terminals = await invoke<TerminalType[]>("get_available_terminals");
+      const saved = localStorage.getItem(TERMINAL_STORAGE_KEY) as TerminalType | null;
+      const terminalType = saved && terminals.includes(saved) ? saved : terminals[0];
+      if (!terminalType) return;
+
+      await invoke("launch_claude", {
+        terminalType,
+        projectPath,
+        continueSession: false,
+        yoloMode: false,
+        resumeSessionId: selectedSessionId,
+        options: loadLaunchOptions(),
+        customTemplate: localStorage.getItem(CUSTOM_TERMINAL_STORAGE_KEY),
+        wslDistro: localStorage.getItem(WSL_DISTRO_STORAGE_KEY),
+      });
+    } catch (err) {
+      console.error("Failed to resume session:", err);
+    }
+  }, [projectPath, selectedSessionId]);
+
   // Load initial events when switching to events tab
   // Uses indexed command for O(k) seeks instead of O(n) scan
   const loadEvents = useCallback(async () => {
@@ -153,6 +185,7 @@ export function ProjectDetailPage({ projectPath }: ProjectDetailPageProps) {
       setEvents([]);
       setEventsTotalCount(0);
       setEventsHasMore(false);
+      setEventsCursor(null);
       return;
     }
 
@@ -168,11 +201,13 @@ export function ProjectDetailPage({ projectPath }: ProjectDetailPageProps) {
       setEvents(response.events);
       setEventsTotalCount(response.totalCount);
       setEventsHasMore(response.hasMore);
+      setEventsCursor(response.nextCursor);
     } catch (err) {
       console.error("Failed to load events:", err);
       setEvents([]);
       setEventsTotalCount(0);
       setEventsHasMore(false);
+      setEventsCursor(null);
     } finally {
       setEventsLoading(false);
     }
@@ -209,17 +244,20 @@ export function ProjectDetailPage({ projectPath }: ProjectDetailPageProps) {
       const response = await invoke<SessionEventsResponse>("get_indexed_events", {
         projectPath,
         sessionId: selectedSessionId,
+        // Cursor keeps pages stable if new events were appended since the last load
         offset: events.length,
+        cursor: eventsCursor,
         limit: 200,
       });
       setEvents((prev) => [...prev, ...response.events]);
       setEventsHasMore(response.hasMore);
+      setEventsCursor(response.nextCursor);
     } catch (err) {
       console.error("Failed to load more events:", err);
     } finally {
       setEventsLoadingMore(false);
     }
-  }, [projectPath, selectedSessionId, events.length, eventsLoadingMore, eventsHasMore]);
+  }, [projectPath, selectedSessionId, events.length, eventsCursor, eventsLoadingMore, eventsHasMore]);
 
   // Load events when tab switches to events
   useEffect(() => {
@@ -233,6 +271,7 @@ export function ProjectDetailPage({ projectPath }: ProjectDetailPageProps) {
     setEvents([]);
     setEventsTotalCount(0);
     setEventsHasMore(false);
+    setEventsCursor(null);
     setSearchQuery("");
     setSearchResults(null);
     setSearchEvents([]);
@@ -395,7 +434,19 @@ export function ProjectDetailPage({ projectPath }: ProjectDetailPageProps) {
         </div>
 
         {/* Right: Session selector */}
-        <div className="shrink-0">
+        <div className="shrink-0 flex items-center gap-1">
+          {!loading && selectedSession && (
+            <Button
+              variant="ghost"
+              size="sm"
+              className="gap-1 h-7 px-2 text-xs"
+              onClick={resumeSession}
+              title="Resume this session in a terminal"
+            >
+              <IconPlayerPlay className="size-3" />
+              Resume
+            </Button>
+          )}
           {loading ? (
             <IconLoader2 className="size-4 animate-spin text-muted-foreground" />
           ) : sessions.length > 0 ? (
@@ -103,6 +103,12 @@ describe("buildFileTree", () => {
     path,
     editType,
     lastEditedAt: new Date().toISOString(),
+    editedBy: null,
+    linesAdded: 0,
+    linesRemoved: 0,
+    editCount: 1,
+    renamedFrom: null,
+    isGenerated: false,
   });
 
   it("should build a tree from flat file paths", () => {
@@ -303,6 +309,7 @@ describe("getEventDisplayLabel", () => {
     isCompactSummary: null,
     isToolResult: false,
     isMeta: false,
+    usage: null,
     ...overrides,
   });
 
@@ -401,6 +408,7 @@ describe("getEventBadgeClass", () => {
     isCompactSummary: null,
     isToolResult: false,
     isMeta: false,
+    usage: null,
     ...overrides,
   });
 
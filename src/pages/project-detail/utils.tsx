@@ -2,6 +2,7 @@ import {
   IconPlus,
   IconMinus,
   IconPlusMinus,
+  IconArrowRight,
 } from "@tabler/icons-react";
 import { useTheme } from "@/components/theme-provider";
 import type { FileEdit, FileEditType, SessionEvent } from "@/lib/types";
@@ -137,6 +138,8 @@ export function getFileEditIcon(editType: FileEditType) {
       return <IconPlusMinus className="size-3.5 shrink-0 text-yellow-500" />;
     case "deleted":
       return <IconMinus className="size-3.5 shrink-0 text-red-500" />;
+    case "renamed":
+      return <IconArrowRight className="size-3.5 shrink-0 text-blue-500" />;
   }
 }
 
@@ -42,6 +42,7 @@ describe("SessionsPage", () => {
       activePaths: new Set(),
       supported: true,
       isActive: vi.fn().mockReturnValue(false),
+      activeCount: vi.fn().mockReturnValue(0),
       refresh: vi.fn(),
     });
   });
@@ -188,6 +189,9 @@ describe("SessionsPage", () => {
       activePaths: new Set(["/active/project"]),
       supported: true,
       isActive: vi.fn().mockImplementation((path) => path === "/active/project"),
+      activeCount: vi.fn().mockImplementation((path) =>
+        path === "/active/project" ? 1 : 0
+      ),
       refresh: vi.fn(),
     });
 
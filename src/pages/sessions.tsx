@@ -9,6 +9,9 @@ import {
   IconFolderOpen,
   IconTerminal2,
   IconSquareRoundedPlus,
+  IconBox,
+  IconServer,
+  IconEye,
 } from "@tabler/icons-react";
 import {
   Tooltip,
@@ -26,8 +29,16 @@ import {
 import { invoke } from "@tauri-apps/api/core";
 import { useProjects } from "@/lib/use-projects";
 import { useActiveSessions } from "@/lib/use-active-sessions";
-import type { AgentType, TerminalType } from "@/lib/types";
-import { TERMINAL_STORAGE_KEY } from "@/pages/settings";
+import type { AgentType, ContainerTarget, TerminalType } from "@/lib/types";
+import { loadLaunchOptions } from "@/lib/launch-options";
+import { loadRemoteHost } from "@/lib/remote-hosts";
+import { RemoteHostDialog } from "@/components/remote-host-dialog";
+import { LaunchPreviewDialog } from "@/components/launch-preview-dialog";
+import {
+  CUSTOM_TERMINAL_STORAGE_KEY,
+  TERMINAL_STORAGE_KEY,
+  WSL_DISTRO_STORAGE_KEY,
+} from "@/pages/settings";
 import { terminalDisplayNames } from "@/lib/types";
 
 const INITIAL_DISPLAY_COUNT = 8;
@@ -78,11 +89,25 @@ interface SessionsPageProps {
 export function SessionsPage({ onSelectProject }: SessionsPageProps) {
   const resolvedTheme = useResolvedTheme();
   const { projects, loading, error } = useProjects();
-  const { supported: activeSessionsSupported, isActive } = useActiveSessions();
+  const {
+    supported: activeSessionsSupported,
+    isActive,
+    activeCount,
+  } = useActiveSessions();
   const [showAll, setShowAll] = useState(false);
   const [selectedTerminal, setSelectedTerminal] = useState<TerminalType | null>(
     null
   );
+  // Projects with a devcontainer, checked when their launch menu is opened
+  const [devcontainerProjects, setDevcontainerProjects] = useState<Set<string>>(
+    new Set()
+  );
+  // Project whose remote host is being configured
+  const [remoteDialogProject, setRemoteDialogProject] = useState<
+    string | null
+  >(null);
+  // Project whose launch command is being previewed
+  const [previewProject, setPreviewProject] = useState<string | null>(null);
 
   // Load terminal preference from settings
   useEffect(() => {
@@ -98,10 +123,26 @@ export function SessionsPage({ onSelectProject }: SessionsPageProps) {
     });
   }, []);
 
+  const checkDevcontainer = async (projectPath: string) => {
+    const hasDevcontainer = await invoke<boolean>("has_devcontainer", {
+      projectPath,
+    });
+    setDevcontainerProjects((prev) => {
+      const next = new Set(prev);
+      if (hasDevcontainer) {
+        next.add(projectPath);
+      } else {
+        next.delete(projectPath);
+      }
+      return next;
+    });
+  };
+
   const launchClaude = async (
     projectPath: string,
     continueSession: boolean,
-    yoloMode: boolean
+    yoloMode: boolean,
+    container: ContainerTarget | null = null
   ) => {
     if (!selectedTerminal) return;
     try {
@@ -110,6 +151,13 @@ export function SessionsPage({ onSelectProject }: SessionsPageProps) {
         projectPath,
         continueSession,
         yoloMode,
+        options: {
+          ...loadLaunchOptions(),
+          container,
+          remote: loadRemoteHost(projectPath),
+        },
+        customTemplate: localStorage.getItem(CUSTOM_TERMINAL_STORAGE_KEY),
+        wslDistro: localStorage.getItem(WSL_DISTRO_STORAGE_KEY),
       });
     } catch (err) {
       console.error("Failed to launch Claude:", err);
@@ -159,6 +207,8 @@ export function SessionsPage({ onSelectProject }: SessionsPageProps) {
               <div className="divide-y divide-border">
                 {displayedProjects.map((project) => {
                   const projectIsActive = isActive(project.projectPath);
+                  const projectActiveCount = activeCount(project.projectPath);
+                  const remoteHost = loadRemoteHost(project.projectPath)?.host;
                   return (
                     <div
                       key={project.projectPath}
@@ -173,6 +223,19 @@ export function SessionsPage({ onSelectProject }: SessionsPageProps) {
                           <span className="text-sm font-medium truncate">
                             {project.projectName}
                           </span>
+                          {!!project.uncommittedChanges && (
+                            <Tooltip>
+                              <TooltipTrigger asChild>
+                                <span className="size-1.5 rounded-full bg-amber-500 shrink-0" />
+                              </TooltipTrigger>
+                              <TooltipContent>
+                                {project.uncommittedChanges} uncommitted{" "}
+                                {project.uncommittedChanges === 1
+                                  ? "change"
+                                  : "changes"}
+                              </TooltipContent>
+                            </Tooltip>
+                          )}
                           <IconArrowRight className="size-3.5 text-muted-foreground opacity-0 group-hover:opacity-100 transition-opacity shrink-0" />
                         </div>
                         <div className="text-xs text-muted-foreground truncate">
@@ -209,7 +272,7 @@ export function SessionsPage({ onSelectProject }: SessionsPageProps) {
                         {activeSessionsSupported && (
                           <Tooltip>
                             <TooltipTrigger asChild>
-                              <span className="cursor-default">
+                              <span className="cursor-default flex items-center gap-0.5">
                                 <IconTerminal2
                                   className={`size-4 ${
                                     projectIsActive
@@ -217,17 +280,28 @@ export function SessionsPage({ onSelectProject }: SessionsPageProps) {
                                       : "text-muted-foreground/40"
                                   }`}
                                 />
+                                {projectActiveCount > 1 && (
+                                  <span className="text-[10px] font-medium text-green-500 tabular-nums">
+                                    {projectActiveCount}
+                                  </span>
+                                )}
                               </span>
                             </TooltipTrigger>
                             <TooltipContent>
-                              {projectIsActive
-                                ? "Active session"
-                                : "No active session"}
+                              {projectActiveCount > 1
+                                ? `${projectActiveCount} active sessions`
+                                : projectIsActive
+                                  ? "Active session"
+                                  : "No active session"}
                             </TooltipContent>
                           </Tooltip>
                         )}
                         {selectedTerminal && (
-                          <DropdownMenu>
+                          <DropdownMenu
+                            onOpenChange={(open) => {
+                              if (open) checkDevcontainer(project.projectPath);
+                            }}
+                          >
                             <Tooltip>
                               <TooltipTrigger asChild>
                                 <DropdownMenuTrigger asChild>
@@ -243,6 +317,11 @@ export function SessionsPage({ onSelectProject }: SessionsPageProps) {
                               <TooltipContent>New session</TooltipContent>
                             </Tooltip>
                             <DropdownMenuContent align="end" className="w-56">
+                              {remoteHost && (
+                                <DropdownMenuLabel className="truncate">
+                                  On {remoteHost}
+                                </DropdownMenuLabel>
+                              )}
                               <DropdownMenuItem
                                 onClick={() =>
                                   launchClaude(
@@ -263,6 +342,24 @@ export function SessionsPage({ onSelectProject }: SessionsPageProps) {
                                 <IconTerminal2 className="size-4" />
                                 New session (YOLO)
                               </DropdownMenuItem>
+                              {!remoteHost &&
+                                devcontainerProjects.has(
+                                  project.projectPath
+                                ) && (
+                                  <DropdownMenuItem
+                                    onClick={() =>
+                                      launchClaude(
+                                        project.projectPath,
+                                        false,
+                                        false,
+                                        { kind: "devcontainer" }
+                                      )
+                                    }
+                                  >
+                                    <IconBox className="size-4" />
+                                    New session in devcontainer
+                                  </DropdownMenuItem>
+                                )}
                               <DropdownMenuSeparator />
                               <DropdownMenuLabel>
                                 Or continue last session
@@ -283,6 +380,23 @@ export function SessionsPage({ onSelectProject }: SessionsPageProps) {
                                 <IconTerminal2 className="size-4" />
                                 Continue session (YOLO)
                               </DropdownMenuItem>
+                              <DropdownMenuSeparator />
+                              <DropdownMenuItem
+                                onClick={() =>
+                                  setRemoteDialogProject(project.projectPath)
+                                }
+                              >
+                                <IconServer className="size-4" />
+                                Remote host…
+                              </DropdownMenuItem>
+                              <DropdownMenuItem
+                                onClick={() =>
+                                  setPreviewProject(project.projectPath)
+                                }
+                              >
+                                <IconEye className="size-4" />
+                                Preview launch command
+                              </DropdownMenuItem>
                             </DropdownMenuContent>
                           </DropdownMenu>
                         )}
@@ -331,6 +445,18 @@ export function SessionsPage({ onSelectProject }: SessionsPageProps) {
         </div>
       </div>
 
+      <RemoteHostDialog
+        projectPath={remoteDialogProject}
+        onClose={() => setRemoteDialogProject(null)}
+      />
+      {selectedTerminal && (
+        <LaunchPreviewDialog
+          projectPath={previewProject}
+          terminalType={selectedTerminal}
+          onClose={() => setPreviewProject(null)}
+        />
+      )}
+
       {/* Status Bar */}
       <div className="shrink-0 border-t border-border bg-muted/30 px-3 py-1 flex items-center justify-end">
         <Tooltip>
@@ -1,7 +1,11 @@
 import { describe, it, expect, vi, beforeEach, afterEach } from "vitest";
 import { render, screen, waitFor } from "@testing-library/react";
 import userEvent from "@testing-library/user-event";
-import { SettingsPage, TERMINAL_STORAGE_KEY } from "./settings";
+import {
+  CUSTOM_TERMINAL_STORAGE_KEY,
+  SettingsPage,
+  TERMINAL_STORAGE_KEY,
+} from "./settings";
 import { ThemeProvider } from "@/components/theme-provider";
 
 // Mock Tauri API
@@ -230,6 +234,28 @@ describe("SettingsPage", () => {
     });
   });
 
+  describe("Custom terminal command", () => {
+    it("should persist the command template when custom is selected", async () => {
+      const user = userEvent.setup();
+      localStorage.setItem(TERMINAL_STORAGE_KEY, "custom");
+      vi.mocked(invoke).mockResolvedValue(["alacritty", "custom"]);
+
+      render(
+        <TestWrapper>
+          <SettingsPage onBack={mockOnBack} />
+        </TestWrapper>
+      );
+
+      await user.click(screen.getByRole("button", { name: /terminal/i }));
+      const input = await screen.findByLabelText("Custom terminal command");
+      await user.type(input, "foot sh -c {{cmd}");
+
+      expect(localStorage.getItem(CUSTOM_TERMINAL_STORAGE_KEY)).toBe(
+        "foot sh -c {cmd}"
+      );
+    });
+  });
+
   describe("Navigation highlighting", () => {
     it("should highlight active section in sidebar", async () => {
       const user = userEvent.setup();
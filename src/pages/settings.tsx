@@ -1,6 +1,16 @@
 import { useState, useEffect } from "react";
 import { cn } from "@/lib/utils";
 import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { Textarea } from "@/components/ui/textarea";
+import { Field, FieldGroup, FieldLabel } from "@/components/ui/field";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
 import {
   Card,
   CardContent,
@@ -20,12 +30,16 @@ import {
   IconTerminal2,
 } from "@tabler/icons-react";
 import { invoke } from "@tauri-apps/api/core";
-import type { TerminalType } from "@/lib/types";
+import type { LaunchOptions, PermissionMode, TerminalType } from "@/lib/types";
 import { terminalDisplayNames } from "@/lib/types";
+import { loadLaunchOptions, saveLaunchOptions } from "@/lib/launch-options";
 
 type SettingsSection = "appearance" | "terminal" | "about";
 
 export const TERMINAL_STORAGE_KEY = "agent-console:default-terminal";
+export const CUSTOM_TERMINAL_STORAGE_KEY =
+  "agent-console:custom-terminal-template";
+export const WSL_DISTRO_STORAGE_KEY = "agent-console:wsl-distro";
 
 interface SettingsPageProps {
   onBack: () => void;
@@ -186,6 +200,9 @@ function TerminalSection() {
   const [selectedTerminal, setSelectedTerminal] = useState<TerminalType | null>(
     null
   );
+  const [customTemplate, setCustomTemplate] = useState(
+    () => localStorage.getItem(CUSTOM_TERMINAL_STORAGE_KEY) ?? ""
+  );
 
   useEffect(() => {
     invoke<TerminalType[]>("get_available_terminals").then((terminals) => {
@@ -207,6 +224,11 @@ function TerminalSection() {
     localStorage.setItem(TERMINAL_STORAGE_KEY, terminal);
   };
 
+  const handleCustomTemplateChange = (template: string) => {
+    setCustomTemplate(template);
+    localStorage.setItem(CUSTOM_TERMINAL_STORAGE_KEY, template);
+  };
+
   return (
     <div className="space-y-6">
       <div>
@@ -271,10 +293,239 @@ function TerminalSection() {
           )}
         </CardContent>
       </Card>
+
+      {selectedTerminal === "custom" && (
+        <Card>
+          <CardHeader>
+            <CardTitle>Custom Command</CardTitle>
+            <CardDescription>
+              Command that opens your terminal. <code>{"{cwd}"}</code> is
+              replaced with the project directory and <code>{"{cmd}"}</code>{" "}
+              with the Claude command.
+            </CardDescription>
+          </CardHeader>
+          <CardContent>
+            <Input
+              aria-label="Custom terminal command"
+              value={customTemplate}
+              onChange={(e) => handleCustomTemplateChange(e.target.value)}
+              placeholder="foot -D {cwd} sh -c {cmd}"
+              className="font-mono"
+            />
+          </CardContent>
+        </Card>
+      )}
+
+      {selectedTerminal === "wsl" && <WslDistroCard />}
+
+      <LaunchOptionsCard />
     </div>
   );
 }
 
+// Select value standing in for "use the default distribution"
+const DEFAULT_WSL_DISTRO = "default";
+
+function WslDistroCard() {
+  const [distros, setDistros] = useState<string[]>([]);
+  const [distro, setDistro] = useState(
+    () => localStorage.getItem(WSL_DISTRO_STORAGE_KEY) ?? DEFAULT_WSL_DISTRO
+  );
+
+  useEffect(() => {
+    invoke<string[]>("get_wsl_distros").then(setDistros);
+  }, []);
+
+  const handleDistroChange = (value: string) => {
+    setDistro(value);
+    if (value === DEFAULT_WSL_DISTRO) {
+      localStorage.removeItem(WSL_DISTRO_STORAGE_KEY);
+    } else {
+      localStorage.setItem(WSL_DISTRO_STORAGE_KEY, value);
+    }
+  };
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle>WSL Distribution</CardTitle>
+        <CardDescription>
+          The distribution Claude sessions are started in.
+        </CardDescription>
+      </CardHeader>
+      <CardContent>
+        <Select value={distro} onValueChange={handleDistroChange}>
+          <SelectTrigger aria-label="WSL distribution">
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent>
+            <SelectItem value={DEFAULT_WSL_DISTRO}>
+              Default distribution
+            </SelectItem>
+            {distros.map((name) => (
+              <SelectItem key={name} value={name}>
+                {name}
+              </SelectItem>
+            ))}
+          </SelectContent>
+        </Select>
+      </CardContent>
+    </Card>
+  );
+}
+
+const permissionModeLabels: Record<PermissionMode, string> = {
+  default: "Ask for permission",
+  acceptEdits: "Accept edits",
+  plan: "Plan only",
+  bypassPermissions: "Bypass permissions",
+};
+
+// Select value standing in for "no --permission-mode flag"
+const UNSET_PERMISSION_MODE = "unset";
+
+// Split a textarea into one value per non-empty line
+function splitLines(text: string): string[] {
+  return text
+    .split("\n")
+    .map((line) => line.trim())
+    .filter((line) => line.length > 0);
+}
+
+// Parse NAME=value lines into environment variables
+function parseEnvLines(text: string): Record<string, string> {
+  const env: Record<string, string> = {};
+  for (const line of splitLines(text)) {
+    const separator = line.indexOf("=");
+    if (separator > 0) {
+      env[line.slice(0, separator).trim()] = line.slice(separator + 1);
+    }
+  }
+  return env;
+}
+
+function LaunchOptionsCard() {
+  const [options, setOptions] = useState<LaunchOptions>(loadLaunchOptions);
+  // Raw textarea contents, so blank lines survive while typing
+  const [addDirsText, setAddDirsText] = useState(() =>
+    options.addDirs.join("\n")
+  );
+  const [extraArgsText, setExtraArgsText] = useState(() =>
+    options.extraArgs.join("\n")
+  );
+  const [envText, setEnvText] = useState(() =>
+    Object.entries(options.env)
+      .map(([name, value]) => `${name}=${value}`)
+      .join("\n")
+  );
+
+  const updateOptions = (changes: Partial<LaunchOptions>) => {
+    const next = { ...options, ...changes };
+    setOptions(next);
+    saveLaunchOptions(next);
+  };
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle>Launch Options</CardTitle>
+        <CardDescription>
+          Flags passed to every Claude session started from Agent Console.
+        </CardDescription>
+      </CardHeader>
+      <CardContent>
+        <FieldGroup>
+          <Field>
+            <FieldLabel htmlFor="launch-model">Model</FieldLabel>
+            <Input
+              id="launch-model"
+              value={options.model ?? ""}
+              onChange={(e) => updateOptions({ model: e.target.value || null })}
+              placeholder="Default model"
+            />
+          </Field>
+          <Field>
+            <FieldLabel htmlFor="launch-permission-mode">
+              Permission mode
+            </FieldLabel>
+            <Select
+              value={options.permissionMode ?? UNSET_PERMISSION_MODE}
+              onValueChange={(value) =>
+                updateOptions({
+                  permissionMode:
+                    value === UNSET_PERMISSION_MODE
+                      ? null
+                      : (value as PermissionMode),
+                })
+              }
+            >
+              <SelectTrigger id="launch-permission-mode">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectItem value={UNSET_PERMISSION_MODE}>
+                  Claude's default
+                </SelectItem>
+                {(Object.keys(permissionModeLabels) as PermissionMode[]).map(
+                  (mode) => (
+                    <SelectItem key={mode} value={mode}>
+                      {permissionModeLabels[mode]}
+                    </SelectItem>
+                  )
+                )}
+              </SelectContent>
+            </Select>
+          </Field>
+          <Field>
+            <FieldLabel htmlFor="launch-add-dirs">
+              Additional directories (one per line)
+            </FieldLabel>
+            <Textarea
+              id="launch-add-dirs"
+              value={addDirsText}
+              onChange={(e) => {
+                setAddDirsText(e.target.value);
+                updateOptions({ addDirs: splitLines(e.target.value) });
+              }}
+              className="font-mono"
+            />
+          </Field>
+          <Field>
+            <FieldLabel htmlFor="launch-extra-args">
+              Extra arguments (one per line)
+            </FieldLabel>
+            <Textarea
+              id="launch-extra-args"
+              value={extraArgsText}
+              onChange={(e) => {
+                setExtraArgsText(e.target.value);
+                updateOptions({ extraArgs: splitLines(e.target.value) });
+              }}
+              placeholder="--verbose"
+              className="font-mono"
+            />
+          </Field>
+          <Field>
+            <FieldLabel htmlFor="launch-env">
+              Environment variables (NAME=value per line)
+            </FieldLabel>
+            <Textarea
+              id="launch-env"
+              value={envText}
+              onChange={(e) => {
+                setEnvText(e.target.value);
+                updateOptions({ env: parseEnvLines(e.target.value) });
+              }}
+              placeholder="CLAUDE_CONFIG_DIR=~/.claude-work"
+              className="font-mono"
+            />
+          </Field>
+        </FieldGroup>
+      </CardContent>
+    </Card>
+  );
+}
+
 function AboutSection() {
   return (
     <div className="space-y-6">
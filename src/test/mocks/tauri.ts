@@ -19,6 +19,7 @@ export const createMockProject = (overrides: Partial<Project> = {}): Project =>
   subagentCount: 2,
   lastActivity: new Date().toISOString(),
   sessions: [],
+  uncommittedChanges: null,
   ...overrides,
 });
 
@@ -59,6 +60,7 @@ export const createMockSessionEvent = (
   isCompactSummary: null,
   isToolResult: false,
   isMeta: false,
+  usage: null,
   ...overrides,
 });
 
@@ -68,6 +70,12 @@ export const createMockFileEdit = (
   path: "src/app.ts",
   editType: "modified",
   lastEditedAt: new Date().toISOString(),
+  editedBy: null,
+  linesAdded: 0,
+  linesRemoved: 0,
+  editCount: 1,
+  renamedFrom: null,
+  isGenerated: false,
   ...overrides,
 });
 
@@ -78,6 +86,12 @@ export const createMockFileDiff = (
   newString: "new content",
   sequence: 0,
   timestamp: new Date().toISOString(),
+  editedBy: null,
+  cellId: null,
+  cellEditMode: null,
+  language: "typescript",
+  appliesCleanly: null,
+  revertsCleanly: null,
   ...overrides,
 });
 
@@ -115,13 +129,25 @@ const defaultInvokeHandler = async (
     case "get_projects":
       return [createMockProject()];
     case "get_active_sessions":
-      return { supported: true, activePaths: [] } as ActiveSessionsResult;
+      return { supported: true, activePaths: [], activeCounts: {}, sessions: [] } as ActiveSessionsResult;
     case "get_project_sessions":
       return [createMockSession()];
     case "get_available_terminals":
       return ["macos-terminal", "iterm2"];
+    case "get_launched_sessions":
+      return [];
+    case "get_wsl_distros":
+      return [];
+    case "has_devcontainer":
+      return false;
+    case "preview_launch_command":
+      return {
+        cwd: "/Users/test/projects/my-app",
+        command: "claude",
+        appleScript: null,
+      };
     case "get_indexed_events":
-      return { events: [], totalCount: 0, offset: 0, hasMore: false };
+      return { events: [], totalCount: 0, offset: 0, hasMore: false, nextCursor: null };
     case "get_indexed_file_edits":
       return [];
     case "get_index_status":
@@ -138,6 +164,9 @@ const defaultInvokeHandler = async (
         current: "",
         existsAtHead: true,
         existsInWorkdir: true,
+        changeType: null,
+        untracked: false,
+        submodule: null,
       };
     default:
       console.warn(`Unhandled Tauri command in mock: ${cmd}`);